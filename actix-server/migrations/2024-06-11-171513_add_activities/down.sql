@@ -0,0 +1,6 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE "deals" DROP COLUMN "last_contacted_at";
+ALTER TABLE "contacts" DROP COLUMN "last_contacted_at";
+
+DROP TRIGGER "update_activities_updated_at" ON "activities";
+DROP TABLE "activities";
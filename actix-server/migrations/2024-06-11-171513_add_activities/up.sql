@@ -0,0 +1,29 @@
+-- Your SQL goes here
+CREATE TABLE "activities" (
+	"id" UUID PRIMARY KEY,
+	"org_id" UUID NOT NULL,
+	"payload" JSONB NOT NULL,
+	"contact_id" UUID,
+	"deal_id" UUID,
+	"company_id" UUID,
+	"occurred_at" TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+	"created_at" TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+	"updated_at" TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+	FOREIGN KEY ("org_id") REFERENCES "orgs" ("id") ON DELETE CASCADE,
+	FOREIGN KEY ("contact_id") REFERENCES "contacts" ("id") ON DELETE SET NULL,
+	FOREIGN KEY ("deal_id") REFERENCES "deals" ("id") ON DELETE SET NULL,
+	FOREIGN KEY ("company_id") REFERENCES "companies" ("id") ON DELETE SET NULL
+);
+
+CREATE INDEX "activities_org_id_occurred_at_idx" ON "activities" ("org_id", "occurred_at");
+CREATE INDEX "activities_contact_id_idx" ON "activities" ("contact_id");
+CREATE INDEX "activities_deal_id_idx" ON "activities" ("deal_id");
+CREATE INDEX "activities_company_id_idx" ON "activities" ("company_id");
+
+CREATE TRIGGER "update_activities_updated_at"
+  BEFORE UPDATE ON "activities"
+  FOR EACH ROW
+  EXECUTE FUNCTION update_modified_column();
+
+ALTER TABLE "contacts" ADD COLUMN "last_contacted_at" TIMESTAMP WITH TIME ZONE;
+ALTER TABLE "deals" ADD COLUMN "last_contacted_at" TIMESTAMP WITH TIME ZONE;
@@ -0,0 +1,7 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE "deals" ADD COLUMN "size" REAL;
+
+UPDATE "deals" SET "size" = "amount" / 100.0 WHERE "amount" IS NOT NULL;
+
+ALTER TABLE "deals" DROP COLUMN "amount", DROP COLUMN "currency";
+ALTER TABLE "orgs" DROP COLUMN "default_currency";
@@ -0,0 +1,15 @@
+-- Your SQL goes here
+ALTER TABLE "orgs" ADD COLUMN "default_currency" TEXT NOT NULL DEFAULT 'USD';
+
+-- Amounts are stored in the minor unit of the deal currency. Existing sizes had
+-- no currency, so they are treated as amounts in the org default (USD).
+ALTER TABLE "deals"
+  ADD COLUMN "amount" BIGINT,
+  ADD COLUMN "currency" TEXT NOT NULL DEFAULT 'USD';
+
+UPDATE "deals" SET "amount" = ROUND("size"::NUMERIC * 100)::BIGINT WHERE "size" IS NOT NULL;
+
+ALTER TABLE "deals" DROP COLUMN "size";
+ALTER TABLE "deals" ALTER COLUMN "currency" DROP DEFAULT;
+ALTER TABLE "deals" ADD CONSTRAINT "deals_currency_iso_4217" CHECK ("currency" ~ '^[A-Z]{3}$');
+ALTER TABLE "orgs" ADD CONSTRAINT "orgs_default_currency_iso_4217" CHECK ("default_currency" ~ '^[A-Z]{3}$');
@@ -0,0 +1,9 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX "deals_org_id_closed_at_idx";
+DROP INDEX "deals_org_id_expected_close_date_idx";
+
+ALTER TABLE "deals"
+  DROP COLUMN "probability",
+  DROP COLUMN "expected_close_date",
+  DROP COLUMN "won",
+  DROP COLUMN "closed_at";
@@ -0,0 +1,9 @@
+-- Your SQL goes here
+ALTER TABLE "deals"
+  ADD COLUMN "probability" INTEGER CHECK ("probability" BETWEEN 0 AND 100),
+  ADD COLUMN "expected_close_date" DATE,
+  ADD COLUMN "won" BOOLEAN,
+  ADD COLUMN "closed_at" TIMESTAMPTZ;
+
+CREATE INDEX "deals_org_id_expected_close_date_idx" ON "deals" ("org_id", "expected_close_date");
+CREATE INDEX "deals_org_id_closed_at_idx" ON "deals" ("org_id", "closed_at");
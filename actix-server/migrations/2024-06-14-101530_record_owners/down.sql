@@ -0,0 +1,10 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX "tasks_org_id_owner_user_id_idx";
+DROP INDEX "companies_org_id_owner_user_id_idx";
+DROP INDEX "deals_org_id_owner_user_id_idx";
+DROP INDEX "contacts_org_id_owner_user_id_idx";
+
+ALTER TABLE "tasks" DROP COLUMN "owner_user_id";
+ALTER TABLE "companies" DROP COLUMN "owner_user_id";
+ALTER TABLE "deals" DROP COLUMN "owner_user_id";
+ALTER TABLE "contacts" DROP COLUMN "owner_user_id";
@@ -0,0 +1,11 @@
+-- Your SQL goes here
+-- Existing records have no known creator, so their owner is left empty.
+ALTER TABLE "contacts" ADD COLUMN "owner_user_id" UUID REFERENCES "users"("id") ON DELETE SET NULL;
+ALTER TABLE "deals" ADD COLUMN "owner_user_id" UUID REFERENCES "users"("id") ON DELETE SET NULL;
+ALTER TABLE "companies" ADD COLUMN "owner_user_id" UUID REFERENCES "users"("id") ON DELETE SET NULL;
+ALTER TABLE "tasks" ADD COLUMN "owner_user_id" UUID REFERENCES "users"("id") ON DELETE SET NULL;
+
+CREATE INDEX "contacts_org_id_owner_user_id_idx" ON "contacts" ("org_id", "owner_user_id");
+CREATE INDEX "deals_org_id_owner_user_id_idx" ON "deals" ("org_id", "owner_user_id");
+CREATE INDEX "companies_org_id_owner_user_id_idx" ON "companies" ("org_id", "owner_user_id");
+CREATE INDEX "tasks_org_id_owner_user_id_idx" ON "tasks" ("org_id", "owner_user_id");
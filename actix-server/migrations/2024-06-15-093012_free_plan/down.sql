@@ -0,0 +1 @@
+DELETE FROM "plans" WHERE "stripe_id" = 'free';
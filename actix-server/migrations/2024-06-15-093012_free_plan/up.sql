@@ -0,0 +1,4 @@
+-- Orgs without a subscription fall back to the free plan
+INSERT INTO "plans" ("id", "stripe_id", "num_users", "num_deals", "price_per_month", "created_at", "updated_at")
+VALUES ('00000000-0000-0000-0000-000000000000', 'free', 3, 50, 0, NOW(), NOW())
+ON CONFLICT ("stripe_id") DO NOTHING;
@@ -0,0 +1,3 @@
+DROP TABLE "billing_events";
+DROP INDEX "subscriptions_stripe_id_key";
+ALTER TABLE "subscriptions" DROP COLUMN "status";
@@ -0,0 +1,10 @@
+-- Subscriptions are kept in sync by the billing webhook, keyed by the provider's id
+ALTER TABLE "subscriptions" ADD COLUMN "status" TEXT NOT NULL DEFAULT 'active';
+CREATE UNIQUE INDEX "subscriptions_stripe_id_key" ON "subscriptions"("stripe_id");
+
+-- Webhook events that have already been applied, so redeliveries are ignored
+CREATE TABLE "billing_events"(
+	"id" TEXT NOT NULL PRIMARY KEY,
+	"event_type" TEXT NOT NULL,
+	"created_at" TIMESTAMP NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,3 @@
+-- Superusers can reach the instance-wide admin routes. Disabled users can't authenticate.
+ALTER TABLE "users" ADD COLUMN "is_superuser" BOOLEAN NOT NULL DEFAULT false;
+ALTER TABLE "users" ADD COLUMN "disabled" BOOLEAN NOT NULL DEFAULT false;
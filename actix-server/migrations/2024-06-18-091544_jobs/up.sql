@@ -0,0 +1,18 @@
+-- Durable background jobs. Workers claim due rows with FOR UPDATE SKIP LOCKED and
+-- hold them until "locked_until", after which another worker may pick them up again
+CREATE TABLE "jobs"(
+	"id" UUID NOT NULL PRIMARY KEY,
+	"kind" TEXT NOT NULL,
+	"payload" JSONB NOT NULL,
+	"status" TEXT NOT NULL DEFAULT 'queued',
+	"priority" INTEGER NOT NULL DEFAULT 0,
+	"run_at" TIMESTAMP NOT NULL DEFAULT NOW(),
+	"attempts" INTEGER NOT NULL DEFAULT 0,
+	"max_attempts" INTEGER NOT NULL DEFAULT 5,
+	"last_error" TEXT,
+	"locked_until" TIMESTAMP,
+	"created_at" TIMESTAMP NOT NULL DEFAULT NOW(),
+	"updated_at" TIMESTAMP NOT NULL DEFAULT NOW()
+);
+CREATE INDEX "jobs_claim_idx" ON "jobs"("priority" DESC, "run_at") WHERE "status" IN ('queued', 'running');
+CREATE INDEX "jobs_status_idx" ON "jobs"("status", "created_at" DESC);
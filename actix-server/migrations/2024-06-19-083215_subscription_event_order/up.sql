@@ -0,0 +1,3 @@
+-- Billing events can arrive out of order. The creation time of the last event applied
+-- to a subscription lets older ones be skipped
+ALTER TABLE "subscriptions" ADD COLUMN "last_event_at" TIMESTAMP;
@@ -0,0 +1,13 @@
+DROP TRIGGER update_contacts_updated_at ON contacts;
+CREATE TRIGGER update_contacts_updated_at
+  BEFORE UPDATE ON contacts
+  FOR EACH ROW
+  EXECUTE FUNCTION update_modified_column();
+
+DROP TRIGGER update_deals_updated_at ON deals;
+CREATE TRIGGER update_deals_updated_at
+  BEFORE UPDATE ON deals
+  FOR EACH ROW
+  EXECUTE FUNCTION update_modified_column();
+
+DROP FUNCTION update_modified_column_except_last_contacted;
@@ -0,0 +1,26 @@
+-- "last_contacted_at" is derived from activities. An update that only moves it leaves
+-- "updated_at" alone, so logging an activity doesn't invalidate the ETags clients hold
+-- for the contact or deal
+CREATE OR REPLACE FUNCTION update_modified_column_except_last_contacted()
+  RETURNS TRIGGER AS $$
+  BEGIN
+    IF NEW.last_contacted_at IS DISTINCT FROM OLD.last_contacted_at
+      AND to_jsonb(NEW) - 'last_contacted_at' = to_jsonb(OLD) - 'last_contacted_at' THEN
+      RETURN NEW;
+    END IF;
+    NEW.updated_at = now();
+    RETURN NEW;
+  END;
+$$ language 'plpgsql';
+
+DROP TRIGGER update_contacts_updated_at ON contacts;
+CREATE TRIGGER update_contacts_updated_at
+  BEFORE UPDATE ON contacts
+  FOR EACH ROW
+  EXECUTE FUNCTION update_modified_column_except_last_contacted();
+
+DROP TRIGGER update_deals_updated_at ON deals;
+CREATE TRIGGER update_deals_updated_at
+  BEFORE UPDATE ON deals
+  FOR EACH ROW
+  EXECUTE FUNCTION update_modified_column_except_last_contacted();
@@ -0,0 +1,40 @@
+use actix_server::{data::schema::users::dsl as users_columns, settings::Settings};
+use diesel::{Connection, ExpressionMethods, QueryDsl, RunQueryDsl};
+
+/// Grants superuser access to an existing user: `create_superuser <email>`
+fn main() {
+    dotenvy::dotenv().ok();
+
+    let Some(email) = std::env::args().nth(1) else {
+        eprintln!("Usage: create_superuser <email>");
+        std::process::exit(1);
+    };
+
+    let settings = match Settings::load() {
+        Ok(settings) => settings,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+    let database_url = settings
+        .database_tls
+        .libpq_url(settings.database_url.expose());
+    let mut conn =
+        diesel::pg::PgConnection::establish(&database_url).expect("Failed to connect to database");
+
+    let updated = diesel::update(users_columns::users.filter(users_columns::email.eq(&email)))
+        .set(users_columns::is_superuser.eq(true))
+        .execute(&mut conn)
+        .expect("Failed to update user");
+
+    if updated == 0 {
+        eprintln!(
+            "No user with email {}. They need to log in once before they can be made a superuser.",
+            email
+        );
+        std::process::exit(1);
+    }
+
+    println!("{} is now a superuser", email);
+}
@@ -0,0 +1,50 @@
+use crate::errors::ServiceError;
+
+pub const DEFAULT_CURRENCY: &str = "USD";
+
+// Active ISO 4217 alphabetic codes. Amounts are always stored in the minor unit
+// of the currency (cents for USD, yen for JPY, fils for BHD, ...).
+const ISO_4217_CODES: &[&str] = &[
+    "AED", "AFN", "ALL", "AMD", "ANG", "AOA", "ARS", "AUD", "AWG", "AZN", "BAM", "BBD", "BDT",
+    "BGN", "BHD", "BIF", "BMD", "BND", "BOB", "BRL", "BSD", "BTN", "BWP", "BYN", "BZD", "CAD",
+    "CDF", "CHF", "CLP", "CNY", "COP", "CRC", "CUP", "CVE", "CZK", "DJF", "DKK", "DOP", "DZD",
+    "EGP", "ERN", "ETB", "EUR", "FJD", "FKP", "GBP", "GEL", "GHS", "GIP", "GMD", "GNF", "GTQ",
+    "GYD", "HKD", "HNL", "HTG", "HUF", "IDR", "ILS", "INR", "IQD", "IRR", "ISK", "JMD", "JOD",
+    "JPY", "KES", "KGS", "KHR", "KMF", "KPW", "KRW", "KWD", "KYD", "KZT", "LAK", "LBP", "LKR",
+    "LRD", "LSL", "LYD", "MAD", "MDL", "MGA", "MKD", "MMK", "MNT", "MOP", "MRU", "MUR", "MVR",
+    "MWK", "MXN", "MYR", "MZN", "NAD", "NGN", "NIO", "NOK", "NPR", "NZD", "OMR", "PAB", "PEN",
+    "PGK", "PHP", "PKR", "PLN", "PYG", "QAR", "RON", "RSD", "RUB", "RWF", "SAR", "SBD", "SCR",
+    "SDG", "SEK", "SGD", "SHP", "SLE", "SOS", "SRD", "SSP", "STN", "SVC", "SYP", "SZL", "THB",
+    "TJS", "TMT", "TND", "TOP", "TRY", "TTD", "TWD", "TZS", "UAH", "UGX", "USD", "UYU", "UZS",
+    "VES", "VND", "VUV", "WST", "XAF", "XCD", "XOF", "XPF", "YER", "ZAR", "ZMW", "ZWL",
+];
+
+/// Number of decimal places in the minor unit of an ISO 4217 currency.
+pub fn minor_unit_exponent(code: &str) -> u32 {
+    match code {
+        "BIF" | "CLP" | "DJF" | "GNF" | "ISK" | "JPY" | "KMF" | "KRW" | "PYG" | "RWF" | "UGX"
+        | "VND" | "VUV" | "XAF" | "XOF" | "XPF" => 0,
+        "BHD" | "IQD" | "JOD" | "KWD" | "LYD" | "OMR" | "TND" => 3,
+        _ => 2,
+    }
+}
+
+/// Every supported currency code with the exponent of its minor unit.
+pub fn currencies() -> impl Iterator<Item = (&'static str, u32)> {
+    ISO_4217_CODES
+        .iter()
+        .map(|code| (*code, minor_unit_exponent(code)))
+}
+
+/// Normalizes a currency code to upper case and checks it against ISO 4217.
+pub fn parse_currency(code: &str) -> Result<String, ServiceError> {
+    let code = code.trim().to_ascii_uppercase();
+    if ISO_4217_CODES.binary_search(&code.as_str()).is_ok() {
+        Ok(code)
+    } else {
+        Err(ServiceError::invalid_field(
+            "currency",
+            format!("{} is not a valid ISO 4217 currency code", code),
+        ))
+    }
+}
@@ -0,0 +1,87 @@
+use crate::errors::ServiceError;
+use actix_web::{
+    http::header::{self, EntityTag, Header, IfMatch},
+    HttpRequest,
+};
+use diesel::{
+    dsl,
+    expression::AsExpression,
+    pg::Pg,
+    sql_types::{Bool, SqlType},
+    BoxableExpression, Expression, ExpressionMethods, IntoSql,
+};
+
+/// The ETag of a record is its `updated_at` exactly as it appears in the record's
+/// JSON, so clients can send `If-Match` for a record they already have.
+pub fn etag(updated_at: chrono::NaiveDateTime) -> header::ETag {
+    let tag = serde_json::to_value(updated_at)
+        .ok()
+        .and_then(|value| value.as_str().map(|tag| tag.to_string()))
+        .unwrap_or_default();
+    header::ETag(EntityTag::new_strong(tag))
+}
+
+/// Whether the write is conditional on the record not having changed. Handlers use
+/// this to skip loading the current record when there's nothing to check.
+pub fn has_if_match(req: &HttpRequest) -> bool {
+    req.headers().contains_key(header::IF_MATCH)
+}
+
+/// Errors with `PreconditionFailed` unless the `If-Match` header matches the
+/// record's current ETag. Requests without the header always pass.
+///
+/// Returns the version the write has to be made against, to be passed on to the
+/// operator doing the write. Checking here alone would let two writers holding the
+/// same ETag both pass before either writes.
+pub fn check_if_match(
+    req: &HttpRequest,
+    updated_at: chrono::NaiveDateTime,
+) -> Result<Option<chrono::NaiveDateTime>, ServiceError> {
+    if !has_if_match(req) {
+        return Ok(None);
+    }
+
+    match IfMatch::parse(req) {
+        Ok(IfMatch::Any) => Ok(None),
+        Ok(IfMatch::Items(tags)) => {
+            let current = etag(updated_at).0;
+            if tags.iter().any(|tag| tag.strong_eq(&current)) {
+                Ok(Some(updated_at))
+            } else {
+                Err(ServiceError::PreconditionFailed)
+            }
+        }
+        Err(_) => Err(ServiceError::BadRequest(
+            "Invalid If-Match header".to_string(),
+        )),
+    }
+}
+
+/// Filter for an update or delete that limits it to the version returned by
+/// [`check_if_match`]. Matches every row when there's no version to check.
+pub fn unchanged_since<T, C>(
+    updated_at: C,
+    expected_updated_at: Option<chrono::NaiveDateTime>,
+) -> Box<dyn BoxableExpression<T, Pg, SqlType = Bool>>
+where
+    T: 'static,
+    C: Expression + ExpressionMethods,
+    C::SqlType: SqlType,
+    chrono::NaiveDateTime: AsExpression<C::SqlType>,
+    dsl::Eq<C, chrono::NaiveDateTime>: BoxableExpression<T, Pg, SqlType = Bool> + 'static,
+{
+    match expected_updated_at {
+        Some(expected_updated_at) => Box::new(updated_at.eq(expected_updated_at)),
+        None => Box::new(true.into_sql::<Bool>()),
+    }
+}
+
+/// The error for a conditional write that matched no row. With a version to check the
+/// record changed since it was read, otherwise it's gone.
+pub fn write_conflict(expected_updated_at: Option<chrono::NaiveDateTime>) -> ServiceError {
+    if expected_updated_at.is_some() {
+        ServiceError::PreconditionFailed
+    } else {
+        ServiceError::NotFound
+    }
+}
@@ -1,2 +1,7 @@
+pub mod currency;
+pub mod etag;
 pub mod models;
+pub mod patch;
+pub mod redact;
 pub mod schema;
+pub mod validation;
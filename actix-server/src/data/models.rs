@@ -1,15 +1,24 @@
+use super::redact::redacted_debug;
 use super::schema::*;
 use crate::prefixes::*;
 use bb8_redis::{bb8, RedisConnectionManager};
-use diesel::expression::ValidGrouping;
+use diesel::{
+    deserialize::FromSql,
+    expression::ValidGrouping,
+    pg::{Pg, PgValue},
+    serialize::ToSql,
+    sql_types::Jsonb,
+};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
 pub type PgPool = diesel_async::pooled_connection::deadpool::Pool<diesel_async::AsyncPgConnection>;
 pub type RedisPool = bb8::Pool<RedisConnectionManager>;
+/// Hands change events received over Redis to the event streams open on this instance.
+pub type ChangeEventSender = tokio::sync::broadcast::Sender<ChangeEvent>;
 
 #[derive(
-    Debug, Serialize, Deserialize, Queryable, Insertable, Selectable, Clone, ToSchema, Identifiable,
+    Serialize, Deserialize, Queryable, Insertable, Selectable, Clone, ToSchema, Identifiable,
 )]
 #[schema(example = json!({
     "id": "e3e3e3e3-e3e3-e3e3-e3e3-e3e3e3e3e3e3",
@@ -17,6 +26,8 @@ pub type RedisPool = bb8::Pool<RedisConnectionManager>;
     "name": "Trieve Team",
     "created_at": "2021-01-01T00:00:00",
     "updated_at": "2021-01-01T00:00:00",
+    "is_superuser": false,
+    "disabled": false,
 }))]
 #[diesel(table_name = users)]
 pub struct User {
@@ -25,8 +36,16 @@ pub struct User {
     pub created_at: chrono::NaiveDateTime,
     pub updated_at: chrono::NaiveDateTime,
     pub name: Option<String>,
+    /// Whether the user can manage the whole instance through the admin routes.
+    #[serde(default)]
+    pub is_superuser: bool,
+    /// Disabled users are treated as logged out.
+    #[serde(default)]
+    pub disabled: bool,
 }
 
+redacted_debug!(User { id, created_at, updated_at, is_superuser, disabled } pii { email, name });
+
 impl User {
     pub fn from_details(email: String, name: Option<String>) -> Self {
         User {
@@ -35,6 +54,8 @@ impl User {
             created_at: chrono::Utc::now().naive_local(),
             updated_at: chrono::Utc::now().naive_local(),
             name: name.map(|n| n.into()),
+            is_superuser: false,
+            disabled: false,
         }
     }
 
@@ -49,6 +70,8 @@ impl User {
             name,
             created_at: chrono::Utc::now().naive_local(),
             updated_at: chrono::Utc::now().naive_local(),
+            is_superuser: false,
+            disabled: false,
         }
     }
 }
@@ -61,6 +84,7 @@ impl User {
     "name": "Trieve Team",
     "created_at": "2021-01-01T00:00:00",
     "updated_at": "2021-01-01T00:00:00",
+    "default_currency": "USD",
 }))]
 #[diesel(table_name = orgs)]
 pub struct Org {
@@ -68,24 +92,32 @@ pub struct Org {
     pub name: String,
     pub created_at: chrono::NaiveDateTime,
     pub updated_at: chrono::NaiveDateTime,
+    /// ISO 4217 code used for new deals that don't specify a currency.
+    pub default_currency: String,
 }
 
 impl Org {
-    pub fn from_details(name: String) -> Self {
+    pub fn from_details(name: String, default_currency: String) -> Self {
         Org {
             id: PrefixedUuid::create(OrgPrefix),
             name,
             created_at: chrono::Utc::now().naive_local(),
             updated_at: chrono::Utc::now().naive_local(),
+            default_currency,
         }
     }
 
-    pub fn from_details_with_id(id: PrefixedUuid<OrgPrefix>, name: String) -> Self {
+    pub fn from_details_with_id(
+        id: PrefixedUuid<OrgPrefix>,
+        name: String,
+        default_currency: String,
+    ) -> Self {
         Org {
             id: id.into(),
             name,
             created_at: chrono::Utc::now().naive_local(),
             updated_at: chrono::Utc::now().naive_local(),
+            default_currency,
         }
     }
 }
@@ -105,37 +137,70 @@ impl Org {
 #[schema(example = json!({
     "id": "e3e3e3e3-e3e3-e3e3-e3e3-e3e3e3e3e3e3",
     "org_id": "e3e3e3e3-e3e3-e3e3-e3e3-e3e3e3e3e3e3",
-    "size": 4.0,
     "active": true,
     "created_at": "2021-01-01T00:00:00",
     "updated_at": "2021-01-01T00:00:00",
+    "last_contacted_at": "2021-01-01T00:00:00",
+    "amount": 125000,
+    "currency": "USD",
+    "probability": 60,
+    "expected_close_date": "2021-03-31",
+    "won": null,
+    "closed_at": null,
+    "owner_user_id": "user-d4d4d4d4-d4d4-d4d4-d4d4-d4d4d4d4d4d4",
 }))]
 #[diesel(table_name = deals)]
 pub struct Deal {
     pub id: PrefixedUuid<DealPrefix>,
     pub name: Option<String>,
     pub org_id: PrefixedUuid<OrgPrefix>,
-    pub size: Option<f32>,
     pub active: bool,
     pub created_at: chrono::NaiveDateTime,
     pub updated_at: chrono::NaiveDateTime,
+    /// When the latest activity linked to the deal occurred. Kept in sync with its
+    /// activities, changing it doesn't change `updated_at`.
+    pub last_contacted_at: Option<chrono::NaiveDateTime>,
+    /// Deal value in the minor unit of `currency` (e.g. cents for USD).
+    pub amount: Option<i64>,
+    /// ISO 4217 currency code of `amount`.
+    pub currency: String,
+    /// Likelihood of the deal closing as won, from 0 to 100.
+    pub probability: Option<i32>,
+    pub expected_close_date: Option<chrono::NaiveDate>,
+    /// Whether the deal was won or lost. Null while the deal is still open.
+    pub won: Option<bool>,
+    pub closed_at: Option<chrono::NaiveDateTime>,
+    /// The user responsible for this deal. Defaults to whoever created it.
+    pub owner_user_id: Option<PrefixedUuid<UserPrefix>>,
 }
 
 impl Deal {
+    #[allow(clippy::too_many_arguments)]
     pub fn from_details(
         org_id: PrefixedUuid<OrgPrefix>,
         name: Option<String>,
-        size: Option<f32>,
+        amount: Option<i64>,
+        currency: String,
+        probability: Option<i32>,
+        expected_close_date: Option<chrono::NaiveDate>,
         active: bool,
+        owner_user_id: Option<PrefixedUuid<UserPrefix>>,
     ) -> Self {
         Deal {
             id: PrefixedUuid::create(DealPrefix),
             name,
             org_id,
-            size,
             active,
             created_at: chrono::Utc::now().naive_local(),
             updated_at: chrono::Utc::now().naive_local(),
+            last_contacted_at: None,
+            amount,
+            currency,
+            probability,
+            expected_close_date,
+            won: None,
+            closed_at: None,
+            owner_user_id,
         }
     }
 }
@@ -269,7 +334,7 @@ impl Plan {
         price_per_month: i32,
     ) -> Self {
         Plan {
-            id: uuid::Uuid::new_v4(),
+            id: uuid::Uuid::now_v7(),
             stripe_id,
             num_users,
             num_deals,
@@ -280,7 +345,51 @@ impl Plan {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, Queryable, Insertable, ValidGrouping, ToSchema)]
+#[derive(Debug, Serialize, Deserialize, Queryable, Insertable, Selectable, Clone)]
+#[diesel(table_name=subscriptions)]
+pub struct Subscription {
+    pub id: uuid::Uuid,
+    pub stripe_id: String,
+    pub org_id: PrefixedUuid<OrgPrefix>,
+    pub plan_id: uuid::Uuid,
+    pub stripe_plan_id: String,
+    pub next_billing_date: chrono::NaiveDateTime,
+    pub start_date: chrono::NaiveDateTime,
+    pub end_date: Option<chrono::NaiveDateTime>,
+    /// See [`SubscriptionStatus`]. Only active subscriptions grant their plan's limits.
+    pub status: String,
+    /// When the last billing event applied to the subscription was created. Events
+    /// older than that are skipped. Empty for subscriptions not managed by billing.
+    pub last_event_at: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+pub enum SubscriptionStatus {
+    Active,
+    PastDue,
+    Canceled,
+}
+
+impl SubscriptionStatus {
+    /// Maps a Stripe subscription status onto the states quotas are enforced with.
+    pub fn from_stripe(status: &str) -> Self {
+        match status {
+            "active" | "trialing" => SubscriptionStatus::Active,
+            "canceled" | "incomplete_expired" => SubscriptionStatus::Canceled,
+            _ => SubscriptionStatus::PastDue,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SubscriptionStatus::Active => "active",
+            SubscriptionStatus::PastDue => "past_due",
+            SubscriptionStatus::Canceled => "canceled",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Queryable, Insertable, ValidGrouping, ToSchema)]
 #[diesel(table_name = invitations)]
 pub struct Invitation {
     pub id: uuid::Uuid,
@@ -292,6 +401,11 @@ pub struct Invitation {
     pub role: i32,
 }
 
+redacted_debug!(
+    Invitation { id, organization_id, used, created_at, updated_at, role }
+    pii { email }
+);
+
 // any type that implements Into<String> can be used to create Invitation
 impl Invitation {
     pub fn from_details(
@@ -300,6 +414,8 @@ impl Invitation {
         role: i32,
     ) -> Self {
         Invitation {
+            // Stays random: the id is the invitation code sent by email, and a v7 id
+            // would give away when it was made and be easier to guess
             id: uuid::Uuid::new_v4(),
             email,
             organization_id,
@@ -337,7 +453,7 @@ impl ApiKey {
         blake3_hash: String,
     ) -> Self {
         ApiKey {
-            id: uuid::Uuid::new_v4(),
+            id: uuid::Uuid::now_v7(),
             user_id,
             name,
             blake3_hash,
@@ -347,7 +463,7 @@ impl ApiKey {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, Queryable, Insertable, Selectable, Clone, ToSchema)]
+#[derive(Serialize, Deserialize, Queryable, Insertable, Selectable, Clone, ToSchema)]
 #[schema(example = json!({
     "id": "c7c7c7c7-c7c7-c7c7-c7c7-c7c7c7c7c7c7",
     "org_id": "e3e3e3e3-e3e3-e3e3-e3e3-e3e3e3e3e3e3",
@@ -355,6 +471,8 @@ impl ApiKey {
     "last_name": "Doe",
     "created_at": "2021-01-01T00:00:00",
     "updated_at": "2021-01-01T00:00:00",
+    "last_contacted_at": "2021-01-01T00:00:00",
+    "owner_user_id": "user-d4d4d4d4-d4d4-d4d4-d4d4-d4d4d4d4d4d4",
 }))]
 #[diesel(table_name = contacts)]
 pub struct Contact {
@@ -364,13 +482,24 @@ pub struct Contact {
     pub last_name: String,
     pub created_at: chrono::NaiveDateTime,
     pub updated_at: chrono::NaiveDateTime,
+    /// When the latest activity linked to the contact occurred. Kept in sync with its
+    /// activities, changing it doesn't change `updated_at`.
+    pub last_contacted_at: Option<chrono::NaiveDateTime>,
+    /// The user responsible for this contact. Defaults to whoever created it.
+    pub owner_user_id: Option<PrefixedUuid<UserPrefix>>,
 }
 
+redacted_debug!(
+    Contact { id, org_id, created_at, updated_at, last_contacted_at, owner_user_id }
+    pii { first_name, last_name }
+);
+
 impl Contact {
     pub fn from_details(
         org_id: PrefixedUuid<OrgPrefix>,
         first_name: String,
         last_name: String,
+        owner_user_id: Option<PrefixedUuid<UserPrefix>>,
     ) -> Self {
         Contact {
             id: PrefixedUuid::create(ContactPrefix),
@@ -379,6 +508,8 @@ impl Contact {
             last_name,
             created_at: chrono::Utc::now().naive_local(),
             updated_at: chrono::Utc::now().naive_local(),
+            last_contacted_at: None,
+            owner_user_id,
         }
     }
 }
@@ -446,7 +577,7 @@ impl Link {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, Queryable, Insertable, Selectable, Clone, ToSchema)]
+#[derive(Serialize, Deserialize, Queryable, Insertable, Selectable, Clone, ToSchema)]
 #[schema(example = json!({
     "id": "c7c7c7c7-c7c7-c7c7-c7c7-c7c7c7c7c7c7",
     "email": "example@example.com",
@@ -461,6 +592,8 @@ pub struct Email {
     pub updated_at: chrono::NaiveDateTime,
 }
 
+redacted_debug!(Email { id, org_id, created_at, updated_at } pii { email });
+
 impl Email {
     pub fn from_details(email: String, org_id: PrefixedUuid<OrgPrefix>) -> Self {
         Email {
@@ -473,7 +606,7 @@ impl Email {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, Queryable, Insertable, Selectable, Clone, ToSchema)]
+#[derive(Serialize, Deserialize, Queryable, Insertable, Selectable, Clone, ToSchema)]
 #[schema(example = json!({
     "id": "c7c7c7c7-c7c7-c7c7-c7c7-c7c7c7c7c7c7",
     "phone": "+1234567890",
@@ -490,6 +623,8 @@ pub struct Phone {
     pub updated_at: chrono::NaiveDateTime,
 }
 
+redacted_debug!(Phone { id, org_id, created_at, updated_at } pii { number });
+
 impl Phone {
     pub fn from_details(org_id: PrefixedUuid<OrgPrefix>, number: String) -> Self {
         Self {
@@ -523,6 +658,7 @@ impl Phone {
     "org_id": "org-b8b8b8b8-b8b8-b8b8-b8b8-b8b8b8b8b8b8",
     "created_at": "2021-01-01T00:00:00",
     "updated_at": "2021-01-01T00:00:00",
+    "owner_user_id": "user-d4d4d4d4-d4d4-d4d4-d4d4-d4d4d4d4d4d4",
 }))]
 #[diesel(table_name = tasks)]
 pub struct Task {
@@ -533,6 +669,8 @@ pub struct Task {
     pub org_id: PrefixedUuid<OrgPrefix>,
     pub created_at: chrono::NaiveDateTime,
     pub updated_at: chrono::NaiveDateTime,
+    /// The user responsible for this task. Defaults to whoever created it.
+    pub owner_user_id: Option<PrefixedUuid<UserPrefix>>,
 }
 
 impl Task {
@@ -541,6 +679,7 @@ impl Task {
         deadline: Option<chrono::NaiveDateTime>,
         description: Option<String>,
         contact_id: Option<PrefixedUuid<ContactPrefix>>,
+        owner_user_id: Option<PrefixedUuid<UserPrefix>>,
     ) -> Self {
         Self {
             id: PrefixedUuid::create(TaskPrefix),
@@ -550,6 +689,7 @@ impl Task {
             org_id,
             created_at: chrono::Utc::now().naive_local(),
             updated_at: chrono::Utc::now().naive_local(),
+            owner_user_id,
         }
     }
 }
@@ -561,6 +701,7 @@ impl Task {
     "org_id": "org-b8b8b8b8-b8b8-b8b8-b8b8-b8b8b8b8b8b8",
     "created_at": "2021-01-01T00:00:00",
     "updated_at": "2021-01-01T00:00:00",
+    "owner_user_id": "user-d4d4d4d4-d4d4-d4d4-d4d4-d4d4d4d4d4d4",
 }))]
 #[diesel(table_name=companies)]
 pub struct Company {
@@ -569,16 +710,23 @@ pub struct Company {
     pub org_id: PrefixedUuid<OrgPrefix>,
     pub created_at: chrono::NaiveDateTime,
     pub updated_at: chrono::NaiveDateTime,
+    /// The user responsible for this company. Defaults to whoever created it.
+    pub owner_user_id: Option<PrefixedUuid<UserPrefix>>,
 }
 
 impl Company {
-    pub fn from_name(name: String, org_id: PrefixedUuid<OrgPrefix>) -> Self {
+    pub fn from_name(
+        name: String,
+        org_id: PrefixedUuid<OrgPrefix>,
+        owner_user_id: Option<PrefixedUuid<UserPrefix>>,
+    ) -> Self {
         Company {
             id: PrefixedUuid::create(CompanyPrefix),
             name,
             org_id,
             created_at: chrono::Utc::now().naive_local(),
             updated_at: chrono::Utc::now().naive_local(),
+            owner_user_id,
         }
     }
 }
@@ -718,3 +866,436 @@ impl TaskUser {
         }
     }
 }
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+pub enum CallOutcome {
+    Connected,
+    NoAnswer,
+    LeftVoicemail,
+    WrongNumber,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+pub enum EmailDirection {
+    Inbound,
+    Outbound,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+#[schema(example = json!({
+    "duration_seconds": 300,
+    "outcome": "Connected",
+}))]
+pub struct CallActivity {
+    #[schema(minimum = 0, maximum = 86400)]
+    pub duration_seconds: i32,
+    pub outcome: CallOutcome,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+#[schema(example = json!({
+    "attendees": ["john@example.com", "jane@example.com"],
+}))]
+pub struct MeetingActivity {
+    #[schema(max_items = 100)]
+    pub attendees: Vec<String>,
+}
+
+redacted_debug!(MeetingActivity {} pii { attendees });
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+#[schema(example = json!({
+    "direction": "Outbound",
+    "subject": "Following up on our call",
+}))]
+pub struct EmailActivity {
+    pub direction: EmailDirection,
+    #[schema(max_length = 10000)]
+    pub subject: String,
+}
+
+/// The typed body of an activity. Stored as JSONB in the same externally
+/// tagged shape it is serialized with, e.g. `{"Call": {...}}`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema, AsExpression, FromSqlRow)]
+#[diesel(sql_type = Jsonb)]
+pub enum ActivityPayload {
+    Call(CallActivity),
+    Meeting(MeetingActivity),
+    Email(EmailActivity),
+}
+
+impl ToSql<Jsonb, Pg> for ActivityPayload {
+    fn to_sql<'b>(
+        &'b self,
+        out: &mut diesel::serialize::Output<'b, '_, Pg>,
+    ) -> diesel::serialize::Result {
+        let value = serde_json::to_value(self)?;
+        <serde_json::Value as ToSql<Jsonb, Pg>>::to_sql(&value, &mut out.reborrow())
+    }
+}
+
+impl FromSql<Jsonb, Pg> for ActivityPayload {
+    fn from_sql(raw: PgValue) -> diesel::deserialize::Result<Self> {
+        let value = <serde_json::Value as FromSql<Jsonb, Pg>>::from_sql(raw)?;
+        Ok(serde_json::from_value(value)?)
+    }
+}
+
+#[derive(
+    Debug, Serialize, Deserialize, Queryable, Insertable, Selectable, Clone, ToSchema, Identifiable,
+)]
+#[schema(example = json!({
+    "id": "activity-b8b8b8b8-b8b8-b8b8-b8b8-b8b8b8b8b8b8",
+    "org_id": "org-b8b8b8b8-b8b8-b8b8-b8b8-b8b8b8b8b8b8",
+    "payload": {"Call": {"duration_seconds": 300, "outcome": "Connected"}},
+    "contact_id": "contact-c7c7c7c7-c7c7-c7c7-c7c7-c7c7c7c7c7c7",
+    "deal_id": "deal-b8b8b8b8-b8b8-b8b8-b8b8-b8b8b8b8b8b8",
+    "company_id": "company-b8b8b8b8-b8b8-b8b8-b8b8-b8b8b8b8b8b8",
+    "occurred_at": "2021-01-01T00:00:00",
+    "created_at": "2021-01-01T00:00:00",
+    "updated_at": "2021-01-01T00:00:00",
+}))]
+#[diesel(table_name = activities)]
+pub struct Activity {
+    pub id: PrefixedUuid<ActivityPrefix>,
+    pub org_id: PrefixedUuid<OrgPrefix>,
+    pub payload: ActivityPayload,
+    pub contact_id: Option<PrefixedUuid<ContactPrefix>>,
+    pub deal_id: Option<PrefixedUuid<DealPrefix>>,
+    pub company_id: Option<PrefixedUuid<CompanyPrefix>>,
+    pub occurred_at: chrono::NaiveDateTime,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: chrono::NaiveDateTime,
+}
+
+impl Activity {
+    pub fn from_details(
+        org_id: PrefixedUuid<OrgPrefix>,
+        payload: ActivityPayload,
+        contact_id: Option<PrefixedUuid<ContactPrefix>>,
+        deal_id: Option<PrefixedUuid<DealPrefix>>,
+        company_id: Option<PrefixedUuid<CompanyPrefix>>,
+        occurred_at: Option<chrono::NaiveDateTime>,
+    ) -> Self {
+        Activity {
+            id: PrefixedUuid::create(ActivityPrefix),
+            org_id,
+            payload,
+            contact_id,
+            deal_id,
+            company_id,
+            occurred_at: occurred_at.unwrap_or_else(|| chrono::Utc::now().naive_local()),
+            created_at: chrono::Utc::now().naive_local(),
+            updated_at: chrono::Utc::now().naive_local(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+#[schema(example = json!({
+    "contacts": 12,
+    "deals": 4,
+    "companies": 2,
+    "tasks": 7,
+}))]
+pub struct ReassignedRecords {
+    pub contacts: i64,
+    pub deals: i64,
+    pub companies: i64,
+    pub tasks: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+#[schema(example = json!({
+    "plan": {
+        "id": "00000000-0000-0000-0000-000000000000",
+        "stripe_id": "free",
+        "num_users": 3,
+        "num_deals": 50,
+        "price_per_month": 0,
+        "created_at": "2021-01-01T00:00:00",
+        "updated_at": "2021-01-01T00:00:00",
+    },
+    "num_users": 2,
+    "num_deals": 17,
+}))]
+pub struct OrgUsage {
+    /// The plan the organization is on. Organizations without a subscription are on the free plan.
+    pub plan: Plan,
+    /// Number of members in the organization.
+    pub num_users: i64,
+    /// Number of deals in the organization.
+    pub num_deals: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, QueryableByName, Clone, ToSchema)]
+#[schema(example = json!({
+    "month": "2021-03-01",
+    "active": true,
+    "currency": "USD",
+    "deal_count": 4,
+    "total_amount": 500000,
+}))]
+pub struct PipelineReportRow {
+    /// First day of the expected close month. Null for deals without an expected close date.
+    #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Date>)]
+    pub month: Option<chrono::NaiveDate>,
+    #[diesel(sql_type = diesel::sql_types::Bool)]
+    pub active: bool,
+    #[diesel(sql_type = diesel::sql_types::Text)]
+    pub currency: String,
+    #[diesel(sql_type = diesel::sql_types::BigInt)]
+    pub deal_count: i64,
+    /// Sum of deal amounts in the minor unit of `currency`.
+    #[diesel(sql_type = diesel::sql_types::BigInt)]
+    pub total_amount: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, QueryableByName, Clone, ToSchema)]
+#[schema(example = json!({
+    "month": "2021-03-01",
+    "currency": "USD",
+    "deal_count": 4,
+    "total_amount": 500000,
+    "weighted_amount": 300000,
+}))]
+pub struct ForecastReportRow {
+    /// First day of the expected close month. Null for deals without an expected close date.
+    #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Date>)]
+    pub month: Option<chrono::NaiveDate>,
+    #[diesel(sql_type = diesel::sql_types::Text)]
+    pub currency: String,
+    #[diesel(sql_type = diesel::sql_types::BigInt)]
+    pub deal_count: i64,
+    #[diesel(sql_type = diesel::sql_types::BigInt)]
+    pub total_amount: i64,
+    /// Sum of each deal amount multiplied by its probability.
+    #[diesel(sql_type = diesel::sql_types::BigInt)]
+    pub weighted_amount: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, QueryableByName, Clone, ToSchema)]
+#[schema(example = json!({
+    "currency": "USD",
+    "deal_count": 4,
+    "average_amount": 125000,
+}))]
+pub struct AverageDealSizeRow {
+    #[diesel(sql_type = diesel::sql_types::Text)]
+    pub currency: String,
+    #[diesel(sql_type = diesel::sql_types::BigInt)]
+    pub deal_count: i64,
+    #[diesel(sql_type = diesel::sql_types::BigInt)]
+    pub average_amount: i64,
+}
+
+#[derive(Debug, QueryableByName, Clone)]
+pub struct DealOutcomeStats {
+    #[diesel(sql_type = diesel::sql_types::BigInt)]
+    pub won_count: i64,
+    #[diesel(sql_type = diesel::sql_types::BigInt)]
+    pub lost_count: i64,
+    #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Double>)]
+    pub average_sales_cycle_days: Option<f64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeEntity {
+    Activity,
+    Company,
+    Contact,
+    Deal,
+    Email,
+    Link,
+    Note,
+    Phone,
+    Task,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeAction {
+    Created,
+    Updated,
+    Deleted,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+#[schema(example = json!({
+    "event_id": "1718000000000-0",
+    "org_id": "org-e3e3e3e3-e3e3-e3e3-e3e3-e3e3e3e3e3e3",
+    "entity": "deal",
+    "action": "updated",
+    "id": "deal-e3e3e3e3-e3e3-e3e3-e3e3-e3e3e3e3e3e3",
+}))]
+pub struct ChangeEvent {
+    /// Position of the event in the organization's event stream. Sent as the SSE event id, so clients can resume from it with `Last-Event-ID`.
+    pub event_id: String,
+    pub org_id: PrefixedUuid<OrgPrefix>,
+    pub entity: ChangeEntity,
+    pub action: ChangeAction,
+    /// Prefixed id of the record that changed.
+    pub id: String,
+}
+
+/// Token bucket limit: `requests` tokens that refill evenly over `window`.
+#[derive(Debug, Clone, Copy)]
+pub struct RateLimit {
+    pub requests: u32,
+    pub window: std::time::Duration,
+}
+
+impl RateLimit {
+    pub fn new(requests: u32, window_secs: u64) -> Self {
+        RateLimit {
+            requests,
+            window: std::time::Duration::from_secs(window_secs),
+        }
+    }
+}
+
+/// Parses a limit written as `<requests>/<window seconds>`, e.g. `"600/60"`.
+impl std::str::FromStr for RateLimit {
+    type Err = String;
+
+    fn from_str(limit: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("expected <requests>/<window seconds>, got {:?}", limit);
+        let (requests, window_secs) = limit.split_once('/').ok_or_else(invalid)?;
+        let rate_limit = RateLimit::new(
+            requests.trim().parse().map_err(|_| invalid())?,
+            window_secs.trim().parse().map_err(|_| invalid())?,
+        );
+        if rate_limit.requests == 0 || rate_limit.window.is_zero() {
+            return Err(invalid());
+        }
+        Ok(rate_limit)
+    }
+}
+
+/// State of a token bucket after taking a token from it.
+#[derive(Debug, Clone, Copy)]
+pub struct RateLimitStatus {
+    pub allowed: bool,
+    pub limit: u32,
+    pub remaining: u32,
+    /// Seconds until the bucket is full again.
+    pub reset_secs: u64,
+    /// Seconds until a token is available again. Zero when the request was allowed.
+    pub retry_after_secs: u64,
+}
+
+/// Email for an invitation. Carries the invitation id rather than the address, so the
+/// address stays in one place and an invitation deleted before the job runs isn't sent.
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub struct SendInvitationJob {
+    pub invitation_id: uuid::Uuid,
+    /// Holds the invitation code, which is as good as a password until it's used.
+    pub registration_url: String,
+}
+
+redacted_debug!(SendInvitationJob { invitation_id } pii { registration_url });
+
+/// The typed body of a job, stored as JSONB in the same externally tagged shape as
+/// [`ActivityPayload`]. Adding a variant adds a kind of job.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub enum JobPayload {
+    SendInvitation(SendInvitationJob),
+}
+
+impl JobPayload {
+    /// Stored next to the payload so jobs can be filtered without parsing it.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            JobPayload::SendInvitation(_) => "send_invitation",
+        }
+    }
+
+    /// Due jobs with a higher priority are claimed first.
+    pub fn priority(&self) -> i32 {
+        match self {
+            JobPayload::SendInvitation(_) => 10,
+        }
+    }
+
+    /// Attempts before the job is moved to the dead-letter state.
+    pub fn max_attempts(&self) -> i32 {
+        match self {
+            JobPayload::SendInvitation(_) => 8,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    /// Waiting for `run_at`, either new or after a failed attempt.
+    Queued,
+    /// Claimed by a worker until `locked_until`.
+    Running,
+    Succeeded,
+    /// Failed `max_attempts` times. Stays put until an admin retries it.
+    Dead,
+}
+
+impl JobStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JobStatus::Queued => "queued",
+            JobStatus::Running => "running",
+            JobStatus::Succeeded => "succeeded",
+            JobStatus::Dead => "dead",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Queryable, QueryableByName, Insertable, Selectable, Clone)]
+#[diesel(table_name = jobs)]
+pub struct Job {
+    pub id: uuid::Uuid,
+    pub kind: String,
+    /// A [`JobPayload`]. Kept untyped so a kind this build doesn't know, e.g. during
+    /// a rolling deploy, fails its own job instead of every query that loads it.
+    pub payload: serde_json::Value,
+    /// See [`JobStatus`].
+    pub status: String,
+    pub priority: i32,
+    pub run_at: chrono::NaiveDateTime,
+    pub attempts: i32,
+    pub max_attempts: i32,
+    pub last_error: Option<String>,
+    pub locked_until: Option<chrono::NaiveDateTime>,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: chrono::NaiveDateTime,
+}
+
+redacted_debug!(
+    Job {
+        id, kind, status, priority, run_at, attempts, max_attempts, last_error, locked_until,
+        created_at, updated_at,
+    }
+    pii { payload }
+);
+
+impl Job {
+    pub fn from_details(
+        payload: &JobPayload,
+        run_at: Option<chrono::NaiveDateTime>,
+    ) -> Result<Self, serde_json::Error> {
+        let now = chrono::Utc::now().naive_local();
+        Ok(Job {
+            id: uuid::Uuid::now_v7(),
+            kind: payload.kind().to_string(),
+            payload: serde_json::to_value(payload)?,
+            status: JobStatus::Queued.as_str().to_string(),
+            priority: payload.priority(),
+            run_at: run_at.unwrap_or(now),
+            attempts: 0,
+            max_attempts: payload.max_attempts(),
+            last_error: None,
+            locked_until: None,
+            created_at: now,
+            updated_at: now,
+        })
+    }
+}
@@ -0,0 +1,25 @@
+use crate::errors::ServiceError;
+use serde::{Deserialize, Deserializer};
+
+/// A field of a JSON Merge Patch (RFC 7396) request body. `None` means the field
+/// was absent and should be left alone, `Some(None)` means it was `null` and
+/// should be cleared, and `Some(Some(value))` replaces it.
+pub type Patch<T> = Option<Option<T>>;
+
+/// Use as `#[serde(default, deserialize_with = "deserialize_patch")]` so an
+/// explicit `null` isn't collapsed into a missing field.
+pub fn deserialize_patch<'de, T, D>(deserializer: D) -> Result<Patch<T>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+/// Rejects `null` for fields backed by a non-nullable column.
+pub fn required<T>(field: &str, patch: Patch<T>) -> Result<Option<T>, ServiceError> {
+    match patch {
+        Some(None) => Err(ServiceError::invalid_field(field, "Cannot be null")),
+        patch => Ok(patch.flatten()),
+    }
+}
@@ -0,0 +1,80 @@
+//! Keeps personal data like names, email addresses and phone numbers out of logs and
+//! Sentry. Types holding it implement `Debug` through [`redacted_debug`], which is what
+//! `#[tracing::instrument]` records, and free text sent to Sentry is scrubbed by
+//! [`scrub_sentry_event`] as a last line of defence.
+use regex::Regex;
+use std::{borrow::Cow, fmt, sync::OnceLock};
+
+/// Printed in place of a personal field.
+pub struct Redacted;
+
+impl fmt::Debug for Redacted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("[redacted]")
+    }
+}
+
+/// Implements `Debug` with the fields after `pii` printed as `[redacted]`. Every field
+/// of the struct has to be listed on one side or the other, so adding a field without
+/// deciding whether it's personal doesn't compile.
+///
+/// ```text
+/// redacted_debug!(Contact { id, org_id } pii { first_name, last_name });
+/// ```
+macro_rules! redacted_debug {
+    ($type:ident { $($field:ident),* $(,)? } pii { $($pii:ident),* $(,)? }) => {
+        impl std::fmt::Debug for $type {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                let $type { $($field,)* $($pii: _,)* } = self;
+                f.debug_struct(stringify!($type))
+                    $(.field(stringify!($field), $field))*
+                    $(.field(stringify!($pii), &$crate::data::redact::Redacted))*
+                    .finish()
+            }
+        }
+    };
+}
+pub(crate) use redacted_debug;
+
+fn email_regex() -> &'static Regex {
+    static EMAIL: OnceLock<Regex> = OnceLock::new();
+    EMAIL.get_or_init(|| {
+        Regex::new(r"[a-zA-Z0-9.!#$%&'*+/=?^_`{|}~-]+@[a-zA-Z0-9-]+(?:\.[a-zA-Z0-9-]+)+")
+            .expect("Valid regex")
+    })
+}
+
+/// Replaces email addresses in free text, e.g. database errors quoting the row that
+/// violated a constraint.
+pub fn scrub(text: &str) -> Cow<'_, str> {
+    email_regex().replace_all(text, "[redacted]")
+}
+
+fn scrub_in_place(text: &mut String) {
+    if let Cow::Owned(scrubbed) = scrub(text) {
+        *text = scrubbed;
+    }
+}
+
+/// `before_send` hook for the Sentry client.
+pub fn scrub_sentry_event(
+    mut event: sentry::protocol::Event<'static>,
+) -> Option<sentry::protocol::Event<'static>> {
+    if let Some(message) = event.message.as_mut() {
+        scrub_in_place(message);
+    }
+    if let Some(logentry) = event.logentry.as_mut() {
+        scrub_in_place(&mut logentry.message);
+    }
+    for exception in event.exception.values.iter_mut() {
+        if let Some(value) = exception.value.as_mut() {
+            scrub_in_place(value);
+        }
+    }
+    for breadcrumb in event.breadcrumbs.values.iter_mut() {
+        if let Some(message) = breadcrumb.message.as_mut() {
+            scrub_in_place(message);
+        }
+    }
+    Some(event)
+}
@@ -1,5 +1,19 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    activities (id) {
+        id -> Uuid,
+        org_id -> Uuid,
+        payload -> Jsonb,
+        contact_id -> Nullable<Uuid>,
+        deal_id -> Nullable<Uuid>,
+        company_id -> Nullable<Uuid>,
+        occurred_at -> Timestamptz,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
     api_keys (id) {
         id -> Uuid,
@@ -11,6 +25,14 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    billing_events (id) {
+        id -> Text,
+        event_type -> Text,
+        created_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     companies (id) {
         id -> Uuid,
@@ -18,6 +40,7 @@ diesel::table! {
         org_id -> Uuid,
         created_at -> Timestamp,
         updated_at -> Timestamp,
+        owner_user_id -> Nullable<Uuid>,
     }
 }
 
@@ -29,6 +52,8 @@ diesel::table! {
         last_name -> Text,
         updated_at -> Timestamptz,
         created_at -> Timestamptz,
+        last_contacted_at -> Nullable<Timestamptz>,
+        owner_user_id -> Nullable<Uuid>,
     }
 }
 
@@ -47,10 +72,17 @@ diesel::table! {
         id -> Uuid,
         name -> Nullable<Text>,
         org_id -> Uuid,
-        size -> Nullable<Float4>,
         active -> Bool,
         updated_at -> Timestamptz,
         created_at -> Timestamptz,
+        last_contacted_at -> Nullable<Timestamptz>,
+        amount -> Nullable<Int8>,
+        currency -> Text,
+        probability -> Nullable<Int4>,
+        expected_close_date -> Nullable<Date>,
+        won -> Nullable<Bool>,
+        closed_at -> Nullable<Timestamptz>,
+        owner_user_id -> Nullable<Uuid>,
     }
 }
 
@@ -77,6 +109,23 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    jobs (id) {
+        id -> Uuid,
+        kind -> Text,
+        payload -> Jsonb,
+        status -> Text,
+        priority -> Int4,
+        run_at -> Timestamp,
+        attempts -> Int4,
+        max_attempts -> Int4,
+        last_error -> Nullable<Text>,
+        locked_until -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     links (id) {
         id -> Uuid,
@@ -113,6 +162,7 @@ diesel::table! {
         name -> Text,
         created_at -> Timestamp,
         updated_at -> Timestamp,
+        default_currency -> Text,
     }
 }
 
@@ -148,6 +198,8 @@ diesel::table! {
         next_billing_date -> Timestamp,
         start_date -> Timestamp,
         end_date -> Nullable<Timestamp>,
+        status -> Text,
+        last_event_at -> Nullable<Timestamp>,
     }
 }
 
@@ -190,6 +242,7 @@ diesel::table! {
         org_id -> Uuid,
         updated_at -> Timestamptz,
         created_at -> Timestamptz,
+        owner_user_id -> Nullable<Uuid>,
     }
 }
 
@@ -200,15 +253,24 @@ diesel::table! {
         created_at -> Timestamp,
         updated_at -> Timestamp,
         name -> Nullable<Text>,
+        is_superuser -> Bool,
+        disabled -> Bool,
     }
 }
 
+diesel::joinable!(activities -> companies (company_id));
+diesel::joinable!(activities -> contacts (contact_id));
+diesel::joinable!(activities -> deals (deal_id));
+diesel::joinable!(activities -> orgs (org_id));
 diesel::joinable!(api_keys -> users (user_id));
 diesel::joinable!(companies -> orgs (org_id));
+diesel::joinable!(companies -> users (owner_user_id));
 diesel::joinable!(contacts -> orgs (org_id));
+diesel::joinable!(contacts -> users (owner_user_id));
 diesel::joinable!(deal_contacts -> contacts (contact_id));
 diesel::joinable!(deal_contacts -> deals (deal_id));
 diesel::joinable!(deals -> orgs (org_id));
+diesel::joinable!(deals -> users (owner_user_id));
 diesel::joinable!(emails -> orgs (org_id));
 diesel::joinable!(invitations -> orgs (organization_id));
 diesel::joinable!(links -> orgs (org_id));
@@ -225,15 +287,19 @@ diesel::joinable!(task_users -> tasks (task_id));
 diesel::joinable!(task_users -> users (user_id));
 diesel::joinable!(tasks -> contacts (contact_id));
 diesel::joinable!(tasks -> orgs (org_id));
+diesel::joinable!(tasks -> users (owner_user_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
+    activities,
     api_keys,
+    billing_events,
     companies,
     contacts,
     deal_contacts,
     deals,
     emails,
     invitations,
+    jobs,
     links,
     notes,
     org_users,
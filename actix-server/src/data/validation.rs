@@ -0,0 +1,93 @@
+//! Checks request payloads run before touching the database. Every problem is collected
+//! so a client can fix all of them after a single `validation_failed` response. The
+//! limits are repeated as `#[schema(...)]` constraints on the payloads so they show up
+//! in the OpenAPI document.
+use crate::errors::{FieldError, ServiceError};
+use chrono::{Datelike, NaiveDate};
+use std::fmt::Display;
+
+/// Names and titles, e.g. of contacts, companies, deals and orgs.
+pub const MAX_NAME_LENGTH: usize = 255;
+/// Free-form text like note bodies and task descriptions.
+pub const MAX_TEXT_LENGTH: usize = 10_000;
+pub const MAX_URL_LENGTH: usize = 2048;
+pub const MAX_EMAIL_LENGTH: usize = 254;
+pub const MAX_PHONE_LENGTH: usize = 32;
+/// Largest deal amount in minor units, ten trillion in a currency with cents.
+pub const MAX_DEAL_AMOUNT: i64 = 1_000_000_000_000_000;
+/// Dates outside these years are almost always typos, like 0224 for 2024.
+pub const MIN_YEAR: i32 = 1900;
+pub const MAX_YEAR: i32 = 2200;
+
+pub trait Validate {
+    /// Fails with `ServiceError::ValidationFailed` listing every invalid field.
+    fn validate(&self) -> Result<(), ServiceError>;
+}
+
+#[derive(Debug, Default)]
+pub struct Validator {
+    errors: Vec<FieldError>,
+}
+
+impl Validator {
+    pub fn reject(&mut self, field: &str, message: impl Into<String>) {
+        self.errors.push(FieldError {
+            field: field.to_string(),
+            message: message.into(),
+        });
+    }
+
+    /// Text that must contain something other than whitespace.
+    pub fn non_empty(&mut self, field: &str, value: &str, max_length: usize) {
+        if value.trim().is_empty() {
+            self.reject(field, "Must not be empty");
+        } else {
+            self.max_length(field, value, max_length);
+        }
+    }
+
+    pub fn max_length(&mut self, field: &str, value: &str, max_length: usize) {
+        if value.chars().count() > max_length {
+            self.reject(
+                field,
+                format!("Must be at most {} characters long", max_length),
+            );
+        }
+    }
+
+    /// An absolute http(s) URL.
+    pub fn url(&mut self, field: &str, value: &str) {
+        if value.chars().count() > MAX_URL_LENGTH {
+            self.max_length(field, value, MAX_URL_LENGTH);
+            return;
+        }
+
+        match url::Url::parse(value) {
+            Ok(url) if matches!(url.scheme(), "http" | "https") && url.has_host() => {}
+            _ => self.reject(field, "Must be a valid http or https URL"),
+        }
+    }
+
+    pub fn range<T: PartialOrd + Display + Copy>(&mut self, field: &str, value: T, min: T, max: T) {
+        if !(min..=max).contains(&value) {
+            self.reject(field, format!("Must be between {} and {}", min, max));
+        }
+    }
+
+    pub fn date(&mut self, field: &str, value: NaiveDate) {
+        if !(MIN_YEAR..=MAX_YEAR).contains(&value.year()) {
+            self.reject(
+                field,
+                format!("Year must be between {} and {}", MIN_YEAR, MAX_YEAR),
+            );
+        }
+    }
+
+    pub fn finish(self) -> Result<(), ServiceError> {
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ServiceError::ValidationFailed(self.errors))
+        }
+    }
+}
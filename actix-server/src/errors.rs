@@ -1,15 +1,75 @@
-use actix_web::{error::ResponseError, HttpResponse};
+use crate::middleware::request_id_middleware::current_request_id;
+use actix_web::{error::ResponseError, http::StatusCode, HttpResponse};
 use derive_more::Display;
-use diesel::result::{DatabaseErrorKind, Error as DBError};
+use diesel::result::{DatabaseErrorInformation, DatabaseErrorKind, Error as DBError};
+use diesel_async::pooled_connection::deadpool::PoolError;
 use serde::{Deserialize, Serialize};
 use std::convert::From;
 use utoipa::ToSchema;
 use uuid::Error as ParseError;
 
+/// Stable, machine-readable identifier of an error. Messages may change, codes don't.
+#[derive(Serialize, Deserialize, Debug, Display, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    #[display(fmt = "internal_server_error")]
+    InternalServerError,
+    #[display(fmt = "bad_request")]
+    BadRequest,
+    #[display(fmt = "validation_failed")]
+    ValidationFailed,
+    #[display(fmt = "unauthorized")]
+    Unauthorized,
+    #[display(fmt = "forbidden")]
+    Forbidden,
+    #[display(fmt = "payment_required")]
+    PaymentRequired,
+    #[display(fmt = "not_found")]
+    NotFound,
+    #[display(fmt = "conflict")]
+    Conflict,
+    #[display(fmt = "precondition_failed")]
+    PreconditionFailed,
+    #[display(fmt = "unprocessable_entity")]
+    UnprocessableEntity,
+    #[display(fmt = "too_many_requests")]
+    TooManyRequests,
+    #[display(fmt = "service_unavailable")]
+    ServiceUnavailable,
+}
+
+/// Why a single field of the request was rejected.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, ToSchema)]
+#[schema(example = json!({"field": "probability", "message": "Probability must be between 0 and 100"}))]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
 #[derive(Serialize, Deserialize, Debug, Display, ToSchema)]
-#[schema(example = json!({"message": "Bad Request"}))]
+#[display(fmt = "{code}: {message}")]
+#[schema(example = json!({"code": "bad_request", "message": "Bad Request"}))]
 pub struct ErrorRespPayload {
+    pub code: ErrorCode,
     pub message: String,
+    /// Per-field problems when `code` is `validation_failed`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub details: Vec<FieldError>,
+    /// Id of the failed request, also sent as `X-Request-Id`. Include it when reporting
+    /// a problem.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+}
+
+impl ErrorRespPayload {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        ErrorRespPayload {
+            code,
+            message: message.into(),
+            details: vec![],
+            request_id: current_request_id(),
+        }
+    }
 }
 
 #[derive(Debug, Display, Clone)]
@@ -20,41 +80,124 @@ pub enum ServiceError {
     #[display(fmt = "BadRequest: {_0}")]
     BadRequest(String),
 
+    #[display(fmt = "Validation Failed: {_0:?}")]
+    ValidationFailed(Vec<FieldError>),
+
     #[display(fmt = "Unauthorized")]
     Unauthorized,
 
     #[display(fmt = "Forbidden")]
     Forbidden,
 
+    #[display(fmt = "Payment Required: {_0}")]
+    PaymentRequired(String),
+
     #[display(fmt = "Not Found")]
     NotFound,
+
+    #[display(fmt = "Precondition Failed")]
+    PreconditionFailed,
+
+    #[display(fmt = "Conflict: {_0}")]
+    Conflict(String),
+
+    #[display(fmt = "Unprocessable Entity: {_0}")]
+    UnprocessableEntity(String),
+
+    #[display(fmt = "Service Unavailable: {_0}")]
+    ServiceUnavailable(String),
+}
+
+impl ServiceError {
+    /// Rejects a single field of the request.
+    pub fn invalid_field(field: impl Into<String>, message: impl Into<String>) -> Self {
+        ServiceError::ValidationFailed(vec![FieldError {
+            field: field.into(),
+            message: message.into(),
+        }])
+    }
+
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            ServiceError::InternalServerError(_) => ErrorCode::InternalServerError,
+            ServiceError::BadRequest(_) => ErrorCode::BadRequest,
+            ServiceError::ValidationFailed(_) => ErrorCode::ValidationFailed,
+            ServiceError::Unauthorized => ErrorCode::Unauthorized,
+            ServiceError::Forbidden => ErrorCode::Forbidden,
+            ServiceError::PaymentRequired(_) => ErrorCode::PaymentRequired,
+            ServiceError::NotFound => ErrorCode::NotFound,
+            ServiceError::PreconditionFailed => ErrorCode::PreconditionFailed,
+            ServiceError::Conflict(_) => ErrorCode::Conflict,
+            ServiceError::UnprocessableEntity(_) => ErrorCode::UnprocessableEntity,
+            ServiceError::ServiceUnavailable(_) => ErrorCode::ServiceUnavailable,
+        }
+    }
+
+    fn payload(&self) -> ErrorRespPayload {
+        let code = self.code();
+        match self {
+            ServiceError::InternalServerError(ref message)
+            | ServiceError::BadRequest(ref message)
+            | ServiceError::PaymentRequired(ref message)
+            | ServiceError::Conflict(ref message)
+            | ServiceError::UnprocessableEntity(ref message)
+            | ServiceError::ServiceUnavailable(ref message) => {
+                ErrorRespPayload::new(code, message.to_string())
+            }
+            ServiceError::ValidationFailed(ref details) => ErrorRespPayload {
+                code,
+                message: match details.as_slice() {
+                    [detail] => detail.message.clone(),
+                    _ => "Some fields of the request are invalid".to_string(),
+                },
+                details: details.clone(),
+                request_id: current_request_id(),
+            },
+            ServiceError::Unauthorized => ErrorRespPayload::new(code, "Unauthorized"),
+            ServiceError::Forbidden => ErrorRespPayload::new(code, "Forbidden"),
+            ServiceError::NotFound => ErrorRespPayload::new(code, "Record not found"),
+            ServiceError::PreconditionFailed => ErrorRespPayload::new(
+                code,
+                "Record was changed since it was fetched. Fetch it again and retry.",
+            ),
+        }
+    }
 }
 
 // impl ResponseError trait allows to convert our errors into http responses with appropriate data
 impl ResponseError for ServiceError {
-    fn error_response(&self) -> HttpResponse {
-        sentry::capture_message(&format!("Error {:?}", self), sentry::Level::Error);
+    fn status_code(&self) -> StatusCode {
         match self {
-            ServiceError::InternalServerError(ref message) => HttpResponse::InternalServerError()
-                .json(ErrorRespPayload {
-                    message: message.to_string(),
-                }),
-            ServiceError::BadRequest(ref message) => {
-                HttpResponse::BadRequest().json(ErrorRespPayload {
-                    message: message.to_string(),
-                })
+            ServiceError::InternalServerError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            ServiceError::BadRequest(_) | ServiceError::ValidationFailed(_) => {
+                StatusCode::BAD_REQUEST
             }
-            ServiceError::Unauthorized => HttpResponse::Unauthorized().json(ErrorRespPayload {
-                message: "Unauthorized".to_string(),
-            }),
-            ServiceError::Forbidden => HttpResponse::Forbidden().json(ErrorRespPayload {
-                message: "Forbidden".to_string(),
-            }),
-            ServiceError::NotFound => HttpResponse::NotFound().json(ErrorRespPayload {
-                message: "Record not found".to_string(),
-            }),
+            ServiceError::Unauthorized => StatusCode::UNAUTHORIZED,
+            ServiceError::Forbidden => StatusCode::FORBIDDEN,
+            ServiceError::PaymentRequired(_) => StatusCode::PAYMENT_REQUIRED,
+            ServiceError::NotFound => StatusCode::NOT_FOUND,
+            ServiceError::PreconditionFailed => StatusCode::PRECONDITION_FAILED,
+            ServiceError::Conflict(_) => StatusCode::CONFLICT,
+            ServiceError::UnprocessableEntity(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            ServiceError::ServiceUnavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
         }
     }
+
+    fn error_response(&self) -> HttpResponse {
+        sentry::with_scope(
+            |scope| {
+                if let Some(request_id) = current_request_id() {
+                    scope.set_tag("request_id", request_id);
+                }
+            },
+            || sentry::capture_message(&format!("Error {:?}", self), sentry::Level::Error),
+        );
+        crate::metrics::get()
+            .service_errors
+            .with_label_values(&[&self.code().to_string()])
+            .inc();
+        HttpResponse::build(self.status_code()).json(self.payload())
+    }
 }
 
 // we can return early in our handlers if UUID provided by the user is not valid
@@ -65,16 +208,72 @@ impl From<ParseError> for ServiceError {
     }
 }
 
+/// Check constraints that don't follow the default `<table>_<column>_check` naming,
+/// with the column each one checks.
+const NAMED_CHECK_CONSTRAINTS: [(&str, &str); 2] = [
+    ("deals_currency_iso_4217", "currency"),
+    ("orgs_default_currency_iso_4217", "default_currency"),
+];
+
+/// Name of the column a constraint violation is about. Postgres only reports the column
+/// for not-null violations, for foreign keys and checks it's recovered from the
+/// constraint name, which follows the default `<table>_<column><suffix>` naming.
+fn violated_column(info: &dyn DatabaseErrorInformation, suffix: &str) -> Option<String> {
+    if let Some(column) = info.column_name() {
+        return Some(column.to_string());
+    }
+
+    let constraint = info.constraint_name()?;
+    if let Some((_, column)) = NAMED_CHECK_CONSTRAINTS
+        .iter()
+        .find(|(name, _)| *name == constraint)
+    {
+        return Some(column.to_string());
+    }
+
+    let constraint = constraint.strip_suffix(suffix)?;
+    match info.table_name() {
+        Some(table) => constraint
+            .strip_prefix(table)
+            .and_then(|column| column.strip_prefix('_'))
+            .map(|column| column.to_string()),
+        None => Some(constraint.to_string()),
+    }
+}
+
 impl From<DBError> for ServiceError {
     fn from(error: DBError) -> ServiceError {
-        // Right now we just care about UniqueViolation from diesel
-        // But this would be helpful to easily map errors as our app grows
         match error {
-            DBError::DatabaseError(kind, info) => {
-                if let DatabaseErrorKind::UniqueViolation = kind {
-                    let message = info.details().unwrap_or_else(|| info.message()).to_string();
-                    return ServiceError::BadRequest(message);
+            DBError::NotFound => ServiceError::NotFound,
+            // The details repeat the conflicting values, which can be personal data like
+            // an email address
+            DBError::DatabaseError(DatabaseErrorKind::UniqueViolation, _) => {
+                ServiceError::Conflict("A record with these values already exists".to_string())
+            }
+            DBError::DatabaseError(DatabaseErrorKind::ForeignKeyViolation, info) => {
+                match violated_column(info.as_ref(), "_fkey") {
+                    Some(column) => {
+                        ServiceError::invalid_field(column, "Referenced record does not exist")
+                    }
+                    None => ServiceError::BadRequest(
+                        "Request references a record that does not exist".to_string(),
+                    ),
+                }
+            }
+            DBError::DatabaseError(DatabaseErrorKind::NotNullViolation, info) => {
+                match violated_column(info.as_ref(), "") {
+                    Some(column) => ServiceError::invalid_field(column, "Field is required"),
+                    None => ServiceError::BadRequest("A required field is missing".to_string()),
                 }
+            }
+            // The message repeats the rejected row
+            DBError::DatabaseError(DatabaseErrorKind::CheckViolation, info) => {
+                match violated_column(info.as_ref(), "_check") {
+                    Some(column) => ServiceError::invalid_field(column, "Value is not allowed"),
+                    None => ServiceError::BadRequest("A field has an invalid value".to_string()),
+                }
+            }
+            DBError::DatabaseError(_, _) => {
                 ServiceError::InternalServerError("Unknown DB Error. Please try again later".into())
             }
             _ => ServiceError::InternalServerError(
@@ -83,3 +282,14 @@ impl From<DBError> for ServiceError {
         }
     }
 }
+
+/// Running out of database connections is a capacity problem, not a bug, so it's
+/// reported as a 503 the client can retry.
+impl From<PoolError> for ServiceError {
+    fn from(error: PoolError) -> ServiceError {
+        log::error!("Could not get database connection: {:?}", error);
+        ServiceError::ServiceUnavailable(
+            "Database is unavailable. Please try again later".to_string(),
+        )
+    }
+}
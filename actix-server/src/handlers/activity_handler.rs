@@ -0,0 +1,355 @@
+use super::auth_handler::AuthedMember;
+use crate::{
+    data::{
+        etag::{check_if_match, etag, has_if_match},
+        models::{Activity, ActivityPayload, ChangeAction, ChangeEntity, PgPool, RedisPool},
+        validation::{Validate, Validator, MAX_EMAIL_LENGTH, MAX_TEXT_LENGTH},
+    },
+    errors::ServiceError,
+    operators::{
+        activity_operator::{
+            create_activity_query, delete_activity_query, get_activity_by_id_query,
+            list_activities_query, update_activity_query,
+        },
+        event_operator::publish_change_event,
+    },
+    prefixes::{ActivityPrefix, CompanyPrefix, ContactPrefix, DealPrefix, PrefixedUuid},
+};
+use actix_web::{web, HttpRequest, HttpResponse};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct CreateActivityReqPayload {
+    payload: ActivityPayload,
+    contact_id: Option<PrefixedUuid<ContactPrefix>>,
+    deal_id: Option<PrefixedUuid<DealPrefix>>,
+    company_id: Option<PrefixedUuid<CompanyPrefix>>,
+    /// When the activity happened. Defaults to now.
+    #[schema(example = "2021-01-01T00:00:00")]
+    occurred_at: Option<chrono::NaiveDateTime>,
+}
+
+impl Validate for CreateActivityReqPayload {
+    fn validate(&self) -> Result<(), ServiceError> {
+        let mut validator = Validator::default();
+        validate_activity_payload(&mut validator, &self.payload);
+        if let Some(occurred_at) = self.occurred_at {
+            validator.date("occurred_at", occurred_at.date());
+        }
+        validator.finish()
+    }
+}
+
+/// Longest call that can be logged, a full day.
+const MAX_CALL_DURATION_SECONDS: i32 = 60 * 60 * 24;
+const MAX_MEETING_ATTENDEES: usize = 100;
+
+fn validate_activity_payload(validator: &mut Validator, payload: &ActivityPayload) {
+    match payload {
+        ActivityPayload::Call(call) => validator.range(
+            "payload.duration_seconds",
+            call.duration_seconds,
+            0,
+            MAX_CALL_DURATION_SECONDS,
+        ),
+        ActivityPayload::Meeting(meeting) => {
+            if meeting.attendees.len() > MAX_MEETING_ATTENDEES {
+                validator.reject(
+                    "payload.attendees",
+                    format!("Must have at most {} attendees", MAX_MEETING_ATTENDEES),
+                );
+            }
+            for attendee in &meeting.attendees {
+                validator.non_empty("payload.attendees", attendee, MAX_EMAIL_LENGTH);
+            }
+        }
+        ActivityPayload::Email(email) => {
+            validator.max_length("payload.subject", &email.subject, MAX_TEXT_LENGTH)
+        }
+    }
+}
+
+#[utoipa::path(
+  post,
+  path = "/activities",
+  context_path = "/api",
+  tag = "activities",
+  request_body(content = CreateActivityReqPayload, description = "JSON request payload to log a new activity", content_type = "application/json"),
+  responses(
+      (status = 201, description = "JSON body representing the activity that was logged", body = Activity),
+      (status = 401, description = "Service error relating to authentication status of the user", body = ErrorRespPayload),
+      (status = 409, description = "Service error when a request with the same Idempotency-Key is still being processed", body = ErrorRespPayload),
+      (status = 422, description = "Service error when the Idempotency-Key was already used for a different request", body = ErrorRespPayload),
+      (status = 400, description = "Service error relating to the request payload", body = ErrorRespPayload),
+  ),
+  params(
+    ("Organization" = String, Header, description = "The organization id to use for the request"),
+    ("Idempotency-Key" = Option<String>, Header, description = "Unique key for this create. Retrying with the same key replays the first response instead of creating a duplicate"),
+  ),
+  security(
+      ("ApiKey" = ["readonly"]),
+  )
+)]
+#[tracing::instrument(skip(pg_pool, redis_pool))]
+pub async fn create_activity(
+    req_payload: web::Json<CreateActivityReqPayload>,
+    org_member: AuthedMember,
+    pg_pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
+) -> Result<HttpResponse, actix_web::Error> {
+    req_payload.validate()?;
+    let req_payload = req_payload.into_inner();
+    let activity = create_activity_query(
+        org_member.org_id,
+        req_payload.payload,
+        req_payload.contact_id,
+        req_payload.deal_id,
+        req_payload.company_id,
+        req_payload.occurred_at,
+        pg_pool,
+    )
+    .await?;
+    publish_change_event(
+        org_member.org_id,
+        ChangeEntity::Activity,
+        ChangeAction::Created,
+        activity.id,
+        redis_pool,
+    )
+    .await;
+    Ok(HttpResponse::Created().json(activity))
+}
+
+#[utoipa::path(
+  get,
+  path = "/activities/{activity_id}",
+  context_path = "/api",
+  tag = "activities",
+  responses(
+      (status = 200, description = "JSON object representing the requested activity", body = Activity, headers(("ETag" = String, description = "Version of the record. Send it back in If-Match to make a write conditional"))),
+      (status = 401, description = "Service error relating to authentication status of the user", body = ErrorRespPayload),
+      (status = 404, description = "Activity not found", body = ErrorRespPayload),
+  ),
+  params(
+    ("Organization" = String, Header, description = "The organization id to use for the request"),
+    ("activity_id" = String, Path, description = "The id of the activity you want to fetch."),
+  ),
+  security(
+      ("ApiKey" = ["readonly"]),
+  )
+)]
+#[tracing::instrument(skip(pg_pool))]
+pub async fn get_activity(
+    org_member: AuthedMember,
+    activity_id: web::Path<PrefixedUuid<ActivityPrefix>>,
+    pg_pool: web::Data<PgPool>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let activity =
+        get_activity_by_id_query(activity_id.into_inner(), org_member.org_id, pg_pool).await?;
+    Ok(HttpResponse::Ok()
+        .insert_header(etag(activity.updated_at))
+        .json(activity))
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct UpdateActivityReqPayload {
+    payload: Option<ActivityPayload>,
+    contact_id: Option<PrefixedUuid<ContactPrefix>>,
+    deal_id: Option<PrefixedUuid<DealPrefix>>,
+    company_id: Option<PrefixedUuid<CompanyPrefix>>,
+    #[schema(example = "2021-01-01T00:00:00")]
+    occurred_at: Option<chrono::NaiveDateTime>,
+}
+
+impl Validate for UpdateActivityReqPayload {
+    fn validate(&self) -> Result<(), ServiceError> {
+        let mut validator = Validator::default();
+        if let Some(payload) = &self.payload {
+            validate_activity_payload(&mut validator, payload);
+        }
+        if let Some(occurred_at) = self.occurred_at {
+            validator.date("occurred_at", occurred_at.date());
+        }
+        validator.finish()
+    }
+}
+
+#[utoipa::path(
+  put,
+  path = "/activities/{activity_id}",
+  context_path = "/api",
+  tag = "activities",
+  request_body(content = UpdateActivityReqPayload, description = "JSON request payload to update the activity", content_type = "application/json"),
+  responses(
+      (status = 200, description = "Object representing the updated activity", body = Activity),
+      (status = 401, description = "Service error relating to authentication status of the user", body = ErrorRespPayload),
+      (status = 400, description = "Service error relating to the request payload", body = ErrorRespPayload),
+      (status = 412, description = "The record was changed since the ETag in If-Match was issued", body = ErrorRespPayload),
+  ),
+  params(
+    ("Organization" = String, Header, description = "The organization id to use for the request"),
+    ("activity_id" = String, Path, description = "The id of the activity you want to update."),
+    ("If-Match" = Option<String>, Header, description = "ETag of the record as last fetched. The write fails with 412 if the record has changed since"),
+  ),
+  security(
+      ("ApiKey" = ["readonly"]),
+  )
+)]
+#[tracing::instrument(skip(pg_pool, redis_pool))]
+pub async fn update_activity(
+    req: HttpRequest,
+    req_payload: web::Json<UpdateActivityReqPayload>,
+    org_member: AuthedMember,
+    activity_id: web::Path<PrefixedUuid<ActivityPrefix>>,
+    pg_pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
+) -> Result<HttpResponse, actix_web::Error> {
+    req_payload.validate()?;
+    let activity_id = activity_id.into_inner();
+    let expected_updated_at = if has_if_match(&req) {
+        let current =
+            get_activity_by_id_query(activity_id, org_member.org_id, pg_pool.clone()).await?;
+        check_if_match(&req, current.updated_at)?
+    } else {
+        None
+    };
+    let req_payload = req_payload.into_inner();
+    let activity = update_activity_query(
+        activity_id,
+        org_member.org_id,
+        req_payload.payload,
+        req_payload.contact_id,
+        req_payload.deal_id,
+        req_payload.company_id,
+        req_payload.occurred_at,
+        expected_updated_at,
+        pg_pool,
+    )
+    .await?;
+    publish_change_event(
+        org_member.org_id,
+        ChangeEntity::Activity,
+        ChangeAction::Updated,
+        activity.id,
+        redis_pool,
+    )
+    .await;
+    Ok(HttpResponse::Ok()
+        .insert_header(etag(activity.updated_at))
+        .json(activity))
+}
+
+#[utoipa::path(
+  delete,
+  path = "/activities/{activity_id}",
+  context_path = "/api",
+  tag = "activities",
+  responses(
+      (status = 204, description = "No content response indicating that the activity was successfully deleted"),
+      (status = 401, description = "Service error relating to authentication status of the user", body = ErrorRespPayload),
+      (status = 412, description = "The record was changed since the ETag in If-Match was issued", body = ErrorRespPayload),
+  ),
+  params(
+    ("Organization" = String, Header, description = "The organization id to use for the request"),
+    ("activity_id" = String, Path, description = "The id of the activity you want to delete."),
+    ("If-Match" = Option<String>, Header, description = "ETag of the record as last fetched. The write fails with 412 if the record has changed since"),
+  ),
+  security(
+      ("ApiKey" = ["readonly"]),
+  )
+)]
+#[tracing::instrument(skip(pg_pool, redis_pool))]
+pub async fn delete_activity(
+    req: HttpRequest,
+    org_member: AuthedMember,
+    activity_id: web::Path<PrefixedUuid<ActivityPrefix>>,
+    pg_pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let activity_id = activity_id.into_inner();
+    let expected_updated_at = if has_if_match(&req) {
+        let current =
+            get_activity_by_id_query(activity_id, org_member.org_id, pg_pool.clone()).await?;
+        check_if_match(&req, current.updated_at)?
+    } else {
+        None
+    };
+    delete_activity_query(activity_id, org_member.org_id, expected_updated_at, pg_pool).await?;
+    publish_change_event(
+        org_member.org_id,
+        ChangeEntity::Activity,
+        ChangeAction::Deleted,
+        activity_id,
+        redis_pool,
+    )
+    .await;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ListActivitiesQuery {
+    limit: Option<i64>,
+    offset: Option<String>,
+    contact_id: Option<String>,
+    deal_id: Option<String>,
+    company_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, Clone)]
+pub struct ActivityList {
+    pub activities: Vec<Activity>,
+    pub total: i64,
+}
+
+#[utoipa::path(
+  get,
+  path = "/activities",
+  context_path = "/api",
+  tag = "activities",
+  responses(
+      (status = 200, description = "List of activities", body = ActivityList),
+      (status = 401, description = "Service error relating to authentication status of the user", body = ErrorRespPayload),
+      (status = 400, description = "Service error relating to the request payload", body = ErrorRespPayload),
+  ),
+  params(
+    ("Organization" = String, Header, description = "The organization id to use for the request"),
+    ("limit" = Option<i64>, Query, description = "The number of records to return"),
+    ("offset" = Option<String>, Query, description = "The UUID of the record to start from"),
+    ("contact_id" = Option<String>, Query, description = "Only return activities linked to this contact"),
+    ("deal_id" = Option<String>, Query, description = "Only return activities linked to this deal"),
+    ("company_id" = Option<String>, Query, description = "Only return activities linked to this company"),
+  ),
+  security(
+      ("ApiKey" = ["readonly"]),
+  )
+)]
+#[tracing::instrument(skip(pg_pool))]
+pub async fn list_activities(
+    query: web::Query<ListActivitiesQuery>,
+    org_member: AuthedMember,
+    pg_pool: web::Data<PgPool>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let ListActivitiesQuery {
+        limit,
+        offset,
+        contact_id,
+        deal_id,
+        company_id,
+    } = query.into_inner();
+    let offset = PrefixedUuid::<ActivityPrefix>::from_optional_str(offset)?;
+    let contact_id = PrefixedUuid::<ContactPrefix>::from_optional_str(contact_id)?;
+    let deal_id = PrefixedUuid::<DealPrefix>::from_optional_str(deal_id)?;
+    let company_id = PrefixedUuid::<CompanyPrefix>::from_optional_str(company_id)?;
+    let (activities, total) = list_activities_query(
+        org_member.org_id,
+        contact_id,
+        deal_id,
+        company_id,
+        pg_pool,
+        limit,
+        offset,
+    )
+    .await?;
+    Ok(HttpResponse::Ok().json(ActivityList { activities, total }))
+}
@@ -0,0 +1,287 @@
+//! Instance administration for superusers. These routes are deliberately left out
+//! of the OpenAPI document, so nothing here derives `ToSchema`.
+use super::auth_handler::SuperUser;
+use crate::{
+    data::{
+        models::{JobStatus, Org, OrgUsage, PgPool, RedisPool, User},
+        redact::redacted_debug,
+        validation::{Validate, Validator, MAX_NAME_LENGTH},
+    },
+    errors::ServiceError,
+    operators::{
+        auth_cache_operator::invalidate_user_cache_query,
+        job_operator::{get_job_by_id_query, list_jobs_query, retry_job_query},
+        org_operator::{get_orgs_for_user_query, list_orgs_query},
+        plan_operator::{
+            assign_plan_to_org_query, create_plan_query, delete_plan_query, get_org_usage_query,
+            list_plans_query, update_plan_query,
+        },
+        user_operator::{get_user_by_email_query, set_user_disabled_query},
+    },
+    prefixes::{OrgPrefix, PrefixedUuid, UserPrefix},
+};
+use actix_web::{web, HttpResponse};
+use serde::{Deserialize, Serialize};
+
+#[tracing::instrument(skip(pg_pool))]
+pub async fn list_plans(
+    _super_user: SuperUser,
+    pg_pool: web::Data<PgPool>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let plans = list_plans_query(pg_pool).await?;
+
+    Ok(HttpResponse::Ok().json(plans))
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreatePlanReqPayload {
+    stripe_id: String,
+    num_users: i32,
+    num_deals: i32,
+    price_per_month: i32,
+}
+
+impl Validate for CreatePlanReqPayload {
+    fn validate(&self) -> Result<(), ServiceError> {
+        let mut validator = Validator::default();
+        validator.non_empty("stripe_id", &self.stripe_id, MAX_NAME_LENGTH);
+        validator.range("num_users", self.num_users, 0, i32::MAX);
+        validator.range("num_deals", self.num_deals, 0, i32::MAX);
+        validator.range("price_per_month", self.price_per_month, 0, i32::MAX);
+        validator.finish()
+    }
+}
+
+#[tracing::instrument(skip(pg_pool))]
+pub async fn create_plan(
+    req_payload: web::Json<CreatePlanReqPayload>,
+    _super_user: SuperUser,
+    pg_pool: web::Data<PgPool>,
+) -> Result<HttpResponse, actix_web::Error> {
+    req_payload.validate()?;
+    let req_payload = req_payload.into_inner();
+    let plan = create_plan_query(
+        req_payload.stripe_id,
+        req_payload.num_users,
+        req_payload.num_deals,
+        req_payload.price_per_month,
+        pg_pool,
+    )
+    .await?;
+
+    Ok(HttpResponse::Created().json(plan))
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdatePlanReqPayload {
+    stripe_id: Option<String>,
+    num_users: Option<i32>,
+    num_deals: Option<i32>,
+    price_per_month: Option<i32>,
+}
+
+impl Validate for UpdatePlanReqPayload {
+    fn validate(&self) -> Result<(), ServiceError> {
+        let mut validator = Validator::default();
+        if let Some(stripe_id) = &self.stripe_id {
+            validator.non_empty("stripe_id", stripe_id, MAX_NAME_LENGTH);
+        }
+        if let Some(num_users) = self.num_users {
+            validator.range("num_users", num_users, 0, i32::MAX);
+        }
+        if let Some(num_deals) = self.num_deals {
+            validator.range("num_deals", num_deals, 0, i32::MAX);
+        }
+        if let Some(price_per_month) = self.price_per_month {
+            validator.range("price_per_month", price_per_month, 0, i32::MAX);
+        }
+        validator.finish()
+    }
+}
+
+#[tracing::instrument(skip(pg_pool))]
+pub async fn update_plan(
+    req_payload: web::Json<UpdatePlanReqPayload>,
+    path: web::Path<uuid::Uuid>,
+    _super_user: SuperUser,
+    pg_pool: web::Data<PgPool>,
+) -> Result<HttpResponse, actix_web::Error> {
+    req_payload.validate()?;
+    let req_payload = req_payload.into_inner();
+    let plan = update_plan_query(
+        path.into_inner(),
+        req_payload.stripe_id,
+        req_payload.num_users,
+        req_payload.num_deals,
+        req_payload.price_per_month,
+        pg_pool,
+    )
+    .await?;
+
+    Ok(HttpResponse::Ok().json(plan))
+}
+
+#[tracing::instrument(skip(pg_pool))]
+pub async fn delete_plan(
+    path: web::Path<uuid::Uuid>,
+    _super_user: SuperUser,
+    pg_pool: web::Data<PgPool>,
+) -> Result<HttpResponse, actix_web::Error> {
+    delete_plan_query(path.into_inner(), pg_pool).await?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListOrgsReqQuery {
+    limit: Option<i64>,
+    offset: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AdminOrg {
+    org: Org,
+    usage: OrgUsage,
+}
+
+#[tracing::instrument(skip(pg_pool))]
+pub async fn list_orgs(
+    query: web::Query<ListOrgsReqQuery>,
+    _super_user: SuperUser,
+    pg_pool: web::Data<PgPool>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let orgs = list_orgs_query(pg_pool.clone(), query.limit, query.offset).await?;
+
+    let mut admin_orgs = Vec::with_capacity(orgs.len());
+    for org in orgs {
+        let usage = get_org_usage_query(org.id, pg_pool.clone()).await?;
+        admin_orgs.push(AdminOrg { org, usage });
+    }
+
+    Ok(HttpResponse::Ok().json(admin_orgs))
+}
+
+#[derive(Deserialize)]
+pub struct GetUserReqQuery {
+    email: String,
+}
+
+redacted_debug!(GetUserReqQuery {} pii { email });
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AdminUser {
+    user: User,
+    orgs: Vec<Org>,
+}
+
+#[tracing::instrument(skip(pg_pool))]
+pub async fn get_user(
+    query: web::Query<GetUserReqQuery>,
+    _super_user: SuperUser,
+    pg_pool: web::Data<PgPool>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let user = get_user_by_email_query(&query.email, pg_pool.clone()).await?;
+    let orgs = get_orgs_for_user_query(user.id, pg_pool, Some(100), None).await?;
+
+    Ok(HttpResponse::Ok().json(AdminUser { user, orgs }))
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SetUserDisabledReqPayload {
+    disabled: bool,
+}
+
+#[tracing::instrument(skip(pg_pool, redis_pool))]
+pub async fn set_user_disabled(
+    req_payload: web::Json<SetUserDisabledReqPayload>,
+    path: web::Path<PrefixedUuid<UserPrefix>>,
+    _super_user: SuperUser,
+    pg_pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let user = set_user_disabled_query(path.into_inner(), req_payload.disabled, pg_pool).await?;
+    // Disabling has to lock the user out right away, not when the cache expires
+    invalidate_user_cache_query(user.id, redis_pool).await;
+
+    Ok(HttpResponse::Ok().json(user))
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AssignSubscriptionReqPayload {
+    plan_id: uuid::Uuid,
+    /// When the manual subscription lapses. Open ended if left out.
+    end_date: Option<chrono::NaiveDateTime>,
+}
+
+impl Validate for AssignSubscriptionReqPayload {
+    fn validate(&self) -> Result<(), ServiceError> {
+        let mut validator = Validator::default();
+        if let Some(end_date) = self.end_date {
+            validator.date("end_date", end_date.date());
+        }
+        validator.finish()
+    }
+}
+
+#[tracing::instrument(skip(pg_pool))]
+pub async fn assign_subscription(
+    req_payload: web::Json<AssignSubscriptionReqPayload>,
+    path: web::Path<PrefixedUuid<OrgPrefix>>,
+    _super_user: SuperUser,
+    pg_pool: web::Data<PgPool>,
+) -> Result<HttpResponse, actix_web::Error> {
+    req_payload.validate()?;
+    let subscription = assign_plan_to_org_query(
+        path.into_inner(),
+        req_payload.plan_id,
+        req_payload.end_date,
+        pg_pool,
+    )
+    .await?;
+
+    Ok(HttpResponse::Ok().json(subscription))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListJobsReqQuery {
+    status: Option<JobStatus>,
+    kind: Option<String>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+}
+
+/// Newest jobs first, e.g. `?status=dead` for the ones that need attention.
+#[tracing::instrument(skip(pg_pool))]
+pub async fn list_jobs(
+    query: web::Query<ListJobsReqQuery>,
+    _super_user: SuperUser,
+    pg_pool: web::Data<PgPool>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let query = query.into_inner();
+    let jobs =
+        list_jobs_query(query.status, query.kind, query.limit, query.offset, pg_pool).await?;
+
+    Ok(HttpResponse::Ok().json(jobs))
+}
+
+#[tracing::instrument(skip(pg_pool))]
+pub async fn get_job(
+    path: web::Path<uuid::Uuid>,
+    _super_user: SuperUser,
+    pg_pool: web::Data<PgPool>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let job = get_job_by_id_query(path.into_inner(), pg_pool).await?;
+
+    Ok(HttpResponse::Ok().json(job))
+}
+
+#[tracing::instrument(skip(pg_pool))]
+pub async fn retry_job(
+    path: web::Path<uuid::Uuid>,
+    _super_user: SuperUser,
+    pg_pool: web::Data<PgPool>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let job = retry_job_query(path.into_inner(), pg_pool).await?;
+
+    Ok(HttpResponse::Ok().json(job))
+}
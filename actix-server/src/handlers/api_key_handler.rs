@@ -1,5 +1,12 @@
 use super::auth_handler::AuthedUser;
-use crate::{data::models::PgPool, operators::api_key_operator::create_api_key_query};
+use crate::{
+    data::{
+        models::PgPool,
+        validation::{Validate, Validator, MAX_NAME_LENGTH},
+    },
+    errors::ServiceError,
+    operators::api_key_operator::create_api_key_query,
+};
 use actix_web::{web, HttpResponse};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
@@ -7,9 +14,18 @@ use utoipa::ToSchema;
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct CreateApiKeyReqPayload {
     /// The name which will be assigned to the new api key.
+    #[schema(min_length = 1, max_length = 255)]
     name: String,
 }
 
+impl Validate for CreateApiKeyReqPayload {
+    fn validate(&self) -> Result<(), ServiceError> {
+        let mut validator = Validator::default();
+        validator.non_empty("name", &self.name, MAX_NAME_LENGTH);
+        validator.finish()
+    }
+}
+
 #[derive(Serialize, Deserialize, ToSchema)]
 pub struct CreateApiKeyRespPayload {
     /// The api key which was created. This is the value which should be used in the Authorization header.
@@ -40,6 +56,7 @@ pub async fn create_api_key(
     req_payload: web::Json<CreateApiKeyReqPayload>,
     pg_pool: web::Data<PgPool>,
 ) -> Result<HttpResponse, actix_web::Error> {
+    req_payload.validate()?;
     let user_id = authed_user.id;
     let name = req_payload.name.clone();
 
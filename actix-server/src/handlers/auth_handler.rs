@@ -1,10 +1,13 @@
 use crate::data::models::{OrgUserLink, RedisPool, UserRole};
+use crate::data::redact::redacted_debug;
+use crate::operators::org_operator::get_org_user_link_query;
 use crate::operators::user_operator::create_user_query;
 use crate::prefixes::{OrgPrefix, PrefixedUuid, UserPrefix};
 use crate::{
     data::models::{PgPool, User},
     errors::ServiceError,
     operators::user_operator::get_user_by_id_query,
+    settings,
 };
 use actix_identity::Identity;
 use actix_web::{web, Error, FromRequest, HttpMessage as _, HttpRequest, HttpResponse};
@@ -99,6 +102,7 @@ impl FromRequest for AdminMember {
 #[derive(Debug, Deserialize, IntoParams)]
 pub struct OwnerMember {
     pub org_id: PrefixedUuid<OrgPrefix>,
+    pub user_id: PrefixedUuid<UserPrefix>,
     pub role: UserRole,
 }
 
@@ -113,6 +117,7 @@ impl FromRequest for OwnerMember {
                 if org_user_link.role == 2 {
                     Ok(OwnerMember {
                         org_id: org_user_link.org_id,
+                        user_id: org_user_link.user_id,
                         role: org_user_link.role.into(),
                     })
                 } else {
@@ -124,21 +129,84 @@ impl FromRequest for OwnerMember {
     }
 }
 
+/// Superusers are flagged in the database (see the `create_superuser` binary) or
+/// listed by email in the comma separated `SUPERUSER_EMAILS` setting.
+pub fn is_superuser(user: &User) -> bool {
+    user.is_superuser
+        || settings::get()
+            .superuser_emails
+            .iter()
+            .any(|email| email.eq_ignore_ascii_case(&user.email))
+}
+
+#[derive(Deserialize)]
+pub struct SuperUser {
+    pub user_id: PrefixedUuid<UserPrefix>,
+    pub email: String,
+}
+
+redacted_debug!(SuperUser { user_id } pii { email });
+
+impl FromRequest for SuperUser {
+    type Error = actix_web::Error;
+    type Future = std::future::Ready<Result<SuperUser, actix_web::Error>>;
+
+    #[inline]
+    fn from_request(req: &HttpRequest, _: &mut actix_web::dev::Payload) -> Self::Future {
+        std::future::ready(match req.extensions().get::<User>() {
+            Some(user) if is_superuser(user) => Ok(SuperUser {
+                user_id: user.id,
+                email: user.email.clone(),
+            }),
+            Some(_) => Err(ServiceError::Forbidden.into()),
+            None => Err(ServiceError::Unauthorized.into()),
+        })
+    }
+}
+
+/// Resolves the `owner` query parameter of list endpoints. `me` is the calling
+/// user, anything else must be a user id.
+pub fn parse_owner_filter(
+    owner: Option<String>,
+    user_id: PrefixedUuid<UserPrefix>,
+) -> Result<Option<PrefixedUuid<UserPrefix>>, ServiceError> {
+    match owner.as_deref() {
+        Some("me") => Ok(Some(user_id)),
+        _ => PrefixedUuid::<UserPrefix>::from_optional_str(owner),
+    }
+}
+
+/// Checks that a record is only ever assigned to a member of the org.
+pub async fn validate_owner(
+    owner_user_id: Option<PrefixedUuid<UserPrefix>>,
+    org_id: PrefixedUuid<OrgPrefix>,
+    pg_pool: web::Data<PgPool>,
+) -> Result<(), ServiceError> {
+    if let Some(owner_user_id) = owner_user_id {
+        get_org_user_link_query(owner_user_id, org_id, pg_pool)
+            .await
+            .map_err(|e| match e {
+                ServiceError::NotFound => ServiceError::invalid_field(
+                    "owner_user_id",
+                    "Owner must be a member of the organization",
+                ),
+                e => e,
+            })?;
+    }
+    Ok(())
+}
+
 #[derive(Clone, Debug, Default, Deserialize, PartialEq)]
 struct AFClaims {}
 
 #[tracing::instrument]
 pub async fn build_oidc_client() -> CoreClient {
-    let issuer_url =
-        std::env::var("OIDC_ISSUER_URL").expect("Issuer URL for OpenID provider must be set");
-    let client_id =
-        std::env::var("OIDC_CLIENT_ID").expect("Client ID for OpenID provider must be set");
-    let auth_redirect_url = std::env::var("OIDC_AUTH_REDIRECT_URL")
-        .expect("Auth redirect URL for OpenID provider must be set");
-    let client_secret =
-        std::env::var("OIDC_CLIENT_SECRET").expect("Client secret for OpenID provider must be set");
-    let base_server_url =
-        std::env::var("BASE_SERVER_URL").expect("Server hostname for OpenID provider must be set");
+    let settings = settings::get();
+    let issuer_url = settings.oidc_issuer_url.clone();
+    let client_id = settings.oidc_client_id.clone();
+    let auth_redirect_url = settings.oidc_auth_redirect_url.clone();
+    let client_secret = settings.oidc_client_secret.expose().to_string();
+    let base_server_url = settings.base_server_url.clone();
 
     //build OpenId Connect client
     let meta_data = CoreProviderMetadata::discover_async(
@@ -163,7 +231,7 @@ pub async fn build_oidc_client() -> CoreClient {
     )
 }
 
-#[tracing::instrument(skip(pg_pool))]
+#[tracing::instrument(skip(pg_pool, email, name))]
 pub async fn create_account(
     email: String,
     name: String,
@@ -203,10 +271,8 @@ pub async fn logout(
     req: HttpRequest,
 ) -> HttpResponse {
     id.logout();
-    let issuer_url =
-        std::env::var("OIDC_ISSUER_URL").expect("Issuer URL for OpenID provider must be set");
-    let client_id =
-        std::env::var("OIDC_CLIENT_ID").expect("Client ID for OpenID provider must be set");
+    let issuer_url = &settings::get().oidc_issuer_url;
+    let client_id = &settings::get().oidc_client_id;
 
     let logout_url = format!(
         "{}/protocol/openid-connect/logout?post_logout_redirect_uri={}&client_id={}",
@@ -290,7 +356,10 @@ pub async fn login(
         nonce,
     };
 
-    let mut redis_conn = redis_pool.get().await.unwrap();
+    let mut redis_conn = redis_pool
+        .get()
+        .await
+        .map_err(|_| ServiceError::ServiceUnavailable("Could not get redis connection".into()))?;
     let _: () = redis_conn
         .set(
             OIDC_SESSION_KEY,
@@ -351,7 +420,7 @@ pub async fn callback(
     let mut redis_conn = redis_pool
         .get()
         .await
-        .map_err(|_| ServiceError::InternalServerError("Could not get redis connection".into()))?;
+        .map_err(|_| ServiceError::ServiceUnavailable("Could not get redis connection".into()))?;
 
     let opt_state: Option<String> =
         redis_conn
@@ -468,24 +537,6 @@ pub async fn callback(
         .finish())
 }
 
-/// Health Check
-///
-/// Confirmation that the service is healthy
-#[utoipa::path(
-    get,
-    path = "/health",
-    context_path = "/api",
-    tag = "health",
-    responses(
-        (status = 200, description = "Confirmation that the service is healthy"),
-        (status = 400, description = "Service error relating to overall service health", body = ErrorRespPayload),
-    ),
-)]
-#[tracing::instrument]
-pub async fn health_check() -> Result<HttpResponse, actix_web::Error> {
-    Ok(HttpResponse::Ok().finish())
-}
-
 /// Local login page for cli
 pub async fn login_cli() -> Result<HttpResponse, ServiceError> {
     let html_page = read_to_string("src/public/login.html").map_err(|e| {
@@ -0,0 +1,59 @@
+use crate::{
+    data::models::PgPool,
+    errors::ServiceError,
+    operators::billing_operator::{
+        handle_billing_event_query, verify_stripe_signature, StripeEvent,
+    },
+    settings,
+};
+use actix_web::{web, HttpRequest, HttpResponse};
+
+/// Billing Webhook
+///
+/// Receives subscription lifecycle events from Stripe. Requests must carry a valid `Stripe-Signature` header signed with `STRIPE_WEBHOOK_SECRET`. Events are applied at most once, so redeliveries are acknowledged without changing anything. Events for organizations that don't exist are acknowledged and ignored. Events arrive out of order, so one older than the last event applied to its subscription, or for a canceled subscription, is acknowledged without changing anything.
+#[utoipa::path(
+  post,
+  path = "/billing/webhook",
+  context_path = "/api",
+  tag = "billing",
+  request_body(content = Object, description = "Raw event payload as sent by Stripe", content_type = "application/json"),
+  responses(
+      (status = 200, description = "The event was applied, had already been applied, or was ignored"),
+      (status = 400, description = "The signature or payload is invalid", body = ErrorRespPayload),
+  ),
+  params(
+    ("Stripe-Signature" = String, Header, description = "Signature of the payload computed with the webhook secret"),
+  ),
+)]
+#[tracing::instrument(skip(body, pg_pool))]
+pub async fn billing_webhook(
+    req: HttpRequest,
+    body: web::Bytes,
+    pg_pool: web::Data<PgPool>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let secret = settings::get()
+        .stripe_webhook_secret
+        .as_ref()
+        .ok_or_else(|| {
+            ServiceError::ServiceUnavailable("Billing webhooks are not configured".to_string())
+        })?;
+    let signature = req
+        .headers()
+        .get("Stripe-Signature")
+        .and_then(|signature| signature.to_str().ok())
+        .ok_or_else(|| ServiceError::BadRequest("Missing Stripe-Signature header".to_string()))?;
+
+    verify_stripe_signature(
+        &body,
+        signature,
+        secret.expose(),
+        chrono::Utc::now().timestamp(),
+    )?;
+
+    let event = serde_json::from_slice::<StripeEvent>(&body)
+        .map_err(|e| ServiceError::BadRequest(format!("Invalid event payload: {}", e)))?;
+
+    handle_billing_event_query(event, pg_pool).await?;
+
+    Ok(HttpResponse::Ok().finish())
+}
@@ -1,21 +1,39 @@
-use super::auth_handler::AuthedMember;
+use super::auth_handler::{parse_owner_filter, validate_owner, AuthedMember};
 use crate::{
-    data::models::PgPool,
-    operators::company_operator::{
-        create_company_query, delete_company_query, get_company_query, list_companies_query,
-        rename_company_query,
+    data::{
+        etag::{check_if_match, etag, has_if_match},
+        models::{ChangeAction, ChangeEntity, PgPool, RedisPool},
+        patch::{deserialize_patch, required, Patch},
+        validation::{Validate, Validator, MAX_NAME_LENGTH},
     },
-    prefixes::{CompanyPrefix, PrefixedUuid},
+    errors::ServiceError,
+    operators::{
+        company_operator::{
+            create_company_query, delete_company_query, get_company_query, list_companies_query,
+            update_company_query,
+        },
+        event_operator::publish_change_event,
+    },
+    prefixes::{CompanyPrefix, PrefixedUuid, UserPrefix},
 };
-use actix_web::{web, HttpResponse};
+use actix_web::{web, HttpRequest, HttpResponse};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct CreateCompanyReqPayload {
+    #[schema(min_length = 1, max_length = 255)]
     name: String,
 }
 
+impl Validate for CreateCompanyReqPayload {
+    fn validate(&self) -> Result<(), ServiceError> {
+        let mut validator = Validator::default();
+        validator.non_empty("name", &self.name, MAX_NAME_LENGTH);
+        validator.finish()
+    }
+}
+
 #[utoipa::path(
   post,
   path = "/companies",
@@ -25,22 +43,36 @@ pub struct CreateCompanyReqPayload {
   responses(
       (status = 201, description = "JSON body representing the company that was created", body = Company),
       (status = 401, description = "Service error relating to authentication status of the user", body = ErrorRespPayload),
+      (status = 409, description = "Service error when a request with the same Idempotency-Key is still being processed", body = ErrorRespPayload),
+      (status = 422, description = "Service error when the Idempotency-Key was already used for a different request", body = ErrorRespPayload),
   ),
   params(
     ("Organization" = String, Header, description = "The organization id to use for the request"),
+    ("Idempotency-Key" = Option<String>, Header, description = "Unique key for this create. Retrying with the same key replays the first response instead of creating a duplicate"),
   ),
   security(
       ("ApiKey" = ["readonly"]),
   )
 )]
-#[tracing::instrument(skip(pg_pool))]
+#[tracing::instrument(skip(pg_pool, redis_pool))]
 pub async fn create_company(
     req_payload: web::Json<CreateCompanyReqPayload>,
     org_member: AuthedMember,
     pg_pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
 ) -> Result<HttpResponse, actix_web::Error> {
+    req_payload.validate()?;
     let name = req_payload.name.clone();
-    let company = create_company_query(name, org_member.org_id, pg_pool).await?;
+    let company =
+        create_company_query(name, org_member.org_id, Some(org_member.user_id), pg_pool).await?;
+    publish_change_event(
+        org_member.org_id,
+        ChangeEntity::Company,
+        ChangeAction::Created,
+        company.id,
+        redis_pool,
+    )
+    .await;
     Ok(HttpResponse::Created().json(company))
 }
 
@@ -52,71 +84,144 @@ pub async fn create_company(
   responses(
       (status = 204, description = "No content response indicating that the company was successfully deleted"),
       (status = 401, description = "Service error relating to authentication status of the user", body = ErrorRespPayload),
+      (status = 412, description = "The record was changed since the ETag in If-Match was issued", body = ErrorRespPayload),
   ),
   params(
     ("Organization" = String, Header, description = "The organization id to use for the request"),
     ("company_id" = String, Path, description = "The id of the company you want to delete."),
+    ("If-Match" = Option<String>, Header, description = "ETag of the record as last fetched. The write fails with 412 if the record has changed since"),
   ),
   security(
       ("ApiKey" = ["readonly"]),
   )
 )]
-#[tracing::instrument(skip(pg_pool))]
+#[tracing::instrument(skip(pg_pool, redis_pool))]
 pub async fn delete_company(
+    req: HttpRequest,
     org_user: AuthedMember,
     company_id: web::Path<PrefixedUuid<CompanyPrefix>>,
     pg_pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
 ) -> Result<HttpResponse, actix_web::Error> {
     let company_id = company_id.into_inner();
+    let expected_updated_at = if has_if_match(&req) {
+        let current = get_company_query(company_id, pg_pool.clone()).await?;
+        check_if_match(&req, current.updated_at)?
+    } else {
+        None
+    };
+
+    delete_company_query(company_id, expected_updated_at, pg_pool).await?;
 
-    delete_company_query(company_id, pg_pool).await?;
+    publish_change_event(
+        org_user.org_id,
+        ChangeEntity::Company,
+        ChangeAction::Deleted,
+        company_id,
+        redis_pool,
+    )
+    .await;
 
     return Ok(HttpResponse::NoContent().finish());
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct UpdateCompanyReqPayload {
-    name: String,
+    #[serde(default, deserialize_with = "deserialize_patch")]
+    #[schema(value_type = Option<String>, min_length = 1, max_length = 255)]
+    name: Patch<String>,
+    /// Reassigns the company to another member of the organization.
+    #[serde(default, deserialize_with = "deserialize_patch")]
+    #[schema(value_type = Option<PrefixedUuid<UserPrefix>>)]
+    owner_user_id: Patch<PrefixedUuid<UserPrefix>>,
+}
+
+impl Validate for UpdateCompanyReqPayload {
+    fn validate(&self) -> Result<(), ServiceError> {
+        let mut validator = Validator::default();
+        if let Some(Some(name)) = &self.name {
+            validator.non_empty("name", name, MAX_NAME_LENGTH);
+        }
+        validator.finish()
+    }
 }
 
 #[utoipa::path(
-  put,
+  patch,
   path = "/companies/{company_id}",
   context_path = "/api",
   tag = "companies",
-  request_body(content = UpdateCompanyReqPayload, description = "JSON request payload to rename the company", content_type = "application/json"),
+  request_body(content = UpdateCompanyReqPayload, description = "JSON merge patch to update the company. Omitted fields are left alone and null fields are cleared.", content_type = "application/json"),
   responses(
       (status = 200, description = "Object representing the renamed note", body = Company),
       (status = 401, description = "Service error relating to authentication status of the user", body = ErrorRespPayload),
+      (status = 400, description = "Service error relating to the request payload", body = ErrorRespPayload),
+      (status = 412, description = "The record was changed since the ETag in If-Match was issued", body = ErrorRespPayload),
   ),
   params(
     ("Organization" = String, Header, description = "The organization id to use for the request"),
     ("company_id" = String, Path, description = "The id of the note you want to update."),
+    ("If-Match" = Option<String>, Header, description = "ETag of the record as last fetched. The write fails with 412 if the record has changed since"),
   ),
   security(
       ("ApiKey" = ["readonly"]),
   )
 )]
-#[tracing::instrument(skip(pg_pool))]
+#[tracing::instrument(skip(pg_pool, redis_pool))]
 pub async fn update_company(
+    req: HttpRequest,
     req_payload: web::Json<UpdateCompanyReqPayload>,
     company_id: web::Path<PrefixedUuid<CompanyPrefix>>,
     org_member: AuthedMember,
     pg_pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
 ) -> Result<HttpResponse, actix_web::Error> {
+    req_payload.validate()?;
     let company_id = company_id.into_inner();
+    let expected_updated_at = if has_if_match(&req) {
+        let current = get_company_query(company_id, pg_pool.clone()).await?;
+        check_if_match(&req, current.updated_at)?
+    } else {
+        None
+    };
+
+    let new_name = required("name", req_payload.name.clone())?;
+
+    validate_owner(
+        req_payload.owner_user_id.flatten(),
+        org_member.org_id,
+        pg_pool.clone(),
+    )
+    .await?;
 
-    let new_name = req_payload.name.clone();
+    let new_company = update_company_query(
+        company_id,
+        new_name,
+        req_payload.owner_user_id,
+        expected_updated_at,
+        pg_pool,
+    )
+    .await?;
 
-    let new_company = rename_company_query(company_id, new_name, pg_pool).await?;
+    publish_change_event(
+        org_member.org_id,
+        ChangeEntity::Company,
+        ChangeAction::Updated,
+        new_company.id,
+        redis_pool,
+    )
+    .await;
 
-    Ok(HttpResponse::Ok().json(new_company))
+    Ok(HttpResponse::Ok()
+        .insert_header(etag(new_company.updated_at))
+        .json(new_company))
 }
 
 #[derive(Debug, Deserialize)]
 pub struct GetCompaniesQuery {
     limit: Option<i64>,
     offset: Option<i64>,
+    owner: Option<String>,
 }
 
 #[utoipa::path(
@@ -127,11 +232,13 @@ pub struct GetCompaniesQuery {
   params(
       ("limit" = Option<i64>, Query, description = "Limit the number of results. Default is 10"),
       ("offset" = Option<i64>, Query, description = "Offset the results. Default is 0"),
+      ("owner" = Option<String>, Query, description = "Only return records owned by this user id, or `me` for the calling user"),
       ("Organization" = String, Header, description = "The organization id to use for the request"),
   ),
   responses(
       (status = 200, description = "List of companies for the organization", body = [Company]),
       (status = 401, description = "Service error relating to authentication status of the user", body = ErrorRespPayload),
+      (status = 400, description = "Service error relating to the request payload", body = ErrorRespPayload),
   ),
   security(
       ("ApiKey" = ["readonly"]),
@@ -143,8 +250,15 @@ pub async fn get_companies_for_org(
     authed_user: AuthedMember,
     pg_pool: web::Data<PgPool>,
 ) -> Result<HttpResponse, actix_web::Error> {
-    let companies =
-        list_companies_query(authed_user.org_id, pg_pool, query.limit, query.offset).await?;
+    let owner_user_id = parse_owner_filter(query.owner.clone(), authed_user.user_id)?;
+    let companies = list_companies_query(
+        authed_user.org_id,
+        owner_user_id,
+        pg_pool,
+        query.limit,
+        query.offset,
+    )
+    .await?;
 
     Ok(HttpResponse::Ok().json(companies))
 }
@@ -155,7 +269,7 @@ pub async fn get_companies_for_org(
   context_path = "/api",
   tag = "companies",
   responses(
-      (status = 200, description = "JSON object representing the requested company", body = Company),
+      (status = 200, description = "JSON object representing the requested company", body = Company, headers(("ETag" = String, description = "Version of the record. Send it back in If-Match to make a write conditional"))),
       (status = 401, description = "Service error relating to authentication status of the user", body = ErrorRespPayload),
   ),
   params(
@@ -177,8 +291,10 @@ pub async fn get_company_by_id(
     let company = get_company_query(company_id, pg_pool).await?;
 
     if company.org_id != org_member.org_id {
-        return Ok(HttpResponse::Unauthorized().finish());
+        return Err(ServiceError::Unauthorized.into());
     }
 
-    Ok(HttpResponse::Ok().json(company))
+    Ok(HttpResponse::Ok()
+        .insert_header(etag(company.updated_at))
+        .json(company))
 }
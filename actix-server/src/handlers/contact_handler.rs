@@ -1,22 +1,45 @@
-use super::auth_handler::OwnerMember;
+use super::auth_handler::{parse_owner_filter, validate_owner, OwnerMember};
 use crate::{
-    data::models::{Contact, PgPool},
-    operators::contact_operator::{
-        create_contact_query, delete_contact_query, get_contact_by_id_query,
-        get_contacts_by_org_id_query, update_contact_query,
+    data::{
+        etag::{check_if_match, etag, has_if_match},
+        models::{ChangeAction, ChangeEntity, Contact, PgPool, RedisPool},
+        patch::{deserialize_patch, required, Patch},
+        redact::redacted_debug,
+        validation::{Validate, Validator, MAX_NAME_LENGTH},
     },
-    prefixes::{ContactPrefix, PrefixedUuid},
+    errors::ServiceError,
+    operators::{
+        contact_operator::{
+            create_contact_query, delete_contact_query, get_contact_by_id_query,
+            get_contacts_by_org_id_query, update_contact_query,
+        },
+        event_operator::publish_change_event,
+    },
+    prefixes::{ContactPrefix, PrefixedUuid, UserPrefix},
 };
-use actix_web::{web, HttpResponse};
+use actix_web::{web, HttpRequest, HttpResponse};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
-#[derive(Debug, Deserialize, Serialize, ToSchema)]
+#[derive(Deserialize, Serialize, ToSchema)]
 pub struct CreateContactReqPayload {
+    #[schema(min_length = 1, max_length = 255)]
     first_name: String,
+    #[schema(min_length = 1, max_length = 255)]
     last_name: String,
 }
 
+redacted_debug!(CreateContactReqPayload {} pii { first_name, last_name });
+
+impl Validate for CreateContactReqPayload {
+    fn validate(&self) -> Result<(), ServiceError> {
+        let mut validator = Validator::default();
+        validator.non_empty("first_name", &self.first_name, MAX_NAME_LENGTH);
+        validator.non_empty("last_name", &self.last_name, MAX_NAME_LENGTH);
+        validator.finish()
+    }
+}
+
 #[utoipa::path(
   post,
   path = "/contacts",
@@ -26,27 +49,41 @@ pub struct CreateContactReqPayload {
   responses(
       (status = 201, description = "JSON body representing the contact that was created", body = Contact),
       (status = 401, description = "Service error relating to authentication status of the user", body = ErrorRespPayload),
+      (status = 409, description = "Service error when a request with the same Idempotency-Key is still being processed", body = ErrorRespPayload),
+      (status = 422, description = "Service error when the Idempotency-Key was already used for a different request", body = ErrorRespPayload),
   ),
   security(
       ("ApiKey" = ["readonly"]),
   ),
   params(
     ("Organization" = String, Header, description = "The org id to use for the request"),
+    ("Idempotency-Key" = Option<String>, Header, description = "Unique key for this create. Retrying with the same key replays the first response instead of creating a duplicate"),
   )
 )]
-#[tracing::instrument(skip(pg_pool))]
+#[tracing::instrument(skip(pg_pool, redis_pool))]
 pub async fn create_contact(
     req_payload: web::Json<CreateContactReqPayload>,
     org_user: OwnerMember,
     pg_pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
 ) -> Result<HttpResponse, actix_web::Error> {
+    req_payload.validate()?;
     let contact = create_contact_query(
         org_user.org_id,
         req_payload.first_name.clone(),
         req_payload.last_name.clone(),
+        Some(org_user.user_id),
         pg_pool,
     )
     .await?;
+    publish_change_event(
+        org_user.org_id,
+        ChangeEntity::Contact,
+        ChangeAction::Created,
+        contact.id,
+        redis_pool,
+    )
+    .await;
     Ok(HttpResponse::Created().json(contact))
 }
 
@@ -58,25 +95,42 @@ pub async fn create_contact(
   responses(
       (status = 204, description = "No content response indicating that the contacts was successfully deleted"),
       (status = 401, description = "Service error relating to authentication status of the user", body = ErrorRespPayload),
+      (status = 412, description = "The record was changed since the ETag in If-Match was issued", body = ErrorRespPayload),
   ),
   params(
     ("contact_id" = String, description = "The contacts id to use for the request"),
     ("Organization" = String, Header, description = "The org id to use for the request"),
+    ("If-Match" = Option<String>, Header, description = "ETag of the record as last fetched. The write fails with 412 if the record has changed since"),
   ),
   security(
       ("ApiKey" = ["readonly"]),
   )
 )]
-#[tracing::instrument(skip(pg_pool))]
+#[tracing::instrument(skip(pg_pool, redis_pool))]
 pub async fn delete_contact(
+    req: HttpRequest,
     org_user: OwnerMember,
     path: web::Path<PrefixedUuid<ContactPrefix>>,
     pg_pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
 ) -> Result<HttpResponse, actix_web::Error> {
     let contact_id = path.into_inner();
-    delete_contact_query(contact_id, pg_pool)
-        .await
-        .map(|_| Ok(HttpResponse::NoContent().finish()))?
+    let expected_updated_at = if has_if_match(&req) {
+        let current = get_contact_by_id_query(contact_id, pg_pool.clone()).await?;
+        check_if_match(&req, current.updated_at)?
+    } else {
+        None
+    };
+    delete_contact_query(contact_id, expected_updated_at, pg_pool).await?;
+    publish_change_event(
+        org_user.org_id,
+        ChangeEntity::Contact,
+        ChangeAction::Deleted,
+        contact_id,
+        redis_pool,
+    )
+    .await;
+    Ok(HttpResponse::NoContent().finish())
 }
 
 #[utoipa::path(
@@ -85,7 +139,7 @@ pub async fn delete_contact(
   context_path = "/api",
   tag = "contacts",
   responses(
-      (status = 200, description = "JSON object representing the requested contact", body = Contact),
+      (status = 200, description = "JSON object representing the requested contact", body = Contact, headers(("ETag" = String, description = "Version of the record. Send it back in If-Match to make a write conditional"))),
       (status = 401, description = "Service error relating to authentication status of the user", body = ErrorRespPayload),
   ),
   security(
@@ -104,26 +158,53 @@ pub async fn get_contact(
 ) -> Result<HttpResponse, actix_web::Error> {
     let contact_id = path.into_inner();
     match get_contact_by_id_query(contact_id, pg_pool).await {
-        Ok(contact) => Ok(HttpResponse::Ok().json(contact)),
-        Err(_) => Ok(HttpResponse::NotFound().finish()),
+        Ok(contact) => Ok(HttpResponse::Ok()
+            .insert_header(etag(contact.updated_at))
+            .json(contact)),
+        Err(_) => Err(ServiceError::NotFound.into()),
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct UpdateContactReqPayload {
-    first_name: Option<String>,
-    last_name: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_patch")]
+    #[schema(value_type = Option<String>, min_length = 1, max_length = 255)]
+    first_name: Patch<String>,
+    #[serde(default, deserialize_with = "deserialize_patch")]
+    #[schema(value_type = Option<String>, min_length = 1, max_length = 255)]
+    last_name: Patch<String>,
+    /// Reassigns the contact to another member of the organization.
+    #[serde(default, deserialize_with = "deserialize_patch")]
+    #[schema(value_type = Option<PrefixedUuid<UserPrefix>>)]
+    owner_user_id: Patch<PrefixedUuid<UserPrefix>>,
+}
+
+redacted_debug!(UpdateContactReqPayload { owner_user_id } pii { first_name, last_name });
+
+impl Validate for UpdateContactReqPayload {
+    fn validate(&self) -> Result<(), ServiceError> {
+        let mut validator = Validator::default();
+        if let Some(Some(first_name)) = &self.first_name {
+            validator.non_empty("first_name", first_name, MAX_NAME_LENGTH);
+        }
+        if let Some(Some(last_name)) = &self.last_name {
+            validator.non_empty("last_name", last_name, MAX_NAME_LENGTH);
+        }
+        validator.finish()
+    }
 }
 
 #[utoipa::path(
-  put,
+  patch,
   path = "/contacts/{contact_id}",
   context_path = "/api",
   tag = "contacts",
-  request_body(content = UpdateContactReqPayload, description = "JSON request payload to update the contact", content_type = "application/json"),
+  request_body(content = UpdateContactReqPayload, description = "JSON merge patch to update the contact. Omitted fields are left alone and null fields are cleared.", content_type = "application/json"),
   responses(
       (status = 200, description = "Object representing the renamed contact", body = Contact),
       (status = 401, description = "Service error relating to authentication status of the user", body = ErrorRespPayload),
+      (status = 400, description = "Service error relating to the request payload", body = ErrorRespPayload),
+      (status = 412, description = "The record was changed since the ETag in If-Match was issued", body = ErrorRespPayload),
   ),
   security(
       ("ApiKey" = ["readonly"]),
@@ -131,24 +212,54 @@ pub struct UpdateContactReqPayload {
   params(
     ("contact_id" = String, description = "The contact id to use for the request"),
     ("Organization" = String, Header, description = "The org id to use for the request"),
+    ("If-Match" = Option<String>, Header, description = "ETag of the record as last fetched. The write fails with 412 if the record has changed since"),
   )
 )]
-#[tracing::instrument(skip(pg_pool))]
+#[tracing::instrument(skip(pg_pool, redis_pool))]
 pub async fn update_contact(
+    req: HttpRequest,
     req_payload: web::Json<UpdateContactReqPayload>,
     path: web::Path<PrefixedUuid<ContactPrefix>>,
     org_user: OwnerMember,
     pg_pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
 ) -> Result<HttpResponse, actix_web::Error> {
+    req_payload.validate()?;
     let contact_id = path.into_inner();
+    let expected_updated_at = if has_if_match(&req) {
+        let current = get_contact_by_id_query(contact_id, pg_pool.clone()).await?;
+        check_if_match(&req, current.updated_at)?
+    } else {
+        None
+    };
+    let first_name = required("first_name", req_payload.first_name.clone())?;
+    let last_name = required("last_name", req_payload.last_name.clone())?;
+    validate_owner(
+        req_payload.owner_user_id.flatten(),
+        org_user.org_id,
+        pg_pool.clone(),
+    )
+    .await?;
     let contact = update_contact_query(
         contact_id,
-        req_payload.first_name.clone(),
-        req_payload.last_name.clone(),
+        first_name,
+        last_name,
+        req_payload.owner_user_id,
+        expected_updated_at,
         pg_pool,
     )
     .await?;
-    Ok(HttpResponse::Ok().json(contact))
+    publish_change_event(
+        org_user.org_id,
+        ChangeEntity::Contact,
+        ChangeAction::Updated,
+        contact.id,
+        redis_pool,
+    )
+    .await;
+    Ok(HttpResponse::Ok()
+        .insert_header(etag(contact.updated_at))
+        .json(contact))
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
@@ -161,6 +272,7 @@ pub struct ContactList {
 pub struct ListContactsQuery {
     pub limit: Option<i64>,
     pub offset: Option<String>,
+    pub owner: Option<String>,
 }
 
 #[utoipa::path(
@@ -178,6 +290,7 @@ pub struct ListContactsQuery {
   params(
     ("limit" = Option<i64>, Query, description = "The number of contacts to return"),
     ("offset" = Option<String>, Query, description = "The offset to start from"),
+    ("owner" = Option<String>, Query, description = "Only return records owned by this user id, or `me` for the calling user"),
     ("Organization" = String, Header, description = "The org id to use for the request"),
   )
 )]
@@ -188,7 +301,9 @@ pub async fn list_contacts(
     pg_pool: web::Data<PgPool>,
 ) -> Result<HttpResponse, actix_web::Error> {
     let offset = PrefixedUuid::<ContactPrefix>::from_optional_str(query.offset.clone())?;
+    let owner_user_id = parse_owner_filter(query.owner.clone(), org_user.user_id)?;
     let (contacts, total) =
-        get_contacts_by_org_id_query(org_user.org_id, pg_pool, query.limit, offset).await?;
+        get_contacts_by_org_id_query(org_user.org_id, owner_user_id, pg_pool, query.limit, offset)
+            .await?;
     Ok(HttpResponse::Ok().json(ContactList { contacts, total }))
 }
@@ -1,25 +1,83 @@
-use super::auth_handler::OwnerMember;
+use super::auth_handler::{parse_owner_filter, validate_owner, AuthedUser, OwnerMember};
 use crate::{
-    data::models::{Contact, Deal, DealContact, PgPool},
+    data::{
+        currency::{currencies, parse_currency},
+        etag::{check_if_match, etag, has_if_match},
+        models::{
+            AverageDealSizeRow, ChangeAction, ChangeEntity, Contact, Deal, DealContact,
+            ForecastReportRow, PgPool, PipelineReportRow, RedisPool,
+        },
+        patch::{deserialize_patch, required, Patch},
+        validation::{Validate, Validator, MAX_DEAL_AMOUNT, MAX_NAME_LENGTH},
+    },
+    errors::ServiceError,
     operators::{
         contact_operator::get_contacts_by_deal_id_query,
         deal_operator::{
             create_contact_for_deal_query, create_deal_query, delete_contact_from_deal_query,
-            delete_deal_query, get_deal_by_id_query, list_deals_by_org_id_query, update_deal_query,
+            delete_deal_query, get_deal_by_id_query, get_forecast_report_query,
+            get_performance_report_query, get_pipeline_report_query, list_deals_by_org_id_query,
+            update_deal_query,
         },
+        event_operator::publish_change_event,
+        plan_operator::check_deal_quota,
     },
-    prefixes::{ContactPrefix, DealPrefix, PrefixedUuid},
+    prefixes::{ContactPrefix, DealPrefix, PrefixedUuid, UserPrefix},
 };
-use actix_web::{web, HttpResponse};
+use actix_web::{web, HttpRequest, HttpResponse};
+use diesel_async::{scoped_futures::ScopedFutureExt, AsyncConnection};
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 use utoipa::ToSchema;
 
 #[derive(Debug, Deserialize, Serialize, ToSchema)]
 pub struct CreateDealReqPayload {
-    size: Option<f32>,
+    /// Deal value in the minor unit of the currency (e.g. cents for USD).
+    #[schema(minimum = 0, maximum = 1000000000000000)]
+    amount: Option<i64>,
+    /// ISO 4217 currency code. Defaults to the organization's default currency.
+    currency: Option<String>,
+    /// Likelihood of the deal closing as won, from 0 to 100.
+    #[schema(minimum = 0, maximum = 100)]
+    probability: Option<i32>,
+    #[schema(example = "2021-03-31")]
+    expected_close_date: Option<chrono::NaiveDate>,
+    #[schema(min_length = 1, max_length = 255)]
     name: Option<String>,
     active: Option<bool>,
+    /// Contacts to link to the deal. The deal isn't created if any of them can't be linked.
+    #[schema(max_items = 100)]
+    contact_ids: Option<Vec<PrefixedUuid<ContactPrefix>>>,
+}
+
+/// Most contacts that can be linked while creating a deal.
+const MAX_DEAL_CONTACTS: usize = 100;
+
+impl Validate for CreateDealReqPayload {
+    fn validate(&self) -> Result<(), ServiceError> {
+        let mut validator = Validator::default();
+        if let Some(contact_ids) = &self.contact_ids {
+            if contact_ids.len() > MAX_DEAL_CONTACTS {
+                validator.reject(
+                    "contact_ids",
+                    format!("Must have at most {} contacts", MAX_DEAL_CONTACTS),
+                );
+            }
+        }
+        if let Some(name) = &self.name {
+            validator.non_empty("name", name, MAX_NAME_LENGTH);
+        }
+        if let Some(amount) = self.amount {
+            validator.range("amount", amount, 0, MAX_DEAL_AMOUNT);
+        }
+        if let Some(probability) = self.probability {
+            validator.range("probability", probability, 0, 100);
+        }
+        if let Some(expected_close_date) = self.expected_close_date {
+            validator.date("expected_close_date", expected_close_date);
+        }
+        validator.finish()
+    }
 }
 
 #[utoipa::path(
@@ -31,28 +89,63 @@ pub struct CreateDealReqPayload {
   responses(
       (status = 201, description = "JSON body representing the deal that was created", body = Deal),
       (status = 401, description = "Service error relating to authentication status of the user", body = ErrorRespPayload),
+      (status = 409, description = "Service error when a request with the same Idempotency-Key is still being processed", body = ErrorRespPayload),
+      (status = 422, description = "Service error when the Idempotency-Key was already used for a different request", body = ErrorRespPayload),
+      (status = 400, description = "Service error relating to the request payload", body = ErrorRespPayload),
+      (status = 402, description = "Error indicating that the organization has reached a limit of its plan", body = ErrorRespPayload),
   ),
   security(
       ("ApiKey" = ["readonly"]),
   ),
   params(
     ("Organization" = String, Header, description = "The org id to use for the request"),
+    ("Idempotency-Key" = Option<String>, Header, description = "Unique key for this create. Retrying with the same key replays the first response instead of creating a duplicate"),
   )
 )]
-#[tracing::instrument(skip(pg_pool))]
+#[tracing::instrument(skip(pg_pool, redis_pool))]
 pub async fn create_deal(
     req_payload: web::Json<CreateDealReqPayload>,
     org_user: OwnerMember,
     pg_pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
 ) -> Result<HttpResponse, actix_web::Error> {
-    let deal = create_deal_query(
+    req_payload.validate()?;
+    let currency = req_payload
+        .currency
+        .as_deref()
+        .map(parse_currency)
+        .transpose()?;
+    let mut conn = pg_pool.get().await.map_err(ServiceError::from)?;
+    // The quota check and the insert share a transaction so they see the same deals
+    let deal = conn
+        .transaction::<_, ServiceError, _>(|conn| {
+            async move {
+                check_deal_quota(org_user.org_id, conn).await?;
+                create_deal_query(
+                    org_user.org_id,
+                    req_payload.name.clone(),
+                    req_payload.amount,
+                    currency,
+                    req_payload.probability,
+                    req_payload.expected_close_date,
+                    req_payload.active.unwrap_or_default(),
+                    Some(org_user.user_id),
+                    req_payload.contact_ids.clone().unwrap_or_default(),
+                    conn,
+                )
+                .await
+            }
+            .scope_boxed()
+        })
+        .await?;
+    publish_change_event(
         org_user.org_id,
-        req_payload.name.clone(),
-        req_payload.size,
-        req_payload.active.unwrap_or_default(),
-        pg_pool,
+        ChangeEntity::Deal,
+        ChangeAction::Created,
+        deal.id,
+        redis_pool,
     )
-    .await?;
+    .await;
     Ok(HttpResponse::Created().json(deal))
 }
 
@@ -64,25 +157,42 @@ pub async fn create_deal(
   responses(
       (status = 204, description = "No content response indicating that the deal was successfully deleted"),
       (status = 401, description = "Service error relating to authentication status of the user", body = ErrorRespPayload),
+      (status = 412, description = "The record was changed since the ETag in If-Match was issued", body = ErrorRespPayload),
   ),
   params(
     ("deal_id" = String, description = "The deal id to use for the request"),
-    ("Organization" = String, Header, description = "The org id to use for the request")
+    ("Organization" = String, Header, description = "The org id to use for the request"),
+    ("If-Match" = Option<String>, Header, description = "ETag of the record as last fetched. The write fails with 412 if the record has changed since"),
   ),
   security(
       ("ApiKey" = ["readonly"]),
   )
 )]
-#[tracing::instrument(skip(pg_pool))]
+#[tracing::instrument(skip(pg_pool, redis_pool))]
 pub async fn delete_deal(
+    req: HttpRequest,
     org_user: OwnerMember,
     path: web::Path<PrefixedUuid<DealPrefix>>,
     pg_pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
 ) -> Result<HttpResponse, actix_web::Error> {
     let deal_id = path.into_inner();
-    delete_deal_query(deal_id, pg_pool)
-        .await
-        .map(|_| Ok(HttpResponse::NoContent().finish()))?
+    let expected_updated_at = if has_if_match(&req) {
+        let current = get_deal_by_id_query(deal_id, pg_pool.clone()).await?;
+        check_if_match(&req, current.updated_at)?
+    } else {
+        None
+    };
+    delete_deal_query(deal_id, expected_updated_at, pg_pool).await?;
+    publish_change_event(
+        org_user.org_id,
+        ChangeEntity::Deal,
+        ChangeAction::Deleted,
+        deal_id,
+        redis_pool,
+    )
+    .await;
+    Ok(HttpResponse::NoContent().finish())
 }
 
 #[utoipa::path(
@@ -91,7 +201,7 @@ pub async fn delete_deal(
   context_path = "/api",
   tag = "deals",
   responses(
-      (status = 200, description = "JSON object representing the requested deal", body = Deal),
+      (status = 200, description = "JSON object representing the requested deal", body = Deal, headers(("ETag" = String, description = "Version of the record. Send it back in If-Match to make a write conditional"))),
       (status = 401, description = "Service error relating to authentication status of the user", body = ErrorRespPayload),
   ),
   security(
@@ -110,53 +220,140 @@ pub async fn get_deal(
 ) -> Result<HttpResponse, actix_web::Error> {
     let deal_id = path.into_inner();
     match get_deal_by_id_query(deal_id, pg_pool).await {
-        Ok(deal) => Ok(HttpResponse::Ok().json(deal)),
-        Err(_) => Ok(HttpResponse::NotFound().finish()),
+        Ok(deal) => Ok(HttpResponse::Ok()
+            .insert_header(etag(deal.updated_at))
+            .json(deal)),
+        Err(_) => Err(ServiceError::NotFound.into()),
     }
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct UpdateDealReqPayload {
-    name: Option<String>,
-    size: Option<f32>,
-    active: Option<bool>,
+    #[serde(default, deserialize_with = "deserialize_patch")]
+    #[schema(value_type = Option<String>, min_length = 1, max_length = 255)]
+    name: Patch<String>,
+    /// Deal value in the minor unit of the currency (e.g. cents for USD).
+    #[serde(default, deserialize_with = "deserialize_patch")]
+    #[schema(value_type = Option<i64>, minimum = 0, maximum = 1000000000000000)]
+    amount: Patch<i64>,
+    /// ISO 4217 currency code. Changing it requires sending `amount` in the new
+    /// currency, unless the deal has no amount.
+    #[serde(default, deserialize_with = "deserialize_patch")]
+    #[schema(value_type = Option<String>)]
+    currency: Patch<String>,
+    /// Likelihood of the deal closing as won, from 0 to 100.
+    #[serde(default, deserialize_with = "deserialize_patch")]
+    #[schema(value_type = Option<i32>, minimum = 0, maximum = 100)]
+    probability: Patch<i32>,
+    #[serde(default, deserialize_with = "deserialize_patch")]
+    #[schema(value_type = Option<chrono::NaiveDate>, example = "2021-03-31")]
+    expected_close_date: Patch<chrono::NaiveDate>,
+    /// Closes the deal as won or lost and records when it was closed. Null reopens the deal.
+    #[serde(default, deserialize_with = "deserialize_patch")]
+    #[schema(value_type = Option<bool>)]
+    won: Patch<bool>,
+    #[serde(default, deserialize_with = "deserialize_patch")]
+    #[schema(value_type = Option<bool>)]
+    active: Patch<bool>,
+    /// Reassigns the deal to another member of the organization.
+    #[serde(default, deserialize_with = "deserialize_patch")]
+    #[schema(value_type = Option<PrefixedUuid<UserPrefix>>)]
+    owner_user_id: Patch<PrefixedUuid<UserPrefix>>,
+}
+
+impl Validate for UpdateDealReqPayload {
+    fn validate(&self) -> Result<(), ServiceError> {
+        let mut validator = Validator::default();
+        if let Some(Some(name)) = &self.name {
+            validator.non_empty("name", name, MAX_NAME_LENGTH);
+        }
+        if let Some(Some(amount)) = self.amount {
+            validator.range("amount", amount, 0, MAX_DEAL_AMOUNT);
+        }
+        if let Some(Some(probability)) = self.probability {
+            validator.range("probability", probability, 0, 100);
+        }
+        if let Some(Some(expected_close_date)) = self.expected_close_date {
+            validator.date("expected_close_date", expected_close_date);
+        }
+        validator.finish()
+    }
 }
 
 #[utoipa::path(
-  put,
+  patch,
   path = "/deals/{deal_id}",
   context_path = "/api",
   tag = "deals",
-  request_body(content = UpdateDealReqPayload, description = "JSON request payload to update the deal", content_type = "application/json"),
+  request_body(content = UpdateDealReqPayload, description = "JSON merge patch to update the deal. Omitted fields are left alone and null fields are cleared.", content_type = "application/json"),
   responses(
       (status = 200, description = "Object representing the renamed deal", body = Deal),
       (status = 401, description = "Service error relating to authentication status of the user", body = ErrorRespPayload),
+      (status = 400, description = "Service error relating to the request payload", body = ErrorRespPayload),
+      (status = 412, description = "The record was changed since the ETag in If-Match was issued", body = ErrorRespPayload),
   ),
   security(
       ("ApiKey" = ["readonly"]),
   ),
   params(
     ("deal_id" = String, description = "The deal id to use for the request"),
-    ("Organization" = String, Header, description = "The org id to use for the request")
+    ("Organization" = String, Header, description = "The org id to use for the request"),
+    ("If-Match" = Option<String>, Header, description = "ETag of the record as last fetched. The write fails with 412 if the record has changed since"),
   )
 )]
-#[tracing::instrument(skip(pg_pool))]
+#[tracing::instrument(skip(pg_pool, redis_pool))]
 pub async fn update_deal(
+    req: HttpRequest,
     req_payload: web::Json<UpdateDealReqPayload>,
     path: web::Path<PrefixedUuid<DealPrefix>>,
     org_user: OwnerMember,
     pg_pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
 ) -> Result<HttpResponse, actix_web::Error> {
+    req_payload.validate()?;
     let deal_id = path.into_inner();
+    let expected_updated_at = if has_if_match(&req) {
+        let current = get_deal_by_id_query(deal_id, pg_pool.clone()).await?;
+        check_if_match(&req, current.updated_at)?
+    } else {
+        None
+    };
+    let currency = required("currency", req_payload.currency.clone())?
+        .as_deref()
+        .map(parse_currency)
+        .transpose()?;
+    let active = required("active", req_payload.active)?;
+    validate_owner(
+        req_payload.owner_user_id.flatten(),
+        org_user.org_id,
+        pg_pool.clone(),
+    )
+    .await?;
     let deal = update_deal_query(
         deal_id,
         req_payload.name.clone(),
-        req_payload.size,
-        req_payload.active,
+        req_payload.amount,
+        currency,
+        req_payload.probability,
+        req_payload.expected_close_date,
+        req_payload.won,
+        active,
+        req_payload.owner_user_id,
+        expected_updated_at,
         pg_pool,
     )
     .await?;
-    Ok(HttpResponse::Ok().json(deal))
+    publish_change_event(
+        org_user.org_id,
+        ChangeEntity::Deal,
+        ChangeAction::Updated,
+        deal.id,
+        redis_pool,
+    )
+    .await;
+    Ok(HttpResponse::Ok()
+        .insert_header(etag(deal.updated_at))
+        .json(deal))
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
@@ -197,14 +394,16 @@ pub enum DealResourceList {
 #[tracing::instrument(skip(pg_pool))]
 pub async fn create_deal_resource(
     path: web::Path<(PrefixedUuid<DealPrefix>, DealResType, String)>,
-    _org_user: OwnerMember,
+    org_user: OwnerMember,
     pg_pool: web::Data<PgPool>,
 ) -> Result<HttpResponse, actix_web::Error> {
     let (deal_id, resource, resource_id) = path.into_inner();
     match resource {
         DealResType::Contact => {
             let contact_id = PrefixedUuid::<ContactPrefix>::from_str(&resource_id)?;
-            let deal_contact = create_contact_for_deal_query(deal_id, contact_id, pg_pool).await?;
+            let deal_contact =
+                create_contact_for_deal_query(deal_id, org_user.org_id, contact_id, pg_pool)
+                    .await?;
             Ok(HttpResponse::Ok().json(DealResource::Contact(deal_contact)))
         }
     }
@@ -216,6 +415,13 @@ pub struct ListDealResourceQuery {
     offset: Option<String>,
 }
 
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ListDealsQuery {
+    limit: Option<i64>,
+    offset: Option<String>,
+    owner: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize, ToSchema, Clone)]
 pub struct DealResourceListWithPagination {
     pub data: DealResourceList,
@@ -315,6 +521,7 @@ pub struct ListDealByOrgRespBody {
   params(
     ("limit" = Option<i64>, Query, description = "The number of records to return"),
     ("offset" = Option<String>, Query, description = "The UUID of the record to start from"),
+    ("owner" = Option<String>, Query, description = "Only return records owned by this user id, or `me` for the calling user"),
     ("Organization" = String, Header, description = "The organization id to use for the request"),
   ),
   security(
@@ -322,14 +529,187 @@ pub struct ListDealByOrgRespBody {
   ),
 )]
 pub async fn list_deal_by_org(
-    query: web::Query<ListDealResourceQuery>,
+    query: web::Query<ListDealsQuery>,
     pg_pool: web::Data<PgPool>,
     org_user: OwnerMember,
 ) -> Result<HttpResponse, actix_web::Error> {
     let offset = PrefixedUuid::<DealPrefix>::from_optional_str(query.offset.clone())?;
-    let deals = list_deals_by_org_id_query(org_user.org_id, pg_pool, query.limit, offset).await?;
+    let owner_user_id = parse_owner_filter(query.owner.clone(), org_user.user_id)?;
+    let deals =
+        list_deals_by_org_id_query(org_user.org_id, owner_user_id, pg_pool, query.limit, offset)
+            .await?;
     Ok(HttpResponse::Ok().json(ListDealByOrgRespBody {
         deals: deals.0,
         total: deals.1,
     }))
 }
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct DealReportQuery {
+    from: Option<chrono::NaiveDate>,
+    to: Option<chrono::NaiveDate>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, Clone)]
+pub struct PipelineReport {
+    pub rows: Vec<PipelineReportRow>,
+}
+
+#[utoipa::path(
+  get,
+  path = "/deals/reports/pipeline",
+  context_path = "/api",
+  tag = "deals",
+  responses(
+      (status = 200, description = "Open pipeline value by expected close month, active flag and currency", body = PipelineReport),
+      (status = 401, description = "Service error relating to authentication status of the user", body = ErrorRespPayload),
+      (status = 400, description = "Service error relating to the request payload", body = ErrorRespPayload),
+  ),
+  params(
+    ("from" = Option<chrono::NaiveDate>, Query, description = "Only include deals expected to close on or after this date"),
+    ("to" = Option<chrono::NaiveDate>, Query, description = "Only include deals expected to close on or before this date"),
+    ("Organization" = String, Header, description = "The organization id to use for the request"),
+  ),
+  security(
+      ("ApiKey" = ["readonly"]),
+  ),
+)]
+#[tracing::instrument(skip(pg_pool))]
+pub async fn get_pipeline_report(
+    query: web::Query<DealReportQuery>,
+    org_user: OwnerMember,
+    pg_pool: web::Data<PgPool>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let rows = get_pipeline_report_query(org_user.org_id, query.from, query.to, pg_pool).await?;
+    Ok(HttpResponse::Ok().json(PipelineReport { rows }))
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, Clone)]
+pub struct ForecastReport {
+    pub rows: Vec<ForecastReportRow>,
+}
+
+#[utoipa::path(
+  get,
+  path = "/deals/reports/forecast",
+  context_path = "/api",
+  tag = "deals",
+  responses(
+      (status = 200, description = "Probability weighted forecast of open, active deals by expected close month and currency", body = ForecastReport),
+      (status = 401, description = "Service error relating to authentication status of the user", body = ErrorRespPayload),
+      (status = 400, description = "Service error relating to the request payload", body = ErrorRespPayload),
+  ),
+  params(
+    ("from" = Option<chrono::NaiveDate>, Query, description = "Only include deals expected to close on or after this date"),
+    ("to" = Option<chrono::NaiveDate>, Query, description = "Only include deals expected to close on or before this date"),
+    ("Organization" = String, Header, description = "The organization id to use for the request"),
+  ),
+  security(
+      ("ApiKey" = ["readonly"]),
+  ),
+)]
+#[tracing::instrument(skip(pg_pool))]
+pub async fn get_forecast_report(
+    query: web::Query<DealReportQuery>,
+    org_user: OwnerMember,
+    pg_pool: web::Data<PgPool>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let rows = get_forecast_report_query(org_user.org_id, query.from, query.to, pg_pool).await?;
+    Ok(HttpResponse::Ok().json(ForecastReport { rows }))
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, Clone)]
+#[schema(example = json!({
+    "won_count": 3,
+    "lost_count": 1,
+    "win_rate": 0.75,
+    "average_sales_cycle_days": 42.5,
+    "average_deal_size": [{"currency": "USD", "deal_count": 3, "average_amount": 125000}],
+}))]
+pub struct PerformanceReport {
+    pub won_count: i64,
+    pub lost_count: i64,
+    /// Share of closed deals that were won, from 0 to 1. Null when no deals were closed.
+    pub win_rate: Option<f64>,
+    /// Average number of days from creation to close for won deals.
+    pub average_sales_cycle_days: Option<f64>,
+    /// Average amount of won deals, per currency.
+    pub average_deal_size: Vec<AverageDealSizeRow>,
+}
+
+#[utoipa::path(
+  get,
+  path = "/deals/reports/performance",
+  context_path = "/api",
+  tag = "deals",
+  responses(
+      (status = 200, description = "Win rate, average deal size and sales cycle length for deals closed in the date range", body = PerformanceReport),
+      (status = 401, description = "Service error relating to authentication status of the user", body = ErrorRespPayload),
+      (status = 400, description = "Service error relating to the request payload", body = ErrorRespPayload),
+  ),
+  params(
+    ("from" = Option<chrono::NaiveDate>, Query, description = "Only include deals closed on or after this date"),
+    ("to" = Option<chrono::NaiveDate>, Query, description = "Only include deals closed on or before this date"),
+    ("Organization" = String, Header, description = "The organization id to use for the request"),
+  ),
+  security(
+      ("ApiKey" = ["readonly"]),
+  ),
+)]
+#[tracing::instrument(skip(pg_pool))]
+pub async fn get_performance_report(
+    query: web::Query<DealReportQuery>,
+    org_user: OwnerMember,
+    pg_pool: web::Data<PgPool>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let (stats, average_deal_size) =
+        get_performance_report_query(org_user.org_id, query.from, query.to, pg_pool).await?;
+    let closed_count = stats.won_count + stats.lost_count;
+    let win_rate = if closed_count > 0 {
+        Some(stats.won_count as f64 / closed_count as f64)
+    } else {
+        None
+    };
+    Ok(HttpResponse::Ok().json(PerformanceReport {
+        won_count: stats.won_count,
+        lost_count: stats.lost_count,
+        win_rate,
+        average_sales_cycle_days: stats.average_sales_cycle_days,
+        average_deal_size,
+    }))
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, Clone)]
+#[schema(example = json!({"code": "JPY", "minor_unit_exponent": 0}))]
+pub struct Currency {
+    /// ISO 4217 currency code.
+    pub code: String,
+    /// Number of decimal places in the minor unit amounts are stored in, e.g. 2 for
+    /// USD and 0 for JPY.
+    pub minor_unit_exponent: u32,
+}
+
+#[utoipa::path(
+  get,
+  path = "/deals/currencies",
+  context_path = "/api",
+  tag = "deals",
+  responses(
+      (status = 200, description = "Currencies deals can be in, with the minor unit their amounts are stored in", body = Vec<Currency>),
+      (status = 401, description = "Service error relating to authentication status of the user", body = ErrorRespPayload),
+  ),
+  security(
+      ("ApiKey" = ["readonly"]),
+  ),
+)]
+#[tracing::instrument]
+pub async fn list_currencies(_user: AuthedUser) -> Result<HttpResponse, actix_web::Error> {
+    Ok(HttpResponse::Ok().json(
+        currencies()
+            .map(|(code, minor_unit_exponent)| Currency {
+                code: code.to_string(),
+                minor_unit_exponent,
+            })
+            .collect::<Vec<_>>(),
+    ))
+}
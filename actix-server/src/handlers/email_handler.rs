@@ -1,20 +1,40 @@
 use super::auth_handler::OwnerMember;
 use crate::{
-    data::models::PgPool,
-    operators::email_db_operator::{
-        create_email_query, delete_email_query, get_email_by_id_query, update_email_query,
+    data::{
+        etag::{check_if_match, etag, has_if_match},
+        models::{ChangeAction, ChangeEntity, PgPool, RedisPool},
+        redact::redacted_debug,
+        validation::{Validate, Validator, MAX_EMAIL_LENGTH},
+    },
+    errors::ServiceError,
+    operators::{
+        email_db_operator::{
+            create_email_query, delete_email_query, get_email_by_id_query, update_email_query,
+        },
+        event_operator::publish_change_event,
     },
     prefixes::{EmailPrefix, PrefixedUuid},
 };
-use actix_web::{web, HttpResponse};
+use actix_web::{web, HttpRequest, HttpResponse};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
-#[derive(Debug, Deserialize, Serialize, ToSchema)]
+#[derive(Deserialize, Serialize, ToSchema)]
 pub struct CreateEmailReqPayload {
+    #[schema(max_length = 254)]
     email: String,
 }
 
+redacted_debug!(CreateEmailReqPayload {} pii { email });
+
+impl Validate for CreateEmailReqPayload {
+    fn validate(&self) -> Result<(), ServiceError> {
+        let mut validator = Validator::default();
+        validator.max_length("email", &self.email, MAX_EMAIL_LENGTH);
+        validator.finish()
+    }
+}
+
 #[utoipa::path(
   post,
   path = "/emails",
@@ -24,21 +44,34 @@ pub struct CreateEmailReqPayload {
   responses(
       (status = 201, description = "JSON body representing the email that was created", body = Email),
       (status = 401, description = "Service error relating to authentication status of the user", body = ErrorRespPayload),
+      (status = 409, description = "Service error when a request with the same Idempotency-Key is still being processed", body = ErrorRespPayload),
+      (status = 422, description = "Service error when the Idempotency-Key was already used for a different request", body = ErrorRespPayload),
   ),
   params(
     ("Organization" = String, Header, description = "The org id to use for the request"),
+    ("Idempotency-Key" = Option<String>, Header, description = "Unique key for this create. Retrying with the same key replays the first response instead of creating a duplicate"),
   ),
   security(
       ("ApiKey" = ["readonly"]),
   )
 )]
-#[tracing::instrument(skip(pg_pool))]
+#[tracing::instrument(skip(pg_pool, redis_pool))]
 pub async fn create_email(
     req_payload: web::Json<CreateEmailReqPayload>,
     org_user: OwnerMember,
     pg_pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
 ) -> Result<HttpResponse, actix_web::Error> {
+    req_payload.validate()?;
     let email = create_email_query(org_user.org_id, req_payload.email.clone(), pg_pool).await?;
+    publish_change_event(
+        org_user.org_id,
+        ChangeEntity::Email,
+        ChangeAction::Created,
+        email.id,
+        redis_pool,
+    )
+    .await;
     Ok(HttpResponse::Created().json(email))
 }
 
@@ -50,25 +83,42 @@ pub async fn create_email(
   responses(
       (status = 204, description = "No content response indicating that the email was successfully deleted"),
       (status = 401, description = "Service error relating to authentication status of the user", body = ErrorRespPayload),
+      (status = 412, description = "The record was changed since the ETag in If-Match was issued", body = ErrorRespPayload),
   ),
   params(
     ("email_id" = String, description = "The email id to use for the request"),
     ("Organization" = String, Header, description = "The org id to use for the request"),
+    ("If-Match" = Option<String>, Header, description = "ETag of the record as last fetched. The write fails with 412 if the record has changed since"),
   ),
   security(
       ("ApiKey" = ["readonly"]),
   )
 )]
-#[tracing::instrument(skip(pg_pool))]
+#[tracing::instrument(skip(pg_pool, redis_pool))]
 pub async fn delete_email(
+    req: HttpRequest,
     org_user: OwnerMember,
     path: web::Path<PrefixedUuid<EmailPrefix>>,
     pg_pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
 ) -> Result<HttpResponse, actix_web::Error> {
     let email_id = path.into_inner();
-    delete_email_query(email_id, pg_pool)
-        .await
-        .map(|_| Ok(HttpResponse::NoContent().finish()))?
+    let expected_updated_at = if has_if_match(&req) {
+        let current = get_email_by_id_query(email_id, pg_pool.clone()).await?;
+        check_if_match(&req, current.updated_at)?
+    } else {
+        None
+    };
+    delete_email_query(email_id, expected_updated_at, pg_pool).await?;
+    publish_change_event(
+        org_user.org_id,
+        ChangeEntity::Email,
+        ChangeAction::Deleted,
+        email_id,
+        redis_pool,
+    )
+    .await;
+    Ok(HttpResponse::NoContent().finish())
 }
 
 #[utoipa::path(
@@ -77,7 +127,7 @@ pub async fn delete_email(
   context_path = "/api",
   tag = "emails",
   responses(
-      (status = 200, description = "JSON object representing the requested email", body = Email),
+      (status = 200, description = "JSON object representing the requested email", body = Email, headers(("ETag" = String, description = "Version of the record. Send it back in If-Match to make a write conditional"))),
       (status = 401, description = "Service error relating to authentication status of the user", body = ErrorRespPayload),
   ),
   security(
@@ -96,16 +146,31 @@ pub async fn get_email(
 ) -> Result<HttpResponse, actix_web::Error> {
     let email_id = path.into_inner();
     match get_email_by_id_query(email_id, pg_pool).await {
-        Ok(email) => Ok(HttpResponse::Ok().json(email)),
-        Err(_) => Ok(HttpResponse::NotFound().finish()),
+        Ok(email) => Ok(HttpResponse::Ok()
+            .insert_header(etag(email.updated_at))
+            .json(email)),
+        Err(_) => Err(ServiceError::NotFound.into()),
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct UpdateEmailReqPayload {
+    #[schema(max_length = 254)]
     email: Option<String>,
 }
 
+redacted_debug!(UpdateEmailReqPayload {} pii { email });
+
+impl Validate for UpdateEmailReqPayload {
+    fn validate(&self) -> Result<(), ServiceError> {
+        let mut validator = Validator::default();
+        if let Some(email) = &self.email {
+            validator.max_length("email", email, MAX_EMAIL_LENGTH);
+        }
+        validator.finish()
+    }
+}
+
 #[utoipa::path(
   put,
   path = "/emails/{email_id}",
@@ -115,6 +180,7 @@ pub struct UpdateEmailReqPayload {
   responses(
       (status = 200, description = "Object representing the renamed email", body = Email),
       (status = 401, description = "Service error relating to authentication status of the user", body = ErrorRespPayload),
+      (status = 412, description = "The record was changed since the ETag in If-Match was issued", body = ErrorRespPayload),
   ),
   security(
       ("ApiKey" = ["readonly"]),
@@ -122,16 +188,42 @@ pub struct UpdateEmailReqPayload {
   params(
     ("email_id" = String, description = "The email id to use for the request"),
     ("Organization" = String, Header, description = "The org id to use for the request"),
+    ("If-Match" = Option<String>, Header, description = "ETag of the record as last fetched. The write fails with 412 if the record has changed since"),
   ),
 )]
-#[tracing::instrument(skip(pg_pool))]
+#[tracing::instrument(skip(pg_pool, redis_pool))]
 pub async fn update_email(
+    req: HttpRequest,
     req_payload: web::Json<UpdateEmailReqPayload>,
     path: web::Path<PrefixedUuid<EmailPrefix>>,
     org_user: OwnerMember,
     pg_pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
 ) -> Result<HttpResponse, actix_web::Error> {
+    req_payload.validate()?;
     let email_id = path.into_inner();
-    let email = update_email_query(email_id, req_payload.email.clone(), pg_pool).await?;
-    Ok(HttpResponse::Ok().json(email))
+    let expected_updated_at = if has_if_match(&req) {
+        let current = get_email_by_id_query(email_id, pg_pool.clone()).await?;
+        check_if_match(&req, current.updated_at)?
+    } else {
+        None
+    };
+    let email = update_email_query(
+        email_id,
+        req_payload.email.clone(),
+        expected_updated_at,
+        pg_pool,
+    )
+    .await?;
+    publish_change_event(
+        org_user.org_id,
+        ChangeEntity::Email,
+        ChangeAction::Updated,
+        email.id,
+        redis_pool,
+    )
+    .await;
+    Ok(HttpResponse::Ok()
+        .insert_header(etag(email.updated_at))
+        .json(email))
 }
@@ -0,0 +1,131 @@
+use super::auth_handler::{AuthedMember, AuthedUser};
+use crate::{
+    data::models::{ChangeEvent, ChangeEventSender, PgPool, RedisPool},
+    errors::ServiceError,
+    operators::{
+        event_operator::{get_change_events_since_query, parse_event_id},
+        org_operator::get_org_user_link_query,
+    },
+    prefixes::{OrgPrefix, PrefixedUuid},
+};
+use actix_web::{web, HttpRequest, HttpResponse};
+use futures::StreamExt;
+use serde::Deserialize;
+use tokio::sync::broadcast::error::RecvError;
+use utoipa::IntoParams;
+
+/// How long a stream may stay silent before a comment is sent to keep proxies from closing it.
+const KEEP_ALIVE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(15);
+
+fn format_event(event: &ChangeEvent) -> web::Bytes {
+    web::Bytes::from(format!(
+        "id: {}\ndata: {}\n\n",
+        event.event_id,
+        serde_json::to_string(event).unwrap_or_default()
+    ))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct StreamEventsReqQuery {
+    /// Organization to stream events for. Browsers can't set headers on an `EventSource`, so this can be used instead of the `Organization` header.
+    org_id: Option<PrefixedUuid<OrgPrefix>>,
+}
+
+/// Stream Org Changes
+///
+/// Server-sent events stream of changes to the records of an organization. Each message carries a ChangeEvent as its data and the event id as its SSE id. Reconnecting with `Last-Event-ID` replays the retained events that were missed. Clients that fall too far behind are disconnected and should reconnect the same way.
+#[utoipa::path(
+  get,
+  path = "/events/stream",
+  context_path = "/api",
+  tag = "events",
+  responses(
+      (status = 200, description = "text/event-stream of change events in the organization", body = ChangeEvent, content_type = "text/event-stream"),
+      (status = 401, description = "Service error relating to authentication status of the user", body = ErrorRespPayload),
+      (status = 400, description = "Service error relating to the Last-Event-ID header", body = ErrorRespPayload),
+  ),
+  params(
+    ("Organization" = Option<String>, Header, description = "The organization id to use for the request"),
+    ("Last-Event-ID" = Option<String>, Header, description = "Id of the last event the client received. Retained events after it are sent first."),
+    StreamEventsReqQuery,
+  ),
+  security(
+      ("ApiKey" = ["readonly"]),
+  )
+)]
+#[tracing::instrument(skip(change_events, redis_pool, pg_pool))]
+pub async fn stream_events(
+    req: HttpRequest,
+    query: web::Query<StreamEventsReqQuery>,
+    authed_user: AuthedUser,
+    org_member: Option<AuthedMember>,
+    change_events: web::Data<ChangeEventSender>,
+    redis_pool: web::Data<RedisPool>,
+    pg_pool: web::Data<PgPool>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let org_id = match (query.org_id, org_member) {
+        (Some(org_id), _) => {
+            get_org_user_link_query(authed_user.id, org_id, pg_pool)
+                .await
+                .map_err(|_| ServiceError::Unauthorized)?;
+            org_id
+        }
+        (None, Some(org_member)) => org_member.org_id,
+        (None, None) => return Err(ServiceError::Unauthorized.into()),
+    };
+
+    // Subscribe before replaying so nothing published in between is lost
+    let receiver = change_events.subscribe();
+
+    let last_event_id = req
+        .headers()
+        .get("Last-Event-ID")
+        .and_then(|last_event_id| last_event_id.to_str().ok())
+        .map(|last_event_id| last_event_id.trim().to_string())
+        .filter(|last_event_id| !last_event_id.is_empty());
+
+    let missed_events = match last_event_id.as_deref() {
+        Some(last_event_id) => {
+            get_change_events_since_query(org_id, last_event_id, redis_pool).await?
+        }
+        None => vec![],
+    };
+
+    // Live events at or before the last replayed one were already sent
+    let replayed_up_to = missed_events
+        .last()
+        .map(|event| event.event_id.as_str())
+        .or(last_event_id.as_deref())
+        .and_then(parse_event_id);
+
+    let replay = futures::stream::iter(
+        missed_events
+            .into_iter()
+            .map(|event| Ok::<_, actix_web::Error>(format_event(&event))),
+    );
+
+    let live = futures::stream::unfold(receiver, move |mut receiver| async move {
+        loop {
+            match actix_web::rt::time::timeout(KEEP_ALIVE_INTERVAL, receiver.recv()).await {
+                Err(_) => {
+                    return Some((Ok(web::Bytes::from_static(b": keep-alive\n\n")), receiver))
+                }
+                Ok(Ok(event)) => {
+                    let already_sent = parse_event_id(&event.event_id) <= replayed_up_to;
+                    if event.org_id == org_id && !already_sent {
+                        return Some((Ok(format_event(&event)), receiver));
+                    }
+                }
+                // A slow client is dropped rather than silently skipping events. It
+                // reconnects with Last-Event-ID and gets what it missed replayed
+                Ok(Err(RecvError::Lagged(_))) | Ok(Err(RecvError::Closed)) => return None,
+            }
+        }
+    });
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header(("Cache-Control", "no-cache"))
+        .insert_header(("X-Accel-Buffering", "no"))
+        .streaming(replay.chain(live)))
+}
@@ -0,0 +1,184 @@
+use crate::{
+    data::models::{PgPool, RedisPool},
+    errors::ServiceError,
+    metrics,
+    operators::{
+        health_operator::{ping_database_query, ping_redis_query},
+        job_operator::count_jobs_by_status_query,
+    },
+    settings,
+    workers::{WorkerHealth, WorkerStatus},
+};
+use actix_web::{http::header::AUTHORIZATION, web, HttpRequest, HttpResponse};
+use serde::{Deserialize, Serialize};
+use std::{future::Future, time::Instant};
+use utoipa::ToSchema;
+
+/// How long a dependency may take to answer before it counts as unavailable.
+const CHECK_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(2);
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthStatus {
+    Ok,
+    Unavailable,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[schema(example = json!({"status": "ok", "latency_ms": 3}))]
+pub struct DependencyHealth {
+    pub status: HealthStatus,
+    /// How long the check took, in milliseconds.
+    pub latency_ms: u64,
+    /// Why the dependency is unavailable.
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ReadinessRespPayload {
+    /// `ok` when every dependency is and the server isn't shutting down.
+    pub status: HealthStatus,
+    pub database: DependencyHealth,
+    pub redis: DependencyHealth,
+    /// Background workers of this instance. Informational, a failing worker doesn't
+    /// make the server unready.
+    pub workers: Vec<WorkerHealth>,
+}
+
+async fn check_dependency(
+    check: impl Future<Output = Result<(), ServiceError>>,
+) -> DependencyHealth {
+    let started = Instant::now();
+    let error = match actix_web::rt::time::timeout(CHECK_TIMEOUT, check).await {
+        Ok(Ok(())) => None,
+        Ok(Err(ServiceError::ServiceUnavailable(message))) => Some(message),
+        Ok(Err(e)) => Some(e.to_string()),
+        Err(_) => Some(format!(
+            "No response within {} seconds",
+            CHECK_TIMEOUT.as_secs()
+        )),
+    };
+
+    DependencyHealth {
+        status: match error {
+            None => HealthStatus::Ok,
+            Some(_) => HealthStatus::Unavailable,
+        },
+        latency_ms: started.elapsed().as_millis() as u64,
+        error,
+    }
+}
+
+/// Health Check
+///
+/// Same as the liveness check, kept for existing clients.
+#[utoipa::path(
+    get,
+    path = "/health",
+    context_path = "/api",
+    tag = "health",
+    responses(
+        (status = 200, description = "Confirmation that the service is healthy"),
+        (status = 400, description = "Service error relating to overall service health", body = ErrorRespPayload),
+    ),
+)]
+#[tracing::instrument]
+pub async fn health_check() -> Result<HttpResponse, actix_web::Error> {
+    Ok(HttpResponse::Ok().finish())
+}
+
+/// Liveness Check
+///
+/// Confirms the process is up and serving requests. Doesn't touch the database or Redis, so an outage of either doesn't get the server restarted.
+#[utoipa::path(
+    get,
+    path = "/health/live",
+    context_path = "/api",
+    tag = "health",
+    responses(
+        (status = 200, description = "The server is running"),
+    ),
+)]
+#[tracing::instrument]
+pub async fn liveness_check() -> Result<HttpResponse, actix_web::Error> {
+    Ok(HttpResponse::Ok().finish())
+}
+
+/// Readiness Check
+///
+/// Checks that the database and Redis answer within two seconds and that the server isn't shutting down. Load balancers should only route traffic to the server while this returns 200.
+#[utoipa::path(
+    get,
+    path = "/health/ready",
+    context_path = "/api",
+    tag = "health",
+    responses(
+        (status = 200, description = "Every dependency is available", body = ReadinessRespPayload),
+        (status = 503, description = "At least one dependency is unavailable", body = ReadinessRespPayload),
+    ),
+)]
+#[tracing::instrument(skip(pg_pool, redis_pool, worker_status))]
+pub async fn readiness_check(
+    pg_pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
+    worker_status: web::Data<WorkerStatus>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let (database, redis) = futures::join!(
+        check_dependency(ping_database_query(pg_pool)),
+        check_dependency(ping_redis_query(redis_pool)),
+    );
+
+    let status = if database.status == HealthStatus::Ok
+        && redis.status == HealthStatus::Ok
+        && !worker_status.is_shutting_down()
+    {
+        HealthStatus::Ok
+    } else {
+        HealthStatus::Unavailable
+    };
+    let payload = ReadinessRespPayload {
+        status,
+        database,
+        redis,
+        workers: worker_status.health(),
+    };
+
+    Ok(match status {
+        HealthStatus::Ok => HttpResponse::Ok().json(payload),
+        HealthStatus::Unavailable => HttpResponse::ServiceUnavailable().json(payload),
+    })
+}
+
+/// Prometheus metrics. Needs `Authorization: Bearer <METRICS_TOKEN>` when a token is
+/// configured. Kept out of ApiDoc on purpose.
+pub async fn get_metrics(
+    req: HttpRequest,
+    pg_pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
+) -> Result<HttpResponse, ServiceError> {
+    let settings = settings::get();
+    if let Some(token) = &settings.metrics_token {
+        let authorized = req
+            .headers()
+            .get(AUTHORIZATION)
+            .and_then(|header| header.to_str().ok())
+            .and_then(|header| header.strip_prefix("Bearer "))
+            .is_some_and(|bearer| bearer == token.expose());
+        if !authorized {
+            return Err(ServiceError::Unauthorized);
+        }
+    }
+
+    let metrics = metrics::get();
+    metrics.record_pools(&pg_pool, &redis_pool, settings.redis_connections);
+    // A scrape while the database is down still reports everything else
+    match count_jobs_by_status_query(pg_pool).await {
+        Ok(counts) => metrics.record_jobs(&counts),
+        Err(e) => log::warn!("Could not count jobs for metrics: {:?}", e),
+    }
+    let (content_type, body) = metrics.render().map_err(|e| {
+        ServiceError::InternalServerError(format!("Could not render metrics: {}", e))
+    })?;
+
+    Ok(HttpResponse::Ok().content_type(content_type).body(body))
+}
@@ -1,24 +1,30 @@
 use super::auth_handler::{AdminMember, AuthedMember};
 use crate::{
-    data::models::{Invitation, PgPool},
+    data::{
+        models::{Invitation, PgPool},
+        redact::redacted_debug,
+        validation::{Validate, Validator, MAX_EMAIL_LENGTH},
+    },
     errors::ServiceError,
     operators::{
         invitation_operator::{
-            create_invitation_query, delete_invitation_by_id_query, get_invitation_by_id_query,
-            get_invitations_for_organization_query, send_invitation,
+            delete_invitation_by_id_query, get_invitation_by_id_query,
+            get_invitations_for_organization_query, insert_invitation,
         },
-        org_operator::add_user_to_org_query,
+        org_operator::add_user_to_org,
+        plan_operator::check_user_quota,
         user_operator::get_user_by_email_query,
     },
     prefixes::{OrgPrefix, PrefixedUuid},
 };
 use actix_web::{web, HttpResponse};
+use diesel_async::{scoped_futures::ScopedFutureExt, AsyncConnection, AsyncPgConnection};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
 #[tracing::instrument]
 pub fn email_regex() -> regex::Regex {
-    regex::Regex::new(r"^[a-zA-Z0-9.!#$%&’*+/=?^_`{|}~-]+@[a-zA-Z0-9-]+(?:\.[a-zA-Z0-9-]+)*")
+    regex::Regex::new(r"^[a-zA-Z0-9.!#$%&’*+/=?^_`{|}~-]+@[a-zA-Z0-9-]+(?:\.[a-zA-Z0-9-]+)*$")
         .unwrap()
 }
 
@@ -27,20 +33,40 @@ pub struct InvitationResponse {
     pub registration_url: String,
 }
 
-#[derive(Deserialize, ToSchema, Serialize, Clone, Debug)]
+#[derive(Deserialize, ToSchema, Serialize, Clone)]
 pub struct InvitationData {
     /// The id of the organization to invite the user to.
     pub organization_id: PrefixedUuid<OrgPrefix>,
     /// The role the user will have in the organization. 0 = User, 1 = Admin, 2 = Owner.
+    #[schema(minimum = 0, maximum = 2)]
     pub user_role: i32,
     /// The email of the user to invite. Must be a valid email as they will be sent an email to register.
+    #[schema(max_length = 254)]
     pub email: String,
     /// The url of the app that the user will be directed to in order to set their password. Usually admin.trieve.ai, but may differ for local dev or self-hosted setups.
+    #[schema(max_length = 2048)]
     pub app_url: String,
     /// The url that the user will be redirected to after setting their password.
+    #[schema(max_length = 2048)]
     pub redirect_uri: String,
 }
 
+redacted_debug!(InvitationData { organization_id, user_role, app_url, redirect_uri } pii { email });
+
+impl Validate for InvitationData {
+    fn validate(&self) -> Result<(), ServiceError> {
+        let mut validator = Validator::default();
+        validator.range("user_role", self.user_role, 0, 2);
+        if !email_regex().is_match(&self.email) {
+            validator.reject("email", "Invalid email");
+        }
+        validator.max_length("email", &self.email, MAX_EMAIL_LENGTH);
+        validator.url("app_url", &self.app_url);
+        validator.url("redirect_uri", &self.redirect_uri);
+        validator.finish()
+    }
+}
+
 /// Send Invitation
 ///
 /// Invitations act as a way to invite users to join an organization. After a user is invited, they will automatically be added to the organization with the role specified in the invitation once they set their.
@@ -51,8 +77,10 @@ pub struct InvitationData {
     tag = "invitation",
     request_body(content = InvitationData, description = "JSON request payload to send an invitation", content_type = "application/json"),
     responses(
-        (status = 204, description = "Ok response. Indicates that invitation email was sent correctly."),
+        (status = 204, description = "Ok response. Indicates that the invitation was created and its email queued."),
         (status = 400, description = "Invalid email or some other error", body = ErrorRespPayload),
+        (status = 402, description = "Error indicating that the organization has reached the member limit of its plan", body = ErrorRespPayload),
+        (status = 429, description = "Too many invitations were sent recently. Retry-After says when to try again", body = ErrorRespPayload),
     ),
     params(
         ("Organization" = String, Header, description = "The organization id to use for the request"),
@@ -67,11 +95,9 @@ pub async fn post_invitation(
     pool: web::Data<PgPool>,
     user: AuthedMember,
 ) -> Result<HttpResponse, ServiceError> {
+    invitation_data.validate()?;
     let invitation_data = invitation_data.into_inner();
     let email = invitation_data.email;
-    if !email_regex().is_match(&email) {
-        return Err(ServiceError::BadRequest("Invalid email".to_string()));
-    }
 
     let org_role = user.role;
 
@@ -87,57 +113,57 @@ pub async fn post_invitation(
     let db_user_result: Result<crate::data::models::User, ServiceError> =
         get_user_by_email_query(email.as_str(), pool.clone()).await;
 
-    if let Ok(db_user) = db_user_result {
-        add_user_to_org_query(
-            db_user.id,
-            existing_user_org_id,
-            existing_user_role.into(),
-            pool.clone(),
-        )
-        .await?;
-    }
-
-    let invitation = create_invitation(
-        invitation_data.app_url,
-        email.clone(),
-        invitation_data.organization_id,
-        invitation_data.redirect_uri,
-        invitation_data.user_role,
-        pool,
-    )
+    let mut conn = pool.get().await?;
+
+    // An existing user is only added to the org if the invitation is created too
+    conn.transaction::<_, ServiceError, _>(|conn| {
+        async move {
+            check_user_quota(existing_user_org_id, true, conn).await?;
+
+            if let Ok(db_user) = db_user_result {
+                add_user_to_org(
+                    db_user.id,
+                    existing_user_org_id,
+                    existing_user_role.into(),
+                    conn,
+                )
+                .await?;
+            }
+
+            create_invitation(
+                invitation_data.app_url,
+                email,
+                invitation_data.organization_id,
+                invitation_data.redirect_uri,
+                invitation_data.user_role,
+                conn,
+            )
+            .await
+        }
+        .scope_boxed()
+    })
     .await?;
 
-    send_invitation(invitation.registration_url, invitation.invitation).await?;
-
     Ok(HttpResponse::NoContent().finish())
 }
 
-pub struct InvitationWithUrl {
-    invitation: Invitation,
-    registration_url: String,
-}
-
-#[tracing::instrument(skip(pool))]
+#[tracing::instrument(skip(conn, email))]
 pub async fn create_invitation(
     app_url: String,
     email: String,
     organization_id: PrefixedUuid<OrgPrefix>,
     redirect_uri: String,
     user_role: i32,
-    pool: web::Data<PgPool>,
-) -> Result<InvitationWithUrl, ServiceError> {
-    let invitation = create_invitation_query(email, organization_id, user_role, pool).await?;
-    // send_invitation(app_url, &invitation)
+    conn: &mut AsyncPgConnection,
+) -> Result<Invitation, ServiceError> {
+    let invitation = Invitation::from_details(email, organization_id, user_role);
 
     //TODO:figure out how to get redirect_uri
     let registration_url = format!(
         "{}/auth?inv_code={}&organization_id={}&redirect_uri={}",
         app_url, invitation.id, organization_id, redirect_uri
     );
-    Ok(InvitationWithUrl {
-        invitation,
-        registration_url,
-    })
+    insert_invitation(invitation, registration_url, conn).await
 }
 
 /// Get Invitations
@@ -1,20 +1,38 @@
 use super::auth_handler::OwnerMember;
 use crate::{
-    data::models::PgPool,
-    operators::link_operator::{
-        create_link_query, delete_link_query, get_link_by_id_query, update_link_query,
+    data::{
+        etag::{check_if_match, etag, has_if_match},
+        models::{ChangeAction, ChangeEntity, PgPool, RedisPool},
+        validation::{Validate, Validator},
+    },
+    errors::ServiceError,
+    operators::{
+        event_operator::publish_change_event,
+        link_operator::{
+            create_link_query, delete_link_query, get_link_by_id_query, update_link_query,
+        },
     },
     prefixes::{LinkPrefix, PrefixedUuid},
 };
-use actix_web::{web, HttpResponse};
+use actix_web::{web, HttpRequest, HttpResponse};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
 #[derive(Debug, Deserialize, Serialize, ToSchema)]
 pub struct CreateLinkReqPayload {
+    /// Absolute http or https URL.
+    #[schema(max_length = 2048)]
     link: String,
 }
 
+impl Validate for CreateLinkReqPayload {
+    fn validate(&self) -> Result<(), ServiceError> {
+        let mut validator = Validator::default();
+        validator.url("link", &self.link);
+        validator.finish()
+    }
+}
+
 #[utoipa::path(
   post,
   path = "/links",
@@ -24,21 +42,34 @@ pub struct CreateLinkReqPayload {
   responses(
       (status = 201, description = "JSON body representing the link that was created", body = Link),
       (status = 401, description = "Service error relating to authentication status of the user", body = ErrorRespPayload),
+      (status = 409, description = "Service error when a request with the same Idempotency-Key is still being processed", body = ErrorRespPayload),
+      (status = 422, description = "Service error when the Idempotency-Key was already used for a different request", body = ErrorRespPayload),
   ),
   params(
       ("Organization" = String, Header, description = "The org id to use for the request"),
+      ("Idempotency-Key" = Option<String>, Header, description = "Unique key for this create. Retrying with the same key replays the first response instead of creating a duplicate"),
   ),
   security(
       ("ApiKey" = ["readonly"]),
   )
 )]
-#[tracing::instrument(skip(pg_pool))]
+#[tracing::instrument(skip(pg_pool, redis_pool))]
 pub async fn create_link(
     req_payload: web::Json<CreateLinkReqPayload>,
     org_user: OwnerMember,
     pg_pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
 ) -> Result<HttpResponse, actix_web::Error> {
+    req_payload.validate()?;
     let link = create_link_query(org_user.org_id, req_payload.link.clone(), pg_pool).await?;
+    publish_change_event(
+        org_user.org_id,
+        ChangeEntity::Link,
+        ChangeAction::Created,
+        link.id,
+        redis_pool,
+    )
+    .await;
     Ok(HttpResponse::Created().json(link))
 }
 
@@ -50,25 +81,42 @@ pub async fn create_link(
   responses(
       (status = 204, description = "No content response indicating that the link was successfully deleted"),
       (status = 401, description = "Service error relating to authentication status of the user", body = ErrorRespPayload),
+      (status = 412, description = "The record was changed since the ETag in If-Match was issued", body = ErrorRespPayload),
   ),
   params(
     ("link_id" = String, description = "The link id to use for the request"),
     ("Organization" = String, Header, description = "The org id to use for the request"),
+    ("If-Match" = Option<String>, Header, description = "ETag of the record as last fetched. The write fails with 412 if the record has changed since"),
   ),
   security(
       ("ApiKey" = ["readonly"]),
   )
 )]
-#[tracing::instrument(skip(pg_pool))]
+#[tracing::instrument(skip(pg_pool, redis_pool))]
 pub async fn delete_link(
+    req: HttpRequest,
     org_user: OwnerMember,
     path: web::Path<PrefixedUuid<LinkPrefix>>,
     pg_pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
 ) -> Result<HttpResponse, actix_web::Error> {
     let link_id = path.into_inner();
-    delete_link_query(link_id, pg_pool)
-        .await
-        .map(|_| Ok(HttpResponse::NoContent().finish()))?
+    let expected_updated_at = if has_if_match(&req) {
+        let current = get_link_by_id_query(link_id, pg_pool.clone()).await?;
+        check_if_match(&req, current.updated_at)?
+    } else {
+        None
+    };
+    delete_link_query(link_id, expected_updated_at, pg_pool).await?;
+    publish_change_event(
+        org_user.org_id,
+        ChangeEntity::Link,
+        ChangeAction::Deleted,
+        link_id,
+        redis_pool,
+    )
+    .await;
+    Ok(HttpResponse::NoContent().finish())
 }
 
 #[utoipa::path(
@@ -77,7 +125,7 @@ pub async fn delete_link(
   context_path = "/api",
   tag = "links",
   responses(
-      (status = 200, description = "JSON object representing the requested link", body = Link),
+      (status = 200, description = "JSON object representing the requested link", body = Link, headers(("ETag" = String, description = "Version of the record. Send it back in If-Match to make a write conditional"))),
       (status = 401, description = "Service error relating to authentication status of the user", body = ErrorRespPayload),
   ),
   security(
@@ -96,16 +144,30 @@ pub async fn get_link(
 ) -> Result<HttpResponse, actix_web::Error> {
     let link_id = path.into_inner();
     match get_link_by_id_query(link_id, pg_pool).await {
-        Ok(link) => Ok(HttpResponse::Ok().json(link)),
-        Err(_) => Ok(HttpResponse::NotFound().finish()),
+        Ok(link) => Ok(HttpResponse::Ok()
+            .insert_header(etag(link.updated_at))
+            .json(link)),
+        Err(_) => Err(ServiceError::NotFound.into()),
     }
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct UpdateLinkReqPayload {
+    /// Absolute http or https URL.
+    #[schema(max_length = 2048)]
     link: Option<String>,
 }
 
+impl Validate for UpdateLinkReqPayload {
+    fn validate(&self) -> Result<(), ServiceError> {
+        let mut validator = Validator::default();
+        if let Some(link) = &self.link {
+            validator.url("link", link);
+        }
+        validator.finish()
+    }
+}
+
 #[utoipa::path(
   put,
   path = "/links/{link_id}",
@@ -115,6 +177,7 @@ pub struct UpdateLinkReqPayload {
   responses(
       (status = 200, description = "Object representing the renamed link", body = Link),
       (status = 401, description = "Service error relating to authentication status of the user", body = ErrorRespPayload),
+      (status = 412, description = "The record was changed since the ETag in If-Match was issued", body = ErrorRespPayload),
   ),
   security(
       ("ApiKey" = ["readonly"]),
@@ -122,16 +185,42 @@ pub struct UpdateLinkReqPayload {
   params(
     ("link_id" = String, description = "The link id to use for the request"),
     ("Organization" = String, Header, description = "The org id to use for the request"),
+    ("If-Match" = Option<String>, Header, description = "ETag of the record as last fetched. The write fails with 412 if the record has changed since"),
   ),
 )]
-#[tracing::instrument(skip(pg_pool))]
+#[tracing::instrument(skip(pg_pool, redis_pool))]
 pub async fn update_link(
+    req: HttpRequest,
     req_payload: web::Json<UpdateLinkReqPayload>,
     path: web::Path<PrefixedUuid<LinkPrefix>>,
     org_user: OwnerMember,
     pg_pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
 ) -> Result<HttpResponse, actix_web::Error> {
+    req_payload.validate()?;
     let link_id = path.into_inner();
-    let link = update_link_query(link_id, req_payload.link.clone(), pg_pool).await?;
-    Ok(HttpResponse::Ok().json(link))
+    let expected_updated_at = if has_if_match(&req) {
+        let current = get_link_by_id_query(link_id, pg_pool.clone()).await?;
+        check_if_match(&req, current.updated_at)?
+    } else {
+        None
+    };
+    let link = update_link_query(
+        link_id,
+        req_payload.link.clone(),
+        expected_updated_at,
+        pg_pool,
+    )
+    .await?;
+    publish_change_event(
+        org_user.org_id,
+        ChangeEntity::Link,
+        ChangeAction::Updated,
+        link.id,
+        redis_pool,
+    )
+    .await;
+    Ok(HttpResponse::Ok()
+        .insert_header(etag(link.updated_at))
+        .json(link))
 }
@@ -1,12 +1,18 @@
+pub mod activity_handler;
+pub mod admin_handler;
 pub mod api_key_handler;
 pub mod auth_handler;
+pub mod billing_handler;
 pub mod company_handler;
 pub mod contact_handler;
 pub mod deal_handler;
 pub mod email_handler;
+pub mod event_handler;
+pub mod health_handler;
 pub mod invitation_handler;
 pub mod link_handler;
 pub mod note_handler;
 pub mod org_handler;
 pub mod phone_handler;
+pub mod resolve_handler;
 pub mod task_handler;
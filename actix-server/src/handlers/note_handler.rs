@@ -1,21 +1,38 @@
 use super::auth_handler::AuthedMember;
 use crate::{
-    data::models::PgPool,
-    operators::note_operator::{
-        create_note_query, delete_note_query, get_note_by_id_query, get_notes_for_org_query,
-        update_note_body_query,
+    data::{
+        etag::{check_if_match, etag, has_if_match},
+        models::{ChangeAction, ChangeEntity, PgPool, RedisPool},
+        validation::{Validate, Validator, MAX_NAME_LENGTH, MAX_TEXT_LENGTH},
+    },
+    errors::ServiceError,
+    operators::{
+        event_operator::publish_change_event,
+        note_operator::{
+            create_note_query, delete_note_query, get_note_by_id_query, get_notes_for_org_query,
+            update_note_body_query,
+        },
     },
     prefixes::{NotePrefix, PrefixedUuid},
 };
-use actix_web::{web, HttpResponse};
+use actix_web::{web, HttpRequest, HttpResponse};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct CreateNoteReqPayload {
+    #[schema(min_length = 1, max_length = 255)]
     title: String,
 }
 
+impl Validate for CreateNoteReqPayload {
+    fn validate(&self) -> Result<(), ServiceError> {
+        let mut validator = Validator::default();
+        validator.non_empty("title", &self.title, MAX_NAME_LENGTH);
+        validator.finish()
+    }
+}
+
 #[utoipa::path(
   post,
   path = "/notes",
@@ -25,22 +42,35 @@ pub struct CreateNoteReqPayload {
   responses(
       (status = 201, description = "JSON body representing the note that was created", body = Note),
       (status = 401, description = "Service error relating to authentication status of the user", body = ErrorRespPayload),
+      (status = 409, description = "Service error when a request with the same Idempotency-Key is still being processed", body = ErrorRespPayload),
+      (status = 422, description = "Service error when the Idempotency-Key was already used for a different request", body = ErrorRespPayload),
   ),
   params(
     ("Organization" = String, Header, description = "The organization id to use for the request"),
+    ("Idempotency-Key" = Option<String>, Header, description = "Unique key for this create. Retrying with the same key replays the first response instead of creating a duplicate"),
   ),
   security(
       ("ApiKey" = ["readonly"]),
   )
 )]
-#[tracing::instrument(skip(pg_pool))]
+#[tracing::instrument(skip(pg_pool, redis_pool))]
 pub async fn create_note(
     req_payload: web::Json<CreateNoteReqPayload>,
     org_member: AuthedMember,
     pg_pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
 ) -> Result<HttpResponse, actix_web::Error> {
+    req_payload.validate()?;
     let title = req_payload.title.clone();
     let note = create_note_query(title, org_member.org_id, pg_pool).await?;
+    publish_change_event(
+        org_member.org_id,
+        ChangeEntity::Note,
+        ChangeAction::Created,
+        note.id,
+        redis_pool,
+    )
+    .await;
     Ok(HttpResponse::Created().json(note))
 }
 
@@ -52,33 +82,61 @@ pub async fn create_note(
   responses(
       (status = 204, description = "No content response indicating that the note was successfully deleted"),
       (status = 401, description = "Service error relating to authentication status of the user", body = ErrorRespPayload),
+      (status = 412, description = "The record was changed since the ETag in If-Match was issued", body = ErrorRespPayload),
   ),
   params(
     ("Organization" = String, Header, description = "The organization id to use for the request"),
     ("note_id" = String, Path, description = "The id of the note you want to delete."),
+    ("If-Match" = Option<String>, Header, description = "ETag of the record as last fetched. The write fails with 412 if the record has changed since"),
   ),
   security(
       ("ApiKey" = ["readonly"]),
   )
 )]
-#[tracing::instrument(skip(pg_pool))]
+#[tracing::instrument(skip(pg_pool, redis_pool))]
 pub async fn delete_note(
+    req: HttpRequest,
     org_user: AuthedMember,
     note_id: web::Path<PrefixedUuid<NotePrefix>>,
     pg_pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
 ) -> Result<HttpResponse, actix_web::Error> {
     let note_id = note_id.into_inner();
+    let expected_updated_at = if has_if_match(&req) {
+        let current = get_note_by_id_query(note_id, pg_pool.clone()).await?;
+        check_if_match(&req, current.updated_at)?
+    } else {
+        None
+    };
 
-    delete_note_query(note_id, pg_pool).await?;
+    delete_note_query(note_id, expected_updated_at, pg_pool).await?;
+
+    publish_change_event(
+        org_user.org_id,
+        ChangeEntity::Note,
+        ChangeAction::Deleted,
+        note_id,
+        redis_pool,
+    )
+    .await;
 
     return Ok(HttpResponse::NoContent().finish());
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct UpdateNoteReqPayload {
+    #[schema(max_length = 10000)]
     body: String,
 }
 
+impl Validate for UpdateNoteReqPayload {
+    fn validate(&self) -> Result<(), ServiceError> {
+        let mut validator = Validator::default();
+        validator.max_length("body", &self.body, MAX_TEXT_LENGTH);
+        validator.finish()
+    }
+}
+
 #[utoipa::path(
   put,
   path = "/notes/{note_id}",
@@ -88,29 +146,51 @@ pub struct UpdateNoteReqPayload {
   responses(
       (status = 200, description = "Object representing the renamed note", body = Note),
       (status = 401, description = "Service error relating to authentication status of the user", body = ErrorRespPayload),
+      (status = 412, description = "The record was changed since the ETag in If-Match was issued", body = ErrorRespPayload),
   ),
   params(
     ("Organization" = String, Header, description = "The organization id to use for the request"),
     ("note_id" = String, Path, description = "The id of the note you want to update."),
+    ("If-Match" = Option<String>, Header, description = "ETag of the record as last fetched. The write fails with 412 if the record has changed since"),
   ),
   security(
       ("ApiKey" = ["readonly"]),
   )
 )]
-#[tracing::instrument(skip(pg_pool))]
+#[tracing::instrument(skip(pg_pool, redis_pool))]
 pub async fn update_note(
+    req: HttpRequest,
     req_payload: web::Json<UpdateNoteReqPayload>,
     note_id: web::Path<PrefixedUuid<NotePrefix>>,
     org_member: AuthedMember,
     pg_pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
 ) -> Result<HttpResponse, actix_web::Error> {
+    req_payload.validate()?;
     let note_id = note_id.into_inner();
+    let expected_updated_at = if has_if_match(&req) {
+        let current = get_note_by_id_query(note_id, pg_pool.clone()).await?;
+        check_if_match(&req, current.updated_at)?
+    } else {
+        None
+    };
 
     let new_body = req_payload.body.clone();
 
-    let new_note = update_note_body_query(note_id, new_body, pg_pool).await?;
+    let new_note = update_note_body_query(note_id, new_body, expected_updated_at, pg_pool).await?;
+
+    publish_change_event(
+        org_member.org_id,
+        ChangeEntity::Note,
+        ChangeAction::Updated,
+        new_note.id,
+        redis_pool,
+    )
+    .await;
 
-    Ok(HttpResponse::Ok().json(new_note))
+    Ok(HttpResponse::Ok()
+        .insert_header(etag(new_note.updated_at))
+        .json(new_note))
 }
 
 #[derive(Debug, Deserialize)]
@@ -155,7 +235,7 @@ pub async fn get_notes_for_org(
   context_path = "/api",
   tag = "notes",
   responses(
-      (status = 200, description = "JSON object representing the requested note", body = Note),
+      (status = 200, description = "JSON object representing the requested note", body = Note, headers(("ETag" = String, description = "Version of the record. Send it back in If-Match to make a write conditional"))),
       (status = 401, description = "Service error relating to authentication status of the user", body = ErrorRespPayload),
   ),
   params(
@@ -177,8 +257,10 @@ pub async fn get_note_by_id(
     let note = get_note_by_id_query(note_id, pg_pool).await?;
 
     if note.org_id != org_member.org_id {
-        return Ok(HttpResponse::Unauthorized().finish());
+        return Err(ServiceError::Unauthorized.into());
     }
 
-    Ok(HttpResponse::Ok().json(note))
+    Ok(HttpResponse::Ok()
+        .insert_header(etag(note.updated_at))
+        .json(note))
 }
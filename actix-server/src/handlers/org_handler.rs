@@ -1,19 +1,40 @@
-use super::auth_handler::{AuthedMember, AuthedUser, OwnerMember};
+use super::auth_handler::{validate_owner, AdminMember, AuthedMember, AuthedUser, OwnerMember};
 use crate::{
-    data::models::{Org, PgPool, UserRole},
-    operators::org_operator::{
-        create_org_query, delete_org_query, get_orgs_for_user_query, remove_user_from_org_query,
-        update_org_query, user_in_org_query,
+    data::{
+        currency::{parse_currency, DEFAULT_CURRENCY},
+        etag::{check_if_match, etag},
+        models::{Org, PgPool, RedisPool, UserRole},
+        validation::{Validate, Validator, MAX_NAME_LENGTH},
     },
-    prefixes::{OrgPrefix, PrefixedUuid},
+    errors::ServiceError,
+    operators::{
+        auth_cache_operator::invalidate_org_user_cache_query,
+        org_operator::{
+            create_org_query, delete_org_query, get_orgs_for_user_query, reassign_records_query,
+            remove_user_from_org_query, update_org_query, user_in_org_query,
+        },
+        plan_operator::get_org_usage_query,
+    },
+    prefixes::{OrgPrefix, PrefixedUuid, UserPrefix},
 };
-use actix_web::{web, HttpResponse};
+use actix_web::{web, HttpRequest, HttpResponse};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct CreateOrgReqPayload {
+    #[schema(min_length = 1, max_length = 255)]
     name: String,
+    /// ISO 4217 code used for new deals that don't specify a currency. Defaults to USD.
+    default_currency: Option<String>,
+}
+
+impl Validate for CreateOrgReqPayload {
+    fn validate(&self) -> Result<(), ServiceError> {
+        let mut validator = Validator::default();
+        validator.non_empty("name", &self.name, MAX_NAME_LENGTH);
+        validator.finish()
+    }
 }
 
 #[utoipa::path(
@@ -25,9 +46,15 @@ pub struct CreateOrgReqPayload {
   responses(
       (status = 201, description = "JSON body representing the organization that was created", body = Org),
       (status = 401, description = "Service error relating to authentication status of the user", body = ErrorRespPayload),
+      (status = 409, description = "Service error when a request with the same Idempotency-Key is still being processed", body = ErrorRespPayload),
+      (status = 422, description = "Service error when the Idempotency-Key was already used for a different request", body = ErrorRespPayload),
+      (status = 400, description = "Service error relating to the request payload", body = ErrorRespPayload),
   ),
   security(
       ("ApiKey" = ["readonly"]),
+  ),
+  params(
+    ("Idempotency-Key" = Option<String>, Header, description = "Unique key for this create. Retrying with the same key replays the first response instead of creating a duplicate"),
   )
 )]
 #[tracing::instrument(skip(pg_pool))]
@@ -36,8 +63,18 @@ pub async fn create_org(
     authed_user: AuthedUser,
     pg_pool: web::Data<PgPool>,
 ) -> Result<HttpResponse, actix_web::Error> {
+    req_payload.validate()?;
     let name = req_payload.name.clone();
-    let org = create_org_query(name, authed_user, pg_pool).await?;
+    let default_currency = parse_currency(
+        req_payload
+            .default_currency
+            .as_deref()
+            .unwrap_or(DEFAULT_CURRENCY),
+    )
+    .map_err(|_| {
+        ServiceError::invalid_field("default_currency", "Not a valid ISO 4217 currency code")
+    })?;
+    let org = create_org_query(name, default_currency, authed_user, pg_pool).await?;
 
     Ok(HttpResponse::Created().json(org))
 }
@@ -50,33 +87,41 @@ pub async fn create_org(
   responses(
       (status = 204, description = "No content response indicating that the organization was successfully deleted"),
       (status = 401, description = "Service error relating to authentication status of the user", body = ErrorRespPayload),
+      (status = 412, description = "The record was changed since the ETag in If-Match was issued", body = ErrorRespPayload),
   ),
   params(
     ("Organization" = String, Header, description = "The organization id to use for the request"),
     ("org_id" = String, Path, description = "The id of the organization you want to fetch."),
+    ("If-Match" = Option<String>, Header, description = "ETag of the record as last fetched. The write fails with 412 if the record has changed since"),
   ),
   security(
       ("ApiKey" = ["readonly"]),
   )
 )]
-#[tracing::instrument(skip(pg_pool))]
+#[tracing::instrument(skip(pg_pool, redis_pool))]
 pub async fn delete_org(
+    req: HttpRequest,
     authed_user: AuthedUser,
     org_user: OwnerMember,
     path: web::Path<PrefixedUuid<OrgPrefix>>,
     pg_pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
 ) -> Result<HttpResponse, actix_web::Error> {
     let org_id = path.into_inner();
 
     if org_user.org_id != org_id {
-        return Ok(HttpResponse::Unauthorized().finish());
+        return Err(ServiceError::Unauthorized.into());
     }
 
     match user_in_org_query(org_id, authed_user.id, pg_pool.clone()).await? {
-        Some(org) => delete_org_query(org.id, pg_pool)
-            .await
-            .map(|_| Ok(HttpResponse::NoContent().finish()))?,
-        None => Ok(HttpResponse::Unauthorized().finish()),
+        Some(org) => {
+            let expected_updated_at = check_if_match(&req, org.updated_at)?;
+            delete_org_query(org.id, expected_updated_at, pg_pool).await?;
+            invalidate_org_user_cache_query(org.id, None, redis_pool).await;
+
+            Ok(HttpResponse::NoContent().finish())
+        }
+        None => Err(ServiceError::Unauthorized.into()),
     }
 }
 
@@ -98,21 +143,27 @@ pub async fn delete_org(
       ("ApiKey" = ["readonly"]),
   )
 )]
-#[tracing::instrument(skip(pg_pool))]
+#[tracing::instrument(skip(pg_pool, redis_pool))]
 pub async fn leave_org(
     org_user: AuthedMember,
     path: web::Path<PrefixedUuid<OrgPrefix>>,
     pg_pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
 ) -> Result<HttpResponse, actix_web::Error> {
     let org_id = path.into_inner();
 
     if org_user.role == UserRole::Owner {
-        return Ok(HttpResponse::BadRequest().finish());
+        return Err(ServiceError::BadRequest(
+            "Owners can't leave an organization they own".to_string(),
+        )
+        .into());
     }
 
-    remove_user_from_org_query(org_id, org_user.user_id, pg_pool)
-        .await
-        .map(|_| Ok(HttpResponse::NoContent().finish()))?
+    let reassigned = remove_user_from_org_query(org_id, org_user.user_id, pg_pool).await?;
+    invalidate_org_user_cache_query(org_id, Some(org_user.user_id), redis_pool.clone()).await;
+    reassigned.publish_updated(org_id, redis_pool).await;
+
+    Ok(HttpResponse::NoContent().finish())
 }
 
 #[utoipa::path(
@@ -121,7 +172,7 @@ pub async fn leave_org(
   context_path = "/api",
   tag = "orgs",
   responses(
-      (status = 200, description = "JSON object representing the requested organization", body = Org),
+      (status = 200, description = "JSON object representing the requested organization", body = Org, headers(("ETag" = String, description = "Version of the record. Send it back in If-Match to make a write conditional"))),
       (status = 401, description = "Service error relating to authentication status of the user", body = ErrorRespPayload),
   ),
   params(
@@ -141,14 +192,27 @@ pub async fn get_org(
     let org_id = path.into_inner();
 
     match user_in_org_query(org_id, org_member.user_id, pg_pool).await? {
-        Some(org) => Ok(HttpResponse::Ok().json(org)),
-        None => Ok(HttpResponse::Unauthorized().finish()),
+        Some(org) => Ok(HttpResponse::Ok()
+            .insert_header(etag(org.updated_at))
+            .json(org)),
+        None => Err(ServiceError::Unauthorized.into()),
     }
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct UpdateOrgReqPayload {
+    #[schema(min_length = 1, max_length = 255)]
     name: String,
+    /// ISO 4217 code used for new deals that don't specify a currency.
+    default_currency: Option<String>,
+}
+
+impl Validate for UpdateOrgReqPayload {
+    fn validate(&self) -> Result<(), ServiceError> {
+        let mut validator = Validator::default();
+        validator.non_empty("name", &self.name, MAX_NAME_LENGTH);
+        validator.finish()
+    }
 }
 
 #[utoipa::path(
@@ -160,10 +224,13 @@ pub struct UpdateOrgReqPayload {
   responses(
       (status = 200, description = "Object representing the renamed organization", body = Org),
       (status = 401, description = "Service error relating to authentication status of the user", body = ErrorRespPayload),
+      (status = 400, description = "Service error relating to the request payload", body = ErrorRespPayload),
+      (status = 412, description = "The record was changed since the ETag in If-Match was issued", body = ErrorRespPayload),
   ),
   params(
     ("Organization" = String, Header, description = "The organization id to use for the request"),
     ("org_id" = String, Path, description = "The id of the organization you want to fetch."),
+    ("If-Match" = Option<String>, Header, description = "ETag of the record as last fetched. The write fails with 412 if the record has changed since"),
   ),
   security(
       ("ApiKey" = ["readonly"]),
@@ -171,23 +238,39 @@ pub struct UpdateOrgReqPayload {
 )]
 #[tracing::instrument(skip(pg_pool))]
 pub async fn update_org(
+    req: HttpRequest,
     req_payload: web::Json<UpdateOrgReqPayload>,
     path: web::Path<PrefixedUuid<OrgPrefix>>,
     authed_user: AuthedUser,
     pg_pool: web::Data<PgPool>,
 ) -> Result<HttpResponse, actix_web::Error> {
+    req_payload.validate()?;
     let org_id = path.into_inner();
-    let mut org = Org::from_details_with_id(org_id, req_payload.name.clone());
+    let default_currency = req_payload
+        .default_currency
+        .as_deref()
+        .map(parse_currency)
+        .transpose()?;
 
     match user_in_org_query(org_id, authed_user.id, pg_pool.clone()).await {
         Ok(opt_org) => match opt_org {
             Some(prev_org) => {
+                let expected_updated_at = check_if_match(&req, prev_org.updated_at)?;
+                let mut org = Org::from_details_with_id(
+                    org_id,
+                    req_payload.name.clone(),
+                    default_currency.unwrap_or(prev_org.default_currency),
+                );
                 org.created_at = prev_org.created_at;
-                update_org_query(org, pg_pool)
+                update_org_query(org, expected_updated_at, pg_pool)
                     .await
-                    .map(|org| Ok(HttpResponse::Ok().json(org)))?
+                    .map(|org| {
+                        Ok(HttpResponse::Ok()
+                            .insert_header(etag(org.updated_at))
+                            .json(org))
+                    })?
             }
-            None => Ok(HttpResponse::Unauthorized().finish()),
+            None => Err(ServiceError::Unauthorized.into()),
         },
         Err(e) => Err(e.into()),
     }
@@ -227,3 +310,92 @@ pub async fn get_orgs_for_authed_user(
 
     Ok(HttpResponse::Ok().json(user_orgs))
 }
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ReassignRecordsReqPayload {
+    /// The user whose contacts, deals, companies and tasks should be handed over.
+    from_user_id: PrefixedUuid<UserPrefix>,
+    /// The member of the organization who will own the records.
+    to_user_id: PrefixedUuid<UserPrefix>,
+}
+
+#[utoipa::path(
+  post,
+  path = "/orgs/{org_id}/reassign",
+  context_path = "/api",
+  tag = "orgs",
+  request_body(content = ReassignRecordsReqPayload, description = "JSON request payload to reassign every record owned by one user to another", content_type = "application/json"),
+  responses(
+      (status = 200, description = "Number of records that were reassigned", body = ReassignedRecords),
+      (status = 401, description = "Service error relating to authentication status of the user", body = ErrorRespPayload),
+      (status = 400, description = "Service error relating to the request payload", body = ErrorRespPayload),
+  ),
+  params(
+    ("Organization" = String, Header, description = "The organization id to use for the request"),
+    ("org_id" = String, Path, description = "The id of the organization to reassign records in."),
+  ),
+  security(
+      ("ApiKey" = ["readonly"]),
+  )
+)]
+#[tracing::instrument(skip(pg_pool, redis_pool))]
+pub async fn reassign_records(
+    req_payload: web::Json<ReassignRecordsReqPayload>,
+    org_user: AdminMember,
+    path: web::Path<PrefixedUuid<OrgPrefix>>,
+    pg_pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let org_id = path.into_inner();
+
+    if org_user.org_id != org_id {
+        return Err(ServiceError::Unauthorized.into());
+    }
+
+    validate_owner(Some(req_payload.to_user_id), org_id, pg_pool.clone()).await?;
+
+    let reassigned = reassign_records_query(
+        org_id,
+        req_payload.from_user_id,
+        req_payload.to_user_id,
+        pg_pool,
+    )
+    .await?;
+    reassigned.publish_updated(org_id, redis_pool).await;
+
+    Ok(HttpResponse::Ok().json(reassigned.counts()))
+}
+
+#[utoipa::path(
+  get,
+  path = "/orgs/{org_id}/usage",
+  context_path = "/api",
+  tag = "orgs",
+  responses(
+      (status = 200, description = "The organization's plan and how much of it is in use", body = OrgUsage),
+      (status = 401, description = "Service error relating to authentication status of the user", body = ErrorRespPayload),
+  ),
+  params(
+    ("Organization" = String, Header, description = "The organization id to use for the request"),
+    ("org_id" = String, Path, description = "The id of the organization you want to fetch usage for."),
+  ),
+  security(
+      ("ApiKey" = ["readonly"]),
+  )
+)]
+#[tracing::instrument(skip(pg_pool))]
+pub async fn get_org_usage(
+    org_member: AuthedMember,
+    path: web::Path<PrefixedUuid<OrgPrefix>>,
+    pg_pool: web::Data<PgPool>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let org_id = path.into_inner();
+
+    if org_member.org_id != org_id {
+        return Err(ServiceError::Unauthorized.into());
+    }
+
+    let usage = get_org_usage_query(org_id, pg_pool).await?;
+
+    Ok(HttpResponse::Ok().json(usage))
+}
@@ -1,20 +1,40 @@
 use super::auth_handler::OwnerMember;
 use crate::{
-    data::models::PgPool,
-    operators::phone_operator::{
-        create_phone_query, delete_phone_query, get_phone_by_id_query, update_phone_query,
+    data::{
+        etag::{check_if_match, etag, has_if_match},
+        models::{ChangeAction, ChangeEntity, PgPool, RedisPool},
+        redact::redacted_debug,
+        validation::{Validate, Validator, MAX_PHONE_LENGTH},
+    },
+    errors::ServiceError,
+    operators::{
+        event_operator::publish_change_event,
+        phone_operator::{
+            create_phone_query, delete_phone_query, get_phone_by_id_query, update_phone_query,
+        },
     },
     prefixes::{PhonePrefix, PrefixedUuid},
 };
-use actix_web::{web, HttpResponse};
+use actix_web::{web, HttpRequest, HttpResponse};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
-#[derive(Debug, Deserialize, Serialize, ToSchema)]
+#[derive(Deserialize, Serialize, ToSchema)]
 pub struct CreatePhoneReqPayload {
+    #[schema(max_length = 32)]
     number: String,
 }
 
+redacted_debug!(CreatePhoneReqPayload {} pii { number });
+
+impl Validate for CreatePhoneReqPayload {
+    fn validate(&self) -> Result<(), ServiceError> {
+        let mut validator = Validator::default();
+        validator.max_length("number", &self.number, MAX_PHONE_LENGTH);
+        validator.finish()
+    }
+}
+
 #[utoipa::path(
   post,
   path = "/phones",
@@ -24,21 +44,34 @@ pub struct CreatePhoneReqPayload {
   responses(
       (status = 201, description = "JSON body representing the phone that was created", body = Phone),
       (status = 401, description = "Service error relating to authentication status of the user", body = ErrorRespPayload),
+      (status = 409, description = "Service error when a request with the same Idempotency-Key is still being processed", body = ErrorRespPayload),
+      (status = 422, description = "Service error when the Idempotency-Key was already used for a different request", body = ErrorRespPayload),
   ),
   security(
       ("ApiKey" = ["readonly"]),
   ),
   params(
     ("Organization" = String, Header, description = "The org id to use for the request"),
+    ("Idempotency-Key" = Option<String>, Header, description = "Unique key for this create. Retrying with the same key replays the first response instead of creating a duplicate"),
   )
 )]
-#[tracing::instrument(skip(pg_pool))]
+#[tracing::instrument(skip(pg_pool, redis_pool))]
 pub async fn create_phone(
     req_payload: web::Json<CreatePhoneReqPayload>,
     org_user: OwnerMember,
     pg_pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
 ) -> Result<HttpResponse, actix_web::Error> {
+    req_payload.validate()?;
     let phone = create_phone_query(org_user.org_id, req_payload.number.clone(), pg_pool).await?;
+    publish_change_event(
+        org_user.org_id,
+        ChangeEntity::Phone,
+        ChangeAction::Created,
+        phone.id,
+        redis_pool,
+    )
+    .await;
     Ok(HttpResponse::Created().json(phone))
 }
 
@@ -50,25 +83,42 @@ pub async fn create_phone(
   responses(
       (status = 204, description = "No content response indicating that the phone was successfully deleted"),
       (status = 401, description = "Service error relating to authentication status of the user", body = ErrorRespPayload),
+      (status = 412, description = "The record was changed since the ETag in If-Match was issued", body = ErrorRespPayload),
   ),
   params(
     ("phone_id" = String, description = "The phone id to use for the request"),
     ("Organization" = String, Header, description = "The org id to use for the request"),
+    ("If-Match" = Option<String>, Header, description = "ETag of the record as last fetched. The write fails with 412 if the record has changed since"),
   ),
   security(
       ("ApiKey" = ["readonly"]),
   )
 )]
-#[tracing::instrument(skip(pg_pool))]
+#[tracing::instrument(skip(pg_pool, redis_pool))]
 pub async fn delete_phone(
+    req: HttpRequest,
     org_user: OwnerMember,
     path: web::Path<PrefixedUuid<PhonePrefix>>,
     pg_pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
 ) -> Result<HttpResponse, actix_web::Error> {
     let phone_id = path.into_inner();
-    delete_phone_query(phone_id, pg_pool)
-        .await
-        .map(|_| Ok(HttpResponse::NoContent().finish()))?
+    let expected_updated_at = if has_if_match(&req) {
+        let current = get_phone_by_id_query(phone_id, pg_pool.clone()).await?;
+        check_if_match(&req, current.updated_at)?
+    } else {
+        None
+    };
+    delete_phone_query(phone_id, expected_updated_at, pg_pool).await?;
+    publish_change_event(
+        org_user.org_id,
+        ChangeEntity::Phone,
+        ChangeAction::Deleted,
+        phone_id,
+        redis_pool,
+    )
+    .await;
+    Ok(HttpResponse::NoContent().finish())
 }
 
 #[utoipa::path(
@@ -77,7 +127,7 @@ pub async fn delete_phone(
   context_path = "/api",
   tag = "phones",
   responses(
-      (status = 200, description = "JSON object representing the requested phone", body = Phone),
+      (status = 200, description = "JSON object representing the requested phone", body = Phone, headers(("ETag" = String, description = "Version of the record. Send it back in If-Match to make a write conditional"))),
       (status = 401, description = "Service error relating to authentication status of the user", body = ErrorRespPayload),
   ),
   security(
@@ -96,16 +146,31 @@ pub async fn get_phone(
 ) -> Result<HttpResponse, actix_web::Error> {
     let phone_id = path.into_inner();
     match get_phone_by_id_query(phone_id, pg_pool).await {
-        Ok(phone) => Ok(HttpResponse::Ok().json(phone)),
-        Err(_) => Ok(HttpResponse::NotFound().finish()),
+        Ok(phone) => Ok(HttpResponse::Ok()
+            .insert_header(etag(phone.updated_at))
+            .json(phone)),
+        Err(_) => Err(ServiceError::NotFound.into()),
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct UpdatePhoneReqPayload {
+    #[schema(max_length = 32)]
     number: Option<String>,
 }
 
+redacted_debug!(UpdatePhoneReqPayload {} pii { number });
+
+impl Validate for UpdatePhoneReqPayload {
+    fn validate(&self) -> Result<(), ServiceError> {
+        let mut validator = Validator::default();
+        if let Some(number) = &self.number {
+            validator.max_length("number", number, MAX_PHONE_LENGTH);
+        }
+        validator.finish()
+    }
+}
+
 #[utoipa::path(
   put,
   path = "/phones/{phone_id}",
@@ -115,6 +180,7 @@ pub struct UpdatePhoneReqPayload {
   responses(
       (status = 200, description = "Object representing the altered phone", body = Phone),
       (status = 401, description = "Service error relating to authentication status of the user", body = ErrorRespPayload),
+      (status = 412, description = "The record was changed since the ETag in If-Match was issued", body = ErrorRespPayload),
   ),
   security(
       ("ApiKey" = ["readonly"]),
@@ -122,16 +188,42 @@ pub struct UpdatePhoneReqPayload {
   params (
     ("phone_id" = String, description = "The phone id to use for the request"),
     ("Organization" = String, Header, description = "The org id to use for the request"),
+    ("If-Match" = Option<String>, Header, description = "ETag of the record as last fetched. The write fails with 412 if the record has changed since"),
   )
 )]
-#[tracing::instrument(skip(pg_pool))]
+#[tracing::instrument(skip(pg_pool, redis_pool))]
 pub async fn update_phone(
+    req: HttpRequest,
     req_payload: web::Json<UpdatePhoneReqPayload>,
     path: web::Path<PrefixedUuid<PhonePrefix>>,
     org_user: OwnerMember,
     pg_pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
 ) -> Result<HttpResponse, actix_web::Error> {
+    req_payload.validate()?;
     let phone_id = path.into_inner();
-    let phone = update_phone_query(phone_id, req_payload.number.clone(), pg_pool).await?;
-    Ok(HttpResponse::Ok().json(phone))
+    let expected_updated_at = if has_if_match(&req) {
+        let current = get_phone_by_id_query(phone_id, pg_pool.clone()).await?;
+        check_if_match(&req, current.updated_at)?
+    } else {
+        None
+    };
+    let phone = update_phone_query(
+        phone_id,
+        req_payload.number.clone(),
+        expected_updated_at,
+        pg_pool,
+    )
+    .await?;
+    publish_change_event(
+        org_user.org_id,
+        ChangeEntity::Phone,
+        ChangeAction::Updated,
+        phone.id,
+        redis_pool,
+    )
+    .await;
+    Ok(HttpResponse::Ok()
+        .insert_header(etag(phone.updated_at))
+        .json(phone))
 }
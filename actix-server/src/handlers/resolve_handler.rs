@@ -0,0 +1,166 @@
+use super::auth_handler::AuthedMember;
+use crate::{
+    data::models::{
+        Activity, Company, Contact, Deal, Email, Link, Note, Org, PgPool, Phone, Task, User,
+    },
+    errors::ServiceError,
+    operators::{
+        activity_operator::get_activity_by_id_query,
+        company_operator::get_company_query,
+        contact_operator::get_contact_by_id_query,
+        deal_operator::get_deal_by_id_query,
+        email_db_operator::get_email_by_id_query,
+        link_operator::get_link_by_id_query,
+        note_operator::get_note_by_id_query,
+        org_operator::{get_org_user_link_query, user_in_org_query},
+        phone_operator::get_phone_by_id_query,
+        task_operator::get_task_by_id_query,
+        user_operator::get_user_by_id_query,
+    },
+    prefixes::{
+        ActivityPrefix, CompanyPrefix, ContactPrefix, DealPrefix, EmailPrefix, LinkPrefix,
+        NotePrefix, OrgPrefix, PhonePrefix, PrefixedUuid, TaskPrefix, UserPrefix,
+    },
+};
+use actix_web::{web, HttpResponse};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+/// A record looked up by its prefixed id, along with the type the prefix stands for.
+#[derive(Serialize, ToSchema)]
+#[serde(tag = "type", content = "entity", rename_all = "snake_case")]
+pub enum ResolvedEntity {
+    Activity(Activity),
+    Company(Company),
+    Contact(Contact),
+    Deal(Deal),
+    Email(Email),
+    Link(Link),
+    Note(Note),
+    Org(Org),
+    Phone(Phone),
+    Task(Task),
+    User(User),
+}
+
+/// Resolve Id
+///
+/// Look up any record by its prefixed id, e.g. `deal-…` or `contact-…`, without knowing its type up front. Only records of the organization in the Organization header are found; records of other organizations are reported as not found.
+#[utoipa::path(
+  get,
+  path = "/resolve/{prefixed_id}",
+  context_path = "/api",
+  tag = "resolve",
+  responses(
+      (status = 200, description = "JSON object with the type of the record and the record itself", body = ResolvedEntity),
+      (status = 400, description = "The id is malformed or its prefix isn't one that can be resolved", body = ErrorRespPayload),
+      (status = 401, description = "Service error relating to authentication status of the user", body = ErrorRespPayload),
+      (status = 404, description = "No record with this id in the organization", body = ErrorRespPayload),
+  ),
+  params(
+    ("Organization" = String, Header, description = "The organization id to use for the request"),
+    ("prefixed_id" = String, Path, description = "The prefixed id of the record, e.g. deal-0190e3b4-…"),
+  ),
+  security(
+      ("ApiKey" = ["readonly"]),
+  )
+)]
+#[tracing::instrument(skip(pg_pool))]
+pub async fn resolve_id(
+    path: web::Path<String>,
+    org_member: AuthedMember,
+    pg_pool: web::Data<PgPool>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let entity = resolve_prefixed_id(&path.into_inner(), &org_member, pg_pool).await?;
+    Ok(HttpResponse::Ok().json(entity))
+}
+
+async fn resolve_prefixed_id(
+    prefixed_id: &str,
+    org_member: &AuthedMember,
+    pg_pool: web::Data<PgPool>,
+) -> Result<ResolvedEntity, ServiceError> {
+    let (prefix, _) = prefixed_id.split_once('-').ok_or_else(|| {
+        ServiceError::BadRequest("Expected a prefixed id like deal-<uuid>".to_string())
+    })?;
+
+    // Records of other orgs are reported missing rather than forbidden, so ids can't
+    // be probed across orgs
+    let in_org = |org_id: PrefixedUuid<OrgPrefix>| {
+        if org_id == org_member.org_id {
+            Ok(())
+        } else {
+            Err(ServiceError::NotFound)
+        }
+    };
+
+    let entity = match prefix {
+        ActivityPrefix::PREFIX => ResolvedEntity::Activity(
+            get_activity_by_id_query(prefixed_id.parse()?, org_member.org_id, pg_pool).await?,
+        ),
+        CompanyPrefix::PREFIX => {
+            let company = get_company_query(prefixed_id.parse()?, pg_pool)
+                .await
+                .map_err(|_| ServiceError::NotFound)?;
+            in_org(company.org_id)?;
+            ResolvedEntity::Company(company)
+        }
+        ContactPrefix::PREFIX => {
+            let contact = get_contact_by_id_query(prefixed_id.parse()?, pg_pool).await?;
+            in_org(contact.org_id)?;
+            ResolvedEntity::Contact(contact)
+        }
+        DealPrefix::PREFIX => {
+            let deal = get_deal_by_id_query(prefixed_id.parse()?, pg_pool).await?;
+            in_org(deal.org_id)?;
+            ResolvedEntity::Deal(deal)
+        }
+        EmailPrefix::PREFIX => {
+            let email = get_email_by_id_query(prefixed_id.parse()?, pg_pool).await?;
+            in_org(email.org_id)?;
+            ResolvedEntity::Email(email)
+        }
+        LinkPrefix::PREFIX => {
+            let link = get_link_by_id_query(prefixed_id.parse()?, pg_pool).await?;
+            in_org(link.org_id)?;
+            ResolvedEntity::Link(link)
+        }
+        NotePrefix::PREFIX => {
+            let note = get_note_by_id_query(prefixed_id.parse()?, pg_pool).await?;
+            in_org(note.org_id)?;
+            ResolvedEntity::Note(note)
+        }
+        OrgPrefix::PREFIX => {
+            let org_id = prefixed_id.parse()?;
+            in_org(org_id)?;
+            let org = user_in_org_query(org_id, org_member.user_id, pg_pool)
+                .await?
+                .ok_or(ServiceError::NotFound)?;
+            ResolvedEntity::Org(org)
+        }
+        PhonePrefix::PREFIX => {
+            let phone = get_phone_by_id_query(prefixed_id.parse()?, pg_pool).await?;
+            in_org(phone.org_id)?;
+            ResolvedEntity::Phone(phone)
+        }
+        TaskPrefix::PREFIX => {
+            let task = get_task_by_id_query(prefixed_id.parse()?, pg_pool).await?;
+            in_org(task.org_id)?;
+            ResolvedEntity::Task(task)
+        }
+        UserPrefix::PREFIX => {
+            // Users aren't owned by an org, they're visible to the orgs they're members of
+            let user_id: PrefixedUuid<UserPrefix> = prefixed_id.parse()?;
+            get_org_user_link_query(user_id, org_member.org_id, pg_pool.clone()).await?;
+            ResolvedEntity::User(get_user_by_id_query(&user_id, pg_pool).await?)
+        }
+        _ => {
+            return Err(ServiceError::BadRequest(format!(
+                "Ids with the {} prefix can't be resolved",
+                prefix
+            )))
+        }
+    };
+
+    Ok(entity)
+}
@@ -1,34 +1,57 @@
 use std::str::FromStr;
 
-use super::auth_handler::OwnerMember;
+use super::auth_handler::{parse_owner_filter, validate_owner, OwnerMember};
 use crate::{
-    data::models::{Deal, Link, PgPool, TaskDeal, TaskLink, TaskUser, User},
+    data::{
+        etag::{check_if_match, etag, has_if_match},
+        models::{
+            ChangeAction, ChangeEntity, Deal, Link, PgPool, RedisPool, Task, TaskDeal, TaskLink,
+            TaskUser, User,
+        },
+        patch::{deserialize_patch, Patch},
+        validation::{Validate, Validator, MAX_TEXT_LENGTH},
+    },
     errors::ServiceError,
     operators::{
         deal_operator::list_deals_by_task_id_query,
+        event_operator::publish_change_event,
         link_operator::list_links_by_task_id_query,
         task_operator::{
             create_deal_for_task_query, create_link_for_task_query, create_task_query,
             create_user_for_task_query, delete_deal_from_task_query, delete_link_from_task_query,
             delete_task_query, delete_user_from_task_query, get_task_by_id_query,
-            update_task_query,
+            list_tasks_by_org_id_query, update_task_query,
         },
         user_operator::list_users_by_task_id_query,
     },
     prefixes::{ContactPrefix, DealPrefix, LinkPrefix, PrefixedUuid, TaskPrefix, UserPrefix},
 };
-use actix_web::{web, HttpResponse};
+use actix_web::{web, HttpRequest, HttpResponse};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
 #[derive(Debug, Deserialize, Serialize, ToSchema)]
 pub struct CreateTaskReqPayload {
+    #[schema(max_length = 10000)]
     description: Option<String>,
     #[schema(example = "2021-01-01T00:00:00")]
     deadline: Option<chrono::NaiveDateTime>,
     contact_id: Option<PrefixedUuid<ContactPrefix>>,
 }
 
+impl Validate for CreateTaskReqPayload {
+    fn validate(&self) -> Result<(), ServiceError> {
+        let mut validator = Validator::default();
+        if let Some(description) = &self.description {
+            validator.max_length("description", description, MAX_TEXT_LENGTH);
+        }
+        if let Some(deadline) = self.deadline {
+            validator.date("deadline", deadline.date());
+        }
+        validator.finish()
+    }
+}
+
 #[utoipa::path(
   post,
   path = "/tasks",
@@ -38,29 +61,43 @@ pub struct CreateTaskReqPayload {
   responses(
       (status = 201, description = "JSON body representing the task that was created", body = Task),
       (status = 401, description = "Service error relating to authentication status of the user", body = ErrorRespPayload),
+      (status = 409, description = "Service error when a request with the same Idempotency-Key is still being processed", body = ErrorRespPayload),
+      (status = 422, description = "Service error when the Idempotency-Key was already used for a different request", body = ErrorRespPayload),
       (status = 400, description = "Service error relating to the request payload", body = ErrorRespPayload),
   ),
   security(
       ("ApiKey" = ["readonly"]),
   ),
   params(
-    ("Organization" = String, Header, description = "The organization id to use for the request")
+    ("Organization" = String, Header, description = "The organization id to use for the request"),
+    ("Idempotency-Key" = Option<String>, Header, description = "Unique key for this create. Retrying with the same key replays the first response instead of creating a duplicate"),
   ),
 )]
-#[tracing::instrument(skip(pg_pool))]
+#[tracing::instrument(skip(pg_pool, redis_pool))]
 pub async fn create_task(
     req_payload: web::Json<CreateTaskReqPayload>,
     org_user: OwnerMember,
     pg_pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
 ) -> Result<HttpResponse, actix_web::Error> {
+    req_payload.validate()?;
     let task = create_task_query(
         org_user.org_id,
         req_payload.contact_id,
         req_payload.description.clone(),
         req_payload.deadline,
+        Some(org_user.user_id),
         pg_pool,
     )
     .await?;
+    publish_change_event(
+        org_user.org_id,
+        ChangeEntity::Task,
+        ChangeAction::Created,
+        task.id,
+        redis_pool,
+    )
+    .await;
     Ok(HttpResponse::Created().json(task))
 }
 
@@ -72,25 +109,42 @@ pub async fn create_task(
   responses(
       (status = 204, description = "No content response indicating that the task was successfully deleted"),
       (status = 401, description = "Service error relating to authentication status of the user", body = ErrorRespPayload),
+      (status = 412, description = "The record was changed since the ETag in If-Match was issued", body = ErrorRespPayload),
   ),
   params(
     ("task_id" = String, description = "The task id to use for the request"),
     ("Organization" = String, Header, description = "The organization id to use for the request"),
+    ("If-Match" = Option<String>, Header, description = "ETag of the record as last fetched. The write fails with 412 if the record has changed since"),
   ),
   security(
       ("ApiKey" = ["readonly"]),
   )
 )]
-#[tracing::instrument(skip(pg_pool))]
+#[tracing::instrument(skip(pg_pool, redis_pool))]
 pub async fn delete_task(
+    req: HttpRequest,
     org_user: OwnerMember,
     path: web::Path<PrefixedUuid<TaskPrefix>>,
     pg_pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
 ) -> Result<HttpResponse, actix_web::Error> {
     let task_id = path.into_inner();
-    delete_task_query(task_id, pg_pool)
-        .await
-        .map(|_| Ok(HttpResponse::NoContent().finish()))?
+    let expected_updated_at = if has_if_match(&req) {
+        let current = get_task_by_id_query(task_id, pg_pool.clone()).await?;
+        check_if_match(&req, current.updated_at)?
+    } else {
+        None
+    };
+    delete_task_query(task_id, expected_updated_at, pg_pool).await?;
+    publish_change_event(
+        org_user.org_id,
+        ChangeEntity::Task,
+        ChangeAction::Deleted,
+        task_id,
+        redis_pool,
+    )
+    .await;
+    Ok(HttpResponse::NoContent().finish())
 }
 
 #[utoipa::path(
@@ -99,7 +153,7 @@ pub async fn delete_task(
   context_path = "/api",
   tag = "tasks",
   responses(
-      (status = 200, description = "JSON object representing the requested task", body = Task),
+      (status = 200, description = "JSON object representing the requested task", body = Task, headers(("ETag" = String, description = "Version of the record. Send it back in If-Match to make a write conditional"))),
       (status = 401, description = "Service error relating to authentication status of the user", body = ErrorRespPayload),
   ),
   params(
@@ -118,55 +172,108 @@ pub async fn get_task(
 ) -> Result<HttpResponse, actix_web::Error> {
     let task_id = path.into_inner();
     match get_task_by_id_query(task_id, pg_pool).await {
-        Ok(task) => Ok(HttpResponse::Ok().json(task)),
-        Err(_) => Ok(HttpResponse::NotFound().finish()),
+        Ok(task) => Ok(HttpResponse::Ok()
+            .insert_header(etag(task.updated_at))
+            .json(task)),
+        Err(_) => Err(ServiceError::NotFound.into()),
     }
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct UpdateTaskReqPayload {
-    description: Option<String>,
-    #[schema(example = "2021-01-01T00:00:00")]
-    deadline: Option<chrono::NaiveDateTime>,
-    contact_id: Option<PrefixedUuid<ContactPrefix>>,
+    #[serde(default, deserialize_with = "deserialize_patch")]
+    #[schema(value_type = Option<String>, max_length = 10000)]
+    description: Patch<String>,
+    #[serde(default, deserialize_with = "deserialize_patch")]
+    #[schema(value_type = Option<chrono::NaiveDateTime>, example = "2021-01-01T00:00:00")]
+    deadline: Patch<chrono::NaiveDateTime>,
+    #[serde(default, deserialize_with = "deserialize_patch")]
+    #[schema(value_type = Option<PrefixedUuid<ContactPrefix>>)]
+    contact_id: Patch<PrefixedUuid<ContactPrefix>>,
+    /// Reassigns the task to another member of the organization.
+    #[serde(default, deserialize_with = "deserialize_patch")]
+    #[schema(value_type = Option<PrefixedUuid<UserPrefix>>)]
+    owner_user_id: Patch<PrefixedUuid<UserPrefix>>,
+}
+
+impl Validate for UpdateTaskReqPayload {
+    fn validate(&self) -> Result<(), ServiceError> {
+        let mut validator = Validator::default();
+        if let Some(Some(description)) = &self.description {
+            validator.max_length("description", description, MAX_TEXT_LENGTH);
+        }
+        if let Some(Some(deadline)) = self.deadline {
+            validator.date("deadline", deadline.date());
+        }
+        validator.finish()
+    }
 }
 
 #[utoipa::path(
-  put,
+  patch,
   path = "/tasks/{task_id}",
   context_path = "/api",
   tag = "tasks",
-  request_body(content = UpdateTaskReqPayload, description = "JSON request payload to update the task", content_type = "application/json"),
+  request_body(content = UpdateTaskReqPayload, description = "JSON merge patch to update the task. Omitted fields are left alone and null fields are cleared.", content_type = "application/json"),
   responses(
       (status = 200, description = "Object representing the updated task", body = Task),
       (status = 401, description = "Service error relating to authentication status of the user", body = ErrorRespPayload),
       (status = 400, description = "Service error relating to the request payload", body = ErrorRespPayload),
+      (status = 412, description = "The record was changed since the ETag in If-Match was issued", body = ErrorRespPayload),
   ),
   params(
     ("task_id" = String, description = "The task id to use for the request"),
     ("Organization" = String, Header, description = "The organization id to use for the request"),
+    ("If-Match" = Option<String>, Header, description = "ETag of the record as last fetched. The write fails with 412 if the record has changed since"),
   ),
   security(
       ("ApiKey" = ["readonly"]),
   ),
 )]
-#[tracing::instrument(skip(pg_pool))]
+#[tracing::instrument(skip(pg_pool, redis_pool))]
 pub async fn update_task(
+    req: HttpRequest,
     req_payload: web::Json<UpdateTaskReqPayload>,
     path: web::Path<PrefixedUuid<TaskPrefix>>,
     org_user: OwnerMember,
     pg_pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
 ) -> Result<HttpResponse, actix_web::Error> {
+    req_payload.validate()?;
     let task_id = path.into_inner();
+    let expected_updated_at = if has_if_match(&req) {
+        let current = get_task_by_id_query(task_id, pg_pool.clone()).await?;
+        check_if_match(&req, current.updated_at)?
+    } else {
+        None
+    };
+    validate_owner(
+        req_payload.owner_user_id.flatten(),
+        org_user.org_id,
+        pg_pool.clone(),
+    )
+    .await?;
     let task = update_task_query(
         task_id,
         req_payload.description.clone(),
         req_payload.deadline,
         req_payload.contact_id,
+        req_payload.owner_user_id,
+        expected_updated_at,
         pg_pool,
     )
     .await?;
-    Ok(HttpResponse::Ok().json(task))
+    publish_change_event(
+        org_user.org_id,
+        ChangeEntity::Task,
+        ChangeAction::Updated,
+        task.id,
+        redis_pool,
+    )
+    .await;
+    Ok(HttpResponse::Ok()
+        .insert_header(etag(task.updated_at))
+        .json(task))
 }
 
 #[derive(Serialize, Deserialize, Clone, ToSchema)]
@@ -346,3 +453,50 @@ pub async fn list_task_resource(
         }
     }
 }
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ListTasksQuery {
+    limit: Option<i64>,
+    offset: Option<String>,
+    owner: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct TaskList {
+    pub tasks: Vec<Task>,
+    pub total: i64,
+}
+
+#[utoipa::path(
+  get,
+  path = "/tasks/list",
+  context_path = "/api",
+  tag = "tasks",
+  responses(
+      (status = 200, description = "List of tasks for the organization", body = TaskList),
+      (status = 401, description = "Service error relating to authentication status of the user", body = ErrorRespPayload),
+      (status = 400, description = "Service error relating to the request payload", body = ErrorRespPayload),
+  ),
+  params(
+    ("limit" = Option<i64>, Query, description = "The number of records to return"),
+    ("offset" = Option<String>, Query, description = "The UUID of the record to start from"),
+    ("owner" = Option<String>, Query, description = "Only return records owned by this user id, or `me` for the calling user"),
+    ("Organization" = String, Header, description = "The organization id to use for the request"),
+  ),
+  security(
+      ("ApiKey" = ["readonly"]),
+  ),
+)]
+#[tracing::instrument(skip(pg_pool))]
+pub async fn list_tasks(
+    query: web::Query<ListTasksQuery>,
+    org_user: OwnerMember,
+    pg_pool: web::Data<PgPool>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let offset = PrefixedUuid::<TaskPrefix>::from_optional_str(query.offset.clone())?;
+    let owner_user_id = parse_owner_filter(query.owner.clone(), org_user.user_id)?;
+    let (tasks, total) =
+        list_tasks_by_org_id_query(org_user.org_id, owner_user_id, pg_pool, query.limit, offset)
+            .await?;
+    Ok(HttpResponse::Ok().json(TaskList { tasks, total }))
+}
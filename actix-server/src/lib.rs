@@ -3,13 +3,19 @@
 #[macro_use]
 extern crate diesel;
 use crate::{
-    errors::ServiceError, handlers::auth_handler::build_oidc_client, middleware::auth_middleware,
+    errors::ServiceError,
+    handlers::auth_handler::build_oidc_client,
+    middleware::{
+        auth_middleware, csrf_middleware, idempotency_middleware, metrics_middleware,
+        rate_limit_middleware, request_id_middleware,
+    },
 };
 use actix_cors::Cors;
 use actix_identity::IdentityMiddleware;
 use actix_session::{config::PersistentSession, storage::RedisSessionStore, SessionMiddleware};
 use actix_web::{
-    cookie::{Key, SameSite},
+    cookie::Key,
+    error::JsonPayloadError,
     middleware::Logger,
     web::{self, PayloadConfig},
     App, HttpServer,
@@ -20,9 +26,10 @@ use diesel_async::pooled_connection::ManagerConfig;
 use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
 use futures_util::future::BoxFuture;
 use futures_util::FutureExt;
-use openssl::ssl::SslVerifyMode;
-use openssl::ssl::{SslConnector, SslMethod};
+use openssl::error::ErrorStack;
+use openssl::ssl::{SslConnector, SslFiletype, SslMethod, SslVerifyMode};
 use postgres_openssl::MakeTlsConnector;
+use settings::{DatabaseTls, LogFormat, SslMode};
 use tracing_subscriber::{prelude::*, EnvFilter, Layer};
 use utoipa_redoc::{Redoc, Servable};
 use utoipa_swagger_ui::SwaggerUi;
@@ -30,13 +37,17 @@ use utoipa_swagger_ui::SwaggerUi;
 pub mod data;
 pub mod errors;
 pub mod handlers;
+pub mod metrics;
 pub mod middleware;
 pub mod operators;
 pub mod prefixes;
+pub mod settings;
+pub mod workers;
 
 pub const SECONDS_IN_MINUTE: u64 = 60;
 pub const SECONDS_IN_HOUR: u64 = 60 * SECONDS_IN_MINUTE;
 pub const SECONDS_IN_DAY: u64 = 24 * SECONDS_IN_HOUR;
+pub const SESSION_COOKIE_NAME: &str = "actix-server";
 pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!("./migrations");
 
 fn run_migrations(url: &str) {
@@ -46,18 +57,95 @@ fn run_migrations(url: &str) {
         .expect("Failed to run migrations");
 }
 
-pub fn establish_connection(
-    config: &str,
-) -> BoxFuture<diesel::ConnectionResult<diesel_async::AsyncPgConnection>> {
-    let fut = async {
-        let mut tls = SslConnector::builder(SslMethod::tls()).unwrap();
+/// Resolves on SIGTERM, or on Ctrl-C when running in a terminal.
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use actix_web::rt::signal::unix::{signal, SignalKind};
 
-        tls.set_verify(SslVerifyMode::NONE);
-        let tls_connector = MakeTlsConnector::new(tls.build());
+        if let Ok(mut terminate) = signal(SignalKind::terminate()) {
+            futures::future::select(
+                Box::pin(terminate.recv()),
+                Box::pin(actix_web::rt::signal::ctrl_c()),
+            )
+            .await;
+            return;
+        }
+    }
+
+    let _ = actix_web::rt::signal::ctrl_c().await;
+}
+
+fn cors(settings: &settings::Settings) -> Cors {
+    if settings.allows_any_origin() {
+        return Cors::permissive();
+    }
+
+    settings
+        .cors_origins
+        .iter()
+        .fold(Cors::default(), |cors, origin| cors.allowed_origin(origin))
+        .allow_any_method()
+        .allow_any_header()
+        .expose_any_header()
+        .supports_credentials()
+        .max_age(3600)
+}
+
+fn tls_connector(tls: &DatabaseTls) -> Result<MakeTlsConnector, ErrorStack> {
+    let mut builder = SslConnector::builder(SslMethod::tls())?;
+
+    if tls.mode.verifies_certificate() {
+        builder.set_verify(SslVerifyMode::PEER);
+        if let Some(root_cert) = &tls.root_cert {
+            builder.set_ca_file(root_cert)?;
+        }
+    } else {
+        builder.set_verify(SslVerifyMode::NONE);
+    }
+
+    if let (Some(cert), Some(key)) = (&tls.client_cert, &tls.client_key) {
+        builder.set_certificate_chain_file(cert)?;
+        builder.set_private_key_file(key, SslFiletype::PEM)?;
+        builder.check_private_key()?;
+    }
+
+    let mut connector = MakeTlsConnector::new(builder.build());
+    if tls.mode == SslMode::VerifyCa {
+        connector.set_callback(|config, _| {
+            config.set_verify_hostname(false);
+            Ok(())
+        });
+    }
+    Ok(connector)
+}
+
+pub fn establish_connection<'a>(
+    config: &'a str,
+    tls: &'static DatabaseTls,
+) -> BoxFuture<'a, diesel::ConnectionResult<diesel_async::AsyncPgConnection>> {
+    let fut = async move {
+        let bad_connection =
+            |e: &dyn std::fmt::Display| diesel::ConnectionError::BadConnection(e.to_string());
 
-        let (client, conn) = tokio_postgres::connect(config, tls_connector)
+        let mut pg_config = config
+            .parse::<tokio_postgres::Config>()
+            .map_err(|e| bad_connection(&e))?;
+        // Certificate checks happen in the connector, tokio-postgres only needs to
+        // know whether TLS is optional
+        pg_config.ssl_mode(match tls.mode {
+            SslMode::Disable => tokio_postgres::config::SslMode::Disable,
+            SslMode::Prefer => tokio_postgres::config::SslMode::Prefer,
+            SslMode::Require | SslMode::VerifyCa | SslMode::VerifyFull => {
+                tokio_postgres::config::SslMode::Require
+            }
+        });
+        let tls_connector = tls_connector(tls).map_err(|e| bad_connection(&e))?;
+
+        let (client, conn) = pg_config
+            .connect(tls_connector)
             .await
-            .map_err(|e| diesel::ConnectionError::BadConnection(e.to_string()))?;
+            .map_err(|e| bad_connection(&e))?;
 
         tokio::spawn(async move {
             if let Err(e) = conn.await {
@@ -88,6 +176,62 @@ impl Modify for SecurityAddon {
     }
 }
 
+/// Documents the errors any operation can run into, on top of the ones each handler
+/// lists for itself.
+struct CommonErrorResponses;
+
+impl Modify for CommonErrorResponses {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let error_response = |description: &str| {
+            utoipa::openapi::ResponseBuilder::new()
+                .description(description)
+                .content(
+                    "application/json",
+                    utoipa::openapi::ContentBuilder::new()
+                        .schema(utoipa::openapi::Ref::from_schema_name("ErrorRespPayload"))
+                        .build(),
+                )
+                .build()
+        };
+
+        for path_item in openapi.paths.paths.values_mut() {
+            for operation in path_item.operations.values_mut() {
+                let takes_input = operation.request_body.is_some()
+                    || operation.parameters.as_ref().is_some_and(|parameters| {
+                        parameters.iter().any(|parameter| {
+                            matches!(
+                                parameter.parameter_in,
+                                utoipa::openapi::path::ParameterIn::Path
+                            )
+                        })
+                    });
+                let authenticated = operation.security.is_some();
+
+                let responses = &mut operation.responses.responses;
+                if takes_input {
+                    responses.entry("400".to_string()).or_insert_with(|| {
+                        error_response("The request is malformed or a field is invalid").into()
+                    });
+                }
+                if authenticated {
+                    responses.entry("401".to_string()).or_insert_with(|| {
+                        error_response("The request is missing valid credentials").into()
+                    });
+                }
+                responses.entry("429".to_string()).or_insert_with(|| {
+                    error_response("Rate limit exceeded. Retry-After says when to try again").into()
+                });
+                responses
+                    .entry("500".to_string())
+                    .or_insert_with(|| error_response("Unexpected error on the server").into());
+                responses.entry("503".to_string()).or_insert_with(|| {
+                    error_response("The server is temporarily out of capacity. Retry later").into()
+                });
+            }
+        }
+    }
+}
+
 #[derive(OpenApi)]
 #[openapi(
     info(
@@ -108,19 +252,25 @@ impl Modify for SecurityAddon {
         (url = "http://localhost:8090",
         description = "Local development server"),
     ),
-    modifiers(&SecurityAddon),
+    modifiers(&SecurityAddon, &CommonErrorResponses),
     paths(
         handlers::auth_handler::login,
         handlers::auth_handler::logout,
         handlers::auth_handler::whoami,
         handlers::auth_handler::callback,
         handlers::api_key_handler::create_api_key,
-        handlers::auth_handler::health_check,
+        handlers::health_handler::health_check,
+        handlers::health_handler::liveness_check,
+        handlers::health_handler::readiness_check,
+        handlers::billing_handler::billing_webhook,
+        handlers::event_handler::stream_events,
         handlers::org_handler::create_org,
         handlers::org_handler::delete_org,
         handlers::org_handler::update_org,
         handlers::org_handler::get_orgs_for_authed_user,
         handlers::org_handler::leave_org,
+        handlers::org_handler::reassign_records,
+        handlers::org_handler::get_org_usage,
         handlers::invitation_handler::post_invitation,
         handlers::invitation_handler::get_invitations,
         handlers::invitation_handler::delete_invitation,
@@ -132,6 +282,10 @@ impl Modify for SecurityAddon {
         handlers::deal_handler::create_deal_resource,
         handlers::deal_handler::delete_deal_resource,
         handlers::deal_handler::list_deal_by_org,
+        handlers::deal_handler::get_pipeline_report,
+        handlers::deal_handler::get_forecast_report,
+        handlers::deal_handler::get_performance_report,
+        handlers::deal_handler::list_currencies,
         handlers::contact_handler::create_contact,
         handlers::contact_handler::delete_contact,
         handlers::contact_handler::update_contact,
@@ -161,11 +315,18 @@ impl Modify for SecurityAddon {
         handlers::task_handler::create_task_resource,
         handlers::task_handler::delete_task_resource,
         handlers::task_handler::list_task_resource,
+        handlers::task_handler::list_tasks,
         handlers::company_handler::delete_company,
         handlers::company_handler::create_company,
         handlers::company_handler::update_company,
         handlers::company_handler::get_companies_for_org,
         handlers::company_handler::get_company_by_id,
+        handlers::activity_handler::create_activity,
+        handlers::activity_handler::get_activity,
+        handlers::activity_handler::update_activity,
+        handlers::activity_handler::delete_activity,
+        handlers::activity_handler::list_activities,
+        handlers::resolve_handler::resolve_id,
     ),
     components(
         schemas(
@@ -174,14 +335,21 @@ impl Modify for SecurityAddon {
             handlers::api_key_handler::CreateApiKeyReqPayload,
             handlers::org_handler::CreateOrgReqPayload,
             handlers::org_handler::UpdateOrgReqPayload,
+            handlers::org_handler::ReassignRecordsReqPayload,
             handlers::deal_handler::CreateDealReqPayload,
             handlers::deal_handler::UpdateDealReqPayload,
             handlers::deal_handler::ListDealResourceQuery,
+            handlers::deal_handler::ListDealsQuery,
             handlers::deal_handler::DealResourceList,
             handlers::deal_handler::DealResource,
             handlers::deal_handler::DealResType,
             handlers::deal_handler::DealResourceListWithPagination,
             handlers::deal_handler::ListDealByOrgRespBody,
+            handlers::deal_handler::DealReportQuery,
+            handlers::deal_handler::PipelineReport,
+            handlers::deal_handler::ForecastReport,
+            handlers::deal_handler::PerformanceReport,
+            handlers::deal_handler::Currency,
             handlers::contact_handler::CreateContactReqPayload,
             handlers::contact_handler::UpdateContactReqPayload,
             handlers::contact_handler::ContactList,
@@ -203,8 +371,15 @@ impl Modify for SecurityAddon {
             handlers::task_handler::TaskResourceList,
             handlers::task_handler::TaskResourceListWithPagination,
             handlers::task_handler::GetTaskResourceQuery,
+            handlers::task_handler::ListTasksQuery,
+            handlers::task_handler::TaskList,
             handlers::company_handler::UpdateCompanyReqPayload,
             handlers::company_handler::CreateCompanyReqPayload,
+            handlers::activity_handler::CreateActivityReqPayload,
+            handlers::activity_handler::UpdateActivityReqPayload,
+            handlers::activity_handler::ListActivitiesQuery,
+            handlers::activity_handler::ActivityList,
+            handlers::resolve_handler::ResolvedEntity,
             models::User,
             models::Invitation,
             models::Org,
@@ -220,7 +395,30 @@ impl Modify for SecurityAddon {
             models::TaskDeal,
             models::TaskLink,
             models::TaskUser,
+            models::Activity,
+            models::ActivityPayload,
+            models::CallActivity,
+            models::CallOutcome,
+            models::MeetingActivity,
+            models::EmailActivity,
+            models::EmailDirection,
+            models::ReassignedRecords,
+            models::Plan,
+            models::OrgUsage,
+            models::ChangeEvent,
+            models::ChangeEntity,
+            models::ChangeAction,
+            models::PipelineReportRow,
+            models::ForecastReportRow,
+            models::AverageDealSizeRow,
             errors::ErrorRespPayload,
+            errors::ErrorCode,
+            errors::FieldError,
+            handlers::health_handler::ReadinessRespPayload,
+            handlers::health_handler::DependencyHealth,
+            handlers::health_handler::HealthStatus,
+            workers::WorkerHealth,
+            workers::WorkerState,
             prefixes::PrefixedUuid<prefixes::OrgPrefix>,
             prefixes::PrefixedUuid<prefixes::OrgUserPrefix>,
             prefixes::PrefixedUuid<prefixes::UserPrefix>,
@@ -236,6 +434,7 @@ impl Modify for SecurityAddon {
             prefixes::PrefixedUuid<prefixes::TaskDealPrefix>,
             prefixes::PrefixedUuid<prefixes::TaskLinkPrefix>,
             prefixes::PrefixedUuid<prefixes::TaskUserPrefix>,
+            prefixes::PrefixedUuid<prefixes::ActivityPrefix>,
         )
     ),
     tags(
@@ -251,6 +450,10 @@ impl Modify for SecurityAddon {
         (name = "phones", description = "Phone endpoints. Used to manage phones"),
         (name = "tasks", description = "Task endpoints. Used to manage tasks"),
         (name = "companies", description = "Company endpoints. Used to manage companies"),
+        (name = "activities", description = "Activity endpoints. Used to log calls, meetings and emails"),
+        (name = "billing", description = "Billing endpoints. Used to keep subscriptions in sync with the billing provider"),
+        (name = "events", description = "Event endpoints. Used to follow changes to an organization as they happen"),
+        (name = "resolve", description = "Resolve endpoint. Used to look up any record by its prefixed id"),
     ),
 )]
 pub struct ApiDoc;
@@ -259,94 +462,136 @@ pub struct ApiDoc;
 pub fn main() -> std::io::Result<()> {
     dotenvy::dotenv().ok();
 
-    let sentry_url = std::env::var("SENTRY_URL");
-    let _guard = if let Ok(sentry_url) = sentry_url {
-        log::info!("Sentry monitoring enabled");
+    let settings = match settings::Settings::init() {
+        Ok(settings) => settings,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
 
-        let guard = sentry::init((
-            sentry_url,
+    let sentry_guard = settings.sentry_url.as_ref().map(|sentry_url| {
+        std::env::set_var("RUST_BACKTRACE", "1");
+        sentry::init((
+            sentry_url.expose(),
             sentry::ClientOptions {
                 release: sentry::release_name!(),
                 traces_sample_rate: 1.0,
+                before_send: Some(std::sync::Arc::new(data::redact::scrub_sentry_event)),
                 ..Default::default()
             },
-        ));
-
-        tracing_subscriber::Registry::default()
-            .with(sentry::integrations::tracing::layer())
-            .with(
-                tracing_subscriber::fmt::layer().with_filter(
-                    EnvFilter::from_default_env()
-                        .add_directive(tracing_subscriber::filter::LevelFilter::INFO.into()),
-                ),
-            )
-            .init();
+        ))
+    });
 
-        std::env::set_var("RUST_BACKTRACE", "1");
-        Some(guard)
-    } else {
-        tracing_subscriber::Registry::default()
-            .with(
-                tracing_subscriber::fmt::layer().with_filter(
-                    EnvFilter::from_default_env()
-                        .add_directive(tracing_subscriber::filter::LevelFilter::INFO.into()),
-                ),
-            )
-            .init();
-
-        None
+    let fmt_layer = match settings.log_format {
+        LogFormat::Text => tracing_subscriber::fmt::layer().boxed(),
+        LogFormat::Json => tracing_subscriber::fmt::layer().json().boxed(),
     };
+    tracing_subscriber::Registry::default()
+        .with(
+            sentry_guard
+                .as_ref()
+                .map(|_| sentry::integrations::tracing::layer()),
+        )
+        .with(
+            fmt_layer.with_filter(
+                EnvFilter::from_default_env()
+                    .add_directive(tracing_subscriber::filter::LevelFilter::INFO.into()),
+            ),
+        )
+        .init();
 
-    let database_url = std::env::var("DATABASE_URL").expect("DATABASE_URL should be set");
-    let redis_url = std::env::var("REDIS_URL").expect("REDIS_URL should be set");
+    if sentry_guard.is_some() {
+        log::info!("Sentry monitoring enabled");
+    }
+    log::info!("Loaded settings: {:?}", settings);
+    if settings.uses_dev_secret_key() {
+        log::warn!("SECRET_KEY is not set, sessions are signed with the development key");
+    }
 
-    run_migrations(&database_url);
+    run_migrations(&settings.database_tls.libpq_url(settings.database_url.expose()));
 
     actix_web::rt::System::new().block_on(async move {
         // create db connection pool
         let mut config = ManagerConfig::default();
-        config.custom_setup = Box::new(establish_connection);
+        config.custom_setup = Box::new(move |url| establish_connection(url, &settings.database_tls));
 
         let mgr = AsyncDieselConnectionManager::<diesel_async::AsyncPgConnection>::new_with_config(
-            database_url,
+            settings.database_url.expose(),
             config,
         );
 
+        // Waiting for a connection is bounded so an exhausted pool surfaces as a 503
+        // instead of requests hanging
         let pg_pool = diesel_async::pooled_connection::deadpool::Pool::builder(mgr)
-            .max_size(10)
+            .max_size(settings.database_pool_size)
+            .wait_timeout(Some(std::time::Duration::from_secs(
+                settings.database_pool_wait_secs,
+            )))
+            .runtime(deadpool::Runtime::Tokio1)
             .build()
             .unwrap();
 
-        let redis_store = RedisSessionStore::new(redis_url.clone())
+        let redis_store = RedisSessionStore::new(settings.redis_url.expose())
             .await
             .expect("Failed to create redis store");
 
-        let redis_manager =
-            bb8_redis::RedisConnectionManager::new(redis_url).expect("Failed to connect to redis");
-
-        let redis_connections: u32 = std::env::var("REDIS_CONNECTIONS")
-            .unwrap_or("200".to_string())
-            .parse()
-            .unwrap_or(200);
+        let redis_manager = bb8_redis::RedisConnectionManager::new(settings.redis_url.expose())
+            .expect("Failed to connect to redis");
 
         let redis_pool = bb8_redis::bb8::Pool::builder()
-            .max_size(redis_connections)
+            .max_size(settings.redis_connections)
             .build(redis_manager)
             .await
             .expect("Failed to create redis pool");
 
         let oidc_client = build_oidc_client().await;
 
-        println!("{:?}", std::env::var("SECRET_KEY"));
+        let api_rate_limit = rate_limit_middleware::RateLimitMiddlewareFactory::new(
+            "api",
+            settings.rate_limit_api,
+            Some(settings.rate_limit_api_org),
+        );
+        let invitation_rate_limit = rate_limit_middleware::RateLimitMiddlewareFactory::new(
+            "invitations",
+            settings.rate_limit_invitations,
+            Some(settings.rate_limit_invitations_org),
+        );
 
-        HttpServer::new(move || {
+        let (change_events, _) = tokio::sync::broadcast::channel::<models::ChangeEvent>(1024);
+        actix_web::rt::spawn(operators::event_operator::listen_for_change_events(
+            settings.redis_url.expose().to_string(),
+            change_events.clone(),
+        ));
+
+        let mut worker_runtime = workers::WorkerRuntime::new(web::Data::new(pg_pool.clone()));
+        worker_runtime.spawn(workers::billing_events_worker::BillingEventsWorker {
+            pg_pool: web::Data::new(pg_pool.clone()),
+        });
+        worker_runtime.spawn(workers::job_worker::JobWorker {
+            pg_pool: web::Data::new(pg_pool.clone()),
+        });
+        worker_runtime.spawn(workers::job_worker::JobRetentionWorker {
+            pg_pool: web::Data::new(pg_pool.clone()),
+        });
+        let worker_status = worker_runtime.status();
+
+        let server = HttpServer::new(move || {
             App::new()
-                .app_data(PayloadConfig::new(134200000))
+                .app_data(PayloadConfig::new(settings.max_request_body_bytes))
                 .app_data(
                     web::JsonConfig::default()
-                        .limit(134200000)
-                        .error_handler(|err, _req| {
-                            ServiceError::BadRequest(format!("{}", err)).into()
+                        .limit(settings.max_request_body_bytes)
+                        .error_handler(|err, _req| match err {
+                            JsonPayloadError::Overflow { limit }
+                            | JsonPayloadError::OverflowKnownLength { limit, .. } => {
+                                ServiceError::BadRequest(format!(
+                                    "Request body is larger than the limit of {} bytes",
+                                    limit
+                                ))
+                                .into()
+                            }
+                            err => ServiceError::BadRequest(format!("{}", err)).into(),
                         }),
                 )
                 .app_data(
@@ -357,34 +602,38 @@ pub fn main() -> std::io::Result<()> {
                 .app_data(web::Data::new(pg_pool.clone()))
                 .app_data(web::Data::new(oidc_client.clone()))
                 .app_data(web::Data::new(redis_pool.clone()))
+                .app_data(web::Data::new(change_events.clone()))
+                .app_data(web::Data::new(worker_status.clone()))
                 .wrap(sentry_actix::Sentry::new())
+                .wrap(idempotency_middleware::IdempotencyMiddlewareFactory)
                 .wrap(auth_middleware::AuthMiddlewareFactory)
+                .wrap(csrf_middleware::CsrfMiddlewareFactory::new(settings))
                 .wrap(
                     IdentityMiddleware::builder()
                         .login_deadline(Some(std::time::Duration::from_secs(SECONDS_IN_DAY)))
                         .visit_deadline(Some(std::time::Duration::from_secs(SECONDS_IN_DAY)))
                         .build(),
                 )
-                .wrap(Cors::permissive())
+                .wrap(cors(settings))
                 .wrap(
                     SessionMiddleware::builder(
                         redis_store.clone(),
-                        Key::from(
-                            std::env::var("SECRET_KEY")
-                                .unwrap_or_else(|_| "0123".repeat(16))
-                                .as_bytes(),
-                        ),
+                        Key::from(settings.secret_key.expose().as_bytes()),
                     )
                     .session_lifecycle(
                         PersistentSession::default().session_ttl(time::Duration::days(1)),
                     )
-                    .cookie_name("actix-server".to_owned())
-                    .cookie_same_site(SameSite::Lax)
-                    .cookie_secure(false)
+                    .cookie_name(SESSION_COOKIE_NAME.to_owned())
+                    .cookie_same_site(settings.cookie_same_site)
+                    .cookie_secure(settings.secure_cookies)
                     .cookie_path("/".to_owned())
                     .build(),
                 )
-                .wrap(Logger::default())
+                .wrap(metrics_middleware::MetricsMiddlewareFactory)
+                .wrap(request_id_middleware::RequestIdMiddlewareFactory)
+                .wrap(Logger::new(
+                    r#"%a "%r" %s %b "%{Referer}i" "%{User-Agent}i" %T %{x-request-id}o"#,
+                ))
                 .service(Redoc::with_url("/redoc", ApiDoc::openapi()))
                 .service(
                     SwaggerUi::new("/swagger-ui/{_:.*}")
@@ -395,8 +644,29 @@ pub fn main() -> std::io::Result<()> {
                     web::resource("/auth/cli")
                         .route(web::get().to(handlers::auth_handler::login_cli)),
                 )
+                .service(
+                    web::resource("/metrics")
+                        .route(web::get().to(handlers::health_handler::get_metrics)),
+                )
+                // Registered ahead of the /api scope so probes aren't rate limited
+                .service(
+                    web::scope("/api/health")
+                        .service(
+                            web::resource("")
+                                .route(web::get().to(handlers::health_handler::health_check)),
+                        )
+                        .service(
+                            web::resource("/live")
+                                .route(web::get().to(handlers::health_handler::liveness_check)),
+                        )
+                        .service(
+                            web::resource("/ready")
+                                .route(web::get().to(handlers::health_handler::readiness_check)),
+                        ),
+                )
                 .service(
                     web::scope("/api")
+                        .wrap(api_rate_limit.clone())
                         .service(
                             web::scope("/orgs")
                                 .service(
@@ -412,6 +682,16 @@ pub fn main() -> std::io::Result<()> {
                                     web::resource("/leave/{org_id}")
                                         .route(web::delete().to(handlers::org_handler::leave_org)),
                                 )
+                                .service(
+                                    web::resource("/{org_id}/reassign").route(
+                                        web::post().to(handlers::org_handler::reassign_records),
+                                    ),
+                                )
+                                .service(
+                                    web::resource("/{org_id}/usage").route(
+                                        web::get().to(handlers::org_handler::get_org_usage),
+                                    ),
+                                )
                                 .service(
                                     web::resource("/{org_id}")
                                         .route(web::delete().to(handlers::org_handler::delete_org))
@@ -421,9 +701,14 @@ pub fn main() -> std::io::Result<()> {
                         )
                         .service(
                             web::scope("/invitation")
-                                .service(web::resource("").route(
-                                    web::post().to(handlers::invitation_handler::post_invitation),
-                                ))
+                                .service(
+                                    web::resource("")
+                                        .route(
+                                            web::post()
+                                                .to(handlers::invitation_handler::post_invitation),
+                                        )
+                                        .wrap(invitation_rate_limit.clone()),
+                                )
                                 .service(
                                     web::resource("/{invitation_id}").route(
                                         web::delete()
@@ -479,12 +764,44 @@ pub fn main() -> std::io::Result<()> {
                                             web::put()
                                                 .to(handlers::company_handler::update_company),
                                         )
+                                        .route(
+                                            web::patch()
+                                                .to(handlers::company_handler::update_company),
+                                        )
                                         .route(
                                             web::delete()
                                                 .to(handlers::company_handler::delete_company),
                                         ),
                                 ),
                         )
+                        .service(
+                            web::scope("/activities")
+                                .service(
+                                    web::resource("")
+                                        .route(
+                                            web::post()
+                                                .to(handlers::activity_handler::create_activity),
+                                        )
+                                        .route(
+                                            web::get()
+                                                .to(handlers::activity_handler::list_activities),
+                                        ),
+                                )
+                                .service(
+                                    web::resource("/{activity_id}")
+                                        .route(
+                                            web::get().to(handlers::activity_handler::get_activity),
+                                        )
+                                        .route(
+                                            web::put()
+                                                .to(handlers::activity_handler::update_activity),
+                                        )
+                                        .route(
+                                            web::delete()
+                                                .to(handlers::activity_handler::delete_activity),
+                                        ),
+                                ),
+                        )
                         .service(
                             web::scope("/auth")
                                 .service(
@@ -520,6 +837,22 @@ pub fn main() -> std::io::Result<()> {
                                             web::get().to(handlers::deal_handler::list_deal_by_org),
                                             )
                                     )
+                                .service(
+                                    web::resource("/reports/pipeline")
+                                        .route(web::get().to(handlers::deal_handler::get_pipeline_report)),
+                                )
+                                .service(
+                                    web::resource("/reports/forecast")
+                                        .route(web::get().to(handlers::deal_handler::get_forecast_report)),
+                                )
+                                .service(
+                                    web::resource("/reports/performance")
+                                        .route(web::get().to(handlers::deal_handler::get_performance_report)),
+                                )
+                                .service(
+                                    web::resource("/currencies")
+                                        .route(web::get().to(handlers::deal_handler::list_currencies)),
+                                )
                                 .service(
                                     web::scope("/{deal_id}")
                                     .service(
@@ -528,7 +861,8 @@ pub fn main() -> std::io::Result<()> {
                                             web::delete().to(handlers::deal_handler::delete_deal),
                                         )
                                         .route(web::get().to(handlers::deal_handler::get_deal))
-                                        .route(web::put().to(handlers::deal_handler::update_deal)),
+                                        .route(web::put().to(handlers::deal_handler::update_deal))
+                                        .route(web::patch().to(handlers::deal_handler::update_deal)),
                                         )
                                     .service(
                                         web::scope("/{resource_type}")
@@ -565,6 +899,10 @@ pub fn main() -> std::io::Result<()> {
                                         .route(
                                             web::put()
                                                 .to(handlers::contact_handler::update_contact),
+                                        )
+                                        .route(
+                                            web::patch()
+                                                .to(handlers::contact_handler::update_contact),
                                         ),
                                 )                        
                                 )
@@ -625,6 +963,10 @@ pub fn main() -> std::io::Result<()> {
                                     web::resource("")
                                         .route(web::post().to(handlers::task_handler::create_task)),
                                 )
+                                .service(
+                                    web::resource("/list")
+                                        .route(web::get().to(handlers::task_handler::list_tasks)),
+                                )
                                 .service(
                                     web::scope("/{task_id}")
                                         .service(
@@ -639,6 +981,10 @@ pub fn main() -> std::io::Result<()> {
                                                 .route(
                                                     web::put()
                                                         .to(handlers::task_handler::update_task),
+                                                )
+                                                .route(
+                                                    web::patch()
+                                                        .to(handlers::task_handler::update_task),
                                                 ),
                                         )
                                         .service(
@@ -656,14 +1002,85 @@ pub fn main() -> std::io::Result<()> {
                                 ),
                         )
                         .service(
-                            web::resource("/health")
-                                .route(web::get().to(handlers::auth_handler::health_check)),
+                            web::resource("/resolve/{prefixed_id}")
+                                .route(web::get().to(handlers::resolve_handler::resolve_id)),
+                        )
+                        .service(
+                            web::resource("/events/stream")
+                                .route(web::get().to(handlers::event_handler::stream_events)),
+                        )
+                        .service(
+                            web::resource("/billing/webhook")
+                                .route(web::post().to(handlers::billing_handler::billing_webhook)),
+                        )
+                        // Instance administration. Kept out of ApiDoc on purpose.
+                        .service(
+                            web::scope("/admin")
+                                .service(
+                                    web::resource("/plans")
+                                        .route(web::get().to(handlers::admin_handler::list_plans))
+                                        .route(web::post().to(handlers::admin_handler::create_plan)),
+                                )
+                                .service(
+                                    web::resource("/plans/{plan_id}")
+                                        .route(web::put().to(handlers::admin_handler::update_plan))
+                                        .route(web::delete().to(handlers::admin_handler::delete_plan)),
+                                )
+                                .service(
+                                    web::resource("/orgs")
+                                        .route(web::get().to(handlers::admin_handler::list_orgs)),
+                                )
+                                .service(
+                                    web::resource("/orgs/{org_id}/subscription").route(
+                                        web::put().to(handlers::admin_handler::assign_subscription),
+                                    ),
+                                )
+                                .service(
+                                    web::resource("/users")
+                                        .route(web::get().to(handlers::admin_handler::get_user)),
+                                )
+                                .service(
+                                    web::resource("/users/{user_id}/disabled").route(
+                                        web::put().to(handlers::admin_handler::set_user_disabled),
+                                    ),
+                                )
+                                .service(
+                                    web::resource("/jobs")
+                                        .route(web::get().to(handlers::admin_handler::list_jobs)),
+                                )
+                                .service(
+                                    web::resource("/jobs/{job_id}")
+                                        .route(web::get().to(handlers::admin_handler::get_job)),
+                                )
+                                .service(
+                                    web::resource("/jobs/{job_id}/retry")
+                                        .route(web::post().to(handlers::admin_handler::retry_job)),
+                                ),
                         ),
                 )
         })
-        .bind(("0.0.0.0", 8090))?
-        .run()
-        .await
+        .workers(settings.workers)
+        .shutdown_timeout(settings.shutdown_timeout_secs)
+        // Signals are handled below so the workers stop together with the server
+        .disable_signals()
+        .bind(settings.bind_address)?
+        .run();
+
+        let server_handle = server.handle();
+        let signal_status = worker_runtime.status();
+        actix_web::rt::spawn(async move {
+            shutdown_signal().await;
+            log::info!("Shutting down");
+            signal_status.shut_down();
+            server_handle.stop(true).await;
+        });
+
+        let result = server.await;
+        worker_runtime.status().shut_down();
+        worker_runtime
+            .wait(std::time::Duration::from_secs(settings.shutdown_timeout_secs))
+            .await;
+        result
     })?;
 
     Ok(())
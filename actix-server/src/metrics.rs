@@ -0,0 +1,147 @@
+//! Prometheus metrics served at `/metrics`. Request and error counters are updated as
+//! requests are handled, pool and job queue gauges are sampled whenever the endpoint is
+//! scraped.
+use crate::data::models::{JobStatus, PgPool, RedisPool};
+use prometheus::{
+    Encoder, HistogramOpts, HistogramVec, IntCounterVec, IntGaugeVec, Opts, Registry, TextEncoder,
+};
+use std::sync::OnceLock;
+
+static METRICS: OnceLock<Metrics> = OnceLock::new();
+
+pub struct Metrics {
+    registry: Registry,
+    /// By method, route pattern and status code.
+    pub http_requests: IntCounterVec,
+    /// By method and route pattern.
+    pub http_request_duration: HistogramVec,
+    /// Error responses by `ErrorCode`, i.e. by `ServiceError` variant.
+    pub service_errors: IntCounterVec,
+    /// Postgres pool connections by state: `in_use`, `idle`, `waiting` and `max`.
+    pub db_pool_connections: IntGaugeVec,
+    /// Redis pool connections by state: `in_use`, `idle` and `max`.
+    pub redis_pool_connections: IntGaugeVec,
+    /// Jobs by status, see `JobStatus`.
+    pub jobs: IntGaugeVec,
+}
+
+impl Metrics {
+    fn new() -> Self {
+        let registry = Registry::new();
+
+        let http_requests = IntCounterVec::new(
+            Opts::new("http_requests_total", "HTTP requests handled"),
+            &["method", "route", "status"],
+        )
+        .expect("Valid metric");
+        let http_request_duration = HistogramVec::new(
+            HistogramOpts::new(
+                "http_request_duration_seconds",
+                "Time taken to handle HTTP requests",
+            ),
+            &["method", "route"],
+        )
+        .expect("Valid metric");
+        let service_errors = IntCounterVec::new(
+            Opts::new("service_errors_total", "Error responses by error code"),
+            &["code"],
+        )
+        .expect("Valid metric");
+        let db_pool_connections = IntGaugeVec::new(
+            Opts::new("db_pool_connections", "Postgres pool connections by state"),
+            &["state"],
+        )
+        .expect("Valid metric");
+        let redis_pool_connections = IntGaugeVec::new(
+            Opts::new("redis_pool_connections", "Redis pool connections by state"),
+            &["state"],
+        )
+        .expect("Valid metric");
+        let jobs = IntGaugeVec::new(Opts::new("jobs", "Background jobs by status"), &["status"])
+            .expect("Valid metric");
+
+        for collector in [
+            Box::new(http_requests.clone()) as Box<dyn prometheus::core::Collector>,
+            Box::new(http_request_duration.clone()),
+            Box::new(service_errors.clone()),
+            Box::new(db_pool_connections.clone()),
+            Box::new(redis_pool_connections.clone()),
+            Box::new(jobs.clone()),
+        ] {
+            registry
+                .register(collector)
+                .expect("Metric names are unique");
+        }
+
+        Metrics {
+            registry,
+            http_requests,
+            http_request_duration,
+            service_errors,
+            db_pool_connections,
+            redis_pool_connections,
+            jobs,
+        }
+    }
+
+    pub fn record_pools(&self, pg_pool: &PgPool, redis_pool: &RedisPool, redis_max_size: u32) {
+        // deadpool reports requests waiting for a connection as negative availability
+        let status = pg_pool.status();
+        let idle = status.available.max(0) as i64;
+        for (label, value) in [
+            ("in_use", status.size as i64 - idle),
+            ("idle", idle),
+            ("waiting", (-status.available).max(0) as i64),
+            ("max", status.max_size as i64),
+        ] {
+            self.db_pool_connections
+                .with_label_values(&[label])
+                .set(value);
+        }
+
+        let state = redis_pool.state();
+        for (label, value) in [
+            (
+                "in_use",
+                state.connections as i64 - state.idle_connections as i64,
+            ),
+            ("idle", state.idle_connections as i64),
+            ("max", redis_max_size as i64),
+        ] {
+            self.redis_pool_connections
+                .with_label_values(&[label])
+                .set(value);
+        }
+    }
+
+    /// Statuses without jobs are reported as zero rather than keeping their last count.
+    pub fn record_jobs(&self, counts: &[(String, i64)]) {
+        for status in [
+            JobStatus::Queued,
+            JobStatus::Running,
+            JobStatus::Succeeded,
+            JobStatus::Dead,
+        ] {
+            let count = counts
+                .iter()
+                .find(|(label, _)| label == status.as_str())
+                .map_or(0, |(_, count)| *count);
+            self.jobs.with_label_values(&[status.as_str()]).set(count);
+        }
+    }
+
+    /// Every metric in the Prometheus text exposition format.
+    pub fn render(&self) -> Result<(String, String), prometheus::Error> {
+        let encoder = TextEncoder::new();
+        let mut buffer = vec![];
+        encoder.encode(&self.registry.gather(), &mut buffer)?;
+        Ok((
+            encoder.format_type().to_string(),
+            String::from_utf8_lossy(&buffer).into_owned(),
+        ))
+    }
+}
+
+pub fn get() -> &'static Metrics {
+    METRICS.get_or_init(Metrics::new)
+}
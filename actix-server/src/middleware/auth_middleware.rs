@@ -1,7 +1,8 @@
 use crate::{
-    data::models::{PgPool, User},
-    operators::{
-        org_operator::get_org_user_link_query, user_operator::get_user_from_api_key_query,
+    data::models::{PgPool, RedisPool, User},
+    operators::auth_cache_operator::{
+        get_cached_org_user_link_query, get_cached_user_by_id_query,
+        get_cached_user_from_api_key_query,
     },
     prefixes::{OrgPrefix, PrefixedUuid},
 };
@@ -53,12 +54,15 @@ where
                 if let Some(org_header) = req.headers().get("Organization") {
                     if let Ok(org_header) = org_header.to_str() {
                         if let Ok(org_uuid) = PrefixedUuid::<OrgPrefix>::from_str(org_header) {
-                            let org_user_link = get_org_user_link_query(
+                            let org_user_link = get_cached_org_user_link_query(
                                 user.id,
                                 org_uuid,
                                 req.app_data::<web::Data<PgPool>>()
                                     .expect("PgPool will always be in server state")
                                     .to_owned(),
+                                req.app_data::<web::Data<RedisPool>>()
+                                    .expect("RedisPool will always be in server state")
+                                    .to_owned(),
                             )
                             .await
                             .ok();
@@ -89,21 +93,40 @@ async fn get_user(req: &HttpRequest, pl: &mut Payload, tx: Transaction) -> Optio
     if let Ok(identity) = Identity::from_request(req, pl).into_inner() {
         if let Ok(user_json) = identity.id() {
             if let Ok(user) = serde_json::from_str::<User>(&user_json) {
-                return Some(user);
+                // Reload so that disabling a user or changing their role applies to
+                // sessions that are already open
+                let user = get_cached_user_by_id_query(
+                    user.id,
+                    req.app_data::<web::Data<PgPool>>()
+                        .expect("PgPool will always be in server state")
+                        .to_owned(),
+                    req.app_data::<web::Data<RedisPool>>()
+                        .expect("RedisPool will always be in server state")
+                        .to_owned(),
+                )
+                .await
+                .ok()
+                .filter(|user| !user.disabled);
+                get_user_from_identity_span.finish();
+                return user;
             }
         }
     }
 
     if let Some(auth_header) = req.headers().get("Authorization") {
         if let Ok(auth_header) = auth_header.to_str() {
-            let user = get_user_from_api_key_query(
+            let user = get_cached_user_from_api_key_query(
                 auth_header,
                 req.app_data::<web::Data<PgPool>>()
                     .expect("PgPool will always be in server state")
                     .to_owned(),
+                req.app_data::<web::Data<RedisPool>>()
+                    .expect("RedisPool will always be in server state")
+                    .to_owned(),
             )
             .await
-            .ok();
+            .ok()
+            .filter(|user| !user.disabled);
             get_user_from_identity_span.finish();
             return user;
         }
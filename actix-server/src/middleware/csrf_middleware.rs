@@ -0,0 +1,117 @@
+use crate::{
+    errors::{ErrorCode, ErrorRespPayload},
+    settings::Settings,
+    SESSION_COOKIE_NAME,
+};
+use actix_web::{
+    body::EitherBody,
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    http::{
+        header::{AUTHORIZATION, ORIGIN, REFERER},
+        Method,
+    },
+    Error, HttpResponse,
+};
+use futures_util::future::LocalBoxFuture;
+use std::{
+    future::{ready, Ready},
+    rc::Rc,
+};
+
+/// Origin the request was sent from, taken from `Origin` or, for older browsers that
+/// leave it out, `Referer`.
+fn request_origin(req: &ServiceRequest) -> Option<String> {
+    if let Some(origin) = req.headers().get(ORIGIN) {
+        return origin.to_str().ok().map(|origin| origin.to_string());
+    }
+
+    req.headers()
+        .get(REFERER)
+        .and_then(|referer| referer.to_str().ok())
+        .and_then(|referer| url::Url::parse(referer).ok())
+        .map(|referer| referer.origin().ascii_serialization())
+}
+
+/// Whether a browser could have been tricked into sending the request. Only writes
+/// authenticated by the session cookie can be: API keys travel in a header another
+/// site can't set without passing CORS.
+fn needs_origin_check(req: &ServiceRequest) -> bool {
+    !matches!(*req.method(), Method::GET | Method::HEAD | Method::OPTIONS)
+        && !req.headers().contains_key(AUTHORIZATION)
+        && req.cookie(SESSION_COOKIE_NAME).is_some()
+}
+
+pub struct CsrfMiddleware<S> {
+    service: Rc<S>,
+    allowed_origins: Option<Rc<Vec<String>>>,
+}
+
+impl<S, B> Service<ServiceRequest> for CsrfMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let srv = self.service.clone();
+        let allowed = match &self.allowed_origins {
+            Some(allowed_origins) if needs_origin_check(&req) => {
+                request_origin(&req).is_some_and(|origin| allowed_origins.contains(&origin))
+            }
+            _ => true,
+        };
+
+        Box::pin(async move {
+            if !allowed {
+                let res = HttpResponse::Forbidden().json(ErrorRespPayload::new(
+                    ErrorCode::Forbidden,
+                    "Cross-site request blocked. Use an allowed origin or an API key.",
+                ));
+                return Ok(req.into_response(res).map_into_right_body());
+            }
+
+            Ok(srv.call(req).await?.map_into_left_body())
+        })
+    }
+}
+
+/// Rejects cookie authenticated writes that don't come from an allowed origin.
+#[derive(Clone)]
+pub struct CsrfMiddlewareFactory {
+    /// `None` when any origin is allowed, see [`Settings::allows_any_origin`].
+    allowed_origins: Option<Rc<Vec<String>>>,
+}
+
+impl CsrfMiddlewareFactory {
+    pub fn new(settings: &Settings) -> Self {
+        CsrfMiddlewareFactory {
+            allowed_origins: (!settings.allows_any_origin())
+                .then(|| Rc::new(settings.allowed_origins())),
+        }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for CsrfMiddlewareFactory
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = CsrfMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(CsrfMiddleware {
+            service: Rc::new(service),
+            allowed_origins: self.allowed_origins.clone(),
+        }))
+    }
+}
@@ -0,0 +1,267 @@
+use crate::{
+    data::models::{RedisPool, User},
+    errors::ServiceError,
+    operators::idempotency_operator::{
+        claim_idempotency_key_query, idempotency_fingerprint, refresh_idempotency_key_query,
+        release_idempotency_key_query, save_idempotent_response_query, IdempotencyRecord,
+        IdempotentResponse, IDEMPOTENCY_LOCK_REFRESH_SECS,
+    },
+};
+use actix_web::{
+    body::{self, BoxBody, MessageBody},
+    dev::{forward_ready, Payload, Service, ServiceRequest, ServiceResponse, Transform},
+    error::PayloadError,
+    http::{header, Method, StatusCode},
+    web, Error, HttpMessage, HttpResponse,
+};
+use futures_util::{
+    future::{self, Either, LocalBoxFuture},
+    pin_mut, stream,
+};
+use std::{
+    convert::Infallible,
+    future::{ready, Ready},
+    rc::Rc,
+    time::Duration,
+};
+
+const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+const IDEMPOTENT_REPLAYED_HEADER: &str = "Idempotent-Replayed";
+const MAX_IDEMPOTENCY_KEY_LEN: usize = 255;
+
+/// Headers that describe the connection or the session rather than the response, so
+/// they aren't replayed. Content-Length is recomputed from the stored body.
+const UNREPLAYED_HEADERS: [header::HeaderName; 11] = [
+    header::CONNECTION,
+    header::CONTENT_LENGTH,
+    header::DATE,
+    header::PROXY_AUTHENTICATE,
+    header::PROXY_AUTHORIZATION,
+    header::SET_COOKIE,
+    header::TE,
+    header::TRAILER,
+    header::TRANSFER_ENCODING,
+    header::UPGRADE,
+    header::HeaderName::from_static("keep-alive"),
+];
+
+/// Whether a response is the final answer for its request. Server errors, and client
+/// errors that depend on the moment the request was made, like rate limits, write
+/// conflicts and quota, are left for a retry to redo.
+fn is_replayable(status: StatusCode) -> bool {
+    status.is_success()
+        || (status.is_client_error()
+            && !matches!(
+                status,
+                StatusCode::PAYMENT_REQUIRED
+                    | StatusCode::REQUEST_TIMEOUT
+                    | StatusCode::CONFLICT
+                    | StatusCode::PRECONDITION_FAILED
+                    | StatusCode::TOO_MANY_REQUESTS
+            ))
+}
+
+/// Makes POST requests that carry an `Idempotency-Key` header safe to retry. The first
+/// response for a key is stored and replayed for retries, keys are scoped to the user
+/// and organization making the request.
+pub struct IdempotencyMiddleware<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for IdempotencyMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+    fn call(&self, mut req: ServiceRequest) -> Self::Future {
+        let srv = self.service.clone();
+        Box::pin(async move {
+            let idempotency_key = req
+                .headers()
+                .get(IDEMPOTENCY_KEY_HEADER)
+                .and_then(|idempotency_key| idempotency_key.to_str().ok())
+                .map(|idempotency_key| idempotency_key.trim().to_string())
+                .filter(|idempotency_key| !idempotency_key.is_empty());
+            let user_id = req.extensions().get::<User>().map(|user| user.id);
+
+            // Unauthenticated requests are rejected by the handlers anyway
+            let (idempotency_key, user_id) = match (idempotency_key, user_id) {
+                (Some(idempotency_key), Some(user_id)) if req.method() == Method::POST => {
+                    (idempotency_key, user_id)
+                }
+                _ => return Ok(srv.call(req).await?.map_into_boxed_body()),
+            };
+
+            if idempotency_key.len() > MAX_IDEMPOTENCY_KEY_LEN {
+                return Err(ServiceError::BadRequest(format!(
+                    "Idempotency-Key can be at most {} characters",
+                    MAX_IDEMPOTENCY_KEY_LEN
+                ))
+                .into());
+            }
+
+            let redis_pool = req
+                .app_data::<web::Data<RedisPool>>()
+                .expect("RedisPool will always be in server state")
+                .to_owned();
+
+            // Read the body to fingerprint it, then put it back for the handler
+            let request_body = req.extract::<web::Bytes>().await?;
+            let fingerprint =
+                idempotency_fingerprint(req.method().as_str(), req.path(), &request_body);
+            req.set_payload(Payload::Stream {
+                payload: Box::pin(stream::once(ready(Ok::<_, PayloadError>(request_body)))),
+            });
+
+            let org_id = req
+                .headers()
+                .get("Organization")
+                .and_then(|org_id| org_id.to_str().ok())
+                .unwrap_or_default();
+            let key = format!("idempotency:{}:{}:{}", user_id, org_id, idempotency_key);
+
+            match claim_idempotency_key_query(&key, &fingerprint, redis_pool.clone()).await? {
+                None => {}
+                Some(record) if record.fingerprint != fingerprint => {
+                    return Err(ServiceError::UnprocessableEntity(
+                        "Idempotency-Key was already used for a different request".to_string(),
+                    )
+                    .into())
+                }
+                Some(IdempotencyRecord {
+                    response: Some(response),
+                    ..
+                }) => {
+                    let mut replay = HttpResponse::build(
+                        StatusCode::from_u16(response.status).unwrap_or(StatusCode::OK),
+                    );
+                    for (name, value) in response.headers {
+                        replay.append_header((name, value));
+                    }
+                    replay.insert_header((IDEMPOTENT_REPLAYED_HEADER, "true"));
+
+                    return Ok(req.into_response(replay.body(response.body)));
+                }
+                Some(_) => {
+                    return Err(ServiceError::Conflict(
+                        "The request with this Idempotency-Key is still being processed"
+                            .to_string(),
+                    )
+                    .into())
+                }
+            }
+
+            // The lock is kept alive for as long as the handler runs, however long that is
+            let call = srv.call(req);
+            let hold = hold_idempotency_key(&key, &fingerprint, redis_pool.clone());
+            pin_mut!(call, hold);
+            let res = match future::select(call, hold).await {
+                Either::Left((res, _)) => res,
+                Either::Right((never, _)) => match never {},
+            };
+
+            let res = match res {
+                Ok(res) => res,
+                Err(e) => {
+                    release_idempotency_key(&key, redis_pool).await;
+                    return Err(e);
+                }
+            };
+
+            if !is_replayable(res.status()) {
+                release_idempotency_key(&key, redis_pool).await;
+                return Ok(res.map_into_boxed_body());
+            }
+
+            let (http_req, http_res) = res.into_parts();
+            let (http_res, response_body) = http_res.into_parts();
+            let response_body = match body::to_bytes(response_body).await {
+                Ok(response_body) => response_body,
+                Err(_) => {
+                    release_idempotency_key(&key, redis_pool).await;
+                    return Err(ServiceError::InternalServerError(
+                        "Error reading response body".to_string(),
+                    )
+                    .into());
+                }
+            };
+
+            match String::from_utf8(response_body.to_vec()) {
+                Ok(body) => {
+                    let response = IdempotentResponse {
+                        status: http_res.status().as_u16(),
+                        headers: http_res
+                            .headers()
+                            .iter()
+                            .filter(|(name, _)| !UNREPLAYED_HEADERS.contains(name))
+                            .filter_map(|(name, value)| {
+                                Some((name.to_string(), value.to_str().ok()?.to_string()))
+                            })
+                            .collect(),
+                        body,
+                    };
+                    if let Err(e) =
+                        save_idempotent_response_query(&key, &fingerprint, response, redis_pool)
+                            .await
+                    {
+                        log::error!("Error storing idempotent response: {:?}", e);
+                    }
+                }
+                // Only text bodies are stored, anything else can simply be retried
+                Err(_) => release_idempotency_key(&key, redis_pool).await,
+            }
+
+            Ok(ServiceResponse::new(
+                http_req,
+                http_res.set_body(response_body).map_into_boxed_body(),
+            ))
+        })
+    }
+}
+
+/// Refreshes the lock on `key` until dropped.
+async fn hold_idempotency_key(
+    key: &str,
+    fingerprint: &str,
+    redis_pool: web::Data<RedisPool>,
+) -> Infallible {
+    loop {
+        actix_web::rt::time::sleep(Duration::from_secs(IDEMPOTENCY_LOCK_REFRESH_SECS)).await;
+        if let Err(e) = refresh_idempotency_key_query(key, fingerprint, redis_pool.clone()).await {
+            log::error!("Error refreshing idempotency key: {:?}", e);
+        }
+    }
+}
+
+async fn release_idempotency_key(key: &str, redis_pool: web::Data<RedisPool>) {
+    if let Err(e) = release_idempotency_key_query(key, redis_pool).await {
+        log::error!("Error releasing idempotency key: {:?}", e);
+    }
+}
+
+pub struct IdempotencyMiddlewareFactory;
+
+impl<S, B> Transform<S, ServiceRequest> for IdempotencyMiddlewareFactory
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = IdempotencyMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(IdempotencyMiddleware {
+            service: Rc::new(service),
+        }))
+    }
+}
@@ -1 +1,6 @@
 pub mod auth_middleware;
+pub mod csrf_middleware;
+pub mod idempotency_middleware;
+pub mod metrics_middleware;
+pub mod rate_limit_middleware;
+pub mod request_id_middleware;
@@ -0,0 +1,212 @@
+use crate::{
+    data::models::{OrgUserLink, RateLimit, RateLimitStatus, RedisPool, User},
+    errors::{ErrorCode, ErrorRespPayload},
+    operators::{api_key_operator::hash_api_key, rate_limit_operator::take_rate_limit_token_query},
+    settings,
+};
+use actix_web::{
+    body::EitherBody,
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    http::header::{HeaderMap, HeaderName, HeaderValue, RETRY_AFTER},
+    web, Error, HttpMessage, HttpResponse,
+};
+use futures_util::future::LocalBoxFuture;
+use std::{
+    future::{ready, Ready},
+    net::{IpAddr, SocketAddr},
+    rc::Rc,
+};
+
+fn insert_rate_limit_headers(headers: &mut HeaderMap, status: &RateLimitStatus) {
+    headers.insert(
+        HeaderName::from_static("ratelimit-limit"),
+        HeaderValue::from(status.limit),
+    );
+    headers.insert(
+        HeaderName::from_static("ratelimit-remaining"),
+        HeaderValue::from(status.remaining),
+    );
+    headers.insert(
+        HeaderName::from_static("ratelimit-reset"),
+        HeaderValue::from(status.reset_secs),
+    );
+    if !status.allowed {
+        headers.insert(
+            RETRY_AFTER,
+            HeaderValue::from(status.retry_after_secs.max(1)),
+        );
+    }
+}
+
+/// Who a request counts against: the API key it was made with, otherwise the logged in
+/// user, otherwise the client address.
+fn caller_key(req: &ServiceRequest) -> String {
+    let user_id = req.extensions().get::<User>().map(|user| user.id);
+    let api_key = req
+        .headers()
+        .get("Authorization")
+        .and_then(|api_key| api_key.to_str().ok());
+
+    match (user_id, api_key) {
+        (Some(_), Some(api_key)) => format!("key:{}", hash_api_key(api_key)),
+        (Some(user_id), None) => format!("user:{}", user_id),
+        (None, _) => match client_addr(req) {
+            Some(addr) => format!("ip:{}", addr),
+            None => "ip:unknown".to_string(),
+        },
+    }
+}
+
+/// Address of the client that sent the request. Forwarded headers can be set by anyone,
+/// so `X-Forwarded-For` is only followed while the address it came from is a trusted
+/// proxy, from the right, where each proxy appends the address it got the request from.
+fn client_addr(req: &ServiceRequest) -> Option<IpAddr> {
+    let trusted_proxies = &settings::get().trusted_proxies;
+    let mut client = req.peer_addr()?.ip();
+    if !trusted_proxies.contains(&client) {
+        return Some(client);
+    }
+
+    let forwarded_for = req
+        .headers()
+        .get_all("X-Forwarded-For")
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(|addr| addr.trim())
+        .collect::<Vec<_>>();
+    for addr in forwarded_for.into_iter().rev() {
+        if !trusted_proxies.contains(&client) {
+            break;
+        }
+        match addr
+            .parse::<IpAddr>()
+            .or_else(|_| addr.parse::<SocketAddr>().map(|addr| addr.ip()))
+        {
+            Ok(addr) => client = addr,
+            Err(_) => break,
+        }
+    }
+    Some(client)
+}
+
+pub struct RateLimitMiddleware<S> {
+    service: Rc<S>,
+    group: &'static str,
+    caller_limit: RateLimit,
+    org_limit: Option<RateLimit>,
+}
+
+impl<S, B> Service<ServiceRequest> for RateLimitMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let srv = self.service.clone();
+        let group = self.group;
+        let caller_limit = self.caller_limit;
+        let org_limit = self.org_limit;
+        Box::pin(async move {
+            let redis_pool = req
+                .app_data::<web::Data<RedisPool>>()
+                .expect("RedisPool will always be in server state")
+                .to_owned();
+
+            let caller_key = format!("rate_limit:{}:{}", group, caller_key(&req));
+            let org_key = req
+                .extensions()
+                .get::<OrgUserLink>()
+                .map(|org_user_link| format!("rate_limit:{}:org:{}", group, org_user_link.org_id));
+
+            // The API stays up when Redis doesn't, requests just aren't limited then
+            let mut statuses = vec![];
+            match take_rate_limit_token_query(&caller_key, caller_limit, redis_pool.clone()).await {
+                Ok(status) => statuses.push(status),
+                Err(e) => log::error!("Error checking rate limit: {:?}", e),
+            }
+            if let (Some(org_key), Some(org_limit)) = (org_key, org_limit) {
+                if statuses.iter().all(|status| status.allowed) {
+                    match take_rate_limit_token_query(&org_key, org_limit, redis_pool).await {
+                        Ok(status) => statuses.push(status),
+                        Err(e) => log::error!("Error checking rate limit: {:?}", e),
+                    }
+                }
+            }
+
+            // Report whichever bucket is closest to running out
+            let status = statuses
+                .into_iter()
+                .min_by_key(|status| (status.allowed, status.remaining));
+
+            match status {
+                Some(status) if !status.allowed => {
+                    let mut res = HttpResponse::TooManyRequests().json(ErrorRespPayload::new(
+                        ErrorCode::TooManyRequests,
+                        format!(
+                            "Rate limit exceeded. Retry in {} seconds.",
+                            status.retry_after_secs.max(1)
+                        ),
+                    ));
+                    insert_rate_limit_headers(res.headers_mut(), &status);
+
+                    Ok(req.into_response(res).map_into_right_body())
+                }
+                Some(status) => {
+                    let mut res = srv.call(req).await?;
+                    insert_rate_limit_headers(res.headers_mut(), &status);
+
+                    Ok(res.map_into_left_body())
+                }
+                None => Ok(srv.call(req).await?.map_into_left_body()),
+            }
+        })
+    }
+}
+
+/// Limits the requests to the routes it wraps. Every caller gets a bucket of
+/// `caller_limit` and, when given, every organization a shared one of `org_limit`.
+/// Buckets are per `group`, so wrapping a scope again adds a stricter limit on top.
+#[derive(Clone)]
+pub struct RateLimitMiddlewareFactory {
+    group: &'static str,
+    caller_limit: RateLimit,
+    org_limit: Option<RateLimit>,
+}
+
+impl RateLimitMiddlewareFactory {
+    pub fn new(group: &'static str, caller_limit: RateLimit, org_limit: Option<RateLimit>) -> Self {
+        RateLimitMiddlewareFactory {
+            group,
+            caller_limit,
+            org_limit,
+        }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for RateLimitMiddlewareFactory
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = RateLimitMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RateLimitMiddleware {
+            service: Rc::new(service),
+            group: self.group,
+            caller_limit: self.caller_limit,
+            org_limit: self.org_limit,
+        }))
+    }
+}
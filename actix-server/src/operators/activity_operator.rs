@@ -0,0 +1,339 @@
+use crate::{
+    data::{
+        etag::{unchanged_since, write_conflict},
+        models::{Activity, ActivityPayload, PgPool},
+    },
+    errors::ServiceError,
+    prefixes::{ActivityPrefix, CompanyPrefix, ContactPrefix, DealPrefix, OrgPrefix, PrefixedUuid},
+};
+use actix_web::web;
+use diesel::{ExpressionMethods, OptionalExtension, QueryDsl};
+use diesel_async::{
+    scoped_futures::ScopedFutureExt, AsyncConnection, AsyncPgConnection, RunQueryDsl,
+};
+
+/// Recomputes `last_contacted_at` on the contacts and deals the given activities are
+/// linked to, as the latest activity each has left. Pass an activity both before and
+/// after a change so the records it was unlinked from are recomputed too.
+async fn refresh_last_contacted(
+    activities: &[&Activity],
+    conn: &mut AsyncPgConnection,
+) -> Result<(), ServiceError> {
+    use crate::data::schema::activities::dsl as activities_columns;
+    use crate::data::schema::contacts::dsl as contacts_columns;
+    use crate::data::schema::deals::dsl as deals_columns;
+
+    let mut contact_ids = activities
+        .iter()
+        .filter_map(|activity| activity.contact_id)
+        .collect::<Vec<_>>();
+    contact_ids.sort_by_key(|contact_id| contact_id.id);
+    contact_ids.dedup();
+    let mut deal_ids = activities
+        .iter()
+        .filter_map(|activity| activity.deal_id)
+        .collect::<Vec<_>>();
+    deal_ids.sort_by_key(|deal_id| deal_id.id);
+    deal_ids.dedup();
+
+    // Each record is locked before it's recomputed, so a concurrent change to its
+    // activities commits first and is seen by the recompute rather than overwritten
+    for contact_id in contact_ids {
+        contacts_columns::contacts
+            .filter(contacts_columns::id.eq(contact_id))
+            .select(contacts_columns::id)
+            .for_update()
+            .execute(conn)
+            .await
+            .map_err(ServiceError::from)?;
+        diesel::update(contacts_columns::contacts)
+            .filter(contacts_columns::id.eq(contact_id))
+            .set(
+                contacts_columns::last_contacted_at.eq(activities_columns::activities
+                    .filter(activities_columns::contact_id.eq(contact_id))
+                    .select(diesel::dsl::max(activities_columns::occurred_at))
+                    .single_value()),
+            )
+            .execute(conn)
+            .await
+            .map_err(ServiceError::from)?;
+    }
+
+    for deal_id in deal_ids {
+        deals_columns::deals
+            .filter(deals_columns::id.eq(deal_id))
+            .select(deals_columns::id)
+            .for_update()
+            .execute(conn)
+            .await
+            .map_err(ServiceError::from)?;
+        diesel::update(deals_columns::deals)
+            .filter(deals_columns::id.eq(deal_id))
+            .set(
+                deals_columns::last_contacted_at.eq(activities_columns::activities
+                    .filter(activities_columns::deal_id.eq(deal_id))
+                    .select(diesel::dsl::max(activities_columns::occurred_at))
+                    .single_value()),
+            )
+            .execute(conn)
+            .await
+            .map_err(ServiceError::from)?;
+    }
+
+    Ok(())
+}
+
+/// Errors with an invalid field for any linked record that isn't in the org, so an
+/// activity can't point at another org's contact, deal or company.
+async fn check_links_in_org(
+    org_id: PrefixedUuid<OrgPrefix>,
+    contact_id: Option<PrefixedUuid<ContactPrefix>>,
+    deal_id: Option<PrefixedUuid<DealPrefix>>,
+    company_id: Option<PrefixedUuid<CompanyPrefix>>,
+    conn: &mut AsyncPgConnection,
+) -> Result<(), ServiceError> {
+    use crate::data::schema::companies::dsl as companies_columns;
+    use crate::data::schema::contacts::dsl as contacts_columns;
+    use crate::data::schema::deals::dsl as deals_columns;
+
+    if let Some(contact_id) = contact_id {
+        let contacts = contacts_columns::contacts
+            .filter(contacts_columns::id.eq(contact_id))
+            .filter(contacts_columns::org_id.eq(org_id))
+            .count()
+            .get_result::<i64>(conn)
+            .await
+            .map_err(ServiceError::from)?;
+        if contacts == 0 {
+            return Err(ServiceError::invalid_field(
+                "contact_id",
+                format!("Contact {} does not exist", contact_id),
+            ));
+        }
+    }
+
+    if let Some(deal_id) = deal_id {
+        let deals = deals_columns::deals
+            .filter(deals_columns::id.eq(deal_id))
+            .filter(deals_columns::org_id.eq(org_id))
+            .count()
+            .get_result::<i64>(conn)
+            .await
+            .map_err(ServiceError::from)?;
+        if deals == 0 {
+            return Err(ServiceError::invalid_field(
+                "deal_id",
+                format!("Deal {} does not exist", deal_id),
+            ));
+        }
+    }
+
+    if let Some(company_id) = company_id {
+        let companies = companies_columns::companies
+            .filter(companies_columns::id.eq(company_id))
+            .filter(companies_columns::org_id.eq(org_id))
+            .count()
+            .get_result::<i64>(conn)
+            .await
+            .map_err(ServiceError::from)?;
+        if companies == 0 {
+            return Err(ServiceError::invalid_field(
+                "company_id",
+                format!("Company {} does not exist", company_id),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+#[tracing::instrument(skip(pg_pool))]
+pub async fn create_activity_query(
+    org_id: PrefixedUuid<OrgPrefix>,
+    payload: ActivityPayload,
+    contact_id: Option<PrefixedUuid<ContactPrefix>>,
+    deal_id: Option<PrefixedUuid<DealPrefix>>,
+    company_id: Option<PrefixedUuid<CompanyPrefix>>,
+    occurred_at: Option<chrono::NaiveDateTime>,
+    pg_pool: web::Data<PgPool>,
+) -> Result<Activity, ServiceError> {
+    use crate::data::schema::activities::dsl as activities_columns;
+    let mut conn = pg_pool.get().await?;
+
+    conn.transaction::<_, ServiceError, _>(|conn| {
+        async move {
+            check_links_in_org(org_id, contact_id, deal_id, company_id, conn).await?;
+            let new_activity = Activity::from_details(
+                org_id,
+                payload,
+                contact_id,
+                deal_id,
+                company_id,
+                occurred_at,
+            );
+            let activity = diesel::insert_into(activities_columns::activities)
+                .values(&new_activity)
+                .get_result::<Activity>(conn)
+                .await
+                .map_err(ServiceError::from)?;
+            refresh_last_contacted(&[&activity], conn).await?;
+            Ok(activity)
+        }
+        .scope_boxed()
+    })
+    .await
+}
+
+pub async fn get_activity_by_id_query(
+    activity_id: PrefixedUuid<ActivityPrefix>,
+    org_id: PrefixedUuid<OrgPrefix>,
+    pg_pool: web::Data<PgPool>,
+) -> Result<Activity, ServiceError> {
+    use crate::data::schema::activities::dsl as activities_columns;
+    let mut conn = pg_pool.get().await?;
+    let activity = activities_columns::activities
+        .filter(activities_columns::id.eq(activity_id))
+        .filter(activities_columns::org_id.eq(org_id))
+        .first::<Activity>(&mut conn)
+        .await
+        .map_err(|_| ServiceError::NotFound)?;
+    Ok(activity)
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn update_activity_query(
+    activity_id: PrefixedUuid<ActivityPrefix>,
+    org_id: PrefixedUuid<OrgPrefix>,
+    payload: Option<ActivityPayload>,
+    contact_id: Option<PrefixedUuid<ContactPrefix>>,
+    deal_id: Option<PrefixedUuid<DealPrefix>>,
+    company_id: Option<PrefixedUuid<CompanyPrefix>>,
+    occurred_at: Option<chrono::NaiveDateTime>,
+    expected_updated_at: Option<chrono::NaiveDateTime>,
+    pg_pool: web::Data<PgPool>,
+) -> Result<Activity, ServiceError> {
+    use crate::data::schema::activities::dsl as activities_columns;
+    let mut conn = pg_pool.get().await?;
+
+    conn.transaction::<_, ServiceError, _>(|conn| {
+        async move {
+            check_links_in_org(org_id, contact_id, deal_id, company_id, conn).await?;
+            let target = activities_columns::activities
+                .filter(activities_columns::id.eq(activity_id))
+                .filter(unchanged_since(
+                    activities_columns::updated_at,
+                    expected_updated_at,
+                ))
+                .filter(activities_columns::org_id.eq(org_id));
+            let Some(activity) = target
+                .for_update()
+                .first::<Activity>(conn)
+                .await
+                .optional()
+                .map_err(ServiceError::from)?
+            else {
+                return Err(write_conflict(expected_updated_at));
+            };
+            let updated_activity = diesel::update(activities_columns::activities)
+                .filter(activities_columns::id.eq(activity.id))
+                .set((
+                    payload.map(|payload| activities_columns::payload.eq(payload)),
+                    contact_id.map(|contact_id| activities_columns::contact_id.eq(contact_id)),
+                    deal_id.map(|deal_id| activities_columns::deal_id.eq(deal_id)),
+                    company_id.map(|company_id| activities_columns::company_id.eq(company_id)),
+                    occurred_at.map(|occurred_at| activities_columns::occurred_at.eq(occurred_at)),
+                ))
+                .get_result::<Activity>(conn)
+                .await
+                .map_err(ServiceError::from)?;
+            refresh_last_contacted(&[&activity, &updated_activity], conn).await?;
+            Ok(updated_activity)
+        }
+        .scope_boxed()
+    })
+    .await
+}
+
+pub async fn delete_activity_query(
+    activity_id: PrefixedUuid<ActivityPrefix>,
+    org_id: PrefixedUuid<OrgPrefix>,
+    expected_updated_at: Option<chrono::NaiveDateTime>,
+    pg_pool: web::Data<PgPool>,
+) -> Result<(), ServiceError> {
+    use crate::data::schema::activities::dsl as activities_columns;
+    let mut conn = pg_pool.get().await?;
+
+    conn.transaction::<_, ServiceError, _>(|conn| {
+        async move {
+            let deleted = diesel::delete(activities_columns::activities)
+                .filter(activities_columns::id.eq(activity_id))
+                .filter(unchanged_since(
+                    activities_columns::updated_at,
+                    expected_updated_at,
+                ))
+                .filter(activities_columns::org_id.eq(org_id))
+                .get_result::<Activity>(conn)
+                .await
+                .optional()
+                .map_err(|_| {
+                    ServiceError::InternalServerError("Error deleting activity".to_string())
+                })?;
+
+            match deleted {
+                Some(activity) => refresh_last_contacted(&[&activity], conn).await,
+                None if expected_updated_at.is_some() => Err(ServiceError::PreconditionFailed),
+                None => Ok(()),
+            }
+        }
+        .scope_boxed()
+    })
+    .await
+}
+
+pub async fn list_activities_query(
+    org_id: PrefixedUuid<OrgPrefix>,
+    contact_id: Option<PrefixedUuid<ContactPrefix>>,
+    deal_id: Option<PrefixedUuid<DealPrefix>>,
+    company_id: Option<PrefixedUuid<CompanyPrefix>>,
+    pg_pool: web::Data<PgPool>,
+    limit: Option<i64>,
+    offset: Option<PrefixedUuid<ActivityPrefix>>,
+) -> Result<(Vec<Activity>, i64), ServiceError> {
+    use crate::data::schema::activities::dsl as activities_columns;
+    let mut conn = pg_pool.get().await?;
+    let limit = limit.unwrap_or(10);
+    let offset = offset.unwrap_or(PrefixedUuid::zero_id(ActivityPrefix));
+
+    let mut query = activities_columns::activities
+        .filter(activities_columns::org_id.eq(org_id))
+        .into_boxed();
+    let mut count_query = activities_columns::activities
+        .filter(activities_columns::org_id.eq(org_id))
+        .into_boxed();
+    if let Some(contact_id) = contact_id {
+        query = query.filter(activities_columns::contact_id.eq(contact_id));
+        count_query = count_query.filter(activities_columns::contact_id.eq(contact_id));
+    }
+    if let Some(deal_id) = deal_id {
+        query = query.filter(activities_columns::deal_id.eq(deal_id));
+        count_query = count_query.filter(activities_columns::deal_id.eq(deal_id));
+    }
+    if let Some(company_id) = company_id {
+        query = query.filter(activities_columns::company_id.eq(company_id));
+        count_query = count_query.filter(activities_columns::company_id.eq(company_id));
+    }
+
+    let activities = query
+        .filter(activities_columns::id.gt(offset))
+        .order(activities_columns::id)
+        .limit(limit)
+        .load::<Activity>(&mut conn)
+        .await
+        .map_err(|_| ServiceError::InternalServerError("Error fetching activities".to_string()))?;
+    let count = count_query
+        .count()
+        .get_result::<i64>(&mut conn)
+        .await
+        .map_err(|_| ServiceError::InternalServerError("Error counting activities".to_string()))?;
+    Ok((activities, count))
+}
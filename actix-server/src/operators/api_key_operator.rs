@@ -21,7 +21,7 @@ pub fn generate_api_key() -> String {
     api_key
 }
 
-#[tracing::instrument]
+#[tracing::instrument(skip(password))]
 pub fn hash_api_key(password: &str) -> String {
     blake3::hash(password.as_bytes()).to_string()
 }
@@ -37,7 +37,7 @@ pub async fn create_api_key_query(
     let raw_api_key = generate_api_key();
     let blake3_hash = hash_api_key(&raw_api_key);
 
-    let mut conn = pg_pool.get().await.unwrap();
+    let mut conn = pg_pool.get().await?;
 
     let api_key_struct = ApiKey::from_details(user_id, name, blake3_hash.clone());
 
@@ -0,0 +1,233 @@
+//! Redis cache for the lookups the authentication middleware makes on every request.
+//! Writes that change who a user is or what they may do in an org invalidate the
+//! affected entries, the TTL only bounds how long a missed invalidation can linger.
+use crate::{
+    data::models::{OrgUserLink, PgPool, RedisPool, User},
+    errors::ServiceError,
+    operators::{
+        api_key_operator::hash_api_key,
+        org_operator::get_org_user_link_query,
+        user_operator::{get_user_by_id_query, get_user_from_api_key_query},
+    },
+    prefixes::{OrgPrefix, PrefixedUuid, UserPrefix},
+};
+use actix_web::web;
+use bb8_redis::redis;
+use std::str::FromStr;
+
+const AUTH_CACHE_TTL_SECS: u64 = 60 * 5;
+
+fn api_key_cache_key(blake3_hash: &str) -> String {
+    format!("auth_cache:api_key:{}", blake3_hash)
+}
+
+fn user_cache_key(user_id: PrefixedUuid<UserPrefix>) -> String {
+    format!("auth_cache:user:{}", user_id)
+}
+
+/// Every membership gets its own key, so each expires on its own. They share the org's
+/// prefix, so deleting an org can find them all.
+fn org_cache_prefix(org_id: PrefixedUuid<OrgPrefix>) -> String {
+    format!("auth_cache:org:{}:user:", org_id)
+}
+
+fn org_user_cache_key(
+    org_id: PrefixedUuid<OrgPrefix>,
+    user_id: PrefixedUuid<UserPrefix>,
+) -> String {
+    format!("{}{}", org_cache_prefix(org_id), user_id)
+}
+
+/// Runs a cache command, treating Redis errors as a miss so auth keeps working
+/// without the cache.
+async fn cache_command<T: redis::FromRedisValue>(
+    cmd: redis::Cmd,
+    redis_pool: &web::Data<RedisPool>,
+) -> Option<T> {
+    let mut redis_conn = match redis_pool.get().await {
+        Ok(redis_conn) => redis_conn,
+        Err(e) => {
+            log::error!("Could not get redis connection for auth cache: {:?}", e);
+            return None;
+        }
+    };
+
+    match cmd.query_async::<_, T>(&mut *redis_conn).await {
+        Ok(value) => Some(value),
+        Err(e) => {
+            log::error!("Auth cache command failed: {:?}", e);
+            None
+        }
+    }
+}
+
+#[tracing::instrument(skip(pg_pool, redis_pool), fields(cache_hit))]
+pub async fn get_cached_user_by_id_query(
+    user_id: PrefixedUuid<UserPrefix>,
+    pg_pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
+) -> Result<User, ServiceError> {
+    let cached = cache_command::<Option<String>>(
+        redis::cmd("GET").arg(user_cache_key(user_id)).clone(),
+        &redis_pool,
+    )
+    .await
+    .flatten()
+    .and_then(|user| serde_json::from_str::<User>(&user).ok());
+    tracing::Span::current().record("cache_hit", cached.is_some());
+
+    if let Some(user) = cached {
+        return Ok(user);
+    }
+
+    let user = get_user_by_id_query(&user_id, pg_pool).await?;
+    if let Ok(serialized) = serde_json::to_string(&user) {
+        cache_command::<()>(
+            redis::cmd("SET")
+                .arg(user_cache_key(user_id))
+                .arg(serialized)
+                .arg("EX")
+                .arg(AUTH_CACHE_TTL_SECS)
+                .clone(),
+            &redis_pool,
+        )
+        .await;
+    }
+
+    Ok(user)
+}
+
+#[tracing::instrument(skip(api_key, pg_pool, redis_pool), fields(cache_hit))]
+pub async fn get_cached_user_from_api_key_query(
+    api_key: &str,
+    pg_pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
+) -> Result<User, ServiceError> {
+    let cache_key = api_key_cache_key(&hash_api_key(api_key));
+
+    let cached_user_id =
+        cache_command::<Option<String>>(redis::cmd("GET").arg(&cache_key).clone(), &redis_pool)
+            .await
+            .flatten()
+            .and_then(|user_id| PrefixedUuid::<UserPrefix>::from_str(&user_id).ok());
+    tracing::Span::current().record("cache_hit", cached_user_id.is_some());
+
+    if let Some(user_id) = cached_user_id {
+        return get_cached_user_by_id_query(user_id, pg_pool, redis_pool).await;
+    }
+
+    // Only keys that exist are cached, so a new key works right away
+    let user = get_user_from_api_key_query(api_key, pg_pool).await?;
+    cache_command::<()>(
+        redis::cmd("SET")
+            .arg(cache_key)
+            .arg(user.id.to_string())
+            .arg("EX")
+            .arg(AUTH_CACHE_TTL_SECS)
+            .clone(),
+        &redis_pool,
+    )
+    .await;
+
+    Ok(user)
+}
+
+#[tracing::instrument(skip(pg_pool, redis_pool), fields(cache_hit))]
+pub async fn get_cached_org_user_link_query(
+    user_id: PrefixedUuid<UserPrefix>,
+    org_id: PrefixedUuid<OrgPrefix>,
+    pg_pool: web::Data<PgPool>,
+    redis_pool: web::Data<RedisPool>,
+) -> Result<OrgUserLink, ServiceError> {
+    let cached = cache_command::<Option<String>>(
+        redis::cmd("GET")
+            .arg(org_user_cache_key(org_id, user_id))
+            .clone(),
+        &redis_pool,
+    )
+    .await
+    .flatten()
+    .and_then(|org_user_link| serde_json::from_str::<OrgUserLink>(&org_user_link).ok());
+    tracing::Span::current().record("cache_hit", cached.is_some());
+
+    if let Some(org_user_link) = cached {
+        return Ok(org_user_link);
+    }
+
+    let org_user_link = get_org_user_link_query(user_id, org_id, pg_pool).await?;
+    if let Ok(serialized) = serde_json::to_string(&org_user_link) {
+        cache_command::<()>(
+            redis::cmd("SET")
+                .arg(org_user_cache_key(org_id, user_id))
+                .arg(serialized)
+                .arg("EX")
+                .arg(AUTH_CACHE_TTL_SECS)
+                .clone(),
+            &redis_pool,
+        )
+        .await;
+    }
+
+    Ok(org_user_link)
+}
+
+/// Drops the cached user, e.g. after they were disabled.
+#[tracing::instrument(skip(redis_pool))]
+pub async fn invalidate_user_cache_query(
+    user_id: PrefixedUuid<UserPrefix>,
+    redis_pool: web::Data<RedisPool>,
+) {
+    cache_command::<()>(
+        redis::cmd("DEL").arg(user_cache_key(user_id)).clone(),
+        &redis_pool,
+    )
+    .await;
+}
+
+/// Drops the cached membership of `user_id` in the org, or of every member when
+/// `user_id` is `None`.
+#[tracing::instrument(skip(redis_pool))]
+pub async fn invalidate_org_user_cache_query(
+    org_id: PrefixedUuid<OrgPrefix>,
+    user_id: Option<PrefixedUuid<UserPrefix>>,
+    redis_pool: web::Data<RedisPool>,
+) {
+    if let Some(user_id) = user_id {
+        cache_command::<()>(
+            redis::cmd("DEL")
+                .arg(org_user_cache_key(org_id, user_id))
+                .clone(),
+            &redis_pool,
+        )
+        .await;
+        return;
+    }
+
+    // Org deletions are rare, so a SCAN is cheaper than keeping an index of the keys
+    let pattern = format!("{}*", org_cache_prefix(org_id));
+    let mut cursor = 0u64;
+    loop {
+        let Some((next_cursor, keys)) = cache_command::<(u64, Vec<String>)>(
+            redis::cmd("SCAN")
+                .arg(cursor)
+                .arg("MATCH")
+                .arg(&pattern)
+                .arg("COUNT")
+                .arg(100)
+                .clone(),
+            &redis_pool,
+        )
+        .await
+        else {
+            return;
+        };
+
+        if !keys.is_empty() {
+            cache_command::<()>(redis::cmd("DEL").arg(keys).clone(), &redis_pool).await;
+        }
+        if next_cursor == 0 {
+            return;
+        }
+        cursor = next_cursor;
+    }
+}
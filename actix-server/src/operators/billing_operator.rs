@@ -0,0 +1,448 @@
+use crate::{
+    data::models::{PgPool, Plan, Subscription, SubscriptionStatus},
+    errors::ServiceError,
+    prefixes::{OrgPrefix, PrefixedUuid},
+};
+use actix_web::web;
+use diesel::{
+    upsert::excluded, BoolExpressionMethods, ExpressionMethods, NullableExpressionMethods,
+    OptionalExtension, QueryDsl,
+};
+use diesel_async::{
+    scoped_futures::ScopedFutureExt, AsyncConnection, AsyncPgConnection, RunQueryDsl,
+};
+use openssl::{hash::MessageDigest, memcmp, pkey::PKey, sign::Signer};
+use serde::Deserialize;
+use std::{collections::HashMap, str::FromStr};
+
+/// How old a signed webhook timestamp may be before it is rejected as a replay.
+const SIGNATURE_TOLERANCE_SECONDS: i64 = 300;
+
+#[derive(Debug, Deserialize)]
+pub struct StripeEvent {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub event_type: String,
+    /// Unix timestamp. Events aren't delivered in order, this is what orders them.
+    pub created: i64,
+    pub data: StripeEventData,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StripeEventData {
+    pub object: serde_json::Value,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StripeSubscription {
+    pub id: String,
+    pub status: String,
+    pub start_date: i64,
+    pub current_period_end: i64,
+    pub ended_at: Option<i64>,
+    #[serde(default)]
+    pub metadata: HashMap<String, String>,
+    pub items: StripeList<StripeSubscriptionItem>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StripeList<T> {
+    pub data: Vec<T>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StripeSubscriptionItem {
+    pub price: StripePrice,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StripePrice {
+    pub id: String,
+    pub unit_amount: Option<i64>,
+    #[serde(default)]
+    pub metadata: HashMap<String, String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StripeInvoice {
+    pub subscription: Option<String>,
+}
+
+/// Checks a `Stripe-Signature` header (`t=<timestamp>,v1=<hex hmac>`) against the
+/// raw request body.
+pub fn verify_stripe_signature(
+    payload: &[u8],
+    signature_header: &str,
+    secret: &str,
+    now: i64,
+) -> Result<(), ServiceError> {
+    let invalid = || ServiceError::BadRequest("Invalid webhook signature".to_string());
+
+    let mut timestamp = None;
+    let mut signatures = vec![];
+    for part in signature_header.split(',') {
+        match part.trim().split_once('=') {
+            Some(("t", value)) => timestamp = value.parse::<i64>().ok(),
+            Some(("v1", value)) => signatures.push(value),
+            _ => {}
+        }
+    }
+    let timestamp = timestamp.ok_or_else(invalid)?;
+
+    if (now - timestamp).abs() > SIGNATURE_TOLERANCE_SECONDS {
+        return Err(ServiceError::BadRequest(
+            "Webhook timestamp is outside the tolerance window".to_string(),
+        ));
+    }
+
+    let key = PKey::hmac(secret.as_bytes()).map_err(|_| invalid())?;
+    let mut signer = Signer::new(MessageDigest::sha256(), &key).map_err(|_| invalid())?;
+    signer
+        .update(format!("{}.", timestamp).as_bytes())
+        .map_err(|_| invalid())?;
+    signer.update(payload).map_err(|_| invalid())?;
+    let expected = signer
+        .sign_to_vec()
+        .map_err(|_| invalid())?
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect::<String>();
+
+    if signatures.iter().any(|signature| {
+        signature.len() == expected.len() && memcmp::eq(signature.as_bytes(), expected.as_bytes())
+    }) {
+        Ok(())
+    } else {
+        Err(invalid())
+    }
+}
+
+fn timestamp_to_naive(timestamp: i64) -> Result<chrono::NaiveDateTime, ServiceError> {
+    chrono::NaiveDateTime::from_timestamp_opt(timestamp, 0)
+        .ok_or_else(|| ServiceError::BadRequest(format!("Invalid timestamp {}", timestamp)))
+}
+
+fn parse_object<T: for<'de> Deserialize<'de>>(
+    object: serde_json::Value,
+) -> Result<T, ServiceError> {
+    serde_json::from_value(object)
+        .map_err(|e| ServiceError::BadRequest(format!("Invalid event object: {}", e)))
+}
+
+/// What became of a verified billing event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BillingEventOutcome {
+    Applied,
+    /// An earlier delivery of the same event was already applied
+    AlreadyApplied,
+    /// The event is for an org that doesn't exist (anymore), so there is nothing to update
+    Ignored,
+    /// A newer event for the subscription was already applied, the subscription was
+    /// canceled, or it isn't known
+    Outdated,
+}
+
+/// Applies a verified billing event. The event id is recorded in the same transaction
+/// that applies it, so redeliveries, including concurrent ones, change nothing.
+#[tracing::instrument(skip(pg_pool))]
+pub async fn handle_billing_event_query(
+    event: StripeEvent,
+    pg_pool: web::Data<PgPool>,
+) -> Result<BillingEventOutcome, ServiceError> {
+    let mut conn = pg_pool.get().await?;
+
+    conn.transaction::<_, ServiceError, _>(|conn| {
+        async move { handle_billing_event(event, conn).await }.scope_boxed()
+    })
+    .await
+}
+
+async fn handle_billing_event(
+    event: StripeEvent,
+    conn: &mut AsyncPgConnection,
+) -> Result<BillingEventOutcome, ServiceError> {
+    use crate::data::schema::billing_events::dsl as billing_events_columns;
+
+    // Claiming the id first makes a concurrent delivery wait on the row lock and then
+    // see the conflict, instead of applying the event a second time
+    let claimed = diesel::insert_into(billing_events_columns::billing_events)
+        .values((
+            billing_events_columns::id.eq(&event.id),
+            billing_events_columns::event_type.eq(&event.event_type),
+        ))
+        .on_conflict_do_nothing()
+        .execute(conn)
+        .await
+        .map_err(|_| {
+            ServiceError::InternalServerError("Error recording billing event".to_string())
+        })?;
+
+    if claimed == 0 {
+        return Ok(BillingEventOutcome::AlreadyApplied);
+    }
+
+    let event_created = timestamp_to_naive(event.created)?;
+    match event.event_type.as_str() {
+        "customer.subscription.created" | "customer.subscription.updated" => {
+            let subscription = parse_object::<StripeSubscription>(event.data.object)?;
+            upsert_subscription(subscription, event_created, conn).await
+        }
+        "customer.subscription.deleted" => {
+            let subscription = parse_object::<StripeSubscription>(event.data.object)?;
+            let ended_at = timestamp_to_naive(
+                subscription
+                    .ended_at
+                    .unwrap_or_else(|| chrono::Utc::now().timestamp()),
+            )?;
+            set_subscription_status(
+                &subscription.id,
+                SubscriptionStatus::Canceled,
+                Some(ended_at),
+                event_created,
+                conn,
+            )
+            .await
+        }
+        "invoice.payment_failed" => {
+            let invoice = parse_object::<StripeInvoice>(event.data.object)?;
+            match invoice.subscription {
+                Some(subscription_id) => {
+                    set_subscription_status(
+                        &subscription_id,
+                        SubscriptionStatus::PastDue,
+                        None,
+                        event_created,
+                        conn,
+                    )
+                    .await
+                }
+                None => Ok(BillingEventOutcome::Applied),
+            }
+        }
+        // Everything else is acknowledged so the provider stops retrying it
+        _ => Ok(BillingEventOutcome::Applied),
+    }
+}
+
+/// Forgets applied events older than `before`. Providers stop redelivering an event
+/// after a few days, so old ids no longer protect against anything.
+#[tracing::instrument(skip(pg_pool))]
+pub async fn prune_billing_events_query(
+    before: chrono::NaiveDateTime,
+    pg_pool: web::Data<PgPool>,
+) -> Result<usize, ServiceError> {
+    use crate::data::schema::billing_events::dsl as billing_events_columns;
+
+    let mut conn = pg_pool.get().await?;
+
+    diesel::delete(billing_events_columns::billing_events)
+        .filter(billing_events_columns::created_at.lt(before))
+        .execute(&mut conn)
+        .await
+        .map_err(|_| ServiceError::InternalServerError("Error pruning billing events".to_string()))
+}
+
+/// Finds the plan for a price, creating it from the price's `num_users` and
+/// `num_deals` metadata if we haven't seen it before.
+async fn upsert_plan_for_price(
+    price: &StripePrice,
+    conn: &mut AsyncPgConnection,
+) -> Result<Plan, ServiceError> {
+    use crate::data::schema::plans::dsl as plans_columns;
+
+    let parse_limit = |key: &str| {
+        price
+            .metadata
+            .get(key)
+            .map(|value| {
+                value.parse::<i32>().map_err(|_| {
+                    ServiceError::BadRequest(format!("Price metadata {} must be a number", key))
+                })
+            })
+            .transpose()
+    };
+    let num_users = parse_limit("num_users")?;
+    let num_deals = parse_limit("num_deals")?;
+    let price_per_month = price
+        .unit_amount
+        .map(i32::try_from)
+        .transpose()
+        .map_err(|_| ServiceError::BadRequest("Price amount is too large".to_string()))?;
+
+    let existing_plan = plans_columns::plans
+        .filter(plans_columns::stripe_id.eq(&price.id))
+        .first::<Plan>(conn)
+        .await
+        .optional()
+        .map_err(|_| ServiceError::InternalServerError("Error getting plan".to_string()))?;
+
+    match existing_plan {
+        Some(plan) => diesel::update(plans_columns::plans.filter(plans_columns::id.eq(plan.id)))
+            .set((
+                plans_columns::num_users.eq(num_users.unwrap_or(plan.num_users)),
+                plans_columns::num_deals.eq(num_deals.unwrap_or(plan.num_deals)),
+                plans_columns::price_per_month.eq(price_per_month.unwrap_or(plan.price_per_month)),
+            ))
+            .get_result::<Plan>(conn)
+            .await
+            .map_err(|_| ServiceError::InternalServerError("Error updating plan".to_string())),
+        None => {
+            let (Some(num_users), Some(num_deals)) = (num_users, num_deals) else {
+                return Err(ServiceError::BadRequest(format!(
+                    "Price {} needs num_users and num_deals metadata",
+                    price.id
+                )));
+            };
+            let plan = Plan::from_details(
+                price.id.clone(),
+                num_users,
+                num_deals,
+                price_per_month.unwrap_or_default(),
+            );
+            diesel::insert_into(plans_columns::plans)
+                .values(&plan)
+                .get_result::<Plan>(conn)
+                .await
+                .map_err(|_| ServiceError::InternalServerError("Error creating plan".to_string()))
+        }
+    }
+}
+
+/// Creates or updates the subscription from an event created at `event_created`. An
+/// existing subscription is left alone if it was canceled or a newer event was applied.
+async fn upsert_subscription(
+    stripe_subscription: StripeSubscription,
+    event_created: chrono::NaiveDateTime,
+    conn: &mut AsyncPgConnection,
+) -> Result<BillingEventOutcome, ServiceError> {
+    use crate::data::schema::orgs::dsl as orgs_columns;
+    use crate::data::schema::subscriptions::dsl as subscriptions_columns;
+
+    let org_id = stripe_subscription
+        .metadata
+        .get("org_id")
+        .ok_or_else(|| {
+            ServiceError::BadRequest(format!(
+                "Subscription {} has no org_id metadata",
+                stripe_subscription.id
+            ))
+        })
+        .and_then(|org_id| PrefixedUuid::<OrgPrefix>::from_str(org_id))?;
+    let [item, ..] = stripe_subscription.items.data.as_slice() else {
+        return Err(ServiceError::BadRequest(
+            "Subscription has no items".to_string(),
+        ));
+    };
+
+    let org_exists = orgs_columns::orgs
+        .filter(orgs_columns::id.eq(org_id))
+        .select(orgs_columns::id)
+        .first::<PrefixedUuid<OrgPrefix>>(conn)
+        .await
+        .optional()
+        .map_err(|_| ServiceError::InternalServerError("Error getting org".to_string()))?
+        .is_some();
+    if !org_exists {
+        log::warn!(
+            "Ignoring subscription {} for unknown org {}",
+            stripe_subscription.id,
+            org_id
+        );
+        return Ok(BillingEventOutcome::Ignored);
+    }
+
+    let plan = upsert_plan_for_price(&item.price, conn).await?;
+
+    let subscription = Subscription {
+        id: uuid::Uuid::now_v7(),
+        stripe_id: stripe_subscription.id.clone(),
+        org_id,
+        plan_id: plan.id,
+        stripe_plan_id: plan.stripe_id,
+        next_billing_date: timestamp_to_naive(stripe_subscription.current_period_end)?,
+        start_date: timestamp_to_naive(stripe_subscription.start_date)?,
+        end_date: stripe_subscription
+            .ended_at
+            .map(timestamp_to_naive)
+            .transpose()?,
+        status: SubscriptionStatus::from_stripe(&stripe_subscription.status)
+            .as_str()
+            .to_string(),
+        last_event_at: Some(event_created),
+    };
+
+    let upsert = diesel::insert_into(subscriptions_columns::subscriptions)
+        .values(&subscription)
+        .on_conflict(subscriptions_columns::stripe_id)
+        .do_update()
+        .set((
+            subscriptions_columns::plan_id.eq(excluded(subscriptions_columns::plan_id)),
+            subscriptions_columns::stripe_plan_id
+                .eq(excluded(subscriptions_columns::stripe_plan_id)),
+            subscriptions_columns::next_billing_date
+                .eq(excluded(subscriptions_columns::next_billing_date)),
+            subscriptions_columns::end_date.eq(excluded(subscriptions_columns::end_date)),
+            subscriptions_columns::status.eq(excluded(subscriptions_columns::status)),
+            subscriptions_columns::last_event_at.eq(excluded(subscriptions_columns::last_event_at)),
+        ));
+
+    // Events arrive out of order, and a canceled subscription is never reactivated
+    diesel::query_dsl::methods::FilterDsl::filter(
+        upsert,
+        subscriptions_columns::status
+            .ne(SubscriptionStatus::Canceled.as_str())
+            .and(
+                subscriptions_columns::last_event_at.is_null().or(
+                    subscriptions_columns::last_event_at
+                        .assume_not_null()
+                        .le(event_created),
+                ),
+            ),
+    )
+    .get_result::<Subscription>(conn)
+    .await
+    .optional()
+    .map(|saved| match saved {
+        Some(_) => BillingEventOutcome::Applied,
+        None => BillingEventOutcome::Outdated,
+    })
+    .map_err(|_| ServiceError::InternalServerError("Error saving subscription".to_string()))
+}
+
+/// Moves the subscription to `status` for an event created at `event_created`, with
+/// the same guards as [`upsert_subscription`].
+async fn set_subscription_status(
+    stripe_id: &str,
+    status: SubscriptionStatus,
+    end_date: Option<chrono::NaiveDateTime>,
+    event_created: chrono::NaiveDateTime,
+    conn: &mut AsyncPgConnection,
+) -> Result<BillingEventOutcome, ServiceError> {
+    use crate::data::schema::subscriptions::dsl as subscriptions_columns;
+
+    let updated = diesel::update(
+        subscriptions_columns::subscriptions
+            .filter(subscriptions_columns::stripe_id.eq(stripe_id))
+            .filter(subscriptions_columns::status.ne(SubscriptionStatus::Canceled.as_str()))
+            .filter(
+                subscriptions_columns::last_event_at.is_null().or(
+                    subscriptions_columns::last_event_at
+                        .assume_not_null()
+                        .le(event_created),
+                ),
+            ),
+    )
+    .set((
+        subscriptions_columns::status.eq(status.as_str()),
+        end_date.map(|end_date| subscriptions_columns::end_date.eq(end_date)),
+        subscriptions_columns::last_event_at.eq(event_created),
+    ))
+    .execute(conn)
+    .await
+    .map_err(|_| ServiceError::InternalServerError("Error updating subscription".to_string()))?;
+
+    Ok(match updated {
+        0 => BillingEventOutcome::Outdated,
+        _ => BillingEventOutcome::Applied,
+    })
+}
@@ -1,69 +1,91 @@
 use crate::{
-    data::models::{Company, PgPool},
+    data::{
+        etag::{unchanged_since, write_conflict},
+        models::{Company, PgPool},
+        patch::Patch,
+    },
     errors::ServiceError,
-    prefixes::{CompanyPrefix, OrgPrefix, PrefixedUuid},
+    prefixes::{CompanyPrefix, OrgPrefix, PrefixedUuid, UserPrefix},
 };
 use actix_web::web;
-use diesel::{ExpressionMethods, QueryDsl, SelectableHelper};
+use diesel::{ExpressionMethods, OptionalExtension, QueryDsl, SelectableHelper};
 use diesel_async::RunQueryDsl;
 
 #[tracing::instrument(skip(pg_pool))]
 pub async fn create_company_query(
     name: String,
     org_id: PrefixedUuid<OrgPrefix>,
+    owner_user_id: Option<PrefixedUuid<UserPrefix>>,
     pg_pool: web::Data<PgPool>,
 ) -> Result<Company, ServiceError> {
     use crate::data::schema::companies::dsl as company_columns;
 
-    let mut conn = pg_pool.get().await.unwrap();
+    let mut conn = pg_pool.get().await?;
 
-    let company = Company::from_name(name, org_id);
+    let company = Company::from_name(name, org_id, owner_user_id);
     let company = diesel::insert_into(company_columns::companies)
         .values(&company)
         .get_result::<Company>(&mut conn)
         .await
-        .map_err(|e| {
-            ServiceError::InternalServerError(
-                format!("Error creating org for create_org_query: {}", e).to_string(),
-            )
-        })?;
+        .map_err(ServiceError::from)?;
 
     Ok(company)
 }
 
 pub async fn delete_company_query(
     company_id: PrefixedUuid<CompanyPrefix>,
+    expected_updated_at: Option<chrono::NaiveDateTime>,
     pg_pool: web::Data<PgPool>,
 ) -> Result<(), ServiceError> {
     use crate::data::schema::companies::dsl as company_columns;
 
-    let mut conn = pg_pool.get().await.unwrap();
-
-    diesel::delete(company_columns::companies.filter(company_columns::id.eq(company_id)))
-        .execute(&mut conn)
-        .await
-        .map_err(|e| ServiceError::InternalServerError(format!("Error deleting company: {}", e)))?;
+    let mut conn = pg_pool.get().await?;
+
+    let deleted = diesel::delete(
+        company_columns::companies
+            .filter(company_columns::id.eq(company_id))
+            .filter(unchanged_since(
+                company_columns::updated_at,
+                expected_updated_at,
+            )),
+    )
+    .execute(&mut conn)
+    .await
+    .map_err(|e| ServiceError::InternalServerError(format!("Error deleting company: {}", e)))?;
+    if deleted == 0 && expected_updated_at.is_some() {
+        return Err(ServiceError::PreconditionFailed);
+    }
 
     Ok(())
 }
 
-pub async fn rename_company_query(
+pub async fn update_company_query(
     company_id: PrefixedUuid<CompanyPrefix>,
-    new_name: String,
+    new_name: Option<String>,
+    owner_user_id: Patch<PrefixedUuid<UserPrefix>>,
+    expected_updated_at: Option<chrono::NaiveDateTime>,
     pg_pool: web::Data<PgPool>,
 ) -> Result<Company, ServiceError> {
     use crate::data::schema::companies::dsl as company_columns;
 
-    let mut conn = pg_pool.get().await.unwrap();
+    let mut conn = pg_pool.get().await?;
 
-    let company =
-        diesel::update(company_columns::companies.filter(company_columns::id.eq(company_id)))
-            .set(company_columns::name.eq(new_name))
-            .get_result::<Company>(&mut conn)
-            .await
-            .map_err(|e| {
-                ServiceError::InternalServerError(format!("Error renaming company: {}", e))
-            })?;
+    let target = company_columns::companies
+        .filter(company_columns::id.eq(company_id))
+        .filter(unchanged_since(
+            company_columns::updated_at,
+            expected_updated_at,
+        ));
+    let company = diesel::update(target)
+        .set((
+            new_name.map(|new_name| company_columns::name.eq(new_name)),
+            owner_user_id.map(|owner_user_id| company_columns::owner_user_id.eq(owner_user_id)),
+        ))
+        .get_result::<Company>(&mut conn)
+        .await
+        .optional()
+        .map_err(ServiceError::from)?
+        .ok_or_else(|| write_conflict(expected_updated_at))?;
 
     Ok(company)
 }
@@ -74,7 +96,7 @@ pub async fn get_company_query(
 ) -> Result<Company, ServiceError> {
     use crate::data::schema::companies::dsl as company_columns;
 
-    let mut conn = pg_pool.get().await.unwrap();
+    let mut conn = pg_pool.get().await?;
 
     let company = company_columns::companies
         .filter(company_columns::id.eq(company_id))
@@ -87,19 +109,26 @@ pub async fn get_company_query(
 
 pub async fn list_companies_query(
     org_id: PrefixedUuid<OrgPrefix>,
+    owner_user_id: Option<PrefixedUuid<UserPrefix>>,
     pg_pool: web::Data<PgPool>,
     limit: Option<i64>,
     offset: Option<i64>,
 ) -> Result<Vec<Company>, ServiceError> {
     use crate::data::schema::companies::dsl as company_columns;
 
-    let mut conn = pg_pool.get().await.unwrap();
+    let mut conn = pg_pool.get().await?;
 
     let limit = limit.unwrap_or(10);
     let offset = offset.unwrap_or(0);
 
-    let companies = company_columns::companies
+    let mut query = company_columns::companies
         .filter(company_columns::org_id.eq(org_id))
+        .into_boxed();
+    if let Some(owner_user_id) = owner_user_id {
+        query = query.filter(company_columns::owner_user_id.eq(owner_user_id));
+    }
+
+    let companies = query
         .select(Company::as_select())
         .limit(limit)
         .offset(offset)
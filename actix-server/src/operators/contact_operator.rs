@@ -1,42 +1,55 @@
 use crate::{
-    data::models::{Contact, Deal, DealContact, PgPool},
+    data::{
+        etag::{unchanged_since, write_conflict},
+        models::{Contact, Deal, DealContact, PgPool},
+        patch::Patch,
+    },
     errors::ServiceError,
-    prefixes::{ContactPrefix, DealPrefix, OrgPrefix, PrefixedUuid},
+    prefixes::{ContactPrefix, DealPrefix, OrgPrefix, PrefixedUuid, UserPrefix},
 };
 use actix_web::web;
-use diesel::{BelongingToDsl, ExpressionMethods, QueryDsl, SelectableHelper};
+use diesel::{BelongingToDsl, ExpressionMethods, OptionalExtension, QueryDsl, SelectableHelper};
 use diesel_async::RunQueryDsl;
 
-#[tracing::instrument(skip(pg_pool))]
+#[tracing::instrument(skip(pg_pool, first_name, last_name))]
 pub async fn create_contact_query(
     org_id: PrefixedUuid<OrgPrefix>,
     first_name: String,
     last_name: String,
+    owner_user_id: Option<PrefixedUuid<UserPrefix>>,
     pg_pool: web::Data<PgPool>,
 ) -> Result<Contact, ServiceError> {
     use crate::data::schema::contacts::dsl as contacts_columns;
-    let mut conn = pg_pool.get().await.unwrap();
-    let new_contact = Contact::from_details(org_id, first_name, last_name);
+    let mut conn = pg_pool.get().await?;
+    let new_contact = Contact::from_details(org_id, first_name, last_name, owner_user_id);
     let contact = diesel::insert_into(contacts_columns::contacts)
         .values(&new_contact)
         .get_result::<Contact>(&mut conn)
         .await
-        .map_err(|_| ServiceError::InternalServerError("Error creating contact".to_string()))?;
+        .map_err(ServiceError::from)?;
     Ok(contact)
 }
 
 pub async fn delete_contact_query(
     contact_id: PrefixedUuid<ContactPrefix>,
+    expected_updated_at: Option<chrono::NaiveDateTime>,
     pg_pool: web::Data<PgPool>,
 ) -> Result<(), ServiceError> {
     use crate::data::schema::contacts::dsl as contacts_columns;
-    let mut conn = pg_pool.get().await.unwrap();
+    let mut conn = pg_pool.get().await?;
 
-    diesel::delete(contacts_columns::contacts)
+    let deleted = diesel::delete(contacts_columns::contacts)
         .filter(contacts_columns::id.eq(contact_id))
+        .filter(unchanged_since(
+            contacts_columns::updated_at,
+            expected_updated_at,
+        ))
         .execute(&mut conn)
         .await
         .map_err(|_| ServiceError::InternalServerError("Error deleting contact".to_string()))?;
+    if deleted == 0 && expected_updated_at.is_some() {
+        return Err(ServiceError::PreconditionFailed);
+    }
 
     Ok(())
 }
@@ -45,19 +58,29 @@ pub async fn update_contact_query(
     contact_id: PrefixedUuid<ContactPrefix>,
     first_name: Option<String>,
     last_name: Option<String>,
+    owner_user_id: Patch<PrefixedUuid<UserPrefix>>,
+    expected_updated_at: Option<chrono::NaiveDateTime>,
     pg_pool: web::Data<PgPool>,
 ) -> Result<Contact, ServiceError> {
     use crate::data::schema::contacts::dsl as contacts_columns;
-    let mut conn = pg_pool.get().await.unwrap();
-    let target = contacts_columns::contacts.filter(contacts_columns::id.eq(contact_id));
+    let mut conn = pg_pool.get().await?;
+    let target = contacts_columns::contacts
+        .filter(contacts_columns::id.eq(contact_id))
+        .filter(unchanged_since(
+            contacts_columns::updated_at,
+            expected_updated_at,
+        ));
     let updated_contact = diesel::update(target)
         .set((
             first_name.map(|first_name| contacts_columns::first_name.eq(first_name)),
             last_name.map(|last_name| contacts_columns::last_name.eq(last_name)),
+            owner_user_id.map(|owner_user_id| contacts_columns::owner_user_id.eq(owner_user_id)),
         ))
         .get_result::<Contact>(&mut conn)
         .await
-        .map_err(|_| ServiceError::InternalServerError("Error updating contact".to_string()))?;
+        .optional()
+        .map_err(ServiceError::from)?
+        .ok_or_else(|| write_conflict(expected_updated_at))?;
     Ok(updated_contact)
 }
 
@@ -66,7 +89,7 @@ pub async fn get_contact_by_id_query(
     pg_pool: web::Data<PgPool>,
 ) -> Result<Contact, ServiceError> {
     use crate::data::schema::contacts::dsl as contacts_columns;
-    let mut conn = pg_pool.get().await.unwrap();
+    let mut conn = pg_pool.get().await?;
     let contact = contacts_columns::contacts
         .filter(contacts_columns::id.eq(contact_id))
         .first(&mut conn)
@@ -83,7 +106,7 @@ pub async fn get_contacts_by_deal_id_query(
 ) -> Result<(Vec<Contact>, i64), ServiceError> {
     use crate::data::schema::contacts::dsl as contacts_columns;
     use crate::data::schema::deals::dsl as deals_columns;
-    let mut conn = pg_pool.get().await.unwrap();
+    let mut conn = pg_pool.get().await?;
     let limit = limit.unwrap_or(10);
     let offset = offset.unwrap_or(PrefixedUuid::zero_id(ContactPrefix));
     let deal = deals_columns::deals
@@ -94,7 +117,7 @@ pub async fn get_contacts_by_deal_id_query(
         .inner_join(contacts_columns::contacts)
         .select(Contact::as_select())
         .filter(contacts_columns::id.gt(offset))
-        .order((contacts_columns::updated_at, contacts_columns::id))
+        .order(contacts_columns::id)
         .limit(limit)
         .load::<Contact>(&mut conn)
         .await?;
@@ -109,26 +132,32 @@ pub async fn get_contacts_by_deal_id_query(
 
 pub async fn get_contacts_by_org_id_query(
     org_id: PrefixedUuid<OrgPrefix>,
+    owner_user_id: Option<PrefixedUuid<UserPrefix>>,
     pg_pool: web::Data<PgPool>,
     limit: Option<i64>,
     offset: Option<PrefixedUuid<ContactPrefix>>,
 ) -> Result<(Vec<Contact>, i64), ServiceError> {
     use crate::data::schema::contacts::dsl as contacts_columns;
-    let mut conn = pg_pool.get().await.unwrap();
+    let mut conn = pg_pool.get().await?;
     let limit = limit.unwrap_or(10);
     let offset = offset.unwrap_or(PrefixedUuid::zero_id(ContactPrefix));
-    let contacts = contacts_columns::contacts
+    let mut query = contacts_columns::contacts
         .filter(contacts_columns::org_id.eq(org_id))
+        .into_boxed();
+    let mut count_query = contacts_columns::contacts
+        .filter(contacts_columns::org_id.eq(org_id))
+        .into_boxed();
+    if let Some(owner_user_id) = owner_user_id {
+        query = query.filter(contacts_columns::owner_user_id.eq(owner_user_id));
+        count_query = count_query.filter(contacts_columns::owner_user_id.eq(owner_user_id));
+    }
+    let contacts = query
         .filter(contacts_columns::id.gt(offset))
-        .order((contacts_columns::updated_at, contacts_columns::id))
+        .order(contacts_columns::id)
         .limit(limit)
         .load::<Contact>(&mut conn)
         .await?;
-    let count = contacts_columns::contacts
-        .filter(contacts_columns::org_id.eq(org_id))
-        .count()
-        .get_result::<i64>(&mut conn)
-        .await?;
+    let count = count_query.count().get_result::<i64>(&mut conn).await?;
     println!("offset: {:?}", offset);
     Ok((contacts, count))
 }
@@ -1,67 +1,179 @@
 use crate::{
-    data::models::{Deal, DealContact, PgPool, Task, TaskDeal},
+    data::{
+        etag::{unchanged_since, write_conflict},
+        models::{
+            AverageDealSizeRow, Deal, DealContact, DealOutcomeStats, ForecastReportRow, PgPool,
+            PipelineReportRow, Task, TaskDeal,
+        },
+        patch::Patch,
+    },
     errors::ServiceError,
-    prefixes::{ContactPrefix, DealPrefix, OrgPrefix, PrefixedUuid, TaskPrefix},
+    prefixes::{ContactPrefix, DealPrefix, OrgPrefix, PrefixedUuid, TaskPrefix, UserPrefix},
 };
 use actix_web::web;
-use diesel::{BelongingToDsl, ExpressionMethods, QueryDsl, SelectableHelper};
-use diesel_async::RunQueryDsl;
+use diesel::{
+    dsl::sql,
+    sql_types::{Bool, Date, Nullable, Timestamptz, Uuid},
+    BelongingToDsl, ExpressionMethods, OptionalExtension, QueryDsl, SelectableHelper,
+};
+use diesel_async::{
+    scoped_futures::ScopedFutureExt, AsyncConnection, AsyncPgConnection, RunQueryDsl,
+};
 
-#[tracing::instrument(skip(pg_pool))]
+/// Creates the deal linked to `contact_ids`. Run it in a transaction, so a contact that
+/// can't be linked leaves no deal behind.
+#[allow(clippy::too_many_arguments)]
+#[tracing::instrument(skip(conn))]
 pub async fn create_deal_query(
     org_id: PrefixedUuid<OrgPrefix>,
     name: Option<String>,
-    size: Option<f32>,
+    amount: Option<i64>,
+    currency: Option<String>,
+    probability: Option<i32>,
+    expected_close_date: Option<chrono::NaiveDate>,
     active: bool,
-    pg_pool: web::Data<PgPool>,
+    owner_user_id: Option<PrefixedUuid<UserPrefix>>,
+    contact_ids: Vec<PrefixedUuid<ContactPrefix>>,
+    conn: &mut AsyncPgConnection,
 ) -> Result<Deal, ServiceError> {
     use crate::data::schema::deals::dsl as deals_columns;
-    let mut conn = pg_pool.get().await.unwrap();
-    let new_deal = Deal::from_details(org_id, name, size, active);
+    use crate::data::schema::orgs::dsl as orgs_columns;
+
+    let currency = match currency {
+        Some(currency) => currency,
+        None => orgs_columns::orgs
+            .select(orgs_columns::default_currency)
+            .filter(orgs_columns::id.eq(org_id))
+            .first::<String>(conn)
+            .await
+            .map_err(|_| ServiceError::NotFound)?,
+    };
+    let new_deal = Deal::from_details(
+        org_id,
+        name,
+        amount,
+        currency,
+        probability,
+        expected_close_date,
+        active,
+        owner_user_id,
+    );
     let deal = diesel::insert_into(deals_columns::deals)
         .values(&new_deal)
-        .get_result::<Deal>(&mut conn)
+        .get_result::<Deal>(conn)
         .await
-        .map_err(|_| ServiceError::InternalServerError("Error creating deal".to_string()))?;
+        .map_err(ServiceError::from)?;
+    for contact_id in contact_ids {
+        link_contact_to_deal(&deal, contact_id, conn).await?;
+    }
     Ok(deal)
 }
 
 pub async fn delete_deal_query(
     deal_id: PrefixedUuid<DealPrefix>,
+    expected_updated_at: Option<chrono::NaiveDateTime>,
     pg_pool: web::Data<PgPool>,
 ) -> Result<(), ServiceError> {
     use crate::data::schema::deals::dsl as deals_columns;
-    let mut conn = pg_pool.get().await.unwrap();
+    let mut conn = pg_pool.get().await?;
 
-    diesel::delete(deals_columns::deals)
+    let deleted = diesel::delete(deals_columns::deals)
         .filter(deals_columns::id.eq(deal_id))
+        .filter(unchanged_since(
+            deals_columns::updated_at,
+            expected_updated_at,
+        ))
         .execute(&mut conn)
         .await
         .map_err(|_| ServiceError::InternalServerError("Error deleting deal".to_string()))?;
+    if deleted == 0 && expected_updated_at.is_some() {
+        return Err(ServiceError::PreconditionFailed);
+    }
 
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn update_deal_query(
     deal_id: PrefixedUuid<DealPrefix>,
-    name: Option<String>,
-    size: Option<f32>,
+    name: Patch<String>,
+    amount: Patch<i64>,
+    currency: Option<String>,
+    probability: Patch<i32>,
+    expected_close_date: Patch<chrono::NaiveDate>,
+    won: Patch<bool>,
     active: Option<bool>,
+    owner_user_id: Patch<PrefixedUuid<UserPrefix>>,
+    expected_updated_at: Option<chrono::NaiveDateTime>,
     pg_pool: web::Data<PgPool>,
 ) -> Result<Deal, ServiceError> {
     use crate::data::schema::deals::dsl as deals_columns;
-    let mut conn = pg_pool.get().await.unwrap();
-    let target = deals_columns::deals.filter(deals_columns::id.eq(deal_id));
-    let updated_deal = diesel::update(target)
-        .set((
-            name.map(|name| deals_columns::name.eq(name)),
-            size.map(|size| deals_columns::size.eq(size)),
-            active.map(|active| deals_columns::active.eq(active)),
-        ))
-        .get_result::<Deal>(&mut conn)
-        .await
-        .map_err(|_| ServiceError::InternalServerError("Error updating deal".to_string()))?;
-    Ok(updated_deal)
+    let mut conn = pg_pool.get().await?;
+    conn.transaction::<_, ServiceError, _>(|conn| {
+        async move {
+            // Amounts are in the minor unit of the currency, so the stored amount means
+            // something else in another currency and can't be kept
+            if let (Some(currency), None) = (&currency, amount) {
+                let current = deals_columns::deals
+                    .filter(deals_columns::id.eq(deal_id))
+                    .select((deals_columns::currency, deals_columns::amount))
+                    .for_update()
+                    .first::<(String, Option<i64>)>(conn)
+                    .await
+                    .optional()?;
+                if let Some((current_currency, Some(_))) = current {
+                    if current_currency != *currency {
+                        return Err(ServiceError::invalid_field(
+                            "amount",
+                            "Amount must be sent in the new currency when the currency changes",
+                        ));
+                    }
+                }
+            }
+
+            let target = deals_columns::deals
+                .filter(deals_columns::id.eq(deal_id))
+                .filter(unchanged_since(
+                    deals_columns::updated_at,
+                    expected_updated_at,
+                ));
+            let updated_deal = diesel::update(target)
+                .set((
+                    name.map(|name| deals_columns::name.eq(name)),
+                    amount.map(|amount| deals_columns::amount.eq(amount)),
+                    currency.map(|currency| deals_columns::currency.eq(currency)),
+                    probability.map(|probability| deals_columns::probability.eq(probability)),
+                    expected_close_date.map(|expected_close_date| {
+                        deals_columns::expected_close_date.eq(expected_close_date)
+                    }),
+                    won.map(|won| deals_columns::won.eq(won)),
+                    // Only a change of outcome moves closed_at, so sending the same outcome
+                    // again keeps the time the deal was actually closed
+                    won.map(|won| {
+                        deals_columns::closed_at.eq(sql::<Nullable<Timestamptz>>(
+                            "CASE WHEN won IS NOT DISTINCT FROM ",
+                        )
+                        .bind::<Nullable<Bool>, _>(won)
+                        .sql(" THEN closed_at ELSE ")
+                        .bind::<Nullable<Timestamptz>, _>(
+                            won.map(|_| chrono::Utc::now().naive_local()),
+                        )
+                        .sql(" END"))
+                    }),
+                    active.map(|active| deals_columns::active.eq(active)),
+                    owner_user_id
+                        .map(|owner_user_id| deals_columns::owner_user_id.eq(owner_user_id)),
+                ))
+                .get_result::<Deal>(conn)
+                .await
+                .optional()
+                .map_err(ServiceError::from)?
+                .ok_or_else(|| write_conflict(expected_updated_at))?;
+            Ok(updated_deal)
+        }
+        .scope_boxed()
+    })
+    .await
 }
 
 pub async fn get_deal_by_id_query(
@@ -69,7 +181,7 @@ pub async fn get_deal_by_id_query(
     pg_pool: web::Data<PgPool>,
 ) -> Result<Deal, ServiceError> {
     use crate::data::schema::deals::dsl as deals_columns;
-    let mut conn = pg_pool.get().await.unwrap();
+    let mut conn = pg_pool.get().await?;
     let deal = deals_columns::deals
         .filter(deals_columns::id.eq(deal_id))
         .first(&mut conn)
@@ -86,7 +198,7 @@ pub async fn list_deals_by_task_id_query(
 ) -> Result<(Vec<Deal>, i64), ServiceError> {
     use crate::data::schema::deals::dsl as deals_columns;
     use crate::data::schema::tasks::dsl as tasks_columns;
-    let mut conn = pg_pool.get().await.unwrap();
+    let mut conn = pg_pool.get().await?;
     let limit = limit.unwrap_or(10);
     let offset = offset.unwrap_or(PrefixedUuid::zero_id(DealPrefix));
     let task = tasks_columns::tasks
@@ -98,7 +210,7 @@ pub async fn list_deals_by_task_id_query(
         .inner_join(deals_columns::deals)
         .select(Deal::as_select())
         .filter(deals_columns::id.gt(offset))
-        .order((deals_columns::updated_at, deals_columns::id))
+        .order(deals_columns::id)
         .limit(limit)
         .load::<Deal>(&mut conn)
         .await
@@ -113,22 +225,52 @@ pub async fn list_deals_by_task_id_query(
     Ok((deals, count))
 }
 
+/// Links a contact of the deal's org to the deal.
+pub async fn link_contact_to_deal(
+    deal: &Deal,
+    contact_id: PrefixedUuid<ContactPrefix>,
+    conn: &mut AsyncPgConnection,
+) -> Result<DealContact, ServiceError> {
+    use crate::data::schema::contacts::dsl as contacts_columns;
+    use crate::data::schema::deal_contacts::dsl as deal_contacts_columns;
+    let contacts = contacts_columns::contacts
+        .filter(contacts_columns::id.eq(contact_id))
+        .filter(contacts_columns::org_id.eq(deal.org_id))
+        .count()
+        .get_result::<i64>(conn)
+        .await
+        .map_err(ServiceError::from)?;
+    if contacts == 0 {
+        return Err(ServiceError::invalid_field(
+            "contact_ids",
+            format!("Contact {} does not exist", contact_id),
+        ));
+    }
+    let new_deal_contact = DealContact::from_details(deal.id, contact_id);
+    diesel::insert_into(deal_contacts_columns::deal_contacts)
+        .values(&new_deal_contact)
+        .get_result::<DealContact>(conn)
+        .await
+        .map_err(ServiceError::from)
+}
+
+/// Links a contact to a deal of the org. Both have to be in the org.
 pub async fn create_contact_for_deal_query(
     deal_id: PrefixedUuid<DealPrefix>,
+    org_id: PrefixedUuid<OrgPrefix>,
     contact_id: PrefixedUuid<ContactPrefix>,
     pg_pool: web::Data<PgPool>,
 ) -> Result<DealContact, ServiceError> {
-    use crate::data::schema::deal_contacts::dsl as deal_contacts_columns;
-    let mut conn = pg_pool.get().await.unwrap();
-    let new_deal_contact = DealContact::from_details(deal_id, contact_id);
-    let deal_contact = diesel::insert_into(deal_contacts_columns::deal_contacts)
-        .values(&new_deal_contact)
-        .get_result::<DealContact>(&mut conn)
+    use crate::data::schema::deals::dsl as deals_columns;
+    let mut conn = pg_pool.get().await?;
+    let deal = deals_columns::deals
+        .filter(deals_columns::id.eq(deal_id))
+        .filter(deals_columns::org_id.eq(org_id))
+        .first::<Deal>(&mut conn)
         .await
-        .map_err(|_| {
-            ServiceError::InternalServerError("Error creating deal contact".to_string())
-        })?;
-    Ok(deal_contact)
+        .optional()?
+        .ok_or(ServiceError::NotFound)?;
+    link_contact_to_deal(&deal, contact_id, &mut conn).await
 }
 
 pub async fn delete_contact_from_deal_query(
@@ -137,7 +279,7 @@ pub async fn delete_contact_from_deal_query(
     pg_pool: web::Data<PgPool>,
 ) -> Result<(), ServiceError> {
     use crate::data::schema::deal_contacts::dsl as deal_contacts_columns;
-    let mut conn = pg_pool.get().await.unwrap();
+    let mut conn = pg_pool.get().await?;
     diesel::delete(deal_contacts_columns::deal_contacts)
         .filter(deal_contacts_columns::deal_id.eq(deal_id))
         .filter(deal_contacts_columns::contact_id.eq(contact_id))
@@ -148,25 +290,149 @@ pub async fn delete_contact_from_deal_query(
 
 pub async fn list_deals_by_org_id_query(
     org_id: PrefixedUuid<OrgPrefix>,
+    owner_user_id: Option<PrefixedUuid<UserPrefix>>,
     pg_pool: web::Data<PgPool>,
     limit: Option<i64>,
     offset: Option<PrefixedUuid<DealPrefix>>,
 ) -> Result<(Vec<Deal>, i64), ServiceError> {
     use crate::data::schema::deals::dsl as deals_columns;
-    let mut conn = pg_pool.get().await.unwrap();
+    let mut conn = pg_pool.get().await?;
     let limit = limit.unwrap_or(10);
     let offset = offset.unwrap_or(PrefixedUuid::zero_id(DealPrefix));
-    let deals = deals_columns::deals
+    let mut query = deals_columns::deals
+        .filter(deals_columns::org_id.eq(org_id))
+        .into_boxed();
+    let mut count_query = deals_columns::deals
         .filter(deals_columns::org_id.eq(org_id))
+        .into_boxed();
+    if let Some(owner_user_id) = owner_user_id {
+        query = query.filter(deals_columns::owner_user_id.eq(owner_user_id));
+        count_query = count_query.filter(deals_columns::owner_user_id.eq(owner_user_id));
+    }
+    let deals = query
         .filter(deals_columns::id.gt(offset))
-        .order((deals_columns::updated_at, deals_columns::id))
+        .order(deals_columns::id)
         .limit(limit)
         .load::<Deal>(&mut conn)
         .await?;
-    let count = deals_columns::deals
-        .filter(deals_columns::org_id.eq(org_id))
-        .count()
-        .get_result(&mut conn)
-        .await?;
+    let count = count_query.count().get_result(&mut conn).await?;
     Ok((deals, count))
 }
+
+/// Open deal value grouped by expected close month, active flag and currency.
+/// The date range applies to `expected_close_date`.
+pub async fn get_pipeline_report_query(
+    org_id: PrefixedUuid<OrgPrefix>,
+    from: Option<chrono::NaiveDate>,
+    to: Option<chrono::NaiveDate>,
+    pg_pool: web::Data<PgPool>,
+) -> Result<Vec<PipelineReportRow>, ServiceError> {
+    let mut conn = pg_pool.get().await?;
+    let rows = diesel::sql_query(
+        "SELECT date_trunc('month', expected_close_date)::DATE AS month,
+                active,
+                currency,
+                COUNT(*) AS deal_count,
+                COALESCE(SUM(amount), 0)::BIGINT AS total_amount
+         FROM deals
+         WHERE org_id = $1
+           AND won IS NULL
+           AND ($2::DATE IS NULL OR expected_close_date >= $2)
+           AND ($3::DATE IS NULL OR expected_close_date <= $3)
+         GROUP BY month, active, currency
+         ORDER BY month NULLS LAST, active DESC, currency",
+    )
+    .bind::<Uuid, _>(org_id)
+    .bind::<Nullable<Date>, _>(from)
+    .bind::<Nullable<Date>, _>(to)
+    .load::<PipelineReportRow>(&mut conn)
+    .await
+    .map_err(|_| ServiceError::InternalServerError("Error fetching pipeline report".to_string()))?;
+    Ok(rows)
+}
+
+/// Open, active deal value weighted by probability and grouped by expected
+/// close month and currency. Deals without a probability count as 0%.
+pub async fn get_forecast_report_query(
+    org_id: PrefixedUuid<OrgPrefix>,
+    from: Option<chrono::NaiveDate>,
+    to: Option<chrono::NaiveDate>,
+    pg_pool: web::Data<PgPool>,
+) -> Result<Vec<ForecastReportRow>, ServiceError> {
+    let mut conn = pg_pool.get().await?;
+    let rows = diesel::sql_query(
+        "SELECT date_trunc('month', expected_close_date)::DATE AS month,
+                currency,
+                COUNT(*) AS deal_count,
+                COALESCE(SUM(amount), 0)::BIGINT AS total_amount,
+                COALESCE(ROUND(SUM(amount::NUMERIC * COALESCE(probability, 0)) / 100), 0)::BIGINT
+                    AS weighted_amount
+         FROM deals
+         WHERE org_id = $1
+           AND active
+           AND won IS NULL
+           AND ($2::DATE IS NULL OR expected_close_date >= $2)
+           AND ($3::DATE IS NULL OR expected_close_date <= $3)
+         GROUP BY month, currency
+         ORDER BY month NULLS LAST, currency",
+    )
+    .bind::<Uuid, _>(org_id)
+    .bind::<Nullable<Date>, _>(from)
+    .bind::<Nullable<Date>, _>(to)
+    .load::<ForecastReportRow>(&mut conn)
+    .await
+    .map_err(|_| ServiceError::InternalServerError("Error fetching forecast report".to_string()))?;
+    Ok(rows)
+}
+
+/// Win/loss counts, average sales cycle and average won deal size for deals
+/// closed within the date range.
+pub async fn get_performance_report_query(
+    org_id: PrefixedUuid<OrgPrefix>,
+    from: Option<chrono::NaiveDate>,
+    to: Option<chrono::NaiveDate>,
+    pg_pool: web::Data<PgPool>,
+) -> Result<(DealOutcomeStats, Vec<AverageDealSizeRow>), ServiceError> {
+    let mut conn = pg_pool.get().await?;
+    let stats = diesel::sql_query(
+        "SELECT COUNT(*) FILTER (WHERE won) AS won_count,
+                COUNT(*) FILTER (WHERE NOT won) AS lost_count,
+                (AVG(EXTRACT(EPOCH FROM closed_at - created_at)) FILTER (WHERE won) / 86400)::FLOAT8
+                    AS average_sales_cycle_days
+         FROM deals
+         WHERE org_id = $1
+           AND won IS NOT NULL
+           AND ($2::DATE IS NULL OR closed_at >= $2)
+           AND ($3::DATE IS NULL OR closed_at < $3 + 1)",
+    )
+    .bind::<Uuid, _>(org_id)
+    .bind::<Nullable<Date>, _>(from)
+    .bind::<Nullable<Date>, _>(to)
+    .get_result::<DealOutcomeStats>(&mut conn)
+    .await
+    .map_err(|_| {
+        ServiceError::InternalServerError("Error fetching deal outcome stats".to_string())
+    })?;
+    let average_deal_size = diesel::sql_query(
+        "SELECT currency,
+                COUNT(*) AS deal_count,
+                ROUND(AVG(amount))::BIGINT AS average_amount
+         FROM deals
+         WHERE org_id = $1
+           AND won
+           AND amount IS NOT NULL
+           AND ($2::DATE IS NULL OR closed_at >= $2)
+           AND ($3::DATE IS NULL OR closed_at < $3 + 1)
+         GROUP BY currency
+         ORDER BY currency",
+    )
+    .bind::<Uuid, _>(org_id)
+    .bind::<Nullable<Date>, _>(from)
+    .bind::<Nullable<Date>, _>(to)
+    .load::<AverageDealSizeRow>(&mut conn)
+    .await
+    .map_err(|_| {
+        ServiceError::InternalServerError("Error fetching average deal size".to_string())
+    })?;
+    Ok((stats, average_deal_size))
+}
@@ -1,45 +1,56 @@
 use crate::{
-    data::models::{Email, PgPool},
+    data::{
+        etag::{unchanged_since, write_conflict},
+        models::{Email, PgPool},
+    },
     errors::ServiceError,
     prefixes::{EmailPrefix, OrgPrefix, PrefixedUuid},
 };
 use actix_web::web;
-use diesel::{ExpressionMethods, QueryDsl};
+use diesel::{ExpressionMethods, OptionalExtension, QueryDsl};
 use diesel_async::RunQueryDsl;
 use lettre;
 
-#[tracing::instrument(skip(pg_pool))]
+#[tracing::instrument(skip(pg_pool, email))]
 pub async fn create_email_query(
     org_id: PrefixedUuid<OrgPrefix>,
     email: String,
     pg_pool: web::Data<PgPool>,
 ) -> Result<Email, ServiceError> {
     use crate::data::schema::emails::dsl as emails_columns;
-    let mut conn = pg_pool.get().await.unwrap();
+    let mut conn = pg_pool.get().await?;
     email
         .parse::<lettre::Address>()
-        .map_err(|_| ServiceError::BadRequest("Invalid email".to_string()))?;
+        .map_err(|_| ServiceError::invalid_field("email", "Invalid email"))?;
     let new_email = Email::from_details(email, org_id);
     let email = diesel::insert_into(emails_columns::emails)
         .values(&new_email)
         .get_result::<Email>(&mut conn)
         .await
-        .map_err(|_| ServiceError::InternalServerError("Error creating emails".to_string()))?;
+        .map_err(ServiceError::from)?;
     Ok(email)
 }
 
 pub async fn delete_email_query(
     email_id: PrefixedUuid<EmailPrefix>,
+    expected_updated_at: Option<chrono::NaiveDateTime>,
     pg_pool: web::Data<PgPool>,
 ) -> Result<(), ServiceError> {
     use crate::data::schema::emails::dsl as emails_columns;
-    let mut conn = pg_pool.get().await.unwrap();
+    let mut conn = pg_pool.get().await?;
 
-    diesel::delete(emails_columns::emails)
+    let deleted = diesel::delete(emails_columns::emails)
         .filter(emails_columns::id.eq(email_id))
+        .filter(unchanged_since(
+            emails_columns::updated_at,
+            expected_updated_at,
+        ))
         .execute(&mut conn)
         .await
         .map_err(|_| ServiceError::InternalServerError("Error deleting email".to_string()))?;
+    if deleted == 0 && expected_updated_at.is_some() {
+        return Err(ServiceError::PreconditionFailed);
+    }
 
     Ok(())
 }
@@ -47,21 +58,29 @@ pub async fn delete_email_query(
 pub async fn update_email_query(
     email_id: PrefixedUuid<EmailPrefix>,
     email: Option<String>,
+    expected_updated_at: Option<chrono::NaiveDateTime>,
     pg_pool: web::Data<PgPool>,
 ) -> Result<Email, ServiceError> {
     use crate::data::schema::emails::dsl as emails_columns;
-    let mut conn = pg_pool.get().await.unwrap();
+    let mut conn = pg_pool.get().await?;
     if let Some(email) = &email {
         email
             .parse::<lettre::Address>()
-            .map_err(|_| ServiceError::BadRequest("Invalid email".to_string()))?;
+            .map_err(|_| ServiceError::invalid_field("email", "Invalid email"))?;
     }
-    let target = emails_columns::emails.filter(emails_columns::id.eq(email_id));
+    let target = emails_columns::emails
+        .filter(emails_columns::id.eq(email_id))
+        .filter(unchanged_since(
+            emails_columns::updated_at,
+            expected_updated_at,
+        ));
     let updated_email = diesel::update(target)
         .set((email.map(|email| emails_columns::email.eq(email)),))
         .get_result::<Email>(&mut conn)
         .await
-        .map_err(|_| ServiceError::InternalServerError("Error updating email".to_string()))?;
+        .optional()
+        .map_err(ServiceError::from)?
+        .ok_or_else(|| write_conflict(expected_updated_at))?;
     Ok(updated_email)
 }
 
@@ -70,7 +89,7 @@ pub async fn get_email_by_id_query(
     pg_pool: web::Data<PgPool>,
 ) -> Result<Email, ServiceError> {
     use crate::data::schema::emails::dsl as emails_columns;
-    let mut conn = pg_pool.get().await.unwrap();
+    let mut conn = pg_pool.get().await?;
     let email = emails_columns::emails
         .filter(emails_columns::id.eq(email_id))
         .first(&mut conn)
@@ -1,35 +1,49 @@
-use crate::errors::ServiceError;
+use crate::{
+    errors::ServiceError,
+    settings::{self, SmtpSettings},
+};
 use lettre::message::header::ContentType;
 use lettre::transport::smtp::authentication::Credentials;
 use lettre::{Message, SmtpTransport, Transport};
 
 #[tracing::instrument]
-pub fn get_smtp_creds() -> Credentials {
-    let smtp_username = std::env::var("SMTP_USERNAME").expect("SMTP_USERNAME should be set");
-    let smtp_password = std::env::var("SMTP_PASSWORD").expect("SMTP_PASSWORD should be set");
-
-    Credentials::new(smtp_username.to_owned(), smtp_password.to_owned())
+pub fn get_smtp_creds(smtp: &SmtpSettings) -> Credentials {
+    Credentials::new(smtp.username.to_owned(), smtp.password.expose().to_owned())
 }
 
-#[tracing::instrument]
+#[tracing::instrument(skip(html_email_body, to_address))]
 pub fn send_email(html_email_body: String, to_address: String) -> Result<(), ServiceError> {
-    let smtp_relay = std::env::var("SMTP_RELAY").expect("SMTP_RELAY should be set");
-    let smtp_email_address =
-        std::env::var("SMTP_EMAIL_ADDRESS").expect("SMTP_EMAIL_ADDRESS should be set");
+    let smtp = settings::get().smtp.as_ref().ok_or_else(|| {
+        ServiceError::ServiceUnavailable("Sending email is not configured".to_string())
+    })?;
 
-    let smtp_creds = get_smtp_creds();
-    let mailer = SmtpTransport::relay(smtp_relay.as_str())
-        .expect("Failed to create mailer")
+    let smtp_creds = get_smtp_creds(smtp);
+    let mailer = SmtpTransport::relay(smtp.relay.as_str())
+        .map_err(|e| {
+            log::error!("Error creating mailer: {:?}", e);
+            ServiceError::InternalServerError("Error sending email.".to_string())
+        })?
         .credentials(smtp_creds)
         .build();
 
+    // The sender was validated at startup, so only a bad recipient is on the caller
+    let from_address = smtp.email_address.parse().map_err(|e| {
+        log::error!("Invalid sender address: {:?}", e);
+        ServiceError::InternalServerError("Error sending email.".to_string())
+    })?;
+    let to_address = to_address
+        .parse()
+        .map_err(|_| ServiceError::BadRequest("Invalid email address".to_string()))?;
     let email = Message::builder()
-        .from(smtp_email_address.parse().expect("Invalid email address"))
-        .to(to_address.parse().expect("Invalid email address"))
+        .from(from_address)
+        .to(to_address)
         .subject("Trieve Sign Up Invitation")
         .header(ContentType::TEXT_HTML)
         .body(html_email_body)
-        .expect("Failed to create email");
+        .map_err(|e| {
+            log::error!("Error building email: {:?}", e);
+            ServiceError::InternalServerError("Error sending email.".to_string())
+        })?;
 
     match mailer.send(&email) {
         Ok(_) => Ok(()),
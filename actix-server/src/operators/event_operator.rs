@@ -0,0 +1,153 @@
+use crate::{
+    data::models::{ChangeAction, ChangeEntity, ChangeEvent, ChangeEventSender, RedisPool},
+    errors::ServiceError,
+    prefixes::{OrgPrefix, Prefix, PrefixedUuid},
+};
+use actix_web::web;
+use bb8_redis::redis;
+use futures_util::StreamExt;
+use std::collections::HashMap;
+
+/// Redis pub/sub channel every server instance listens on for change events.
+const CHANGE_EVENTS_CHANNEL: &str = "change_events";
+/// Roughly how many events per org are kept for `Last-Event-ID` resumes.
+const CHANGE_EVENTS_RETENTION: usize = 1000;
+
+fn change_events_key(org_id: PrefixedUuid<OrgPrefix>) -> String {
+    format!("change_events:{}", org_id)
+}
+
+/// Parses a Redis stream id (`<milliseconds>-<sequence>`) so event ids can be ordered.
+pub fn parse_event_id(event_id: &str) -> Option<(u64, u64)> {
+    let (millis, sequence) = event_id.split_once('-')?;
+    Some((millis.parse().ok()?, sequence.parse().ok()?))
+}
+
+/// Records a change in the org's retention buffer and fans it out to every server
+/// instance. Failing to publish never fails the write that caused it.
+pub async fn publish_change_event<P: Prefix>(
+    org_id: PrefixedUuid<OrgPrefix>,
+    entity: ChangeEntity,
+    action: ChangeAction,
+    id: PrefixedUuid<P>,
+    redis_pool: web::Data<RedisPool>,
+) {
+    let event = ChangeEvent {
+        event_id: String::new(),
+        org_id,
+        entity,
+        action,
+        id: id.to_string(),
+    };
+
+    if let Err(e) = publish_change_event_query(event, redis_pool).await {
+        log::error!("Error publishing change event: {:?}", e);
+    }
+}
+
+async fn publish_change_event_query(
+    mut event: ChangeEvent,
+    redis_pool: web::Data<RedisPool>,
+) -> Result<(), ServiceError> {
+    let mut redis_conn = redis_pool
+        .get()
+        .await
+        .map_err(|_| ServiceError::ServiceUnavailable("Could not get redis connection".into()))?;
+
+    let payload = serde_json::to_string(&event).map_err(|_| {
+        ServiceError::InternalServerError("Error serializing change event".to_string())
+    })?;
+    event.event_id = redis::cmd("XADD")
+        .arg(change_events_key(event.org_id))
+        .arg("MAXLEN")
+        .arg("~")
+        .arg(CHANGE_EVENTS_RETENTION)
+        .arg("*")
+        .arg("event")
+        .arg(payload)
+        .query_async::<_, String>(&mut *redis_conn)
+        .await
+        .map_err(|_| ServiceError::InternalServerError("Error storing change event".to_string()))?;
+
+    let payload = serde_json::to_string(&event).map_err(|_| {
+        ServiceError::InternalServerError("Error serializing change event".to_string())
+    })?;
+    redis::cmd("PUBLISH")
+        .arg(CHANGE_EVENTS_CHANNEL)
+        .arg(payload)
+        .query_async::<_, ()>(&mut *redis_conn)
+        .await
+        .map_err(|_| {
+            ServiceError::InternalServerError("Error publishing change event".to_string())
+        })?;
+
+    Ok(())
+}
+
+/// Returns the retained events of an org that came after `last_event_id`, oldest first.
+pub async fn get_change_events_since_query(
+    org_id: PrefixedUuid<OrgPrefix>,
+    last_event_id: &str,
+    redis_pool: web::Data<RedisPool>,
+) -> Result<Vec<ChangeEvent>, ServiceError> {
+    if parse_event_id(last_event_id).is_none() {
+        return Err(ServiceError::BadRequest(
+            "Last-Event-ID is not a valid event id".to_string(),
+        ));
+    }
+
+    let mut redis_conn = redis_pool
+        .get()
+        .await
+        .map_err(|_| ServiceError::ServiceUnavailable("Could not get redis connection".into()))?;
+
+    let entries = redis::cmd("XRANGE")
+        .arg(change_events_key(org_id))
+        .arg(format!("({}", last_event_id))
+        .arg("+")
+        .query_async::<_, Vec<(String, HashMap<String, String>)>>(&mut *redis_conn)
+        .await
+        .map_err(|_| {
+            ServiceError::InternalServerError("Error reading change events".to_string())
+        })?;
+
+    Ok(entries
+        .into_iter()
+        .filter_map(|(event_id, fields)| {
+            let mut event = serde_json::from_str::<ChangeEvent>(fields.get("event")?).ok()?;
+            event.event_id = event_id;
+            Some(event)
+        })
+        .collect())
+}
+
+/// Forwards change events published by any server instance to the event streams
+/// open on this one. Reconnects whenever the subscription drops.
+pub async fn listen_for_change_events(redis_url: String, change_events: ChangeEventSender) {
+    loop {
+        if let Err(e) = forward_change_events(&redis_url, &change_events).await {
+            log::error!("Change event subscription failed: {:?}", e);
+        }
+        actix_web::rt::time::sleep(std::time::Duration::from_secs(1)).await;
+    }
+}
+
+async fn forward_change_events(
+    redis_url: &str,
+    change_events: &ChangeEventSender,
+) -> redis::RedisResult<()> {
+    let client = redis::Client::open(redis_url)?;
+    let mut pubsub = client.get_async_pubsub().await?;
+    pubsub.subscribe(CHANGE_EVENTS_CHANNEL).await?;
+
+    let mut messages = pubsub.on_message();
+    while let Some(message) = messages.next().await {
+        let payload = message.get_payload::<String>()?;
+        if let Ok(event) = serde_json::from_str::<ChangeEvent>(&payload) {
+            // Sending only fails when nobody on this instance is listening
+            let _ = change_events.send(event);
+        }
+    }
+
+    Ok(())
+}
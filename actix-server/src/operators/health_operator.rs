@@ -0,0 +1,40 @@
+use crate::{
+    data::models::{PgPool, RedisPool},
+    errors::ServiceError,
+};
+use actix_web::web;
+use bb8_redis::redis;
+use diesel_async::RunQueryDsl;
+
+/// Checks out a connection and runs a trivial query, so both the pool and the server
+/// behind it have to be working.
+pub async fn ping_database_query(pg_pool: web::Data<PgPool>) -> Result<(), ServiceError> {
+    let mut conn = pg_pool.get().await?;
+
+    diesel::sql_query("SELECT 1")
+        .execute(&mut conn)
+        .await
+        .map_err(|e| {
+            log::error!("Database health check failed: {:?}", e);
+            ServiceError::ServiceUnavailable("Database query failed".to_string())
+        })?;
+
+    Ok(())
+}
+
+pub async fn ping_redis_query(redis_pool: web::Data<RedisPool>) -> Result<(), ServiceError> {
+    let mut redis_conn = redis_pool
+        .get()
+        .await
+        .map_err(|_| ServiceError::ServiceUnavailable("Could not get redis connection".into()))?;
+
+    redis::cmd("PING")
+        .query_async::<_, String>(&mut *redis_conn)
+        .await
+        .map_err(|e| {
+            log::error!("Redis health check failed: {:?}", e);
+            ServiceError::ServiceUnavailable("Redis command failed".to_string())
+        })?;
+
+    Ok(())
+}
@@ -0,0 +1,198 @@
+use crate::{data::models::RedisPool, errors::ServiceError};
+use actix_web::web;
+use bb8_redis::redis;
+use serde::{Deserialize, Serialize};
+
+/// How long a completed response is replayed for retries with the same key.
+const IDEMPOTENT_RESPONSE_TTL_SECS: u64 = 60 * 60 * 24;
+/// How long a key stays locked without being refreshed. The lock is refreshed every
+/// [`IDEMPOTENCY_LOCK_REFRESH_SECS`] while its request runs, so this only bounds how
+/// long a crashed instance can block retries.
+const IDEMPOTENCY_LOCK_TTL_SECS: u64 = 60;
+pub const IDEMPOTENCY_LOCK_REFRESH_SECS: u64 = IDEMPOTENCY_LOCK_TTL_SECS / 3;
+
+/// Extends the lock only if the key still holds it, so a stored response or a lock
+/// taken by a retry after expiry is left alone.
+const REFRESH_LOCK_SCRIPT: &str = r#"
+if redis.call('GET', KEYS[1]) == ARGV[1] then
+  return redis.call('EXPIRE', KEYS[1], ARGV[2])
+end
+return 0
+"#;
+
+/// What is stored under an idempotency key. `response` stays empty while the first
+/// request with the key is still being handled.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IdempotencyRecord {
+    pub fingerprint: String,
+    pub response: Option<IdempotentResponse>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IdempotentResponse {
+    pub status: u16,
+    /// End-to-end headers of the response, in order. Names can repeat.
+    #[serde(default)]
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+/// Fingerprint of a request so a key reused for a different request can be told apart
+/// from a retry.
+pub fn idempotency_fingerprint(method: &str, path: &str, body: &[u8]) -> String {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(method.as_bytes());
+    hasher.update(b" ");
+    hasher.update(path.as_bytes());
+    hasher.update(b"\n");
+    hasher.update(body);
+    hasher.finalize().to_string()
+}
+
+/// What is stored under a key while its request runs.
+fn lock_record(fingerprint: &str) -> Result<String, ServiceError> {
+    serde_json::to_string(&IdempotencyRecord {
+        fingerprint: fingerprint.to_string(),
+        response: None,
+    })
+    .map_err(|_| {
+        ServiceError::InternalServerError("Error serializing idempotency record".to_string())
+    })
+}
+
+/// Locks the key for the request with `fingerprint`. Returns `None` when the lock was
+/// taken, otherwise whatever is already stored under the key.
+#[tracing::instrument(skip(redis_pool))]
+pub async fn claim_idempotency_key_query(
+    key: &str,
+    fingerprint: &str,
+    redis_pool: web::Data<RedisPool>,
+) -> Result<Option<IdempotencyRecord>, ServiceError> {
+    let mut redis_conn = redis_pool
+        .get()
+        .await
+        .map_err(|_| ServiceError::ServiceUnavailable("Could not get redis connection".into()))?;
+
+    let lock = lock_record(fingerprint)?;
+
+    let claimed = redis::cmd("SET")
+        .arg(key)
+        .arg(lock)
+        .arg("NX")
+        .arg("EX")
+        .arg(IDEMPOTENCY_LOCK_TTL_SECS)
+        .query_async::<_, Option<String>>(&mut *redis_conn)
+        .await
+        .map_err(|_| {
+            ServiceError::InternalServerError("Error claiming idempotency key".to_string())
+        })?
+        .is_some();
+
+    if claimed {
+        return Ok(None);
+    }
+
+    let existing = redis::cmd("GET")
+        .arg(key)
+        .query_async::<_, Option<String>>(&mut *redis_conn)
+        .await
+        .map_err(|_| {
+            ServiceError::InternalServerError("Error reading idempotency key".to_string())
+        })?;
+
+    // A lock that expired in between is treated like one that is still held, the
+    // retry after it will claim the key
+    Ok(Some(
+        existing
+            .and_then(|existing| serde_json::from_str::<IdempotencyRecord>(&existing).ok())
+            .unwrap_or(IdempotencyRecord {
+                fingerprint: fingerprint.to_string(),
+                response: None,
+            }),
+    ))
+}
+
+/// Extends the lock taken by [`claim_idempotency_key_query`] for a request that is
+/// still running.
+#[tracing::instrument(skip(redis_pool))]
+pub async fn refresh_idempotency_key_query(
+    key: &str,
+    fingerprint: &str,
+    redis_pool: web::Data<RedisPool>,
+) -> Result<(), ServiceError> {
+    let mut redis_conn = redis_pool
+        .get()
+        .await
+        .map_err(|_| ServiceError::ServiceUnavailable("Could not get redis connection".into()))?;
+
+    redis::cmd("EVAL")
+        .arg(REFRESH_LOCK_SCRIPT)
+        .arg(1)
+        .arg(key)
+        .arg(lock_record(fingerprint)?)
+        .arg(IDEMPOTENCY_LOCK_TTL_SECS)
+        .query_async::<_, i64>(&mut *redis_conn)
+        .await
+        .map_err(|_| {
+            ServiceError::InternalServerError("Error refreshing idempotency key".to_string())
+        })?;
+
+    Ok(())
+}
+
+/// Stores the response of the request that holds the key so retries get it replayed.
+#[tracing::instrument(skip(response, redis_pool))]
+pub async fn save_idempotent_response_query(
+    key: &str,
+    fingerprint: &str,
+    response: IdempotentResponse,
+    redis_pool: web::Data<RedisPool>,
+) -> Result<(), ServiceError> {
+    let mut redis_conn = redis_pool
+        .get()
+        .await
+        .map_err(|_| ServiceError::ServiceUnavailable("Could not get redis connection".into()))?;
+
+    let record = serde_json::to_string(&IdempotencyRecord {
+        fingerprint: fingerprint.to_string(),
+        response: Some(response),
+    })
+    .map_err(|_| {
+        ServiceError::InternalServerError("Error serializing idempotency record".to_string())
+    })?;
+
+    redis::cmd("SET")
+        .arg(key)
+        .arg(record)
+        .arg("EX")
+        .arg(IDEMPOTENT_RESPONSE_TTL_SECS)
+        .query_async::<_, ()>(&mut *redis_conn)
+        .await
+        .map_err(|_| {
+            ServiceError::InternalServerError("Error storing idempotent response".to_string())
+        })?;
+
+    Ok(())
+}
+
+/// Frees the key without storing a response so the request can be retried.
+#[tracing::instrument(skip(redis_pool))]
+pub async fn release_idempotency_key_query(
+    key: &str,
+    redis_pool: web::Data<RedisPool>,
+) -> Result<(), ServiceError> {
+    let mut redis_conn = redis_pool
+        .get()
+        .await
+        .map_err(|_| ServiceError::ServiceUnavailable("Could not get redis connection".into()))?;
+
+    redis::cmd("DEL")
+        .arg(key)
+        .query_async::<_, ()>(&mut *redis_conn)
+        .await
+        .map_err(|_| {
+            ServiceError::InternalServerError("Error releasing idempotency key".to_string())
+        })?;
+
+    Ok(())
+}
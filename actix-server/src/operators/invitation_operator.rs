@@ -1,31 +1,35 @@
-use super::email_operator::send_email;
-use crate::data::models::{Invitation, PgPool};
+use super::{
+    email_operator::send_email, job_operator::enqueue_job, plan_operator::check_user_quota,
+};
+use crate::data::models::{Invitation, JobPayload, PgPool, SendInvitationJob};
 use crate::errors::ServiceError;
 use crate::prefixes::{OrgPrefix, PrefixedUuid};
 use actix_web::web;
 use diesel::prelude::*;
-use diesel_async::RunQueryDsl;
-
-/// Diesel query
-#[tracing::instrument(skip(pool))]
-pub async fn create_invitation_query(
-    email: String,
-    organization_id: PrefixedUuid<OrgPrefix>,
-    user_role: i32,
-    pool: web::Data<PgPool>,
+use diesel_async::{AsyncPgConnection, RunQueryDsl};
+
+/// Inserts the invitation and queues its email. Run it in a transaction, so an
+/// invitation is never left without an email on the way or the other way round.
+#[tracing::instrument(skip(conn, registration_url))]
+pub async fn insert_invitation(
+    invitation: Invitation,
+    registration_url: String,
+    conn: &mut AsyncPgConnection,
 ) -> Result<Invitation, ServiceError> {
     use crate::data::schema::invitations::dsl::invitations;
 
-    let mut conn = pool.get().await.unwrap();
-
-    let new_invitation = Invitation::from_details(email, organization_id, user_role);
-
-    let inserted_invitation = diesel::insert_into(invitations)
-        .values(&new_invitation)
-        .get_result(&mut conn)
+    let inserted_invitation: Invitation = diesel::insert_into(invitations)
+        .values(&invitation)
+        .get_result(conn)
         .await
         .map_err(|_db_error| ServiceError::BadRequest("Error inserting invitation.".to_string()))?;
 
+    let payload = JobPayload::SendInvitation(SendInvitationJob {
+        invitation_id: inserted_invitation.id,
+        registration_url,
+    });
+    enqueue_job(&payload, None, conn).await?;
+
     Ok(inserted_invitation)
 }
 
@@ -36,7 +40,7 @@ pub async fn get_invitation_by_id_query(
 ) -> Result<Invitation, ServiceError> {
     use crate::data::schema::invitations::dsl as invitations_columns;
 
-    let mut conn = pool.get().await.unwrap();
+    let mut conn = pool.get().await?;
 
     let invitation = invitations_columns::invitations
         .filter(invitations_columns::id.eq(id))
@@ -47,8 +51,29 @@ pub async fn get_invitation_by_id_query(
     Ok(invitation)
 }
 
-#[tracing::instrument]
-pub async fn send_invitation(inv_url: String, invitation: Invitation) -> Result<(), ServiceError> {
+/// Runs a [`SendInvitationJob`]. An invitation deleted since the job was queued is
+/// skipped.
+#[tracing::instrument(skip(pool))]
+pub async fn send_invitation(
+    job: SendInvitationJob,
+    pool: web::Data<PgPool>,
+) -> Result<(), ServiceError> {
+    use crate::data::schema::invitations::dsl as invitations_columns;
+
+    let mut conn = pool.get().await?;
+
+    let Some(invitation) = invitations_columns::invitations
+        .filter(invitations_columns::id.eq(job.invitation_id))
+        .first::<Invitation>(&mut conn)
+        .await
+        .optional()
+        .map_err(|_db_error| ServiceError::BadRequest("Error getting invitation.".to_string()))?
+    else {
+        return Ok(());
+    };
+    drop(conn);
+
+    let inv_url = job.registration_url;
     let sg_email_content = format!(
         "You have been invited to join a Trieve organization. <br/>
          Please click on the link below to register. <br/>
@@ -58,22 +83,23 @@ pub async fn send_invitation(inv_url: String, invitation: Invitation) -> Result<
         inv_url.split('?').collect::<Vec<&str>>()[0]
     );
 
-    send_email(sg_email_content, invitation.email)
+    // The SMTP client blocks, keep it off the runtime thread
+    web::block(move || send_email(sg_email_content, invitation.email))
+        .await
+        .map_err(|_| ServiceError::InternalServerError("Error sending email.".to_string()))?
 }
 
-#[tracing::instrument(skip(pool))]
+#[tracing::instrument(skip(conn))]
 pub async fn set_invitation_used(
     id: uuid::Uuid,
-    pool: web::Data<PgPool>,
+    conn: &mut AsyncPgConnection,
 ) -> Result<(), ServiceError> {
     use crate::data::schema::invitations::dsl as invitations_columns;
 
-    let mut conn = pool.get().await.unwrap();
-
     diesel::update(invitations_columns::invitations)
         .filter(invitations_columns::id.eq(id))
         .set(invitations_columns::used.eq(true))
-        .execute(&mut conn)
+        .execute(conn)
         .await
         .map_err(|_db_error| {
             ServiceError::BadRequest("Error setting invitation as used.".to_string())
@@ -82,14 +108,22 @@ pub async fn set_invitation_used(
     Ok(())
 }
 
-#[tracing::instrument(skip(pool))]
+/// Checks the invitation and uses it up. The invitation row stays locked until the
+/// caller's transaction ends, so it can't be redeemed twice at once, and it's only
+/// used up if the rest of the transaction, e.g. adding the user to the org, commits.
+#[tracing::instrument(skip(conn, email))]
 pub async fn check_inv_valid(
     inv_code: uuid::Uuid,
     email: String,
     organization_id: Option<PrefixedUuid<OrgPrefix>>,
-    pool: web::Data<PgPool>,
+    conn: &mut AsyncPgConnection,
 ) -> Result<Invitation, ServiceError> {
-    let invitation = get_invitation_by_id_query(inv_code, pool.clone())
+    use crate::data::schema::invitations::dsl as invitations_columns;
+
+    let invitation = invitations_columns::invitations
+        .filter(invitations_columns::id.eq(inv_code))
+        .for_update()
+        .first::<Invitation>(conn)
         .await
         .map_err(|_| {
             ServiceError::InternalServerError("Could not find invitation for user".to_string())
@@ -101,9 +135,9 @@ pub async fn check_inv_valid(
         ));
     }
 
-    if invitation.organization_id != organization_id.unwrap() {
+    if organization_id != Some(invitation.organization_id) {
         return Err(ServiceError::BadRequest(
-            "Dataset ID does not match invitation".to_string(),
+            "Organization does not match invitation".to_string(),
         ));
     }
 
@@ -112,7 +146,9 @@ pub async fn check_inv_valid(
             "Invitation has already been used".to_string(),
         ));
     }
-    set_invitation_used(invitation.id, pool.clone())
+
+    check_user_quota(invitation.organization_id, false, conn).await?;
+    set_invitation_used(invitation.id, conn)
         .await
         .map_err(|_| {
             ServiceError::InternalServerError("Could not set invitation as used".to_string())
@@ -127,7 +163,7 @@ pub async fn get_invitations_for_organization_query(
 ) -> Result<Vec<Invitation>, ServiceError> {
     use crate::data::schema::invitations::dsl as invitations_columns;
 
-    let mut conn = pool.get().await.unwrap();
+    let mut conn = pool.get().await?;
 
     let invitations = invitations_columns::invitations
         .filter(invitations_columns::organization_id.eq(organization_id))
@@ -144,7 +180,7 @@ pub async fn delete_invitation_by_id_query(
 ) -> Result<(), ServiceError> {
     use crate::data::schema::invitations::dsl as invitations_columns;
 
-    let mut conn = pool.get().await.unwrap();
+    let mut conn = pool.get().await?;
 
     diesel::delete(invitations_columns::invitations.filter(invitations_columns::id.eq(id)))
         .execute(&mut conn)
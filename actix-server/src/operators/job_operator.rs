@@ -0,0 +1,252 @@
+use crate::{
+    data::models::{Job, JobPayload, JobStatus, PgPool},
+    errors::ServiceError,
+};
+use actix_web::web;
+use diesel::{
+    sql_types::{Text, Timestamp},
+    ExpressionMethods, QueryDsl,
+};
+use diesel_async::{AsyncPgConnection, RunQueryDsl};
+
+/// Wait before the first retry, doubled for every attempt after that.
+const RETRY_BASE_DELAY_SECS: i64 = 30;
+/// Longest wait between two attempts.
+const MAX_RETRY_DELAY_SECS: i64 = 60 * 60;
+
+/// Adds a job on the caller's connection, so when the connection is inside a
+/// transaction the job is only queued if the write that triggered it commits.
+#[tracing::instrument(skip(conn))]
+pub async fn enqueue_job(
+    payload: &JobPayload,
+    run_at: Option<chrono::NaiveDateTime>,
+    conn: &mut AsyncPgConnection,
+) -> Result<Job, ServiceError> {
+    use crate::data::schema::jobs::dsl as jobs_columns;
+
+    let job = Job::from_details(payload, run_at).map_err(|e| {
+        ServiceError::InternalServerError(format!("Error serializing job payload: {}", e))
+    })?;
+
+    diesel::insert_into(jobs_columns::jobs)
+        .values(&job)
+        .get_result::<Job>(conn)
+        .await
+        .map_err(|_| ServiceError::InternalServerError("Error enqueuing job".to_string()))
+}
+
+/// Claims the most urgent due job for `lease`. Rows claimed by other workers are
+/// skipped rather than waited for, and a running job whose lease ran out, because its
+/// worker died or hung, is due again.
+#[tracing::instrument(skip(pg_pool))]
+pub async fn claim_job_query(
+    lease: chrono::Duration,
+    pg_pool: web::Data<PgPool>,
+) -> Result<Option<Job>, ServiceError> {
+    let mut conn = pg_pool.get().await?;
+
+    let now = chrono::Utc::now().naive_local();
+    let jobs = diesel::sql_query(
+        "UPDATE jobs SET status = $1, attempts = attempts + 1, locked_until = $2, updated_at = $3 \
+         WHERE id = ( \
+             SELECT id FROM jobs \
+             WHERE (status = $4 AND run_at <= $3) OR (status = $1 AND locked_until < $3) \
+             ORDER BY priority DESC, run_at \
+             LIMIT 1 \
+             FOR UPDATE SKIP LOCKED \
+         ) \
+         RETURNING *",
+    )
+    .bind::<Text, _>(JobStatus::Running.as_str())
+    .bind::<Timestamp, _>(now + lease)
+    .bind::<Timestamp, _>(now)
+    .bind::<Text, _>(JobStatus::Queued.as_str())
+    .load::<Job>(&mut conn)
+    .await
+    .map_err(|e| ServiceError::InternalServerError(format!("Error claiming job: {}", e)))?;
+
+    Ok(jobs.into_iter().next())
+}
+
+/// Marks a claimed job as done. Does nothing if the lease ran out and another worker
+/// claimed the job in the meantime.
+#[tracing::instrument(skip(pg_pool))]
+pub async fn complete_job_query(job: &Job, pg_pool: web::Data<PgPool>) -> Result<(), ServiceError> {
+    use crate::data::schema::jobs::dsl as jobs_columns;
+
+    let mut conn = pg_pool.get().await?;
+
+    diesel::update(jobs_columns::jobs)
+        .filter(jobs_columns::id.eq(job.id))
+        .filter(jobs_columns::attempts.eq(job.attempts))
+        .set((
+            jobs_columns::status.eq(JobStatus::Succeeded.as_str()),
+            jobs_columns::locked_until.eq(None::<chrono::NaiveDateTime>),
+            jobs_columns::last_error.eq(None::<String>),
+            jobs_columns::updated_at.eq(chrono::Utc::now().naive_local()),
+        ))
+        .execute(&mut conn)
+        .await
+        .map_err(|_| ServiceError::InternalServerError("Error completing job".to_string()))?;
+
+    Ok(())
+}
+
+/// Schedules another attempt of a claimed job with exponential backoff, or moves it
+/// to the dead-letter state once it used up its attempts. Returns the new status.
+#[tracing::instrument(skip(pg_pool))]
+pub async fn fail_job_query(
+    job: &Job,
+    error: String,
+    pg_pool: web::Data<PgPool>,
+) -> Result<JobStatus, ServiceError> {
+    use crate::data::schema::jobs::dsl as jobs_columns;
+
+    let mut conn = pg_pool.get().await?;
+
+    let now = chrono::Utc::now().naive_local();
+    let (status, run_at) = if job.attempts >= job.max_attempts {
+        (JobStatus::Dead, job.run_at)
+    } else {
+        let delay = RETRY_BASE_DELAY_SECS
+            .saturating_mul(2i64.saturating_pow((job.attempts - 1).clamp(0, 16) as u32))
+            .min(MAX_RETRY_DELAY_SECS);
+        (JobStatus::Queued, now + chrono::Duration::seconds(delay))
+    };
+
+    diesel::update(jobs_columns::jobs)
+        .filter(jobs_columns::id.eq(job.id))
+        .filter(jobs_columns::attempts.eq(job.attempts))
+        .set((
+            jobs_columns::status.eq(status.as_str()),
+            jobs_columns::run_at.eq(run_at),
+            jobs_columns::locked_until.eq(None::<chrono::NaiveDateTime>),
+            jobs_columns::last_error.eq(Some(error)),
+            jobs_columns::updated_at.eq(now),
+        ))
+        .execute(&mut conn)
+        .await
+        .map_err(|_| ServiceError::InternalServerError("Error failing job".to_string()))?;
+
+    Ok(status)
+}
+
+#[tracing::instrument(skip(pg_pool))]
+pub async fn list_jobs_query(
+    status: Option<JobStatus>,
+    kind: Option<String>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+    pg_pool: web::Data<PgPool>,
+) -> Result<Vec<Job>, ServiceError> {
+    use crate::data::schema::jobs::dsl as jobs_columns;
+
+    let mut conn = pg_pool.get().await?;
+
+    let mut query = jobs_columns::jobs.into_boxed();
+    if let Some(status) = status {
+        query = query.filter(jobs_columns::status.eq(status.as_str()));
+    }
+    if let Some(kind) = kind {
+        query = query.filter(jobs_columns::kind.eq(kind));
+    }
+
+    query
+        .order(jobs_columns::created_at.desc())
+        .limit(limit.unwrap_or(10))
+        .offset(offset.unwrap_or(0))
+        .load::<Job>(&mut conn)
+        .await
+        .map_err(|e| ServiceError::InternalServerError(format!("Error listing jobs: {}", e)))
+}
+
+#[tracing::instrument(skip(pg_pool))]
+pub async fn get_job_by_id_query(
+    id: uuid::Uuid,
+    pg_pool: web::Data<PgPool>,
+) -> Result<Job, ServiceError> {
+    use crate::data::schema::jobs::dsl as jobs_columns;
+
+    let mut conn = pg_pool.get().await?;
+
+    jobs_columns::jobs
+        .filter(jobs_columns::id.eq(id))
+        .first::<Job>(&mut conn)
+        .await
+        .map_err(ServiceError::from)
+}
+
+/// Queues a dead or waiting job to run right away with a fresh set of attempts.
+#[tracing::instrument(skip(pg_pool))]
+pub async fn retry_job_query(
+    id: uuid::Uuid,
+    pg_pool: web::Data<PgPool>,
+) -> Result<Job, ServiceError> {
+    use crate::data::schema::jobs::dsl as jobs_columns;
+
+    let job = get_job_by_id_query(id, pg_pool.clone()).await?;
+    if job.status != JobStatus::Dead.as_str() && job.status != JobStatus::Queued.as_str() {
+        return Err(ServiceError::Conflict(format!(
+            "Only dead or queued jobs can be retried, this one is {}",
+            job.status
+        )));
+    }
+
+    let mut conn = pg_pool.get().await?;
+
+    let now = chrono::Utc::now().naive_local();
+    // Matching the status again keeps a worker that claimed the job in between from
+    // running it twice
+    diesel::update(jobs_columns::jobs)
+        .filter(jobs_columns::id.eq(id))
+        .filter(jobs_columns::status.eq(&job.status))
+        .set((
+            jobs_columns::status.eq(JobStatus::Queued.as_str()),
+            jobs_columns::run_at.eq(now),
+            jobs_columns::attempts.eq(0),
+            jobs_columns::updated_at.eq(now),
+        ))
+        .get_result::<Job>(&mut conn)
+        .await
+        .map_err(|e| match e {
+            diesel::result::Error::NotFound => {
+                ServiceError::Conflict("The job was claimed while retrying it".to_string())
+            }
+            _ => ServiceError::InternalServerError("Error retrying job".to_string()),
+        })
+}
+
+/// Number of jobs per status, for the queue depth metric.
+#[tracing::instrument(skip(pg_pool))]
+pub async fn count_jobs_by_status_query(
+    pg_pool: web::Data<PgPool>,
+) -> Result<Vec<(String, i64)>, ServiceError> {
+    use crate::data::schema::jobs::dsl as jobs_columns;
+
+    let mut conn = pg_pool.get().await?;
+
+    jobs_columns::jobs
+        .group_by(jobs_columns::status)
+        .select((jobs_columns::status, diesel::dsl::count_star()))
+        .load::<(String, i64)>(&mut conn)
+        .await
+        .map_err(|_| ServiceError::InternalServerError("Error counting jobs".to_string()))
+}
+
+/// Deletes jobs that succeeded before `before`. Failed jobs are kept for inspection.
+#[tracing::instrument(skip(pg_pool))]
+pub async fn prune_jobs_query(
+    before: chrono::NaiveDateTime,
+    pg_pool: web::Data<PgPool>,
+) -> Result<usize, ServiceError> {
+    use crate::data::schema::jobs::dsl as jobs_columns;
+
+    let mut conn = pg_pool.get().await?;
+
+    diesel::delete(jobs_columns::jobs)
+        .filter(jobs_columns::status.eq(JobStatus::Succeeded.as_str()))
+        .filter(jobs_columns::updated_at.lt(before))
+        .execute(&mut conn)
+        .await
+        .map_err(|_| ServiceError::InternalServerError("Error pruning jobs".to_string()))
+}
@@ -1,10 +1,13 @@
 use crate::{
-    data::models::{Link, PgPool, Task, TaskLink},
+    data::{
+        etag::{unchanged_since, write_conflict},
+        models::{Link, PgPool, Task, TaskLink},
+    },
     errors::ServiceError,
     prefixes::{LinkPrefix, OrgPrefix, PrefixedUuid, TaskPrefix},
 };
 use actix_web::web;
-use diesel::{BelongingToDsl, ExpressionMethods, QueryDsl, SelectableHelper};
+use diesel::{BelongingToDsl, ExpressionMethods, OptionalExtension, QueryDsl, SelectableHelper};
 use diesel_async::RunQueryDsl;
 
 #[tracing::instrument(skip(pg_pool))]
@@ -14,28 +17,36 @@ pub async fn create_link_query(
     pg_pool: web::Data<PgPool>,
 ) -> Result<Link, ServiceError> {
     use crate::data::schema::links::dsl as links_columns;
-    let mut conn = pg_pool.get().await.unwrap();
+    let mut conn = pg_pool.get().await?;
     let new_link = Link::from_details(link, org_id);
     let link = diesel::insert_into(links_columns::links)
         .values(&new_link)
         .get_result::<Link>(&mut conn)
         .await
-        .map_err(|_| ServiceError::InternalServerError("Error creating links".to_string()))?;
+        .map_err(ServiceError::from)?;
     Ok(link)
 }
 
 pub async fn delete_link_query(
     link_id: PrefixedUuid<LinkPrefix>,
+    expected_updated_at: Option<chrono::NaiveDateTime>,
     pg_pool: web::Data<PgPool>,
 ) -> Result<(), ServiceError> {
     use crate::data::schema::links::dsl as links_columns;
-    let mut conn = pg_pool.get().await.unwrap();
+    let mut conn = pg_pool.get().await?;
 
-    diesel::delete(links_columns::links)
+    let deleted = diesel::delete(links_columns::links)
         .filter(links_columns::id.eq(link_id))
+        .filter(unchanged_since(
+            links_columns::updated_at,
+            expected_updated_at,
+        ))
         .execute(&mut conn)
         .await
         .map_err(|_| ServiceError::InternalServerError("Error deleting link".to_string()))?;
+    if deleted == 0 && expected_updated_at.is_some() {
+        return Err(ServiceError::PreconditionFailed);
+    }
 
     Ok(())
 }
@@ -43,16 +54,24 @@ pub async fn delete_link_query(
 pub async fn update_link_query(
     link_id: PrefixedUuid<LinkPrefix>,
     link: Option<String>,
+    expected_updated_at: Option<chrono::NaiveDateTime>,
     pg_pool: web::Data<PgPool>,
 ) -> Result<Link, ServiceError> {
     use crate::data::schema::links::dsl as links_columns;
-    let mut conn = pg_pool.get().await.unwrap();
-    let target = links_columns::links.filter(links_columns::id.eq(link_id));
+    let mut conn = pg_pool.get().await?;
+    let target = links_columns::links
+        .filter(links_columns::id.eq(link_id))
+        .filter(unchanged_since(
+            links_columns::updated_at,
+            expected_updated_at,
+        ));
     let updated_link = diesel::update(target)
         .set((link.map(|link| links_columns::link.eq(link)),))
         .get_result::<Link>(&mut conn)
         .await
-        .map_err(|_| ServiceError::InternalServerError("Error updating link".to_string()))?;
+        .optional()
+        .map_err(ServiceError::from)?
+        .ok_or_else(|| write_conflict(expected_updated_at))?;
     Ok(updated_link)
 }
 
@@ -61,7 +80,7 @@ pub async fn get_link_by_id_query(
     pg_pool: web::Data<PgPool>,
 ) -> Result<Link, ServiceError> {
     use crate::data::schema::links::dsl as links_columns;
-    let mut conn = pg_pool.get().await.unwrap();
+    let mut conn = pg_pool.get().await?;
     let link = links_columns::links
         .filter(links_columns::id.eq(link_id))
         .first(&mut conn)
@@ -78,7 +97,7 @@ pub async fn list_links_by_task_id_query(
 ) -> Result<(Vec<Link>, i64), ServiceError> {
     use crate::data::schema::links::dsl as links_columns;
     use crate::data::schema::tasks::dsl as tasks_columns;
-    let mut conn = pg_pool.get().await.unwrap();
+    let mut conn = pg_pool.get().await?;
     let limit = limit.unwrap_or(10);
     let offset = offset.unwrap_or(PrefixedUuid::zero_id(LinkPrefix));
     let task = tasks_columns::tasks
@@ -90,7 +109,7 @@ pub async fn list_links_by_task_id_query(
         .inner_join(links_columns::links)
         .select(Link::as_select())
         .filter(links_columns::id.gt(offset))
-        .order((links_columns::updated_at, links_columns::id))
+        .order(links_columns::id)
         .limit(limit)
         .load::<Link>(&mut conn)
         .await
@@ -1,14 +1,22 @@
+pub mod activity_operator;
 pub mod api_key_operator;
+pub mod auth_cache_operator;
+pub mod billing_operator;
 pub mod company_operator;
 pub mod contact_operator;
 pub mod deal_operator;
 pub mod email_db_operator;
 pub mod email_operator;
+pub mod event_operator;
+pub mod health_operator;
+pub mod idempotency_operator;
 pub mod invitation_operator;
+pub mod job_operator;
 pub mod link_operator;
 pub mod note_operator;
 pub mod org_operator;
 pub mod phone_operator;
 pub mod plan_operator;
+pub mod rate_limit_operator;
 pub mod task_operator;
 pub mod user_operator;
@@ -1,10 +1,13 @@
 use crate::{
-    data::models::{Note, PgPool},
+    data::{
+        etag::{unchanged_since, write_conflict},
+        models::{Note, PgPool},
+    },
     errors::ServiceError,
     prefixes::{NotePrefix, OrgPrefix, PrefixedUuid},
 };
 use actix_web::web;
-use diesel::{ExpressionMethods, QueryDsl};
+use diesel::{ExpressionMethods, OptionalExtension, QueryDsl};
 use diesel_async::RunQueryDsl;
 
 pub async fn create_note_query(
@@ -14,35 +17,39 @@ pub async fn create_note_query(
 ) -> Result<Note, ServiceError> {
     use crate::data::schema::notes::dsl as notes_columns;
 
-    let mut conn = pg_pool.get().await.unwrap();
+    let mut conn = pg_pool.get().await?;
     let note = Note::from_title(title, org_id);
 
     let note = diesel::insert_into(notes_columns::notes)
         .values(&note)
         .get_result::<Note>(&mut conn)
         .await
-        .map_err(|e| {
-            ServiceError::InternalServerError(
-                format!("Error creating note for create_note_query: {}", e).to_string(),
-            )
-        })?;
+        .map_err(ServiceError::from)?;
 
     Ok(note)
 }
 
 pub async fn delete_note_query(
     note_id: PrefixedUuid<NotePrefix>,
+    expected_updated_at: Option<chrono::NaiveDateTime>,
     pg_pool: web::Data<PgPool>,
 ) -> Result<(), ServiceError> {
     use crate::data::schema::notes::dsl as notes_columns;
 
-    let mut conn = pg_pool.get().await.unwrap();
+    let mut conn = pg_pool.get().await?;
 
-    diesel::delete(notes_columns::notes)
+    let deleted = diesel::delete(notes_columns::notes)
         .filter(notes_columns::id.eq(note_id))
+        .filter(unchanged_since(
+            notes_columns::updated_at,
+            expected_updated_at,
+        ))
         .execute(&mut conn)
         .await
         .map_err(|_| ServiceError::InternalServerError("Error deleting note".to_string()))?;
+    if deleted == 0 && expected_updated_at.is_some() {
+        return Err(ServiceError::PreconditionFailed);
+    }
 
     Ok(())
 }
@@ -50,19 +57,27 @@ pub async fn delete_note_query(
 pub async fn update_note_body_query(
     note_id: PrefixedUuid<NotePrefix>,
     new_body: String,
+    expected_updated_at: Option<chrono::NaiveDateTime>,
     pg_pool: web::Data<PgPool>,
 ) -> Result<Note, ServiceError> {
     use crate::data::schema::notes::dsl as notes_columns;
 
-    let mut conn = pg_pool.get().await.unwrap();
+    let mut conn = pg_pool.get().await?;
 
-    let target = notes_columns::notes.filter(notes_columns::id.eq(note_id));
+    let target = notes_columns::notes
+        .filter(notes_columns::id.eq(note_id))
+        .filter(unchanged_since(
+            notes_columns::updated_at,
+            expected_updated_at,
+        ));
 
     let updated_note = diesel::update(target)
         .set(notes_columns::body.eq(new_body))
         .get_result::<Note>(&mut conn)
         .await
-        .map_err(|_| ServiceError::InternalServerError("Error updating note body".to_string()))?;
+        .optional()
+        .map_err(ServiceError::from)?
+        .ok_or_else(|| write_conflict(expected_updated_at))?;
 
     Ok(updated_note)
 }
@@ -73,7 +88,7 @@ pub async fn get_note_by_id_query(
 ) -> Result<Note, ServiceError> {
     use crate::data::schema::notes::dsl as notes_columns;
 
-    let mut conn = pg_pool.get().await.unwrap();
+    let mut conn = pg_pool.get().await?;
 
     let note = notes_columns::notes
         .filter(notes_columns::id.eq(note_id))
@@ -92,7 +107,7 @@ pub async fn get_notes_for_org_query(
 ) -> Result<Vec<Note>, ServiceError> {
     use crate::data::schema::notes::dsl as notes_columns;
 
-    let mut conn = pg_pool.get().await.unwrap();
+    let mut conn = pg_pool.get().await?;
 
     let limit = limit.unwrap_or(10);
     let offset = offset.unwrap_or(0);
@@ -1,72 +1,266 @@
 use crate::{
-    data::models::{Org, OrgUserLink, PgPool, UserRole},
+    data::{
+        etag::{unchanged_since, write_conflict},
+        models::{
+            ChangeAction, ChangeEntity, Org, OrgUserLink, PgPool, ReassignedRecords, RedisPool,
+            UserRole,
+        },
+    },
     errors::ServiceError,
     handlers::auth_handler::AuthedUser,
-    prefixes::{OrgPrefix, OrgUserPrefix, PrefixedUuid, UserPrefix},
+    operators::{event_operator::publish_change_event, plan_operator::check_user_quota},
+    prefixes::{
+        CompanyPrefix, ContactPrefix, DealPrefix, OrgPrefix, OrgUserPrefix, PrefixedUuid,
+        TaskPrefix, UserPrefix,
+    },
 };
 use actix_web::web;
-use diesel::{ExpressionMethods, QueryDsl, SelectableHelper};
-use diesel_async::RunQueryDsl;
+use diesel::{ExpressionMethods, OptionalExtension, QueryDsl, SelectableHelper};
+use diesel_async::{
+    scoped_futures::ScopedFutureExt, AsyncConnection, AsyncPgConnection, RunQueryDsl,
+};
 
+/// Creates the org and makes the user its owner. Both happen in one transaction, so
+/// a failure to add the owner doesn't leave an org nobody can reach.
 #[tracing::instrument(skip(pg_pool))]
 pub async fn create_org_query(
     name: String,
+    default_currency: String,
     authed_user: AuthedUser,
     pg_pool: web::Data<PgPool>,
 ) -> Result<Org, ServiceError> {
     use crate::data::schema::orgs::dsl as orgs_columns;
 
-    let mut conn = pg_pool.get().await.unwrap();
+    let mut conn = pg_pool.get().await?;
+
+    conn.transaction::<_, ServiceError, _>(|conn| {
+        async move {
+            let org = Org::from_details(name, default_currency);
+            let org = diesel::insert_into(orgs_columns::orgs)
+                .values(&org)
+                .get_result::<Org>(conn)
+                .await
+                .map_err(|e| {
+                    ServiceError::InternalServerError(
+                        format!("Error creating org for create_org_query: {}", e).to_string(),
+                    )
+                })?;
+
+            // Make the user an owner
+            add_user_to_org(authed_user.id, org.id, UserRole::Owner, conn).await?;
+
+            Ok(org)
+        }
+        .scope_boxed()
+    })
+    .await
+}
 
-    let org = Org::from_details(name);
-    let org = diesel::insert_into(orgs_columns::orgs)
-        .values(&org)
-        .get_result::<Org>(&mut conn)
-        .await
-        .map_err(|e| {
-            ServiceError::InternalServerError(
-                format!("Error creating org for create_org_query: {}", e).to_string(),
+/// The records [`reassign_records`] handed over. Change events are published for them
+/// once the transaction that moved them commits.
+#[derive(Debug, Default)]
+pub struct ReassignedRecordIds {
+    pub contacts: Vec<PrefixedUuid<ContactPrefix>>,
+    pub deals: Vec<PrefixedUuid<DealPrefix>>,
+    pub companies: Vec<PrefixedUuid<CompanyPrefix>>,
+    pub tasks: Vec<PrefixedUuid<TaskPrefix>>,
+}
+
+impl ReassignedRecordIds {
+    pub fn counts(&self) -> ReassignedRecords {
+        ReassignedRecords {
+            contacts: self.contacts.len() as i64,
+            deals: self.deals.len() as i64,
+            companies: self.companies.len() as i64,
+            tasks: self.tasks.len() as i64,
+        }
+    }
+
+    /// Publishes an `Updated` event for every record, their owner changed.
+    pub async fn publish_updated(
+        &self,
+        org_id: PrefixedUuid<OrgPrefix>,
+        redis_pool: web::Data<RedisPool>,
+    ) {
+        for contact_id in &self.contacts {
+            publish_change_event(
+                org_id,
+                ChangeEntity::Contact,
+                ChangeAction::Updated,
+                *contact_id,
+                redis_pool.clone(),
             )
-        })?;
+            .await;
+        }
+        for deal_id in &self.deals {
+            publish_change_event(
+                org_id,
+                ChangeEntity::Deal,
+                ChangeAction::Updated,
+                *deal_id,
+                redis_pool.clone(),
+            )
+            .await;
+        }
+        for company_id in &self.companies {
+            publish_change_event(
+                org_id,
+                ChangeEntity::Company,
+                ChangeAction::Updated,
+                *company_id,
+                redis_pool.clone(),
+            )
+            .await;
+        }
+        for task_id in &self.tasks {
+            publish_change_event(
+                org_id,
+                ChangeEntity::Task,
+                ChangeAction::Updated,
+                *task_id,
+                redis_pool.clone(),
+            )
+            .await;
+        }
+    }
+}
 
-    // Make the user an owner
-    add_user_to_org_query(authed_user.id, org.id, UserRole::Owner, pg_pool).await?;
+/// Moves every contact, deal, company and task owned by `from_user_id` in the
+/// org to `to_user_id`. Passing `None` leaves the records unowned.
+async fn reassign_records(
+    org_id: PrefixedUuid<OrgPrefix>,
+    from_user_id: PrefixedUuid<UserPrefix>,
+    to_user_id: Option<PrefixedUuid<UserPrefix>>,
+    conn: &mut AsyncPgConnection,
+) -> Result<ReassignedRecordIds, ServiceError> {
+    use crate::data::schema::companies::dsl as companies_columns;
+    use crate::data::schema::contacts::dsl as contacts_columns;
+    use crate::data::schema::deals::dsl as deals_columns;
+    use crate::data::schema::tasks::dsl as tasks_columns;
+
+    let contacts = diesel::update(
+        contacts_columns::contacts
+            .filter(contacts_columns::org_id.eq(org_id))
+            .filter(contacts_columns::owner_user_id.eq(from_user_id)),
+    )
+    .set(contacts_columns::owner_user_id.eq(to_user_id))
+    .returning(contacts_columns::id)
+    .get_results(conn)
+    .await?;
+
+    let deals = diesel::update(
+        deals_columns::deals
+            .filter(deals_columns::org_id.eq(org_id))
+            .filter(deals_columns::owner_user_id.eq(from_user_id)),
+    )
+    .set(deals_columns::owner_user_id.eq(to_user_id))
+    .returning(deals_columns::id)
+    .get_results(conn)
+    .await?;
+
+    let companies = diesel::update(
+        companies_columns::companies
+            .filter(companies_columns::org_id.eq(org_id))
+            .filter(companies_columns::owner_user_id.eq(from_user_id)),
+    )
+    .set(companies_columns::owner_user_id.eq(to_user_id))
+    .returning(companies_columns::id)
+    .get_results(conn)
+    .await?;
+
+    let tasks = diesel::update(
+        tasks_columns::tasks
+            .filter(tasks_columns::org_id.eq(org_id))
+            .filter(tasks_columns::owner_user_id.eq(from_user_id)),
+    )
+    .set(tasks_columns::owner_user_id.eq(to_user_id))
+    .returning(tasks_columns::id)
+    .get_results(conn)
+    .await?;
+
+    Ok(ReassignedRecordIds {
+        contacts,
+        deals,
+        companies,
+        tasks,
+    })
+}
 
-    Ok(org)
+pub async fn reassign_records_query(
+    org_id: PrefixedUuid<OrgPrefix>,
+    from_user_id: PrefixedUuid<UserPrefix>,
+    to_user_id: PrefixedUuid<UserPrefix>,
+    pg_pool: web::Data<PgPool>,
+) -> Result<ReassignedRecordIds, ServiceError> {
+    let mut conn = pg_pool.get().await?;
+
+    conn.transaction::<_, ServiceError, _>(|conn| {
+        reassign_records(org_id, from_user_id, Some(to_user_id), conn).scope_boxed()
+    })
+    .await
 }
 
+/// Removes the user from the org and hands their records to an owner of the
+/// org so nothing is left orphaned.
 pub async fn remove_user_from_org_query(
     org_id: PrefixedUuid<OrgPrefix>,
     user_id: PrefixedUuid<UserPrefix>,
     pg_pool: web::Data<PgPool>,
-) -> Result<(), ServiceError> {
+) -> Result<ReassignedRecordIds, ServiceError> {
     use crate::data::schema::org_users::dsl as orgs_users_columns;
 
-    let mut conn = pg_pool.get().await.unwrap();
+    let mut conn = pg_pool.get().await?;
+
+    // Records are only handed over if the user is actually removed
+    conn.transaction::<_, ServiceError, _>(|conn| {
+        async move {
+            let new_owner = orgs_users_columns::org_users
+                .filter(orgs_users_columns::org_id.eq(org_id))
+                .filter(orgs_users_columns::user_id.ne(user_id))
+                .filter(orgs_users_columns::role.eq(i32::from(UserRole::Owner)))
+                .select(orgs_users_columns::user_id)
+                .first::<PrefixedUuid<UserPrefix>>(conn)
+                .await
+                .optional()?;
+
+            let reassigned = reassign_records(org_id, user_id, new_owner, conn).await?;
+
+            diesel::delete(
+                orgs_users_columns::org_users
+                    .filter(orgs_users_columns::user_id.eq(user_id))
+                    .filter(orgs_users_columns::org_id.eq(org_id)),
+            )
+            .execute(conn)
+            .await?;
 
-    diesel::delete(
-        orgs_users_columns::org_users
-            .filter(orgs_users_columns::user_id.eq(user_id))
-            .filter(orgs_users_columns::org_id.eq(org_id)),
-    )
-    .execute(&mut conn)
+            Ok(reassigned)
+        }
+        .scope_boxed()
+    })
     .await
-    .map_err(|e| {
-        ServiceError::InternalServerError(format!("Error removing user from org: {}", e))
-    })?;
-
-    Ok(())
 }
 
-pub async fn add_user_to_org_query(
+/// Adds the user to the org if its plan has room for another member. The org row stays
+/// locked until the caller's transaction ends, so concurrent additions can't both pass
+/// the quota check.
+pub async fn add_user_to_org(
     user_id: PrefixedUuid<UserPrefix>,
     org_id: PrefixedUuid<OrgPrefix>,
     role: UserRole,
-    pg_pool: web::Data<PgPool>,
+    conn: &mut AsyncPgConnection,
 ) -> Result<OrgUserLink, ServiceError> {
     use crate::data::schema::org_users::dsl as orgs_users_columns;
+    use crate::data::schema::orgs::dsl as orgs_columns;
 
-    let mut conn = pg_pool.get().await.unwrap();
+    orgs_columns::orgs
+        .filter(orgs_columns::id.eq(org_id))
+        .select(orgs_columns::id)
+        .for_update()
+        .first::<PrefixedUuid<OrgPrefix>>(conn)
+        .await
+        .map_err(ServiceError::from)?;
+
+    check_user_quota(org_id, false, conn).await?;
 
     let org_user_link = OrgUserLink {
         id: PrefixedUuid::create(OrgUserPrefix),
@@ -77,7 +271,7 @@ pub async fn add_user_to_org_query(
 
     let org_user_link = diesel::insert_into(orgs_users_columns::org_users)
         .values(&org_user_link)
-        .get_result::<OrgUserLink>(&mut conn)
+        .get_result::<OrgUserLink>(conn)
         .await
         .map_err(|e| match e {
             diesel::result::Error::DatabaseError(
@@ -101,7 +295,7 @@ pub async fn user_in_org_query(
     use crate::data::schema::org_users::dsl as orgs_users_columns;
     use crate::data::schema::orgs::dsl as orgs_columns;
 
-    let mut conn = pg_pool.get().await.unwrap();
+    let mut conn = pg_pool.get().await?;
 
     let orgs: Vec<Org> = orgs_columns::orgs
         .inner_join(orgs_users_columns::org_users)
@@ -119,34 +313,52 @@ pub async fn user_in_org_query(
 
 pub async fn delete_org_query(
     org_id: PrefixedUuid<OrgPrefix>,
+    expected_updated_at: Option<chrono::NaiveDateTime>,
     pg_pool: web::Data<PgPool>,
 ) -> Result<(), ServiceError> {
     use crate::data::schema::orgs::dsl as orgs_columns;
 
-    let mut conn = pg_pool.get().await.unwrap();
+    let mut conn = pg_pool.get().await?;
 
-    diesel::delete(orgs_columns::orgs)
+    let deleted = diesel::delete(orgs_columns::orgs)
         .filter(orgs_columns::id.eq(org_id))
+        .filter(unchanged_since(
+            orgs_columns::updated_at,
+            expected_updated_at,
+        ))
         .execute(&mut conn)
         .await
         .map_err(|e| {
             ServiceError::InternalServerError(format!("Error deleting org by id: {}", e))
         })?;
+    if deleted == 0 && expected_updated_at.is_some() {
+        return Err(ServiceError::PreconditionFailed);
+    }
 
     Ok(())
 }
 
-pub async fn update_org_query(org: Org, pg_pool: web::Data<PgPool>) -> Result<Org, ServiceError> {
+pub async fn update_org_query(
+    org: Org,
+    expected_updated_at: Option<chrono::NaiveDateTime>,
+    pg_pool: web::Data<PgPool>,
+) -> Result<Org, ServiceError> {
     use crate::data::schema::orgs::dsl as orgs_columns;
 
-    let mut conn = pg_pool.get().await.unwrap();
+    let mut conn = pg_pool.get().await?;
 
-    let org = diesel::update(orgs_columns::orgs.filter(orgs_columns::id.eq(org.id)))
+    let target = orgs_columns::orgs
+        .filter(orgs_columns::id.eq(org.id))
+        .filter(unchanged_since(
+            orgs_columns::updated_at,
+            expected_updated_at,
+        ));
+    let org = diesel::update(target)
         .set(&org)
         .get_result::<Org>(&mut conn)
         .await
         .map_err(|e| match e {
-            diesel::result::Error::NotFound => ServiceError::NotFound,
+            diesel::result::Error::NotFound => write_conflict(expected_updated_at),
             _ => ServiceError::InternalServerError(format!("Error renaming org: {}", e)),
         })?;
 
@@ -162,7 +374,7 @@ pub async fn get_orgs_for_user_query(
     use crate::data::schema::org_users::dsl as orgs_users_columns;
     use crate::data::schema::orgs::dsl as orgs_columns;
 
-    let mut conn = pg_pool.get().await.unwrap();
+    let mut conn = pg_pool.get().await?;
 
     let limit = limit.unwrap_or(10);
     let offset = offset.unwrap_or(0);
@@ -182,6 +394,28 @@ pub async fn get_orgs_for_user_query(
     Ok(orgs)
 }
 
+/// Lists every org on the instance, oldest first.
+pub async fn list_orgs_query(
+    pg_pool: web::Data<PgPool>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+) -> Result<Vec<Org>, ServiceError> {
+    use crate::data::schema::orgs::dsl as orgs_columns;
+
+    let mut conn = pg_pool.get().await?;
+
+    let orgs = orgs_columns::orgs
+        .select(Org::as_select())
+        .order(orgs_columns::created_at.asc())
+        .limit(limit.unwrap_or(10))
+        .offset(offset.unwrap_or(0))
+        .load::<Org>(&mut conn)
+        .await
+        .map_err(|e| ServiceError::InternalServerError(format!("Error listing orgs: {}", e)))?;
+
+    Ok(orgs)
+}
+
 pub async fn get_org_user_link_query(
     user_id: PrefixedUuid<UserPrefix>,
     org_id: PrefixedUuid<OrgPrefix>,
@@ -189,7 +423,7 @@ pub async fn get_org_user_link_query(
 ) -> Result<OrgUserLink, ServiceError> {
     use crate::data::schema::org_users::dsl as orgs_users_columns;
 
-    let mut conn = pg_pool.get().await.unwrap();
+    let mut conn = pg_pool.get().await?;
 
     let org_user_link = orgs_users_columns::org_users
         .filter(orgs_users_columns::user_id.eq(user_id))
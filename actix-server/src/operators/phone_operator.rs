@@ -1,47 +1,61 @@
 use crate::{
-    data::models::{PgPool, Phone},
+    data::{
+        etag::{unchanged_since, write_conflict},
+        models::{PgPool, Phone},
+    },
     errors::ServiceError,
     prefixes::{OrgPrefix, PhonePrefix, PrefixedUuid},
 };
 use actix_web::web;
-use diesel::{ExpressionMethods, QueryDsl};
+use diesel::{ExpressionMethods, OptionalExtension, QueryDsl};
 use diesel_async::RunQueryDsl;
 
 const MIN_PHONE_LENGTH: usize = 9;
 // 9 is the minimum length of a phone number (e.g. +1234567890) in E.164 format
 
-#[tracing::instrument(skip(pg_pool))]
+#[tracing::instrument(skip(pg_pool, phone))]
 pub async fn create_phone_query(
     org_id: PrefixedUuid<OrgPrefix>,
     phone: String,
     pg_pool: web::Data<PgPool>,
 ) -> Result<Phone, ServiceError> {
     use crate::data::schema::phones::dsl as phones_columns;
-    let mut conn = pg_pool.get().await.unwrap();
+    let mut conn = pg_pool.get().await?;
     let new_phone = Phone::from_details(org_id, phone);
     if new_phone.number.len() < MIN_PHONE_LENGTH {
-        return Err(ServiceError::BadRequest("Invalid phone number".to_string()));
+        return Err(ServiceError::invalid_field(
+            "number",
+            "Invalid phone number",
+        ));
     }
     let phone = diesel::insert_into(phones_columns::phones)
         .values(&new_phone)
         .get_result::<Phone>(&mut conn)
         .await
-        .map_err(|_| ServiceError::InternalServerError("Error creating phone".to_string()))?;
+        .map_err(ServiceError::from)?;
     Ok(phone)
 }
 
 pub async fn delete_phone_query(
     phone_id: PrefixedUuid<PhonePrefix>,
+    expected_updated_at: Option<chrono::NaiveDateTime>,
     pg_pool: web::Data<PgPool>,
 ) -> Result<(), ServiceError> {
     use crate::data::schema::phones::dsl as phones_columns;
-    let mut conn = pg_pool.get().await.unwrap();
+    let mut conn = pg_pool.get().await?;
 
-    diesel::delete(phones_columns::phones)
+    let deleted = diesel::delete(phones_columns::phones)
         .filter(phones_columns::id.eq(phone_id))
+        .filter(unchanged_since(
+            phones_columns::updated_at,
+            expected_updated_at,
+        ))
         .execute(&mut conn)
         .await
         .map_err(|_| ServiceError::InternalServerError("Error deleting phone".to_string()))?;
+    if deleted == 0 && expected_updated_at.is_some() {
+        return Err(ServiceError::PreconditionFailed);
+    }
 
     Ok(())
 }
@@ -49,21 +63,32 @@ pub async fn delete_phone_query(
 pub async fn update_phone_query(
     phone_id: PrefixedUuid<PhonePrefix>,
     phone: Option<String>,
+    expected_updated_at: Option<chrono::NaiveDateTime>,
     pg_pool: web::Data<PgPool>,
 ) -> Result<Phone, ServiceError> {
     use crate::data::schema::phones::dsl as phones_columns;
-    let mut conn = pg_pool.get().await.unwrap();
-    let target = phones_columns::phones.filter(phones_columns::id.eq(phone_id));
+    let mut conn = pg_pool.get().await?;
+    let target = phones_columns::phones
+        .filter(phones_columns::id.eq(phone_id))
+        .filter(unchanged_since(
+            phones_columns::updated_at,
+            expected_updated_at,
+        ));
     if let Some(phone) = &phone {
         if phone.len() < MIN_PHONE_LENGTH {
-            return Err(ServiceError::BadRequest("Invalid phone number".to_string()));
+            return Err(ServiceError::invalid_field(
+                "number",
+                "Invalid phone number",
+            ));
         }
     }
     let updated_phone = diesel::update(target)
         .set((phone.map(|phone| phones_columns::number.eq(phone)),))
         .get_result::<Phone>(&mut conn)
         .await
-        .map_err(|_| ServiceError::InternalServerError("Error updating phone".to_string()))?;
+        .optional()
+        .map_err(ServiceError::from)?
+        .ok_or_else(|| write_conflict(expected_updated_at))?;
     Ok(updated_phone)
 }
 
@@ -72,7 +97,7 @@ pub async fn get_phone_by_id_query(
     pg_pool: web::Data<PgPool>,
 ) -> Result<Phone, ServiceError> {
     use crate::data::schema::phones::dsl as phones_columns;
-    let mut conn = pg_pool.get().await.unwrap();
+    let mut conn = pg_pool.get().await?;
     let phone = phones_columns::phones
         .filter(phones_columns::id.eq(phone_id))
         .first(&mut conn)
@@ -1,10 +1,20 @@
 use crate::{
-    data::models::{PgPool, Plan},
+    data::models::{OrgUsage, PgPool, Plan, Subscription, SubscriptionStatus},
     errors::ServiceError,
+    prefixes::{OrgPrefix, PrefixedUuid},
 };
 use actix_web::web;
-use diesel::{ExpressionMethods, QueryDsl};
-use diesel_async::RunQueryDsl;
+use diesel::{
+    dsl::{exists, not},
+    BoolExpressionMethods, ExpressionMethods, JoinOnDsl, OptionalExtension, QueryDsl,
+    SelectableHelper,
+};
+use diesel_async::{
+    scoped_futures::ScopedFutureExt, AsyncConnection, AsyncPgConnection, RunQueryDsl,
+};
+
+/// Stripe id of the plan seeded for orgs without an active subscription.
+pub const FREE_PLAN_STRIPE_ID: &str = "free";
 
 #[tracing::instrument(skip(pg_pool))]
 pub async fn create_plan_query(
@@ -12,13 +22,12 @@ pub async fn create_plan_query(
     num_users: i32,
     num_deals: i32,
     price_per_month: i32,
-    pg_pool: PgPool,
+    pg_pool: web::Data<PgPool>,
 ) -> Result<Plan, ServiceError> {
     use crate::data::schema::plans::dsl as plans_columns;
 
-    let mut conn = pg_pool.get().await.unwrap();
+    let mut conn = pg_pool.get().await?;
 
-    // TODO: Maybe want a db transaction for all 3 steps?
     let plan = Plan::from_details(stripe_id, num_users, num_deals, price_per_month);
 
     let plan = diesel::insert_into(plans_columns::plans)
@@ -36,17 +45,47 @@ pub async fn delete_plan_query(
 ) -> Result<(), ServiceError> {
     use crate::data::schema::plans::dsl as plans_columns;
 
-    let mut conn = pg_pool.get().await.unwrap();
+    let mut conn = pg_pool.get().await?;
+
+    let plan = plans_columns::plans
+        .filter(plans_columns::id.eq(plan_id))
+        .first::<Plan>(&mut conn)
+        .await
+        .map_err(|_| ServiceError::NotFound)?;
+
+    if plan.stripe_id == FREE_PLAN_STRIPE_ID {
+        return Err(ServiceError::BadRequest(
+            "The free plan can't be deleted".to_string(),
+        ));
+    }
 
     diesel::delete(plans_columns::plans)
         .filter(plans_columns::id.eq(plan_id))
         .execute(&mut conn)
         .await
-        .map_err(|_| ServiceError::InternalServerError("Error deleting plan".to_string()))?;
+        .map_err(|e| match e {
+            diesel::result::Error::DatabaseError(
+                diesel::result::DatabaseErrorKind::ForeignKeyViolation,
+                _,
+            ) => ServiceError::BadRequest("Plan still has subscriptions".to_string()),
+            _ => ServiceError::InternalServerError("Error deleting plan".to_string()),
+        })?;
 
     Ok(())
 }
 
+pub async fn list_plans_query(pg_pool: web::Data<PgPool>) -> Result<Vec<Plan>, ServiceError> {
+    use crate::data::schema::plans::dsl as plans_columns;
+
+    let mut conn = pg_pool.get().await?;
+
+    plans_columns::plans
+        .order(plans_columns::price_per_month.asc())
+        .load::<Plan>(&mut conn)
+        .await
+        .map_err(|_| ServiceError::InternalServerError("Error listing plans".to_string()))
+}
+
 pub async fn update_plan_query(
     plan_id: uuid::Uuid,
     stripe_id: Option<String>,
@@ -57,7 +96,7 @@ pub async fn update_plan_query(
 ) -> Result<Plan, ServiceError> {
     use crate::data::schema::plans::dsl as plans_columns;
 
-    let mut conn = pg_pool.get().await.unwrap();
+    let mut conn = pg_pool.get().await?;
 
     let target = plans_columns::plans.filter(plans_columns::id.eq(plan_id));
 
@@ -81,7 +120,7 @@ pub async fn get_plan_by_id(
 ) -> Result<Plan, ServiceError> {
     use crate::data::schema::plans::dsl as plans_columns;
 
-    let mut conn = pg_pool.get().await.unwrap();
+    let mut conn = pg_pool.get().await?;
 
     let plan = plans_columns::plans
         .filter(plans_columns::id.eq(plan_id))
@@ -91,3 +130,214 @@ pub async fn get_plan_by_id(
 
     Ok(plan)
 }
+
+/// Returns the plan of the org's active subscription, or the free plan if it has none.
+/// Subscriptions with failed payments don't count as active.
+pub async fn get_plan_for_org(
+    org_id: PrefixedUuid<OrgPrefix>,
+    conn: &mut AsyncPgConnection,
+) -> Result<Plan, ServiceError> {
+    use crate::data::schema::plans::dsl as plans_columns;
+    use crate::data::schema::subscriptions::dsl as subscriptions_columns;
+
+    let subscribed_plan = plans_columns::plans
+        .inner_join(
+            subscriptions_columns::subscriptions
+                .on(subscriptions_columns::plan_id.eq(plans_columns::id)),
+        )
+        .filter(subscriptions_columns::org_id.eq(org_id))
+        .filter(subscriptions_columns::status.eq(SubscriptionStatus::Active.as_str()))
+        .filter(
+            subscriptions_columns::end_date
+                .is_null()
+                .or(subscriptions_columns::end_date.gt(chrono::Utc::now().naive_local())),
+        )
+        .order(subscriptions_columns::start_date.desc())
+        .select(Plan::as_select())
+        .first::<Plan>(conn)
+        .await
+        .optional()
+        .map_err(|_| ServiceError::InternalServerError("Error getting org plan".to_string()))?;
+
+    match subscribed_plan {
+        Some(plan) => Ok(plan),
+        None => plans_columns::plans
+            .filter(plans_columns::stripe_id.eq(FREE_PLAN_STRIPE_ID))
+            .first::<Plan>(conn)
+            .await
+            .map_err(|_| ServiceError::InternalServerError("Free plan is missing".to_string())),
+    }
+}
+
+pub async fn get_org_usage(
+    org_id: PrefixedUuid<OrgPrefix>,
+    conn: &mut AsyncPgConnection,
+) -> Result<OrgUsage, ServiceError> {
+    use crate::data::schema::deals::dsl as deals_columns;
+    use crate::data::schema::org_users::dsl as org_users_columns;
+
+    let plan = get_plan_for_org(org_id, conn).await?;
+
+    let num_users = org_users_columns::org_users
+        .filter(org_users_columns::org_id.eq(org_id))
+        .count()
+        .get_result::<i64>(conn)
+        .await
+        .map_err(|_| ServiceError::InternalServerError("Error counting org users".to_string()))?;
+
+    let num_deals = deals_columns::deals
+        .filter(deals_columns::org_id.eq(org_id))
+        .count()
+        .get_result::<i64>(conn)
+        .await
+        .map_err(|_| ServiceError::InternalServerError("Error counting deals".to_string()))?;
+
+    Ok(OrgUsage {
+        plan,
+        num_users,
+        num_deals,
+    })
+}
+
+pub async fn get_org_usage_query(
+    org_id: PrefixedUuid<OrgPrefix>,
+    pg_pool: web::Data<PgPool>,
+) -> Result<OrgUsage, ServiceError> {
+    let mut conn = pg_pool.get().await?;
+
+    get_org_usage(org_id, &mut conn).await
+}
+
+/// Errors with `PaymentRequired` if the org can't create another deal on its plan. The
+/// org row stays locked until the caller's transaction ends, so concurrent creations
+/// can't both pass the quota check.
+pub async fn check_deal_quota(
+    org_id: PrefixedUuid<OrgPrefix>,
+    conn: &mut AsyncPgConnection,
+) -> Result<(), ServiceError> {
+    use crate::data::schema::orgs::dsl as orgs_columns;
+
+    orgs_columns::orgs
+        .filter(orgs_columns::id.eq(org_id))
+        .select(orgs_columns::id)
+        .for_update()
+        .first::<PrefixedUuid<OrgPrefix>>(conn)
+        .await
+        .map_err(ServiceError::from)?;
+
+    let usage = get_org_usage(org_id, conn).await?;
+
+    if usage.num_deals >= usage.plan.num_deals as i64 {
+        return Err(ServiceError::PaymentRequired(format!(
+            "Your plan allows {} deals. Upgrade your plan to create more.",
+            usage.plan.num_deals
+        )));
+    }
+
+    Ok(())
+}
+
+/// Errors with `PaymentRequired` if the org can't take on another member. When
+/// `include_pending_invitations` is set, unused invitations count as members, except
+/// those for people who are members already.
+pub async fn check_user_quota(
+    org_id: PrefixedUuid<OrgPrefix>,
+    include_pending_invitations: bool,
+    conn: &mut AsyncPgConnection,
+) -> Result<(), ServiceError> {
+    use crate::data::schema::invitations::dsl as invitations_columns;
+    use crate::data::schema::org_users::dsl as org_users_columns;
+    use crate::data::schema::users::dsl as users_columns;
+
+    let usage = get_org_usage(org_id, conn).await?;
+
+    let pending_invitations = if include_pending_invitations {
+        let already_member = org_users_columns::org_users
+            .inner_join(users_columns::users)
+            .filter(org_users_columns::org_id.eq(org_id))
+            .filter(users_columns::email.eq(invitations_columns::email));
+
+        invitations_columns::invitations
+            .filter(invitations_columns::organization_id.eq(org_id))
+            .filter(invitations_columns::used.eq(false))
+            .filter(not(exists(already_member)))
+            .count()
+            .get_result::<i64>(conn)
+            .await
+            .map_err(|_| {
+                ServiceError::InternalServerError("Error counting invitations".to_string())
+            })?
+    } else {
+        0
+    };
+
+    if usage.num_users + pending_invitations >= usage.plan.num_users as i64 {
+        return Err(ServiceError::PaymentRequired(format!(
+            "Your plan allows {} members. Upgrade your plan to add more.",
+            usage.plan.num_users
+        )));
+    }
+
+    Ok(())
+}
+
+/// Puts an org on a plan without going through billing. Any other active
+/// subscription of the org is canceled in the same transaction, so the org is never
+/// left with no active subscription or two.
+pub async fn assign_plan_to_org_query(
+    org_id: PrefixedUuid<OrgPrefix>,
+    plan_id: uuid::Uuid,
+    end_date: Option<chrono::NaiveDateTime>,
+    pg_pool: web::Data<PgPool>,
+) -> Result<Subscription, ServiceError> {
+    use crate::data::schema::subscriptions::dsl as subscriptions_columns;
+
+    let plan = get_plan_by_id(plan_id, pg_pool.clone()).await?;
+
+    let mut conn = pg_pool.get().await?;
+
+    conn.transaction::<_, ServiceError, _>(|conn| {
+        async move {
+            let now = chrono::Utc::now().naive_local();
+
+            diesel::update(
+                subscriptions_columns::subscriptions
+                    .filter(subscriptions_columns::org_id.eq(org_id))
+                    .filter(subscriptions_columns::status.eq(SubscriptionStatus::Active.as_str())),
+            )
+            .set((
+                subscriptions_columns::status.eq(SubscriptionStatus::Canceled.as_str()),
+                subscriptions_columns::end_date.eq(now),
+            ))
+            .execute(conn)
+            .await
+            .map_err(|_| {
+                ServiceError::InternalServerError("Error canceling subscriptions".to_string())
+            })?;
+
+            let id = uuid::Uuid::now_v7();
+            let subscription = Subscription {
+                id,
+                stripe_id: format!("manual-{}", id),
+                org_id,
+                plan_id: plan.id,
+                stripe_plan_id: plan.stripe_id,
+                next_billing_date: end_date.unwrap_or(now),
+                start_date: now,
+                end_date,
+                status: SubscriptionStatus::Active.as_str().to_string(),
+                last_event_at: None,
+            };
+
+            diesel::insert_into(subscriptions_columns::subscriptions)
+                .values(&subscription)
+                .get_result::<Subscription>(conn)
+                .await
+                .map_err(|_| {
+                    ServiceError::InternalServerError("Error creating subscription".to_string())
+                })
+        }
+        .scope_boxed()
+    })
+    .await
+}
@@ -0,0 +1,69 @@
+use crate::{
+    data::models::{RateLimit, RateLimitStatus, RedisPool},
+    errors::ServiceError,
+};
+use actix_web::web;
+use bb8_redis::redis;
+
+/// Refills the bucket for the time since it was last used, then takes a token if there
+/// is one. Runs as a script so concurrent requests can't both take the last token.
+const TAKE_TOKEN_SCRIPT: &str = r#"
+local capacity = tonumber(ARGV[1])
+local window_ms = tonumber(ARGV[2])
+local time = redis.call('TIME')
+local now = tonumber(time[1]) * 1000 + math.floor(tonumber(time[2]) / 1000)
+
+local bucket = redis.call('HMGET', KEYS[1], 'tokens', 'updated_at')
+local tokens = tonumber(bucket[1])
+local updated_at = tonumber(bucket[2])
+if tokens == nil or updated_at == nil then
+  tokens = capacity
+  updated_at = now
+end
+tokens = math.min(capacity, tokens + math.max(0, now - updated_at) * capacity / window_ms)
+
+local allowed = 0
+local retry_after_ms = 0
+if tokens >= 1 then
+  tokens = tokens - 1
+  allowed = 1
+else
+  retry_after_ms = math.ceil((1 - tokens) * window_ms / capacity)
+end
+
+redis.call('HSET', KEYS[1], 'tokens', tostring(tokens), 'updated_at', tostring(now))
+redis.call('PEXPIRE', KEYS[1], window_ms)
+
+return {allowed, math.floor(tokens), math.ceil((capacity - tokens) * window_ms / capacity), retry_after_ms}
+"#;
+
+/// Takes a token from the bucket stored under `key`, creating a full one if needed.
+#[tracing::instrument(skip(redis_pool))]
+pub async fn take_rate_limit_token_query(
+    key: &str,
+    limit: RateLimit,
+    redis_pool: web::Data<RedisPool>,
+) -> Result<RateLimitStatus, ServiceError> {
+    let mut redis_conn = redis_pool
+        .get()
+        .await
+        .map_err(|_| ServiceError::ServiceUnavailable("Could not get redis connection".into()))?;
+
+    let (allowed, remaining, reset_ms, retry_after_ms) = redis::cmd("EVAL")
+        .arg(TAKE_TOKEN_SCRIPT)
+        .arg(1)
+        .arg(key)
+        .arg(limit.requests)
+        .arg(limit.window.as_millis() as u64)
+        .query_async::<_, (i64, i64, i64, i64)>(&mut *redis_conn)
+        .await
+        .map_err(|_| ServiceError::InternalServerError("Error checking rate limit".to_string()))?;
+
+    Ok(RateLimitStatus {
+        allowed: allowed == 1,
+        limit: limit.requests,
+        remaining: remaining.max(0) as u32,
+        reset_secs: (reset_ms.max(0) as u64).div_ceil(1000),
+        retry_after_secs: (retry_after_ms.max(0) as u64).div_ceil(1000),
+    })
+}
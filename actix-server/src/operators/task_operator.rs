@@ -1,12 +1,16 @@
 use crate::{
-    data::models::{PgPool, Task, TaskDeal, TaskLink, TaskUser},
+    data::{
+        etag::{unchanged_since, write_conflict},
+        models::{PgPool, Task, TaskDeal, TaskLink, TaskUser},
+        patch::Patch,
+    },
     errors::ServiceError,
     prefixes::{
         ContactPrefix, DealPrefix, LinkPrefix, OrgPrefix, PrefixedUuid, TaskPrefix, UserPrefix,
     },
 };
 use actix_web::web;
-use diesel::{ExpressionMethods, QueryDsl};
+use diesel::{ExpressionMethods, OptionalExtension, QueryDsl};
 use diesel_async::RunQueryDsl;
 
 #[tracing::instrument(skip(pg_pool))]
@@ -15,54 +19,73 @@ pub async fn create_task_query(
     contact_id: Option<PrefixedUuid<ContactPrefix>>,
     description: Option<String>,
     deadline: Option<chrono::NaiveDateTime>,
+    owner_user_id: Option<PrefixedUuid<UserPrefix>>,
     pg_pool: web::Data<PgPool>,
 ) -> Result<Task, ServiceError> {
     use crate::data::schema::tasks::dsl as tasks_columns;
-    let mut conn = pg_pool.get().await.unwrap();
-    let new_task = Task::from_details(org_id, deadline, description, contact_id);
+    let mut conn = pg_pool.get().await?;
+    let new_task = Task::from_details(org_id, deadline, description, contact_id, owner_user_id);
     let task = diesel::insert_into(tasks_columns::tasks)
         .values(&new_task)
         .get_result::<Task>(&mut conn)
         .await
-        .map_err(|_| ServiceError::InternalServerError("Error creating task".to_string()))?;
+        .map_err(ServiceError::from)?;
     Ok(task)
 }
 
 pub async fn delete_task_query(
     task_id: PrefixedUuid<TaskPrefix>,
+    expected_updated_at: Option<chrono::NaiveDateTime>,
     pg_pool: web::Data<PgPool>,
 ) -> Result<(), ServiceError> {
     use crate::data::schema::tasks::dsl as tasks_columns;
-    let mut conn = pg_pool.get().await.unwrap();
+    let mut conn = pg_pool.get().await?;
 
-    diesel::delete(tasks_columns::tasks)
+    let deleted = diesel::delete(tasks_columns::tasks)
         .filter(tasks_columns::id.eq(task_id))
+        .filter(unchanged_since(
+            tasks_columns::updated_at,
+            expected_updated_at,
+        ))
         .execute(&mut conn)
         .await
         .map_err(|_| ServiceError::InternalServerError("Error deleting task".to_string()))?;
+    if deleted == 0 && expected_updated_at.is_some() {
+        return Err(ServiceError::PreconditionFailed);
+    }
 
     Ok(())
 }
 
 pub async fn update_task_query(
     task_id: PrefixedUuid<TaskPrefix>,
-    description: Option<String>,
-    deadline: Option<chrono::NaiveDateTime>,
-    contact_id: Option<PrefixedUuid<ContactPrefix>>,
+    description: Patch<String>,
+    deadline: Patch<chrono::NaiveDateTime>,
+    contact_id: Patch<PrefixedUuid<ContactPrefix>>,
+    owner_user_id: Patch<PrefixedUuid<UserPrefix>>,
+    expected_updated_at: Option<chrono::NaiveDateTime>,
     pg_pool: web::Data<PgPool>,
 ) -> Result<Task, ServiceError> {
     use crate::data::schema::tasks::dsl as tasks_columns;
-    let mut conn = pg_pool.get().await.unwrap();
-    let target = tasks_columns::tasks.filter(tasks_columns::id.eq(task_id));
+    let mut conn = pg_pool.get().await?;
+    let target = tasks_columns::tasks
+        .filter(tasks_columns::id.eq(task_id))
+        .filter(unchanged_since(
+            tasks_columns::updated_at,
+            expected_updated_at,
+        ));
     let updated_task = diesel::update(target)
         .set((
             description.map(|description| tasks_columns::description.eq(description)),
             deadline.map(|deadline| tasks_columns::deadline.eq(deadline)),
             contact_id.map(|contact_id| tasks_columns::contact_id.eq(contact_id)),
+            owner_user_id.map(|owner_user_id| tasks_columns::owner_user_id.eq(owner_user_id)),
         ))
         .get_result::<Task>(&mut conn)
         .await
-        .map_err(|_| ServiceError::InternalServerError("Error updating task".to_string()))?;
+        .optional()
+        .map_err(ServiceError::from)?
+        .ok_or_else(|| write_conflict(expected_updated_at))?;
     Ok(updated_task)
 }
 
@@ -71,7 +94,7 @@ pub async fn get_task_by_id_query(
     pg_pool: web::Data<PgPool>,
 ) -> Result<Task, ServiceError> {
     use crate::data::schema::tasks::dsl as tasks_columns;
-    let mut conn = pg_pool.get().await.unwrap();
+    let mut conn = pg_pool.get().await?;
     let task = tasks_columns::tasks
         .filter(tasks_columns::id.eq(task_id))
         .first(&mut conn)
@@ -80,19 +103,55 @@ pub async fn get_task_by_id_query(
     Ok(task)
 }
 
+pub async fn list_tasks_by_org_id_query(
+    org_id: PrefixedUuid<OrgPrefix>,
+    owner_user_id: Option<PrefixedUuid<UserPrefix>>,
+    pg_pool: web::Data<PgPool>,
+    limit: Option<i64>,
+    offset: Option<PrefixedUuid<TaskPrefix>>,
+) -> Result<(Vec<Task>, i64), ServiceError> {
+    use crate::data::schema::tasks::dsl as tasks_columns;
+    let mut conn = pg_pool.get().await?;
+    let limit = limit.unwrap_or(10);
+    let offset = offset.unwrap_or(PrefixedUuid::zero_id(TaskPrefix));
+    let mut query = tasks_columns::tasks
+        .filter(tasks_columns::org_id.eq(org_id))
+        .into_boxed();
+    let mut count_query = tasks_columns::tasks
+        .filter(tasks_columns::org_id.eq(org_id))
+        .into_boxed();
+    if let Some(owner_user_id) = owner_user_id {
+        query = query.filter(tasks_columns::owner_user_id.eq(owner_user_id));
+        count_query = count_query.filter(tasks_columns::owner_user_id.eq(owner_user_id));
+    }
+    let tasks = query
+        .filter(tasks_columns::id.gt(offset))
+        .order(tasks_columns::id)
+        .limit(limit)
+        .load::<Task>(&mut conn)
+        .await
+        .map_err(|_| ServiceError::InternalServerError("Error fetching tasks".to_string()))?;
+    let count = count_query
+        .count()
+        .get_result::<i64>(&mut conn)
+        .await
+        .map_err(|_| ServiceError::InternalServerError("Error counting tasks".to_string()))?;
+    Ok((tasks, count))
+}
+
 pub async fn create_deal_for_task_query(
     task_id: PrefixedUuid<TaskPrefix>,
     deal_id: PrefixedUuid<DealPrefix>,
     pg_pool: web::Data<PgPool>,
 ) -> Result<TaskDeal, ServiceError> {
     use crate::data::schema::task_deals::dsl as task_deals_columns;
-    let mut conn = pg_pool.get().await.unwrap();
+    let mut conn = pg_pool.get().await?;
     let new_task_deal = TaskDeal::from_details(task_id, deal_id);
     let task_deal = diesel::insert_into(task_deals_columns::task_deals)
         .values(&new_task_deal)
         .get_result::<TaskDeal>(&mut conn)
         .await
-        .map_err(|_| ServiceError::InternalServerError("Error creating task deal".to_string()))?;
+        .map_err(ServiceError::from)?;
     Ok(task_deal)
 }
 
@@ -102,7 +161,7 @@ pub async fn delete_deal_from_task_query(
     pg_pool: web::Data<PgPool>,
 ) -> Result<(), ServiceError> {
     use crate::data::schema::task_deals::dsl as task_deals_columns;
-    let mut conn = pg_pool.get().await.unwrap();
+    let mut conn = pg_pool.get().await?;
     diesel::delete(task_deals_columns::task_deals)
         .filter(task_deals_columns::task_id.eq(task_id))
         .filter(task_deals_columns::deal_id.eq(deal_id))
@@ -118,13 +177,13 @@ pub async fn create_link_for_task_query(
     pg_pool: web::Data<PgPool>,
 ) -> Result<TaskLink, ServiceError> {
     use crate::data::schema::task_links::dsl as task_links_columns;
-    let mut conn = pg_pool.get().await.unwrap();
+    let mut conn = pg_pool.get().await?;
     let new_task_deal = TaskLink::from_details(task_id, link_id);
     let task_link = diesel::insert_into(task_links_columns::task_links)
         .values(&new_task_deal)
         .get_result::<TaskLink>(&mut conn)
         .await
-        .map_err(|_| ServiceError::InternalServerError("Error creating task link".to_string()))?;
+        .map_err(ServiceError::from)?;
     Ok(task_link)
 }
 
@@ -134,7 +193,7 @@ pub async fn delete_link_from_task_query(
     pg_pool: web::Data<PgPool>,
 ) -> Result<(), ServiceError> {
     use crate::data::schema::task_links::dsl as task_links_columns;
-    let mut conn = pg_pool.get().await.unwrap();
+    let mut conn = pg_pool.get().await?;
     diesel::delete(task_links_columns::task_links)
         .filter(task_links_columns::task_id.eq(task_id))
         .filter(task_links_columns::link_id.eq(link_id))
@@ -150,13 +209,13 @@ pub async fn create_user_for_task_query(
     pg_pool: web::Data<PgPool>,
 ) -> Result<TaskUser, ServiceError> {
     use crate::data::schema::task_users::dsl as task_users_columns;
-    let mut conn = pg_pool.get().await.unwrap();
+    let mut conn = pg_pool.get().await?;
     let new_task_user = TaskUser::from_details(task_id, user_id);
     let task_user = diesel::insert_into(task_users_columns::task_users)
         .values(&new_task_user)
         .get_result::<TaskUser>(&mut conn)
         .await
-        .map_err(|_| ServiceError::InternalServerError("Error creating task user".to_string()))?;
+        .map_err(ServiceError::from)?;
     Ok(task_user)
 }
 
@@ -166,7 +225,7 @@ pub async fn delete_user_from_task_query(
     pg_pool: web::Data<PgPool>,
 ) -> Result<(), ServiceError> {
     use crate::data::schema::task_users::dsl as task_users_columns;
-    let mut conn = pg_pool.get().await.unwrap();
+    let mut conn = pg_pool.get().await?;
     diesel::delete(task_users_columns::task_users)
         .filter(task_users_columns::task_id.eq(task_id))
         .filter(task_users_columns::user_id.eq(user_id))
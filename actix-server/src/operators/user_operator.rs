@@ -15,7 +15,7 @@ pub async fn get_user_by_id_query(
 ) -> Result<User, ServiceError> {
     use crate::data::schema::users::dsl as users_columns;
 
-    let mut conn = pg_pool.get().await.unwrap();
+    let mut conn = pg_pool.get().await?;
 
     let user: User = users_columns::users
         .filter(users_columns::id.eq(user_id))
@@ -32,7 +32,7 @@ pub async fn get_user_by_id_query(
     Ok(user)
 }
 
-#[tracing::instrument(skip(pg_pool))]
+#[tracing::instrument(skip(pg_pool, email, name))]
 pub async fn create_user_query(
     user_id: PrefixedUuid<UserPrefix>,
     email: String,
@@ -41,7 +41,7 @@ pub async fn create_user_query(
 ) -> Result<User, ServiceError> {
     use crate::data::schema::users::dsl as users_columns;
 
-    let mut conn = pg_pool.get().await.unwrap();
+    let mut conn = pg_pool.get().await?;
 
     let user = User::from_details_with_id(user_id, email, name);
 
@@ -56,7 +56,7 @@ pub async fn create_user_query(
     Ok(user)
 }
 
-#[tracing::instrument(skip(pg_pool))]
+#[tracing::instrument(skip(pg_pool, api_key))]
 pub async fn get_user_from_api_key_query(
     api_key: &str,
     pg_pool: web::Data<PgPool>,
@@ -66,7 +66,7 @@ pub async fn get_user_from_api_key_query(
 
     let blake3_hash = hash_api_key(api_key);
 
-    let mut conn = pg_pool.get().await.unwrap();
+    let mut conn = pg_pool.get().await?;
 
     let user: User = users_columns::users
         .inner_join(api_keys_columns::api_keys)
@@ -84,14 +84,14 @@ pub async fn get_user_from_api_key_query(
     Ok(user)
 }
 
-#[tracing::instrument(skip(pg_pool))]
+#[tracing::instrument(skip(pg_pool, email))]
 pub async fn get_user_by_email_query(
     email: &str,
     pg_pool: web::Data<PgPool>,
 ) -> Result<User, ServiceError> {
     use crate::data::schema::users::dsl as users_columns;
 
-    let mut conn = pg_pool.get().await.unwrap();
+    let mut conn = pg_pool.get().await?;
 
     let user: User = users_columns::users
         .filter(users_columns::email.eq(email))
@@ -108,6 +108,27 @@ pub async fn get_user_by_email_query(
     Ok(user)
 }
 
+#[tracing::instrument(skip(pg_pool))]
+pub async fn set_user_disabled_query(
+    user_id: PrefixedUuid<UserPrefix>,
+    disabled: bool,
+    pg_pool: web::Data<PgPool>,
+) -> Result<User, ServiceError> {
+    use crate::data::schema::users::dsl as users_columns;
+
+    let mut conn = pg_pool.get().await?;
+
+    diesel::update(users_columns::users.filter(users_columns::id.eq(user_id)))
+        .set((
+            users_columns::disabled.eq(disabled),
+            users_columns::updated_at.eq(chrono::Utc::now().naive_local()),
+        ))
+        .returning(User::as_returning())
+        .get_result::<User>(&mut conn)
+        .await
+        .map_err(|_| ServiceError::NotFound)
+}
+
 pub async fn list_users_by_task_id_query(
     task_id: PrefixedUuid<TaskPrefix>,
     pg_pool: web::Data<PgPool>,
@@ -116,7 +137,7 @@ pub async fn list_users_by_task_id_query(
 ) -> Result<(Vec<User>, i64), ServiceError> {
     use crate::data::schema::tasks::dsl as tasks_columns;
     use crate::data::schema::users::dsl as users_columns;
-    let mut conn = pg_pool.get().await.unwrap();
+    let mut conn = pg_pool.get().await?;
     let limit = limit.unwrap_or(10);
     let offset = offset.unwrap_or(PrefixedUuid::zero_id(UserPrefix));
     let task = tasks_columns::tasks
@@ -128,7 +149,7 @@ pub async fn list_users_by_task_id_query(
         .inner_join(users_columns::users)
         .select(User::as_select())
         .filter(users_columns::id.gt(offset))
-        .order((users_columns::updated_at, users_columns::id))
+        .order(users_columns::id)
         .limit(limit)
         .load::<User>(&mut conn)
         .await
@@ -108,13 +108,23 @@ impl<P: Prefix> ToSql<diesel::sql_types::Uuid, diesel::pg::Pg> for PrefixedUuid<
 
 impl<P: Prefix> PrefixedUuid<P> {
     // TODO: make the prefix an enum, like in the unkey blog
+    /// New ids are UUIDv7, which start with a millisecond timestamp. They sort by
+    /// creation, so `id > offset` pagination follows creation order and inserts land at
+    /// the end of the primary key index instead of at random pages.
     pub fn create(prefix: P) -> Self {
         PrefixedUuid {
             prefix,
-            id: uuid::Uuid::new_v4(),
+            id: uuid::Uuid::now_v7(),
         }
     }
 
+    /// When the id was created. `None` for the random v4 ids issued before ids were
+    /// time-ordered, which stay valid but carry no timestamp.
+    pub fn created_at(&self) -> Option<chrono::NaiveDateTime> {
+        let (secs, nanos) = self.id.get_timestamp()?.to_unix();
+        chrono::NaiveDateTime::from_timestamp_opt(secs as i64, nanos)
+    }
+
     pub fn zero_id(prefix: P) -> Self {
         PrefixedUuid {
             prefix,
@@ -170,6 +180,10 @@ macro_rules! impl_prefix {
         #[derive(Clone, Debug, Serialize, Deserialize, Default, Copy, PartialEq, Eq, ToSchema)]
         pub struct $name;
 
+        impl $name {
+            pub const PREFIX: &'static str = $prefix;
+        }
+
         impl Display for $name {
             fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
                 write!(f, "{}", $prefix)
@@ -207,3 +221,4 @@ impl_prefix!(CompanyPrefix, "company");
 impl_prefix!(TaskDealPrefix, "taskdeal");
 impl_prefix!(TaskLinkPrefix, "tasklink");
 impl_prefix!(TaskUserPrefix, "taskuser");
+impl_prefix!(ActivityPrefix, "activity");
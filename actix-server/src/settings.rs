@@ -0,0 +1,648 @@
+//! Server configuration, read once at startup. Values come from environment variables
+//! and, when `SETTINGS_FILE` points at one, a TOML file whose keys are the lowercase
+//! variable names (`database_url = "..."`). Environment variables win over the file.
+use crate::data::{models::RateLimit, redact::redacted_debug};
+use actix_web::cookie::SameSite;
+use std::{
+    collections::HashMap,
+    fmt,
+    net::{IpAddr, SocketAddr},
+    path::PathBuf,
+    str::FromStr,
+    sync::OnceLock,
+};
+
+static SETTINGS: OnceLock<Settings> = OnceLock::new();
+
+/// Session key used when `SECRET_KEY` isn't set. Only fit for local development.
+const DEV_SECRET_KEY: &str = "0123012301230123012301230123012301230123012301230123012301230123";
+
+/// A value that must never show up in logs. `Debug` prints a placeholder.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Secret(String);
+
+impl Secret {
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("[redacted]")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Environment {
+    Development,
+    Production,
+}
+
+impl FromStr for Environment {
+    type Err = String;
+
+    fn from_str(environment: &str) -> Result<Self, Self::Err> {
+        match environment.to_lowercase().as_str() {
+            "development" => Ok(Environment::Development),
+            "production" => Ok(Environment::Production),
+            _ => Err("expected development or production".to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+    /// Human readable lines.
+    Text,
+    /// One JSON object per line, with the fields of the enclosing spans like
+    /// `request_id`, for log aggregators.
+    Json,
+}
+
+impl FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        match format.to_lowercase().as_str() {
+            "text" => Ok(LogFormat::Text),
+            "json" => Ok(LogFormat::Json),
+            _ => Err("expected text or json".to_string()),
+        }
+    }
+}
+
+fn parse_same_site(same_site: &str) -> Result<SameSite, String> {
+    match same_site.to_lowercase().as_str() {
+        "strict" => Ok(SameSite::Strict),
+        "lax" => Ok(SameSite::Lax),
+        "none" => Ok(SameSite::None),
+        _ => Err("expected strict, lax or none".to_string()),
+    }
+}
+
+/// How Postgres connections use TLS, with the same meaning as libpq's `sslmode`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SslMode {
+    /// Plain text only.
+    Disable,
+    /// TLS when the server offers it, without checking its certificate.
+    Prefer,
+    /// Always TLS, without checking the server's certificate.
+    Require,
+    /// Always TLS, with a certificate signed by a trusted CA.
+    VerifyCa,
+    /// Like `VerifyCa` and the certificate must also match the host name.
+    VerifyFull,
+}
+
+impl SslMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SslMode::Disable => "disable",
+            SslMode::Prefer => "prefer",
+            SslMode::Require => "require",
+            SslMode::VerifyCa => "verify-ca",
+            SslMode::VerifyFull => "verify-full",
+        }
+    }
+
+    pub fn verifies_certificate(&self) -> bool {
+        matches!(self, SslMode::VerifyCa | SslMode::VerifyFull)
+    }
+}
+
+impl FromStr for SslMode {
+    type Err = String;
+
+    fn from_str(mode: &str) -> Result<Self, Self::Err> {
+        match mode.to_lowercase().as_str() {
+            "disable" => Ok(SslMode::Disable),
+            "prefer" => Ok(SslMode::Prefer),
+            "require" => Ok(SslMode::Require),
+            "verify-ca" => Ok(SslMode::VerifyCa),
+            "verify-full" => Ok(SslMode::VerifyFull),
+            _ => Err("expected disable, prefer, require, verify-ca or verify-full".to_string()),
+        }
+    }
+}
+
+/// TLS for the connection pool and migrations.
+#[derive(Debug, Clone)]
+pub struct DatabaseTls {
+    pub mode: SslMode,
+    /// PEM file with the CAs trusted to sign the server's certificate. Required when
+    /// the mode verifies the certificate: migrations connect through libpq, which
+    /// only knows `sslrootcert=system` from version 16 on, and the images ship 15.
+    pub root_cert: Option<PathBuf>,
+    /// PEM client certificate and key, for servers that authenticate clients with
+    /// `cert` in `pg_hba.conf`. Either both are set or neither.
+    pub client_cert: Option<PathBuf>,
+    pub client_key: Option<PathBuf>,
+}
+
+impl DatabaseTls {
+    /// `database_url` with the TLS options appended as libpq connection parameters,
+    /// for connections made through libpq instead of the pool.
+    pub fn libpq_url(&self, database_url: &str) -> String {
+        let mut params = vec![("sslmode", self.mode.as_str().to_string())];
+        for (param, path) in [
+            ("sslrootcert", &self.root_cert),
+            ("sslcert", &self.client_cert),
+            ("sslkey", &self.client_key),
+        ] {
+            if let Some(path) = path {
+                params.push((param, path.display().to_string()));
+            }
+        }
+
+        match url::Url::parse(database_url) {
+            Ok(mut url) => {
+                url.query_pairs_mut().extend_pairs(params);
+                url.to_string()
+            }
+            // Keyword/value connection string, e.g. `host=localhost dbname=crm`
+            Err(_) => params
+                .iter()
+                .fold(database_url.to_string(), |url, (param, value)| {
+                    format!(
+                        "{} {}='{}'",
+                        url,
+                        param,
+                        value.replace('\\', "\\\\").replace('\'', "\\'")
+                    )
+                }),
+        }
+    }
+}
+
+/// Outgoing mail. Either all of the `SMTP_*` variables are set or none are, in which
+/// case invitations can't be emailed.
+#[derive(Debug, Clone)]
+pub struct SmtpSettings {
+    pub relay: String,
+    pub email_address: String,
+    pub username: String,
+    pub password: Secret,
+}
+
+#[derive(Clone)]
+pub struct Settings {
+    /// Production refuses insecure defaults that are convenient during development.
+    pub environment: Environment,
+    /// Defaults to JSON in production.
+    pub log_format: LogFormat,
+    pub bind_address: SocketAddr,
+    /// Number of HTTP workers. Defaults to one per CPU core.
+    pub workers: usize,
+    /// Seconds in-flight requests and background work get to finish on shutdown.
+    pub shutdown_timeout_secs: u64,
+    pub base_server_url: String,
+    pub max_request_body_bytes: usize,
+    /// Origins browsers may call the API from, e.g. `https://app.example.com`. Empty
+    /// allows any origin in development and only the server's own in production.
+    pub cors_origins: Vec<String>,
+    /// Only send the session cookie over HTTPS. Defaults to on in production.
+    pub secure_cookies: bool,
+    pub cookie_same_site: SameSite,
+    pub secret_key: Secret,
+    pub database_url: Secret,
+    pub database_tls: DatabaseTls,
+    pub database_pool_size: usize,
+    /// Seconds a request waits for a database connection before failing with a 503.
+    pub database_pool_wait_secs: u64,
+    pub redis_url: Secret,
+    pub redis_connections: u32,
+    pub oidc_issuer_url: String,
+    pub oidc_client_id: String,
+    pub oidc_client_secret: Secret,
+    pub oidc_auth_redirect_url: String,
+    pub smtp: Option<SmtpSettings>,
+    pub stripe_webhook_secret: Option<Secret>,
+    pub superuser_emails: Vec<String>,
+    pub sentry_url: Option<Secret>,
+    /// Bearer token Prometheus has to send to read `/metrics`. Open when unset.
+    pub metrics_token: Option<Secret>,
+    pub rate_limit_api: RateLimit,
+    pub rate_limit_api_org: RateLimit,
+    pub rate_limit_invitations: RateLimit,
+    pub rate_limit_invitations_org: RateLimit,
+    /// Reverse proxies whose `X-Forwarded-For` is believed when rate limiting by client
+    /// address. Requests from anywhere else are keyed by their peer address.
+    pub trusted_proxies: Vec<IpAddr>,
+}
+
+redacted_debug!(
+    Settings {
+        environment,
+        log_format,
+        bind_address,
+        workers,
+        shutdown_timeout_secs,
+        base_server_url,
+        max_request_body_bytes,
+        cors_origins,
+        secure_cookies,
+        cookie_same_site,
+        secret_key,
+        database_url,
+        database_tls,
+        database_pool_size,
+        database_pool_wait_secs,
+        redis_url,
+        redis_connections,
+        oidc_issuer_url,
+        oidc_client_id,
+        oidc_client_secret,
+        oidc_auth_redirect_url,
+        smtp,
+        stripe_webhook_secret,
+        sentry_url,
+        metrics_token,
+        rate_limit_api,
+        rate_limit_api_org,
+        rate_limit_invitations,
+        rate_limit_invitations_org,
+        trusted_proxies,
+    }
+    pii { superuser_emails }
+);
+
+/// Everything that is wrong with the configuration, so it can be fixed in one go.
+#[derive(Debug)]
+pub struct SettingsError(Vec<String>);
+
+impl fmt::Display for SettingsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Invalid server configuration:")?;
+        for problem in &self.0 {
+            writeln!(f, "  - {}", problem)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for SettingsError {}
+
+/// Where settings are read from. Collects problems instead of stopping at the first.
+struct Source {
+    file: HashMap<String, String>,
+    problems: Vec<String>,
+}
+
+impl Source {
+    fn load() -> Self {
+        let mut source = Source {
+            file: HashMap::new(),
+            problems: vec![],
+        };
+
+        if let Some(path) = std::env::var("SETTINGS_FILE")
+            .ok()
+            .filter(|path| !path.is_empty())
+        {
+            match std::fs::read_to_string(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|contents| {
+                    toml::from_str::<HashMap<String, toml::Value>>(&contents)
+                        .map_err(|e| e.to_string())
+                }) {
+                Ok(values) => {
+                    source.file = values
+                        .into_iter()
+                        .map(|(key, value)| (key.to_uppercase(), toml_to_string(value)))
+                        .collect();
+                }
+                Err(e) => source
+                    .problems
+                    .push(format!("SETTINGS_FILE {} could not be read: {}", path, e)),
+            }
+        }
+
+        source
+    }
+
+    /// Empty values count as unset, `.env.dist` leaves optional variables blank.
+    fn optional(&self, var: &str) -> Option<String> {
+        std::env::var(var)
+            .ok()
+            .or_else(|| self.file.get(var).cloned())
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    }
+
+    fn required(&mut self, var: &str) -> String {
+        self.optional(var).unwrap_or_else(|| {
+            self.problems.push(format!("{} must be set", var));
+            String::new()
+        })
+    }
+
+    fn url(&mut self, var: &str) -> String {
+        let value = self.required(var);
+        if !value.is_empty() && url::Url::parse(&value).is_err() {
+            self.problems
+                .push(format!("{} must be a valid URL, got {:?}", var, value));
+        }
+        value
+    }
+
+    fn parse<T>(&mut self, var: &str, default: T) -> T
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        self.parse_with(var, default, |value| value.parse())
+    }
+
+    fn parse_with<T, E: fmt::Display>(
+        &mut self,
+        var: &str,
+        default: T,
+        parse: impl Fn(&str) -> Result<T, E>,
+    ) -> T {
+        match self.optional(var) {
+            Some(value) => parse(&value).unwrap_or_else(|e| {
+                self.problems
+                    .push(format!("{} is invalid ({}): {:?}", var, e, value));
+                default
+            }),
+            None => default,
+        }
+    }
+
+    fn positive<T>(&mut self, var: &str, default: T) -> T
+    where
+        T: FromStr + PartialOrd + Default + Copy,
+        T::Err: fmt::Display,
+    {
+        let value = self.parse(var, default);
+        if value <= T::default() {
+            self.problems
+                .push(format!("{} must be greater than 0", var));
+        }
+        value
+    }
+
+    /// Comma separated origins, normalized to `scheme://host[:port]`.
+    fn origins(&mut self, var: &str) -> Vec<String> {
+        let mut origins = vec![];
+        for origin in self.list(var) {
+            match url::Url::parse(&origin) {
+                Ok(url) if url.has_host() => origins.push(url.origin().ascii_serialization()),
+                _ => self.problems.push(format!(
+                    "{} must only contain origins, got {:?}",
+                    var, origin
+                )),
+            }
+        }
+        origins
+    }
+
+    /// Path to a file that has to exist.
+    fn file(&mut self, var: &str) -> Option<PathBuf> {
+        let path = PathBuf::from(self.optional(var)?);
+        if !path.is_file() {
+            self.problems
+                .push(format!("{} must point at a file, got {:?}", var, path));
+        }
+        Some(path)
+    }
+
+    /// Comma separated IP addresses.
+    fn ip_addrs(&mut self, var: &str) -> Vec<IpAddr> {
+        let mut addrs = vec![];
+        for addr in self.list(var) {
+            match addr.parse() {
+                Ok(addr) => addrs.push(addr),
+                Err(_) => self.problems.push(format!(
+                    "{} must only contain IP addresses, got {:?}",
+                    var, addr
+                )),
+            }
+        }
+        addrs
+    }
+
+    fn list(&self, var: &str) -> Vec<String> {
+        self.optional(var)
+            .map(|value| {
+                value
+                    .split(',')
+                    .map(|item| item.trim().to_string())
+                    .filter(|item| !item.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+fn toml_to_string(value: toml::Value) -> String {
+    match value {
+        toml::Value::String(value) => value,
+        toml::Value::Array(values) => values
+            .into_iter()
+            .map(toml_to_string)
+            .collect::<Vec<_>>()
+            .join(","),
+        value => value.to_string(),
+    }
+}
+
+impl Settings {
+    /// Reads and validates the configuration without installing it.
+    pub fn load() -> Result<Settings, SettingsError> {
+        let mut source = Source::load();
+
+        let secret_key = match source.optional("SECRET_KEY") {
+            Some(key) if key.len() < 64 => {
+                source
+                    .problems
+                    .push("SECRET_KEY must be at least 64 bytes long".to_string());
+                key
+            }
+            Some(key) => key,
+            None => DEV_SECRET_KEY.to_string(),
+        };
+
+        let smtp_vars = [
+            "SMTP_RELAY",
+            "SMTP_EMAIL_ADDRESS",
+            "SMTP_USERNAME",
+            "SMTP_PASSWORD",
+        ];
+        let smtp = match smtp_vars.map(|var| source.optional(var)) {
+            [Some(relay), Some(email_address), Some(username), Some(password)] => {
+                if email_address.parse::<lettre::message::Mailbox>().is_err() {
+                    source.problems.push(format!(
+                        "SMTP_EMAIL_ADDRESS must be a valid email address, got {:?}",
+                        email_address
+                    ));
+                }
+                Some(SmtpSettings {
+                    relay,
+                    email_address,
+                    username,
+                    password: Secret(password),
+                })
+            }
+            [None, None, None, None] => None,
+            values => {
+                let missing = smtp_vars
+                    .iter()
+                    .zip(values.iter())
+                    .filter(|(_, value)| value.is_none())
+                    .map(|(var, _)| *var)
+                    .collect::<Vec<_>>();
+                source.problems.push(format!(
+                    "SMTP is partially configured, also set {}",
+                    missing.join(", ")
+                ));
+                None
+            }
+        };
+
+        let environment = source.parse("ENVIRONMENT", Environment::Development);
+
+        let database_url = source.required("DATABASE_URL");
+        // tokio-postgres only understands some sslmode values and ignores the
+        // certificate parameters, so they have to come from the DATABASE_SSL* variables
+        if let Ok(url) = url::Url::parse(&database_url) {
+            for (param, _) in url.query_pairs() {
+                if let Some(var) = match param.as_ref() {
+                    "sslmode" => Some("DATABASE_SSLMODE"),
+                    "sslrootcert" => Some("DATABASE_SSLROOTCERT"),
+                    "sslcert" => Some("DATABASE_SSLCERT"),
+                    "sslkey" => Some("DATABASE_SSLKEY"),
+                    _ => None,
+                } {
+                    source.problems.push(format!(
+                        "DATABASE_URL must not contain {}, set {} instead",
+                        param, var
+                    ));
+                }
+            }
+        }
+        let database_tls = DatabaseTls {
+            mode: source.parse(
+                "DATABASE_SSLMODE",
+                match environment {
+                    Environment::Development => SslMode::Prefer,
+                    Environment::Production => SslMode::VerifyFull,
+                },
+            ),
+            root_cert: source.file("DATABASE_SSLROOTCERT"),
+            client_cert: source.file("DATABASE_SSLCERT"),
+            client_key: source.file("DATABASE_SSLKEY"),
+        };
+        if database_tls.mode.verifies_certificate() && database_tls.root_cert.is_none() {
+            source.problems.push(format!(
+                "DATABASE_SSLROOTCERT must be set when DATABASE_SSLMODE is {}",
+                database_tls.mode.as_str()
+            ));
+        }
+        if database_tls.client_cert.is_some() != database_tls.client_key.is_some() {
+            source
+                .problems
+                .push("DATABASE_SSLCERT and DATABASE_SSLKEY must be set together".to_string());
+        }
+
+        let settings = Settings {
+            environment,
+            log_format: source.parse(
+                "LOG_FORMAT",
+                match environment {
+                    Environment::Development => LogFormat::Text,
+                    Environment::Production => LogFormat::Json,
+                },
+            ),
+            bind_address: source.parse("BIND_ADDRESS", SocketAddr::from(([0, 0, 0, 0], 8090))),
+            workers: source.positive(
+                "WORKERS",
+                std::thread::available_parallelism().map_or(1, |cores| cores.get()),
+            ),
+            shutdown_timeout_secs: source.positive("SHUTDOWN_TIMEOUT_SECS", 30),
+            base_server_url: source.url("BASE_SERVER_URL"),
+            max_request_body_bytes: source.positive("MAX_REQUEST_BODY_BYTES", 1024 * 1024),
+            cors_origins: source.origins("CORS_ORIGINS"),
+            secure_cookies: source.parse("SECURE_COOKIES", environment == Environment::Production),
+            cookie_same_site: source.parse_with("COOKIE_SAME_SITE", SameSite::Lax, parse_same_site),
+            secret_key: Secret(secret_key),
+            database_url: Secret(database_url),
+            database_tls,
+            database_pool_size: source.positive("DATABASE_POOL_SIZE", 10),
+            database_pool_wait_secs: source.positive("DATABASE_POOL_WAIT_SECS", 5),
+            redis_url: Secret(source.required("REDIS_URL")),
+            redis_connections: source.positive("REDIS_CONNECTIONS", 200),
+            oidc_issuer_url: source.url("OIDC_ISSUER_URL"),
+            oidc_client_id: source.required("OIDC_CLIENT_ID"),
+            oidc_client_secret: Secret(source.required("OIDC_CLIENT_SECRET")),
+            oidc_auth_redirect_url: source.url("OIDC_AUTH_REDIRECT_URL"),
+            smtp,
+            stripe_webhook_secret: source.optional("STRIPE_WEBHOOK_SECRET").map(Secret),
+            superuser_emails: source.list("SUPERUSER_EMAILS"),
+            sentry_url: source.optional("SENTRY_URL").map(Secret),
+            metrics_token: source.optional("METRICS_TOKEN").map(Secret),
+            rate_limit_api: source.parse("RATE_LIMIT_API", RateLimit::new(600, 60)),
+            rate_limit_api_org: source.parse("RATE_LIMIT_API_ORG", RateLimit::new(3000, 60)),
+            // Every invitation sends an email, so they get a much lower limit
+            rate_limit_invitations: source
+                .parse("RATE_LIMIT_INVITATIONS", RateLimit::new(20, 60 * 60)),
+            rate_limit_invitations_org: source
+                .parse("RATE_LIMIT_INVITATIONS_ORG", RateLimit::new(100, 60 * 60)),
+            trusted_proxies: source.ip_addrs("TRUSTED_PROXIES"),
+        };
+
+        if environment == Environment::Production && settings.uses_dev_secret_key() {
+            source
+                .problems
+                .push("SECRET_KEY must be set in production".to_string());
+        }
+        // Browsers drop SameSite=None cookies that aren't marked secure
+        if settings.cookie_same_site == SameSite::None && !settings.secure_cookies {
+            source
+                .problems
+                .push("COOKIE_SAME_SITE=none requires SECURE_COOKIES=true".to_string());
+        }
+
+        if source.problems.is_empty() {
+            Ok(settings)
+        } else {
+            Err(SettingsError(source.problems))
+        }
+    }
+
+    /// Whether browsers on any site may call the API with the user's session. Only
+    /// the case in development when no origins are configured.
+    pub fn allows_any_origin(&self) -> bool {
+        self.environment == Environment::Development && self.cors_origins.is_empty()
+    }
+
+    /// Origins allowed to make credentialed requests: the configured ones plus the
+    /// server's own.
+    pub fn allowed_origins(&self) -> Vec<String> {
+        let mut origins = self.cors_origins.clone();
+        if let Ok(url) = url::Url::parse(&self.base_server_url) {
+            origins.push(url.origin().ascii_serialization());
+        }
+        origins
+    }
+
+    /// Whether sessions are signed with the publicly known development key.
+    pub fn uses_dev_secret_key(&self) -> bool {
+        self.secret_key.expose() == DEV_SECRET_KEY
+    }
+
+    /// Loads the configuration and makes it available through [`get`].
+    pub fn init() -> Result<&'static Settings, SettingsError> {
+        let settings = Settings::load()?;
+        Ok(SETTINGS.get_or_init(|| settings))
+    }
+}
+
+/// The configuration loaded by [`Settings::init`] at startup.
+pub fn get() -> &'static Settings {
+    SETTINGS
+        .get()
+        .expect("Settings::init must be called before settings are used")
+}
@@ -0,0 +1,43 @@
+use super::Worker;
+use crate::{
+    data::models::PgPool, errors::ServiceError,
+    operators::billing_operator::prune_billing_events_query,
+};
+use actix_web::web;
+use futures_util::{future::LocalBoxFuture, FutureExt};
+use std::time::Duration;
+
+/// Applied billing events are kept this long to recognize redeliveries.
+const BILLING_EVENT_RETENTION_DAYS: i64 = 30;
+
+/// Prunes the ids of billing events that can no longer be redelivered.
+pub struct BillingEventsWorker {
+    pub pg_pool: web::Data<PgPool>,
+}
+
+impl Worker for BillingEventsWorker {
+    fn name(&self) -> &'static str {
+        "billing_events"
+    }
+
+    fn interval(&self) -> Duration {
+        Duration::from_secs(60 * 60)
+    }
+
+    fn exclusive(&self) -> bool {
+        true
+    }
+
+    fn run_once(&self) -> LocalBoxFuture<'_, Result<bool, ServiceError>> {
+        async move {
+            let before = chrono::Utc::now().naive_local()
+                - chrono::Duration::days(BILLING_EVENT_RETENTION_DAYS);
+            let pruned = prune_billing_events_query(before, self.pg_pool.clone()).await?;
+            if pruned > 0 {
+                log::info!("Pruned {} billing events", pruned);
+            }
+            Ok(false)
+        }
+        .boxed_local()
+    }
+}
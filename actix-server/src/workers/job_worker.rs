@@ -0,0 +1,102 @@
+use super::Worker;
+use crate::{
+    data::models::{Job, JobPayload, JobStatus, PgPool},
+    errors::ServiceError,
+    operators::{
+        invitation_operator::send_invitation,
+        job_operator::{claim_job_query, complete_job_query, fail_job_query, prune_jobs_query},
+    },
+};
+use actix_web::web;
+use futures_util::{future::LocalBoxFuture, FutureExt};
+use std::time::Duration;
+
+/// How long a claimed job is reserved for its worker. A job still running after that
+/// is handed to another worker, so it has to be well above the slowest job.
+const JOB_LEASE_MINUTES: i64 = 10;
+/// Succeeded jobs are kept this long for inspection.
+const JOB_RETENTION_DAYS: i64 = 7;
+
+async fn run_job(job: &Job, pg_pool: web::Data<PgPool>) -> Result<(), ServiceError> {
+    let payload: JobPayload = serde_json::from_value(job.payload.clone()).map_err(|e| {
+        ServiceError::InternalServerError(format!("Unknown {} job payload: {}", job.kind, e))
+    })?;
+
+    match payload {
+        JobPayload::SendInvitation(send) => send_invitation(send, pg_pool).await,
+    }
+}
+
+/// Runs queued jobs one at a time. Every instance runs it, rows are claimed with
+/// `FOR UPDATE SKIP LOCKED` so each job goes to one worker.
+pub struct JobWorker {
+    pub pg_pool: web::Data<PgPool>,
+}
+
+impl Worker for JobWorker {
+    fn name(&self) -> &'static str {
+        "jobs"
+    }
+
+    fn interval(&self) -> Duration {
+        Duration::from_secs(5)
+    }
+
+    fn run_once(&self) -> LocalBoxFuture<'_, Result<bool, ServiceError>> {
+        async move {
+            let lease = chrono::Duration::minutes(JOB_LEASE_MINUTES);
+            let Some(job) = claim_job_query(lease, self.pg_pool.clone()).await? else {
+                return Ok(false);
+            };
+
+            // A failing job is retried on its own schedule, the worker itself only
+            // fails when the queue can't be reached
+            match run_job(&job, self.pg_pool.clone()).await {
+                Ok(()) => complete_job_query(&job, self.pg_pool.clone()).await?,
+                Err(e) => {
+                    let status = fail_job_query(&job, e.to_string(), self.pg_pool.clone()).await?;
+                    if status == JobStatus::Dead {
+                        log::error!("Job {} ({}) failed for good: {}", job.id, job.kind, e);
+                    } else {
+                        log::warn!("Job {} ({}) failed, will retry: {}", job.id, job.kind, e);
+                    }
+                }
+            }
+
+            Ok(true)
+        }
+        .boxed_local()
+    }
+}
+
+/// Deletes succeeded jobs once they're past retention.
+pub struct JobRetentionWorker {
+    pub pg_pool: web::Data<PgPool>,
+}
+
+impl Worker for JobRetentionWorker {
+    fn name(&self) -> &'static str {
+        "job_retention"
+    }
+
+    fn interval(&self) -> Duration {
+        Duration::from_secs(60 * 60)
+    }
+
+    fn exclusive(&self) -> bool {
+        true
+    }
+
+    fn run_once(&self) -> LocalBoxFuture<'_, Result<bool, ServiceError>> {
+        async move {
+            let before =
+                chrono::Utc::now().naive_local() - chrono::Duration::days(JOB_RETENTION_DAYS);
+            let pruned = prune_jobs_query(before, self.pg_pool.clone()).await?;
+            if pruned > 0 {
+                log::info!("Pruned {} succeeded jobs", pruned);
+            }
+            Ok(false)
+        }
+        .boxed_local()
+    }
+}
@@ -0,0 +1,293 @@
+//! Background work that runs next to the HTTP server. Every worker is polled in its own
+//! task until shutdown, when the runtime stops starting new runs and waits for the ones
+//! in flight to finish. Several server instances may run the same workers at once:
+//! workers that must not overlap across instances are marked `exclusive` and
+//! coordinate through a Postgres advisory lock, queue consumers claim their rows with
+//! `FOR UPDATE SKIP LOCKED` instead.
+use crate::{data::models::PgPool, errors::ServiceError};
+use actix_web::{rt::task::JoinHandle, web};
+use diesel::sql_types::{BigInt, Bool};
+use diesel_async::RunQueryDsl;
+use futures_util::future::LocalBoxFuture;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+use tokio::sync::watch;
+use utoipa::ToSchema;
+
+pub mod billing_events_worker;
+pub mod job_worker;
+
+/// Longest pause after repeated failures, however short the worker's interval.
+const MAX_BACKOFF: Duration = Duration::from_secs(5 * 60);
+
+pub trait Worker: 'static {
+    /// Identifies the worker in logs and health reports. Also names its advisory lock,
+    /// so it has to be the same on every instance.
+    fn name(&self) -> &'static str;
+
+    /// Pause between runs when the last one found nothing left to do.
+    fn interval(&self) -> Duration;
+
+    /// Whether at most one instance may run the worker at a time.
+    fn exclusive(&self) -> bool {
+        false
+    }
+
+    /// Does one batch of work and returns whether more may be waiting, in which case
+    /// the next run starts right away. Runs are never interrupted, so a batch should
+    /// finish well within the shutdown timeout.
+    fn run_once(&self) -> LocalBoxFuture<'_, Result<bool, ServiceError>>;
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WorkerState {
+    /// Waiting for the next run.
+    Idle,
+    Running,
+    /// Another instance holds the lock of this exclusive worker.
+    Standby,
+    Stopped,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, Clone)]
+#[schema(example = json!({
+    "name": "billing_events",
+    "state": "idle",
+    "last_run_at": "2021-01-01T00:00:00",
+    "last_success_at": "2021-01-01T00:00:00",
+    "consecutive_failures": 0,
+}))]
+pub struct WorkerHealth {
+    pub name: String,
+    pub state: WorkerState,
+    pub last_run_at: Option<chrono::NaiveDateTime>,
+    pub last_success_at: Option<chrono::NaiveDateTime>,
+    /// Error of the last run, cleared once a run succeeds.
+    pub last_error: Option<String>,
+    pub consecutive_failures: u32,
+}
+
+struct StatusInner {
+    health: RwLock<BTreeMap<&'static str, WorkerHealth>>,
+    shutdown: watch::Sender<bool>,
+}
+
+/// Shared view of the workers, cheap to clone. Handlers read worker health from it and
+/// the signal handler stops the workers through it.
+#[derive(Clone)]
+pub struct WorkerStatus {
+    inner: Arc<StatusInner>,
+}
+
+impl WorkerStatus {
+    pub fn health(&self) -> Vec<WorkerHealth> {
+        self.inner
+            .health
+            .read()
+            .map(|health| health.values().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    pub fn is_shutting_down(&self) -> bool {
+        *self.inner.shutdown.borrow()
+    }
+
+    /// Lets every worker finish its current run and stop. Wait for them with
+    /// [`WorkerRuntime::wait`].
+    pub fn shut_down(&self) {
+        self.inner.shutdown.send_replace(true);
+    }
+
+    fn update(&self, name: &'static str, update: impl FnOnce(&mut WorkerHealth)) {
+        if let Ok(mut health) = self.inner.health.write() {
+            if let Some(worker) = health.get_mut(name) {
+                update(worker);
+            }
+        }
+    }
+}
+
+#[derive(QueryableByName)]
+struct AdvisoryLock {
+    #[diesel(sql_type = Bool)]
+    locked: bool,
+}
+
+/// Advisory lock keys are 64-bit integers, derived from the worker name so every
+/// instance agrees on them.
+fn advisory_lock_key(name: &str) -> i64 {
+    let hash = blake3::hash(format!("worker:{}", name).as_bytes());
+    let mut key = [0; 8];
+    key.copy_from_slice(&hash.as_bytes()[..8]);
+    i64::from_le_bytes(key)
+}
+
+/// Runs the worker only if no other instance is running it. Returns `Ok(None)` when
+/// another instance holds the lock.
+async fn run_exclusive(
+    worker: &dyn Worker,
+    pg_pool: &PgPool,
+) -> Result<Option<bool>, ServiceError> {
+    let key = advisory_lock_key(worker.name());
+    let mut conn = pg_pool.get().await?;
+
+    let lock = diesel::sql_query("SELECT pg_try_advisory_lock($1) AS locked")
+        .bind::<BigInt, _>(key)
+        .get_result::<AdvisoryLock>(&mut conn)
+        .await
+        .map_err(|_| ServiceError::InternalServerError("Error taking worker lock".to_string()))?;
+    if !lock.locked {
+        return Ok(None);
+    }
+
+    let result = worker.run_once().await;
+
+    // The lock belongs to the session, so a connection that couldn't release it must
+    // not go back to the pool. Closing it releases the lock.
+    if let Err(e) = diesel::sql_query("SELECT pg_advisory_unlock($1) AS locked")
+        .bind::<BigInt, _>(key)
+        .get_result::<AdvisoryLock>(&mut conn)
+        .await
+    {
+        log::error!("Error releasing lock of worker {}: {:?}", worker.name(), e);
+        drop(deadpool::managed::Object::take(conn));
+    }
+
+    result.map(Some)
+}
+
+async fn run_worker(worker: Box<dyn Worker>, pg_pool: web::Data<PgPool>, status: WorkerStatus) {
+    let name = worker.name();
+    let mut shutdown = status.inner.shutdown.subscribe();
+
+    while !*shutdown.borrow() {
+        status.update(name, |health| health.state = WorkerState::Running);
+        let started_at = chrono::Utc::now().naive_utc();
+
+        let result = if worker.exclusive() {
+            run_exclusive(worker.as_ref(), &pg_pool).await
+        } else {
+            worker.run_once().await.map(Some)
+        };
+
+        let mut failures = 0;
+        status.update(name, |health| {
+            match &result {
+                Ok(Some(_)) => {
+                    health.state = WorkerState::Idle;
+                    health.last_run_at = Some(started_at);
+                    health.last_success_at = Some(started_at);
+                    health.last_error = None;
+                    health.consecutive_failures = 0;
+                }
+                Ok(None) => health.state = WorkerState::Standby,
+                Err(e) => {
+                    health.state = WorkerState::Idle;
+                    health.last_run_at = Some(started_at);
+                    health.last_error = Some(e.to_string());
+                    health.consecutive_failures += 1;
+                }
+            }
+            failures = health.consecutive_failures;
+        });
+
+        let pause = match result {
+            Ok(Some(true)) => continue,
+            Ok(Some(false)) | Ok(None) => worker.interval(),
+            Err(e) => {
+                log::error!("Worker {} failed: {:?}", name, e);
+                worker
+                    .interval()
+                    .saturating_mul(2u32.saturating_pow(failures.min(16)))
+                    .min(MAX_BACKOFF.max(worker.interval()))
+            }
+        };
+
+        // Wakes up early when shutdown starts
+        let _ = actix_web::rt::time::timeout(pause, shutdown.changed()).await;
+    }
+
+    status.update(name, |health| health.state = WorkerState::Stopped);
+}
+
+/// Starts the registered workers and waits for them on shutdown.
+pub struct WorkerRuntime {
+    pg_pool: web::Data<PgPool>,
+    status: WorkerStatus,
+    handles: Vec<(&'static str, JoinHandle<()>)>,
+}
+
+impl WorkerRuntime {
+    pub fn new(pg_pool: web::Data<PgPool>) -> Self {
+        let (shutdown, _) = watch::channel(false);
+        WorkerRuntime {
+            pg_pool,
+            status: WorkerStatus {
+                inner: Arc::new(StatusInner {
+                    health: RwLock::new(BTreeMap::new()),
+                    shutdown,
+                }),
+            },
+            handles: vec![],
+        }
+    }
+
+    pub fn status(&self) -> WorkerStatus {
+        self.status.clone()
+    }
+
+    /// Starts polling the worker on the current thread's runtime.
+    pub fn spawn(&mut self, worker: impl Worker) {
+        let name = worker.name();
+        if let Ok(mut health) = self.status.inner.health.write() {
+            health.insert(
+                name,
+                WorkerHealth {
+                    name: name.to_string(),
+                    state: WorkerState::Idle,
+                    last_run_at: None,
+                    last_success_at: None,
+                    last_error: None,
+                    consecutive_failures: 0,
+                },
+            );
+        }
+
+        let handle = actix_web::rt::spawn(run_worker(
+            Box::new(worker),
+            self.pg_pool.clone(),
+            self.status.clone(),
+        ));
+        self.handles.push((name, handle));
+    }
+
+    /// Waits up to `deadline` for the workers to stop after [`WorkerStatus::shut_down`].
+    /// Runs still going after that are abandoned when the process exits.
+    pub async fn wait(self, deadline: Duration) {
+        let (names, handles): (Vec<_>, Vec<_>) = self.handles.into_iter().unzip();
+        if actix_web::rt::time::timeout(deadline, futures::future::join_all(handles))
+            .await
+            .is_err()
+        {
+            let running = self
+                .status
+                .health()
+                .into_iter()
+                .filter(|health| health.state != WorkerState::Stopped)
+                .map(|health| health.name)
+                .collect::<Vec<_>>();
+            log::warn!(
+                "Workers {} did not stop within {} seconds",
+                running.join(", "),
+                deadline.as_secs()
+            );
+        } else {
+            log::info!("Stopped workers {}", names.join(", "));
+        }
+    }
+}
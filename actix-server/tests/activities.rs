@@ -0,0 +1,191 @@
+mod common;
+
+use actix_server::{
+    data::models::{ActivityPayload, CallActivity, CallOutcome, Contact, PgPool},
+    errors::ServiceError,
+    operators::{
+        activity_operator::{create_activity_query, delete_activity_query, update_activity_query},
+        contact_operator::create_contact_query,
+    },
+    prefixes::{ContactPrefix, DealPrefix, OrgPrefix, PrefixedUuid},
+};
+use actix_web::web;
+use diesel::QueryDsl;
+use diesel_async::RunQueryDsl;
+
+fn call() -> ActivityPayload {
+    ActivityPayload::Call(CallActivity {
+        duration_seconds: 60,
+        outcome: CallOutcome::Connected,
+    })
+}
+
+fn at(hour: u32) -> chrono::NaiveDateTime {
+    chrono::NaiveDate::from_ymd_opt(2024, 6, 1)
+        .unwrap()
+        .and_hms_opt(hour, 0, 0)
+        .unwrap()
+}
+
+async fn create_contact(org_id: PrefixedUuid<OrgPrefix>, pg_pool: &web::Data<PgPool>) -> Contact {
+    create_contact_query(
+        org_id,
+        "Often".to_string(),
+        "Called".to_string(),
+        None,
+        pg_pool.clone(),
+    )
+    .await
+    .unwrap()
+}
+
+async fn get_contact(
+    contact_id: PrefixedUuid<ContactPrefix>,
+    pg_pool: &web::Data<PgPool>,
+) -> Contact {
+    use actix_server::data::schema::contacts::dsl as contacts_columns;
+
+    let mut conn = pg_pool.get().await.unwrap();
+    contacts_columns::contacts
+        .find(contact_id)
+        .first::<Contact>(&mut conn)
+        .await
+        .unwrap()
+}
+
+fn invalid_field(result: Result<impl Sized, ServiceError>) -> Option<String> {
+    match result {
+        Err(ServiceError::ValidationFailed(errors)) => Some(errors[0].field.clone()),
+        _ => None,
+    }
+}
+
+#[actix_web::test]
+#[ignore = "needs a scratch Postgres database in DATABASE_URL"]
+async fn activity_links_must_be_in_org() {
+    let pg_pool = common::pg_pool();
+    let org = common::create_org(&pg_pool).await;
+    let other_org = common::create_org(&pg_pool).await;
+    let foreign_contact = create_contact_query(
+        other_org.id,
+        "Foreign".to_string(),
+        "Contact".to_string(),
+        None,
+        pg_pool.clone(),
+    )
+    .await
+    .unwrap();
+
+    let created = create_activity_query(
+        org.id,
+        call(),
+        Some(foreign_contact.id),
+        None,
+        None,
+        None,
+        pg_pool.clone(),
+    )
+    .await;
+    assert_eq!(invalid_field(created).as_deref(), Some("contact_id"));
+
+    let activity = create_activity_query(org.id, call(), None, None, None, None, pg_pool.clone())
+        .await
+        .unwrap();
+
+    let updated = update_activity_query(
+        activity.id,
+        org.id,
+        None,
+        None,
+        Some(PrefixedUuid::create(DealPrefix)),
+        None,
+        None,
+        None,
+        pg_pool.clone(),
+    )
+    .await;
+    assert_eq!(invalid_field(updated).as_deref(), Some("deal_id"));
+}
+
+#[actix_web::test]
+#[ignore = "needs a scratch Postgres database in DATABASE_URL"]
+async fn last_contacted_falls_back_when_activities_are_deleted() {
+    let pg_pool = common::pg_pool();
+    let org = common::create_org(&pg_pool).await;
+    let contact = create_contact(org.id, &pg_pool).await;
+
+    let mut activities = vec![];
+    for hour in [9, 11] {
+        let activity = create_activity_query(
+            org.id,
+            call(),
+            Some(contact.id),
+            None,
+            None,
+            Some(at(hour)),
+            pg_pool.clone(),
+        )
+        .await
+        .unwrap();
+        activities.push(activity);
+    }
+
+    let touched = get_contact(contact.id, &pg_pool).await;
+    assert_eq!(touched.last_contacted_at, Some(at(11)));
+    // Logging activities doesn't invalidate the contact's ETag
+    assert_eq!(touched.updated_at, contact.updated_at);
+
+    for (deleted, last_contacted_at) in [(1, Some(at(9))), (0, None)] {
+        delete_activity_query(activities[deleted].id, org.id, None, pg_pool.clone())
+            .await
+            .unwrap();
+        assert_eq!(
+            get_contact(contact.id, &pg_pool).await.last_contacted_at,
+            last_contacted_at
+        );
+    }
+}
+
+#[actix_web::test]
+#[ignore = "needs a scratch Postgres database in DATABASE_URL"]
+async fn relinked_activity_moves_last_contacted() {
+    let pg_pool = common::pg_pool();
+    let org = common::create_org(&pg_pool).await;
+    let first = create_contact(org.id, &pg_pool).await;
+    let second = create_contact(org.id, &pg_pool).await;
+
+    let activity = create_activity_query(
+        org.id,
+        call(),
+        Some(first.id),
+        None,
+        None,
+        Some(at(9)),
+        pg_pool.clone(),
+    )
+    .await
+    .unwrap();
+
+    update_activity_query(
+        activity.id,
+        org.id,
+        None,
+        Some(second.id),
+        None,
+        None,
+        None,
+        None,
+        pg_pool.clone(),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        get_contact(first.id, &pg_pool).await.last_contacted_at,
+        None
+    );
+    assert_eq!(
+        get_contact(second.id, &pg_pool).await.last_contacted_at,
+        Some(at(9))
+    );
+}
@@ -0,0 +1,257 @@
+mod common;
+
+use actix_server::{
+    data::models::{PgPool, Subscription},
+    operators::billing_operator::{
+        handle_billing_event_query, verify_stripe_signature, BillingEventOutcome, StripeEvent,
+    },
+    prefixes::{OrgPrefix, PrefixedUuid},
+};
+use actix_web::web;
+use diesel::{ExpressionMethods, OptionalExtension, QueryDsl};
+use diesel_async::RunQueryDsl;
+use openssl::{hash::MessageDigest, pkey::PKey, sign::Signer};
+
+const SECRET: &str = "whsec_test_secret";
+
+fn fixture(name: &str) -> String {
+    std::fs::read_to_string(format!(
+        "{}/tests/fixtures/stripe/{}.json",
+        env!("CARGO_MANIFEST_DIR"),
+        name
+    ))
+    .unwrap()
+}
+
+fn sign(payload: &[u8], timestamp: i64) -> String {
+    let key = PKey::hmac(SECRET.as_bytes()).unwrap();
+    let mut signer = Signer::new(MessageDigest::sha256(), &key).unwrap();
+    signer.update(format!("{}.", timestamp).as_bytes()).unwrap();
+    signer.update(payload).unwrap();
+    let signature = signer
+        .sign_to_vec()
+        .unwrap()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect::<String>();
+    format!("t={},v1={}", timestamp, signature)
+}
+
+/// Loads a recorded event and points it at `org_id`. The event and subscription ids
+/// get `run` appended so reruns against the same database don't collide.
+fn event(name: &str, run: &str, org_id: PrefixedUuid<OrgPrefix>) -> StripeEvent {
+    let mut event = serde_json::from_str::<serde_json::Value>(&fixture(name)).unwrap();
+    event["id"] = format!("{}_{}", event["id"].as_str().unwrap(), run).into();
+
+    let object = &mut event["data"]["object"];
+    let subscription_key = match object["object"].as_str().unwrap() {
+        "subscription" => {
+            object["metadata"]["org_id"] = org_id.to_string().into();
+            "id"
+        }
+        _ => "subscription",
+    };
+    object[subscription_key] =
+        format!("{}_{}", object[subscription_key].as_str().unwrap(), run).into();
+
+    serde_json::from_value(event).unwrap()
+}
+
+async fn get_subscription(
+    org_id: PrefixedUuid<OrgPrefix>,
+    pg_pool: &web::Data<PgPool>,
+) -> Option<Subscription> {
+    use actix_server::data::schema::subscriptions::dsl as subscriptions_columns;
+
+    let mut conn = pg_pool.get().await.unwrap();
+    subscriptions_columns::subscriptions
+        .filter(subscriptions_columns::org_id.eq(org_id))
+        .first::<Subscription>(&mut conn)
+        .await
+        .optional()
+        .unwrap()
+}
+
+#[test]
+fn accepts_valid_signature() {
+    let payload = fixture("customer.subscription.created");
+    let now = 1718548021;
+
+    assert!(verify_stripe_signature(
+        payload.as_bytes(),
+        &sign(payload.as_bytes(), now),
+        SECRET,
+        now
+    )
+    .is_ok());
+}
+
+#[test]
+fn rejects_tampered_body() {
+    let payload = fixture("customer.subscription.created");
+    let now = 1718548021;
+    let signature = sign(payload.as_bytes(), now);
+    let tampered = payload.replace("\"unit_amount\": 4900", "\"unit_amount\": 1");
+
+    assert_ne!(payload, tampered);
+    assert!(verify_stripe_signature(tampered.as_bytes(), &signature, SECRET, now).is_err());
+}
+
+#[test]
+fn rejects_expired_timestamp() {
+    let payload = fixture("customer.subscription.created");
+    let signed_at = 1718548021;
+    let signature = sign(payload.as_bytes(), signed_at);
+
+    assert!(
+        verify_stripe_signature(payload.as_bytes(), &signature, SECRET, signed_at + 301).is_err()
+    );
+}
+
+#[actix_web::test]
+#[ignore = "needs a scratch Postgres database in DATABASE_URL"]
+async fn applies_subscription_lifecycle() {
+    let pg_pool = common::pg_pool();
+    let org = common::create_org(&pg_pool).await;
+    let run = uuid::Uuid::now_v7().to_string();
+
+    for (name, status) in [
+        ("customer.subscription.created", "active"),
+        ("customer.subscription.updated", "active"),
+        ("invoice.payment_failed", "past_due"),
+        ("customer.subscription.deleted", "canceled"),
+    ] {
+        let outcome = handle_billing_event_query(event(name, &run, org.id), pg_pool.clone())
+            .await
+            .unwrap();
+        assert_eq!(outcome, BillingEventOutcome::Applied, "{}", name);
+
+        let subscription = get_subscription(org.id, &pg_pool).await.unwrap();
+        assert_eq!(subscription.status, status, "{}", name);
+        if name == "customer.subscription.updated" {
+            assert_eq!(
+                subscription.stripe_plan_id,
+                "price_1PUc1aHbX3sYq9vBtN6yWe0J"
+            );
+        }
+    }
+
+    let subscription = get_subscription(org.id, &pg_pool).await.unwrap();
+    assert!(subscription.end_date.is_some());
+}
+
+#[actix_web::test]
+#[ignore = "needs a scratch Postgres database in DATABASE_URL"]
+async fn replayed_event_changes_nothing() {
+    let pg_pool = common::pg_pool();
+    let org = common::create_org(&pg_pool).await;
+    let run = uuid::Uuid::now_v7().to_string();
+
+    for name in ["customer.subscription.created", "invoice.payment_failed"] {
+        handle_billing_event_query(event(name, &run, org.id), pg_pool.clone())
+            .await
+            .unwrap();
+    }
+
+    // Applying the creation again would flip the subscription back to active
+    let outcome = handle_billing_event_query(
+        event("customer.subscription.created", &run, org.id),
+        pg_pool.clone(),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(outcome, BillingEventOutcome::AlreadyApplied);
+    assert_eq!(
+        get_subscription(org.id, &pg_pool).await.unwrap().status,
+        "past_due"
+    );
+}
+
+#[actix_web::test]
+#[ignore = "needs a scratch Postgres database in DATABASE_URL"]
+async fn ignores_unknown_org() {
+    let pg_pool = common::pg_pool();
+    let org_id = PrefixedUuid::create(OrgPrefix);
+    let run = uuid::Uuid::now_v7().to_string();
+
+    let outcome = handle_billing_event_query(
+        event("customer.subscription.created", &run, org_id),
+        pg_pool.clone(),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(outcome, BillingEventOutcome::Ignored);
+    assert!(get_subscription(org_id, &pg_pool).await.is_none());
+}
+
+#[actix_web::test]
+#[ignore = "needs a scratch Postgres database in DATABASE_URL"]
+async fn skips_events_older_than_the_last_applied() {
+    let pg_pool = common::pg_pool();
+    let org = common::create_org(&pg_pool).await;
+    let run = uuid::Uuid::now_v7().to_string();
+
+    for (name, outcome) in [
+        (
+            "customer.subscription.updated",
+            BillingEventOutcome::Applied,
+        ),
+        (
+            "customer.subscription.created",
+            BillingEventOutcome::Outdated,
+        ),
+        (
+            "customer.subscription.deleted",
+            BillingEventOutcome::Applied,
+        ),
+        ("invoice.payment_failed", BillingEventOutcome::Outdated),
+    ] {
+        assert_eq!(
+            handle_billing_event_query(event(name, &run, org.id), pg_pool.clone())
+                .await
+                .unwrap(),
+            outcome,
+            "{}",
+            name
+        );
+    }
+
+    let subscription = get_subscription(org.id, &pg_pool).await.unwrap();
+    assert_eq!(subscription.status, "canceled");
+    assert_eq!(
+        subscription.stripe_plan_id,
+        "price_1PUc1aHbX3sYq9vBtN6yWe0J"
+    );
+}
+
+#[actix_web::test]
+#[ignore = "needs a scratch Postgres database in DATABASE_URL"]
+async fn canceled_subscription_stays_canceled() {
+    let pg_pool = common::pg_pool();
+    let org = common::create_org(&pg_pool).await;
+    let run = uuid::Uuid::now_v7().to_string();
+
+    for name in [
+        "customer.subscription.created",
+        "customer.subscription.deleted",
+    ] {
+        handle_billing_event_query(event(name, &run, org.id), pg_pool.clone())
+            .await
+            .unwrap();
+    }
+
+    // Sent a minute after the deletion
+    let mut update = event("customer.subscription.updated", &run, org.id);
+    update.created = 1720135877 + 60;
+    let outcome = handle_billing_event_query(update, pg_pool.clone())
+        .await
+        .unwrap();
+
+    assert_eq!(outcome, BillingEventOutcome::Outdated);
+    assert_eq!(
+        get_subscription(org.id, &pg_pool).await.unwrap().status,
+        "canceled"
+    );
+}
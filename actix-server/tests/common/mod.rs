@@ -0,0 +1,63 @@
+//! Helpers for tests that run against a scratch Postgres database named by
+//! `DATABASE_URL`. Migrations are applied once per test binary; every test creates
+//! its own org, so tests don't see each other's rows and can be rerun on the same
+//! database.
+#![allow(dead_code)]
+
+use actix_server::data::models::{Org, PgPool, User};
+use actix_web::web;
+use diesel_async::{pooled_connection::AsyncDieselConnectionManager, RunQueryDsl};
+use std::sync::Once;
+
+static MIGRATE: Once = Once::new();
+
+fn database_url() -> String {
+    std::env::var("DATABASE_URL").expect("DATABASE_URL must point at a scratch database")
+}
+
+pub fn pg_pool() -> web::Data<PgPool> {
+    let url = database_url();
+
+    MIGRATE.call_once(|| {
+        use diesel::Connection;
+        use diesel_migrations::MigrationHarness;
+
+        let mut conn =
+            diesel::pg::PgConnection::establish(&url).expect("Failed to connect to database");
+        conn.run_pending_migrations(actix_server::MIGRATIONS)
+            .expect("Failed to run migrations");
+    });
+
+    let mgr = AsyncDieselConnectionManager::<diesel_async::AsyncPgConnection>::new(url);
+    let pool = diesel_async::pooled_connection::deadpool::Pool::builder(mgr)
+        .build()
+        .expect("Failed to create pool");
+
+    web::Data::new(pool)
+}
+
+pub async fn create_org(pg_pool: &web::Data<PgPool>) -> Org {
+    use actix_server::data::schema::orgs::dsl as orgs_columns;
+
+    let mut conn = pg_pool.get().await.unwrap();
+    diesel::insert_into(orgs_columns::orgs)
+        .values(&Org::from_details(
+            "Test org".to_string(),
+            "USD".to_string(),
+        ))
+        .get_result::<Org>(&mut conn)
+        .await
+        .unwrap()
+}
+
+pub async fn create_user(pg_pool: &web::Data<PgPool>) -> User {
+    use actix_server::data::schema::users::dsl as users_columns;
+
+    let email = format!("{}@example.com", uuid::Uuid::now_v7());
+    let mut conn = pg_pool.get().await.unwrap();
+    diesel::insert_into(users_columns::users)
+        .values(&User::from_details(email, None))
+        .get_result::<User>(&mut conn)
+        .await
+        .unwrap()
+}
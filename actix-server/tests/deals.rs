@@ -0,0 +1,251 @@
+mod common;
+
+use actix_server::{
+    data::models::{Deal, PgPool},
+    errors::ServiceError,
+    operators::{
+        contact_operator::create_contact_query,
+        deal_operator::{create_contact_for_deal_query, create_deal_query, update_deal_query},
+    },
+    prefixes::{DealPrefix, PrefixedUuid},
+};
+use actix_web::web;
+
+async fn set_won(
+    deal_id: PrefixedUuid<DealPrefix>,
+    won: Option<bool>,
+    pg_pool: &web::Data<PgPool>,
+) -> Deal {
+    update_deal_query(
+        deal_id,
+        None,
+        None,
+        None,
+        None,
+        None,
+        Some(won),
+        None,
+        None,
+        None,
+        pg_pool.clone(),
+    )
+    .await
+    .unwrap()
+}
+
+#[actix_web::test]
+#[ignore = "needs a scratch Postgres database in DATABASE_URL"]
+async fn closed_at_only_moves_when_outcome_changes() {
+    let pg_pool = common::pg_pool();
+    let org = common::create_org(&pg_pool).await;
+    let mut conn = pg_pool.get().await.unwrap();
+    let deal = create_deal_query(
+        org.id,
+        Some("Closing".to_string()),
+        None,
+        None,
+        None,
+        None,
+        true,
+        None,
+        vec![],
+        &mut conn,
+    )
+    .await
+    .unwrap();
+
+    let won = set_won(deal.id, Some(true), &pg_pool).await;
+    let closed_at = won
+        .closed_at
+        .expect("closed_at is set when the deal is won");
+
+    let resent = set_won(deal.id, Some(true), &pg_pool).await;
+    assert_eq!(resent.closed_at, Some(closed_at));
+
+    let lost = set_won(deal.id, Some(false), &pg_pool).await;
+    assert!(lost.closed_at.is_some_and(|lost_at| lost_at > closed_at));
+
+    let reopened = set_won(deal.id, None, &pg_pool).await;
+    assert_eq!(reopened.closed_at, None);
+}
+
+#[actix_web::test]
+#[ignore = "needs a scratch Postgres database in DATABASE_URL"]
+async fn duplicate_link_is_a_conflict_without_details() {
+    let pg_pool = common::pg_pool();
+    let org = common::create_org(&pg_pool).await;
+    let contact = create_contact_query(
+        org.id,
+        "Linked".to_string(),
+        "Twice".to_string(),
+        None,
+        pg_pool.clone(),
+    )
+    .await
+    .unwrap();
+    let mut conn = pg_pool.get().await.unwrap();
+    let deal = create_deal_query(
+        org.id,
+        None,
+        None,
+        None,
+        None,
+        None,
+        true,
+        None,
+        vec![contact.id],
+        &mut conn,
+    )
+    .await
+    .unwrap();
+
+    let result = create_contact_for_deal_query(deal.id, org.id, contact.id, pg_pool.clone()).await;
+
+    match result {
+        Err(ServiceError::Conflict(message)) => {
+            assert!(!message.contains(&contact.id.id.to_string()));
+        }
+        other => panic!("expected a conflict, got {:?}", other.map(|_| ())),
+    }
+}
+
+#[actix_web::test]
+#[ignore = "needs a scratch Postgres database in DATABASE_URL"]
+async fn links_stay_within_the_org() {
+    let pg_pool = common::pg_pool();
+    let org = common::create_org(&pg_pool).await;
+    let other_org = common::create_org(&pg_pool).await;
+    let foreign_contact = create_contact_query(
+        other_org.id,
+        "Foreign".to_string(),
+        "Contact".to_string(),
+        None,
+        pg_pool.clone(),
+    )
+    .await
+    .unwrap();
+    let mut conn = pg_pool.get().await.unwrap();
+    let deal = create_deal_query(
+        org.id,
+        None,
+        None,
+        None,
+        None,
+        None,
+        true,
+        None,
+        vec![],
+        &mut conn,
+    )
+    .await
+    .unwrap();
+
+    let foreign_link =
+        create_contact_for_deal_query(deal.id, org.id, foreign_contact.id, pg_pool.clone()).await;
+    assert!(
+        matches!(foreign_link, Err(ServiceError::ValidationFailed(errors)) if errors[0].field == "contact_ids")
+    );
+
+    let foreign_deal =
+        create_contact_for_deal_query(deal.id, other_org.id, foreign_contact.id, pg_pool.clone())
+            .await;
+    assert!(matches!(foreign_deal, Err(ServiceError::NotFound)));
+}
+
+#[actix_web::test]
+#[ignore = "needs a scratch Postgres database in DATABASE_URL"]
+async fn check_violations_name_the_field() {
+    let pg_pool = common::pg_pool();
+    let org = common::create_org(&pg_pool).await;
+    let mut conn = pg_pool.get().await.unwrap();
+
+    for (currency, probability, field) in [
+        (Some("usd"), None, "currency"),
+        (None, Some(150), "probability"),
+    ] {
+        let result = create_deal_query(
+            org.id,
+            None,
+            None,
+            currency.map(|currency| currency.to_string()),
+            probability,
+            None,
+            true,
+            None,
+            vec![],
+            &mut conn,
+        )
+        .await;
+
+        match result {
+            Err(ServiceError::ValidationFailed(errors)) => assert_eq!(errors[0].field, field),
+            other => panic!("expected an invalid {}, got {:?}", field, other.map(|_| ())),
+        }
+    }
+}
+
+async fn set_currency(
+    deal_id: PrefixedUuid<DealPrefix>,
+    amount: Option<Option<i64>>,
+    currency: &str,
+    pg_pool: &web::Data<PgPool>,
+) -> Result<Deal, ServiceError> {
+    update_deal_query(
+        deal_id,
+        None,
+        amount,
+        Some(currency.to_string()),
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        pg_pool.clone(),
+    )
+    .await
+}
+
+#[actix_web::test]
+#[ignore = "needs a scratch Postgres database in DATABASE_URL"]
+async fn currency_change_needs_an_amount() {
+    let pg_pool = common::pg_pool();
+    let org = common::create_org(&pg_pool).await;
+    let mut conn = pg_pool.get().await.unwrap();
+    let deal = create_deal_query(
+        org.id,
+        Some("Abroad".to_string()),
+        Some(1000),
+        Some("USD".to_string()),
+        None,
+        None,
+        true,
+        None,
+        vec![],
+        &mut conn,
+    )
+    .await
+    .unwrap();
+
+    match set_currency(deal.id, None, "JPY", &pg_pool).await {
+        Err(ServiceError::ValidationFailed(errors)) => assert_eq!(errors[0].field, "amount"),
+        other => panic!("expected an invalid amount, got {:?}", other.map(|_| ())),
+    }
+
+    let same = set_currency(deal.id, None, "USD", &pg_pool).await.unwrap();
+    assert_eq!(same.amount, Some(1000));
+
+    let converted = set_currency(deal.id, Some(Some(1500)), "JPY", &pg_pool)
+        .await
+        .unwrap();
+    assert_eq!(
+        (converted.amount, converted.currency.as_str()),
+        (Some(1500), "JPY")
+    );
+
+    set_currency(deal.id, Some(None), "EUR", &pg_pool)
+        .await
+        .unwrap();
+    let unpriced = set_currency(deal.id, None, "GBP", &pg_pool).await.unwrap();
+    assert_eq!(unpriced.currency, "GBP");
+}
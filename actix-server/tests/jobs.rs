@@ -0,0 +1,81 @@
+mod common;
+
+use actix_server::{
+    data::models::{Invitation, Job, JobStatus, UserRole},
+    operators::invitation_operator::insert_invitation,
+    settings::Settings,
+    workers::{job_worker::JobWorker, Worker},
+};
+use diesel::{PgJsonbExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+
+/// Enough configuration to send email through a relay that isn't there.
+fn init_settings() {
+    for (var, value) in [
+        ("REDIS_URL", "redis://127.0.0.1:1"),
+        ("BASE_SERVER_URL", "http://localhost:8090"),
+        ("OIDC_ISSUER_URL", "http://localhost:8080/realms/trieve"),
+        ("OIDC_CLIENT_ID", "test"),
+        ("OIDC_CLIENT_SECRET", "test"),
+        (
+            "OIDC_AUTH_REDIRECT_URL",
+            "http://localhost:8090/api/auth/callback",
+        ),
+        ("SMTP_RELAY", "127.0.0.1"),
+        ("SMTP_EMAIL_ADDRESS", "noreply@example.com"),
+        ("SMTP_USERNAME", "test"),
+        ("SMTP_PASSWORD", "test"),
+    ] {
+        std::env::set_var(var, value);
+    }
+    Settings::init().expect("Test settings are valid");
+}
+
+#[actix_web::test]
+#[ignore = "needs a scratch Postgres database in DATABASE_URL"]
+async fn bad_address_fails_the_job_not_the_worker() {
+    use actix_server::data::schema::jobs::dsl as jobs_columns;
+
+    let pg_pool = common::pg_pool();
+    init_settings();
+    let org = common::create_org(&pg_pool).await;
+    let mut conn = pg_pool.get().await.unwrap();
+
+    // Stored before the invitation check rejected addresses with trailing junk
+    let invitation = insert_invitation(
+        Invitation::from_details("a@b.c junk".to_string(), org.id, UserRole::User.into()),
+        "https://example.com/auth".to_string(),
+        &mut conn,
+    )
+    .await
+    .unwrap();
+    let job_id = jobs_columns::jobs
+        .filter(jobs_columns::payload.contains(serde_json::json!({
+            "SendInvitation": { "invitation_id": invitation.id },
+        })))
+        .select(jobs_columns::id)
+        .first::<uuid::Uuid>(&mut conn)
+        .await
+        .unwrap();
+
+    // Jobs left queued by other tests run first, they fail on the missing relay
+    let worker = JobWorker {
+        pg_pool: pg_pool.clone(),
+    };
+    let job = loop {
+        assert!(worker.run_once().await.unwrap(), "the job was never run");
+        let job = jobs_columns::jobs
+            .find(job_id)
+            .first::<Job>(&mut conn)
+            .await
+            .unwrap();
+        if job.attempts > 0 {
+            break job;
+        }
+    };
+
+    assert_eq!(job.status, JobStatus::Queued.as_str());
+    assert!(job
+        .last_error
+        .is_some_and(|e| e.contains("Invalid email address")));
+}
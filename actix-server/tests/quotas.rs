@@ -0,0 +1,52 @@
+mod common;
+
+use actix_server::{
+    data::models::{Invitation, UserRole},
+    errors::ServiceError,
+    operators::{
+        invitation_operator::insert_invitation, org_operator::add_user_to_org,
+        plan_operator::check_user_quota,
+    },
+};
+
+#[actix_web::test]
+#[ignore = "needs a scratch Postgres database in DATABASE_URL"]
+async fn invitations_for_members_are_not_counted() {
+    let pg_pool = common::pg_pool();
+    // On the free plan, which allows 3 members
+    let org = common::create_org(&pg_pool).await;
+    let mut conn = pg_pool.get().await.unwrap();
+
+    for _ in 0..2 {
+        let user = common::create_user(&pg_pool).await;
+        add_user_to_org(user.id, org.id, UserRole::User, &mut conn)
+            .await
+            .unwrap();
+        insert_invitation(
+            Invitation::from_details(user.email, org.id, UserRole::User.into()),
+            "https://example.com/auth".to_string(),
+            &mut conn,
+        )
+        .await
+        .unwrap();
+    }
+
+    check_user_quota(org.id, true, &mut conn).await.unwrap();
+
+    insert_invitation(
+        Invitation::from_details(
+            "not-a-member@example.com".to_string(),
+            org.id,
+            UserRole::User.into(),
+        ),
+        "https://example.com/auth".to_string(),
+        &mut conn,
+    )
+    .await
+    .unwrap();
+
+    assert!(matches!(
+        check_user_quota(org.id, true, &mut conn).await,
+        Err(ServiceError::PaymentRequired(_))
+    ));
+}
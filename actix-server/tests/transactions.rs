@@ -0,0 +1,168 @@
+mod common;
+
+use actix_server::{
+    data::models::{Invitation, PgPool, UserRole},
+    errors::ServiceError,
+    operators::{
+        contact_operator::create_contact_query,
+        deal_operator::create_deal_query,
+        invitation_operator::{check_inv_valid, get_invitation_by_id_query, insert_invitation},
+        org_operator::add_user_to_org,
+    },
+    prefixes::{ContactPrefix, OrgPrefix, PrefixedUuid},
+};
+use actix_web::web;
+use diesel::{ExpressionMethods, QueryDsl};
+use diesel_async::{scoped_futures::ScopedFutureExt, AsyncConnection, RunQueryDsl};
+
+async fn create_deal_with_contacts(
+    org_id: PrefixedUuid<OrgPrefix>,
+    contact_ids: Vec<PrefixedUuid<ContactPrefix>>,
+    pg_pool: &web::Data<PgPool>,
+) -> Result<(), ServiceError> {
+    let mut conn = pg_pool.get().await?;
+    conn.transaction::<_, ServiceError, _>(|conn| {
+        async move {
+            create_deal_query(
+                org_id,
+                Some("Rollback".to_string()),
+                None,
+                Some("USD".to_string()),
+                None,
+                None,
+                true,
+                None,
+                contact_ids,
+                conn,
+            )
+            .await
+        }
+        .scope_boxed()
+    })
+    .await?;
+    Ok(())
+}
+
+async fn count_deal_rows(
+    org_id: PrefixedUuid<OrgPrefix>,
+    contact_id: PrefixedUuid<ContactPrefix>,
+    pg_pool: &web::Data<PgPool>,
+) -> (i64, i64) {
+    use actix_server::data::schema::deal_contacts::dsl as deal_contacts_columns;
+    use actix_server::data::schema::deals::dsl as deals_columns;
+
+    let mut conn = pg_pool.get().await.unwrap();
+    let deals = deals_columns::deals
+        .filter(deals_columns::org_id.eq(org_id))
+        .count()
+        .get_result::<i64>(&mut conn)
+        .await
+        .unwrap();
+    let deal_contacts = deal_contacts_columns::deal_contacts
+        .filter(deal_contacts_columns::contact_id.eq(contact_id))
+        .count()
+        .get_result::<i64>(&mut conn)
+        .await
+        .unwrap();
+    (deals, deal_contacts)
+}
+
+#[actix_web::test]
+#[ignore = "needs a scratch Postgres database in DATABASE_URL"]
+async fn deal_with_foreign_contact_is_rolled_back() {
+    let pg_pool = common::pg_pool();
+    let org = common::create_org(&pg_pool).await;
+    let other_org = common::create_org(&pg_pool).await;
+    let contact = create_contact_query(
+        org.id,
+        "Own".to_string(),
+        "Contact".to_string(),
+        None,
+        pg_pool.clone(),
+    )
+    .await
+    .unwrap();
+    let foreign_contact = create_contact_query(
+        other_org.id,
+        "Foreign".to_string(),
+        "Contact".to_string(),
+        None,
+        pg_pool.clone(),
+    )
+    .await
+    .unwrap();
+
+    let result =
+        create_deal_with_contacts(org.id, vec![contact.id, foreign_contact.id], &pg_pool).await;
+
+    assert!(
+        matches!(result, Err(ServiceError::ValidationFailed(errors)) if errors[0].field == "contact_ids")
+    );
+    assert_eq!(count_deal_rows(org.id, contact.id, &pg_pool).await, (0, 0));
+}
+
+#[actix_web::test]
+#[ignore = "needs a scratch Postgres database in DATABASE_URL"]
+async fn deal_with_missing_contact_is_rolled_back() {
+    let pg_pool = common::pg_pool();
+    let org = common::create_org(&pg_pool).await;
+    let contact = create_contact_query(
+        org.id,
+        "Own".to_string(),
+        "Contact".to_string(),
+        None,
+        pg_pool.clone(),
+    )
+    .await
+    .unwrap();
+
+    let result = create_deal_with_contacts(
+        org.id,
+        vec![contact.id, PrefixedUuid::create(ContactPrefix)],
+        &pg_pool,
+    )
+    .await;
+
+    assert!(
+        matches!(result, Err(ServiceError::ValidationFailed(errors)) if errors[0].field == "contact_ids")
+    );
+    assert_eq!(count_deal_rows(org.id, contact.id, &pg_pool).await, (0, 0));
+}
+
+#[actix_web::test]
+#[ignore = "needs a scratch Postgres database in DATABASE_URL"]
+async fn failed_redeem_leaves_invitation_unused() {
+    let pg_pool = common::pg_pool();
+    let org = common::create_org(&pg_pool).await;
+    let user = common::create_user(&pg_pool).await;
+    let mut conn = pg_pool.get().await.unwrap();
+
+    // Already being a member makes adding the user fail after the invitation is marked used
+    add_user_to_org(user.id, org.id, UserRole::User, &mut conn)
+        .await
+        .unwrap();
+    let invitation = insert_invitation(
+        Invitation::from_details(user.email.clone(), org.id, UserRole::User.into()),
+        "https://example.com/auth".to_string(),
+        &mut conn,
+    )
+    .await
+    .unwrap();
+
+    let (inv_code, email) = (invitation.id, user.email.clone());
+    let result = conn
+        .transaction::<_, ServiceError, _>(|conn| {
+            async move {
+                let invitation = check_inv_valid(inv_code, email, Some(org.id), conn).await?;
+                add_user_to_org(user.id, org.id, invitation.role.into(), conn).await
+            }
+            .scope_boxed()
+        })
+        .await;
+
+    assert!(result.is_err());
+    let invitation = get_invitation_by_id_query(invitation.id, pg_pool.clone())
+        .await
+        .unwrap();
+    assert!(!invitation.used);
+}
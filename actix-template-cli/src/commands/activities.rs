@@ -0,0 +1,295 @@
+use super::configure::ActixTemplateConfiguration;
+use crate::{errors::DefaultError, ui::get_cancelable_render_config};
+use actix_web_starter_client::{
+    apis::activities_api::{
+        self, CreateActivityParams, CreateActivitySuccess, ListActivitiesParams,
+        ListActivitiesSuccess, UpdateActivityParams, UpdateActivitySuccess,
+    },
+    models::{
+        Activity, ActivityPayload, ActivityPayloadOneOf, ActivityPayloadOneOf1,
+        ActivityPayloadOneOf2, CallActivity, CallOutcome, CreateActivityReqPayload, EmailActivity,
+        EmailDirection, MeetingActivity, UpdateActivityReqPayload,
+    },
+};
+use clap::{Args, Subcommand};
+use tabled::settings::Style;
+
+#[derive(Subcommand)]
+pub enum ActivityCommands {
+    /// Log a call, meeting or email
+    Log(LogActivity),
+    /// List logged activities
+    List(ListActivities),
+    Edit(EditActivity),
+    Delete(DeleteActivity),
+}
+
+#[derive(Args)]
+pub struct LogActivity {
+    /// The id of the contact the activity was with
+    #[arg(long)]
+    pub contact_id: Option<String>,
+    /// The id of the deal the activity relates to
+    #[arg(long)]
+    pub deal_id: Option<String>,
+    /// The id of the company the activity relates to
+    #[arg(long)]
+    pub company_id: Option<String>,
+}
+
+#[derive(Args)]
+pub struct ListActivities {
+    /// Only list activities linked to this contact
+    #[arg(long)]
+    pub contact_id: Option<String>,
+    /// Only list activities linked to this deal
+    #[arg(long)]
+    pub deal_id: Option<String>,
+    /// Only list activities linked to this company
+    #[arg(long)]
+    pub company_id: Option<String>,
+    /// The number of activities to return
+    #[arg(long, default_value_t = 10)]
+    pub limit: i64,
+}
+
+#[derive(Args)]
+pub struct EditActivity {
+    /// The id of the activity you want to edit
+    pub id: String,
+}
+
+#[derive(Args)]
+pub struct DeleteActivity {
+    /// The id of the activity you want to delete
+    pub id: Option<String>,
+}
+
+fn prompt_payload() -> Result<ActivityPayload, DefaultError> {
+    let kind = inquire::Select::new("What kind of activity?", vec!["Call", "Meeting", "Email"])
+        .prompt()?;
+
+    let payload = match kind {
+        "Call" => {
+            let duration_minutes = inquire::CustomType::<i32>::new("Call duration in minutes:")
+                .with_default(0)
+                .prompt()?;
+            let outcome = match inquire::Select::new(
+                "Call outcome:",
+                vec!["Connected", "No Answer", "Left Voicemail", "Wrong Number"],
+            )
+            .prompt()?
+            {
+                "Connected" => CallOutcome::Connected,
+                "No Answer" => CallOutcome::NoAnswer,
+                "Left Voicemail" => CallOutcome::LeftVoicemail,
+                _ => CallOutcome::WrongNumber,
+            };
+            ActivityPayload::ActivityPayloadOneOf(Box::new(ActivityPayloadOneOf::new(
+                CallActivity::new(duration_minutes * 60, outcome),
+            )))
+        }
+        "Meeting" => {
+            let attendees = inquire::Text::new("Attendees (comma separated):")
+                .with_render_config(get_cancelable_render_config("No Attendees"))
+                .prompt_skippable()?
+                .unwrap_or_default()
+                .split(',')
+                .map(|attendee| attendee.trim().to_string())
+                .filter(|attendee| !attendee.is_empty())
+                .collect();
+            ActivityPayload::ActivityPayloadOneOf1(Box::new(ActivityPayloadOneOf1::new(
+                MeetingActivity::new(attendees),
+            )))
+        }
+        _ => {
+            let direction =
+                match inquire::Select::new("Email direction:", vec!["Outbound", "Inbound"])
+                    .prompt()?
+                {
+                    "Inbound" => EmailDirection::Inbound,
+                    _ => EmailDirection::Outbound,
+                };
+            let subject = inquire::Text::new("Email subject:").prompt()?;
+            ActivityPayload::ActivityPayloadOneOf2(Box::new(ActivityPayloadOneOf2::new(
+                EmailActivity::new(direction, subject),
+            )))
+        }
+    };
+
+    Ok(payload)
+}
+
+fn describe_payload(payload: &ActivityPayload) -> (String, String) {
+    match payload {
+        ActivityPayload::ActivityPayloadOneOf(call) => (
+            "Call".to_string(),
+            format!(
+                "{} min, {}",
+                call.call.duration_seconds / 60,
+                call.call.outcome.to_string()
+            ),
+        ),
+        ActivityPayload::ActivityPayloadOneOf1(meeting) => {
+            ("Meeting".to_string(), meeting.meeting.attendees.join(", "))
+        }
+        ActivityPayload::ActivityPayloadOneOf2(email) => (
+            "Email".to_string(),
+            format!(
+                "{}: {}",
+                email.email.direction.to_string(),
+                email.email.subject
+            ),
+        ),
+    }
+}
+
+pub async fn log_activity_cmd(
+    config: ActixTemplateConfiguration,
+    args: LogActivity,
+) -> Result<(), DefaultError> {
+    let payload = prompt_payload()?;
+
+    let result = activities_api::create_activity(
+        &config.clone().into(),
+        CreateActivityParams {
+            idempotency_key: None,
+            organization: config.org_id,
+            create_activity_req_payload: CreateActivityReqPayload {
+                payload: Box::new(payload),
+                contact_id: args.contact_id.map(Some),
+                deal_id: args.deal_id.map(Some),
+                company_id: args.company_id.map(Some),
+                occurred_at: None,
+            },
+        },
+    )
+    .await?
+    .entity
+    .ok_or_else(|| DefaultError::new("No entity returned from API for create_activity"))?;
+
+    match result {
+        CreateActivitySuccess::Status201(activity) => {
+            println!("Activity logged successfully: {}", activity.id);
+            Ok(())
+        }
+        CreateActivitySuccess::UnknownValue(_) => Err(DefaultError::new(
+            "Could not parse response body logging activity",
+        )),
+    }
+}
+
+pub async fn list_activities_cmd(
+    config: ActixTemplateConfiguration,
+    args: ListActivities,
+) -> Result<(), DefaultError> {
+    let result = activities_api::list_activities(
+        &config.clone().into(),
+        ListActivitiesParams {
+            organization: config.org_id,
+            limit: Some(args.limit),
+            offset: None,
+            contact_id: args.contact_id,
+            deal_id: args.deal_id,
+            company_id: args.company_id,
+        },
+    )
+    .await?
+    .entity
+    .ok_or_else(|| DefaultError::new("No entity returned from API for list_activities"))?;
+
+    match result {
+        ListActivitiesSuccess::Status200(list) => {
+            println!("{}", build_activities_table(&list.activities));
+            Ok(())
+        }
+        ListActivitiesSuccess::UnknownValue(_) => Err(DefaultError::new(
+            "Unknown response from API for list_activities",
+        )),
+    }
+}
+
+fn build_activities_table(activities: &[Activity]) -> String {
+    let mut builder = tabled::builder::Builder::default();
+    builder.push_record(["ID", "Type", "Details", "Occurred At"]);
+
+    if activities.is_empty() {
+        builder.push_record(["No activities found", "", "", ""]);
+    } else {
+        for activity in activities {
+            let (kind, details) = describe_payload(&activity.payload);
+            builder.push_record([
+                activity.id.clone(),
+                kind,
+                details,
+                activity.occurred_at.clone(),
+            ]);
+        }
+    }
+
+    builder.build().with(Style::rounded()).to_string()
+}
+
+pub async fn edit_activity_cmd(
+    config: ActixTemplateConfiguration,
+    activity_id: String,
+) -> Result<(), DefaultError> {
+    let payload = prompt_payload()?;
+
+    let result = activities_api::update_activity(
+        &config.clone().into(),
+        UpdateActivityParams {
+            organization: config.org_id,
+            activity_id,
+            if_match: None,
+            update_activity_req_payload: UpdateActivityReqPayload {
+                payload: Some(Some(Box::new(payload))),
+                ..Default::default()
+            },
+        },
+    )
+    .await?
+    .entity
+    .ok_or_else(|| DefaultError::new("No entity returned from API for update_activity"))?;
+
+    match result {
+        UpdateActivitySuccess::Status200(activity) => {
+            println!("Activity updated successfully: {}", activity.id);
+            Ok(())
+        }
+        UpdateActivitySuccess::UnknownValue(_) => Err(DefaultError::new(
+            "Could not parse response body when updating activity",
+        )),
+    }
+}
+
+pub async fn delete_activity_cmd(
+    config: ActixTemplateConfiguration,
+    activity_id: Option<String>,
+) -> Result<(), DefaultError> {
+    let activity_id = if let Some(activity_id) = activity_id {
+        activity_id
+    } else {
+        inquire::Text::new("Enter activity ID to delete:").prompt()?
+    };
+
+    let delete_response = activities_api::delete_activity(
+        &config.clone().into(),
+        activities_api::DeleteActivityParams {
+            organization: config.org_id,
+            activity_id,
+            if_match: None,
+        },
+    )
+    .await?
+    .status
+    .is_success();
+
+    match delete_response {
+        true => {
+            println!("Activity deleted successfully");
+            Ok(())
+        }
+        false => Err(DefaultError::new("Activity could not be deleted")),
+    }
+}
@@ -1,5 +1,8 @@
 use super::configure::ActixTemplateConfiguration;
-use crate::{errors::DefaultError, ui::get_cancelable_render_config};
+use crate::{
+    errors::{is_edit_conflict, DefaultError},
+    ui::get_cancelable_render_config,
+};
 use actix_web_starter_client::{
     apis::contacts_api,
     models::{self, Contact},
@@ -45,6 +48,7 @@ pub async fn create_contact_cmd(config: ActixTemplateConfiguration) -> Result<()
     let result = contacts_api::create_contact(
         &config.clone().into(),
         contacts_api::CreateContactParams {
+            idempotency_key: None,
             organization: config.org_id,
             create_contact_req_payload: actix_web_starter_client::models::CreateContactReqPayload {
                 first_name,
@@ -106,6 +110,7 @@ pub async fn delete_contact_cmd(
         contacts_api::DeleteContactParams {
             contact_id: id,
             organization: config.org_id,
+            if_match: None,
         },
     )
     .await?
@@ -139,6 +144,13 @@ pub async fn view_contact_cmd(
     println!("First Name: {}", contact.first_name);
     println!("Last Name: {}", contact.last_name);
     println!("ID: {}", contact.id);
+    println!(
+        "Last Contacted: {}",
+        contact
+            .last_contacted_at
+            .unwrap_or(None)
+            .unwrap_or("Never".to_string())
+    );
     Ok(())
 }
 
@@ -168,15 +180,22 @@ pub async fn edit_contact_cmd(
         contacts_api::UpdateContactParams {
             contact_id: id,
             organization: config.org_id,
+            // Refuse to overwrite changes made since the contact was fetched
+            if_match: Some(format!("\"{}\"", contact.updated_at)),
             update_contact_req_payload: models::UpdateContactReqPayload {
                 first_name: Some(Some(first_name)),
                 last_name: Some(Some(last_name)),
+                owner_user_id: None,
             },
         },
     )
     .await
     .unwrap_or_else(|e| {
-        eprintln!("Error updating contact: {:?}", e);
+        if is_edit_conflict(&e) {
+            eprintln!("This contact was changed by someone else since you opened it. Run edit again to start from the latest version.");
+        } else {
+            eprintln!("Error updating contact: {:?}", e);
+        }
         std::process::exit(1);
     })
     .entity
@@ -276,6 +295,7 @@ async fn list_contacts(
         contacts_api::ListContactsParams {
             limit,
             offset,
+            owner: None,
             organization: config.org_id.clone(),
         },
     )
@@ -1,11 +1,23 @@
 use super::configure::ActixTemplateConfiguration;
-use crate::{errors::DefaultError, ui::get_cancelable_render_config};
+use crate::{
+    errors::{is_edit_conflict, DefaultError},
+    ui::get_cancelable_render_config,
+};
 use actix_web_starter_client::{
-    apis::deals_api::{self, CreateDealParams, CreateDealSuccess, GetDealParams, UpdateDealParams},
+    apis::{
+        deals_api::{
+            self, CreateDealParams, CreateDealSuccess, GetDealParams, GetForecastReportParams,
+            GetForecastReportSuccess, GetPerformanceReportParams, GetPerformanceReportSuccess,
+            GetPipelineReportParams, GetPipelineReportSuccess, ListCurrenciesSuccess,
+            UpdateDealParams,
+        },
+        orgs_api::{self, GetOrgsForAuthedUserParams, GetOrgsForAuthedUserSuccess},
+    },
     models::{self, CreateDealReqPayload, Deal, UpdateDealReqPayload},
 };
 use clap::{Args, Subcommand};
 use inquire::validator::Validation;
+use std::collections::HashMap;
 use tabled::settings::Style;
 
 #[derive(Subcommand)]
@@ -15,6 +27,8 @@ pub enum DealCommands {
     Edit(EditDeal),
     View(ViewDeal),
     List,
+    /// Show pipeline, forecast and win rate reports
+    Report(DealReport),
     #[command(subcommand, about = "Commands to manage contacts for a deal")]
     ManageContacts(ManageContactsCommands),
 }
@@ -31,12 +45,209 @@ pub struct EditDeal {
     pub id: String,
 }
 
+#[derive(Args)]
+pub struct DealReport {
+    /// Start of the date range (YYYY-MM-DD)
+    #[arg(long)]
+    pub from: Option<String>,
+    /// End of the date range (YYYY-MM-DD)
+    #[arg(long)]
+    pub to: Option<String>,
+}
+
 #[derive(Args)]
 pub struct ViewDeal {
     /// The id of the deal you want to delete
     pub id: String,
 }
 
+/// Decimal places in the minor unit of each currency, as the server stores amounts.
+type MinorUnitExponents = HashMap<String, u32>;
+
+async fn get_minor_unit_exponents(
+    config: ActixTemplateConfiguration,
+) -> Result<MinorUnitExponents, DefaultError> {
+    let response = deals_api::list_currencies(&config.clone().into())
+        .await?
+        .entity
+        .ok_or_else(|| DefaultError::new("No entity returned from API for list_currencies"))?;
+
+    match response {
+        ListCurrenciesSuccess::Status200(currencies) => Ok(currencies
+            .into_iter()
+            .map(|currency| (currency.code, currency.minor_unit_exponent as u32))
+            .collect()),
+        ListCurrenciesSuccess::UnknownValue(_) => Err(DefaultError::new(
+            "Unknown response from API for list_currencies",
+        )),
+    }
+}
+
+fn minor_unit_exponent(exponents: &MinorUnitExponents, currency: &str) -> u32 {
+    exponents.get(currency).copied().unwrap_or(2)
+}
+
+/// Parses a decimal amount such as `1,250.50` into minor units without going
+/// through floating point.
+fn parse_amount(
+    input: &str,
+    currency: &str,
+    exponents: &MinorUnitExponents,
+) -> Result<i64, String> {
+    let exponent = minor_unit_exponent(exponents, currency);
+    let cleaned = input.trim().replace(',', "");
+    let (negative, digits) = match cleaned.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, cleaned.as_str()),
+    };
+    let (whole, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+
+    if whole.is_empty() && fraction.is_empty() {
+        return Err("Amount cannot be empty".to_string());
+    }
+    if !whole
+        .chars()
+        .chain(fraction.chars())
+        .all(|c| c.is_ascii_digit())
+    {
+        return Err(format!("{} is not a valid amount", input.trim()));
+    }
+    if fraction.len() > exponent as usize {
+        return Err(format!(
+            "{} only allows {} decimal places",
+            currency, exponent
+        ));
+    }
+
+    let padded = format!("{}{:0<width$}", whole, fraction, width = exponent as usize);
+    let amount = padded
+        .parse::<i64>()
+        .map_err(|_| format!("{} is too large", input.trim()))?;
+
+    Ok(if negative { -amount } else { amount })
+}
+
+fn format_amount(amount: i64, currency: &str, exponents: &MinorUnitExponents) -> String {
+    let exponent = minor_unit_exponent(exponents, currency);
+    if exponent == 0 {
+        return format!("{} {}", amount, currency);
+    }
+
+    let divisor = 10_i64.pow(exponent);
+    let sign = if amount < 0 { "-" } else { "" };
+    format!(
+        "{}{}.{:0width$} {}",
+        sign,
+        (amount / divisor).abs(),
+        (amount % divisor).abs(),
+        currency,
+        width = exponent as usize
+    )
+}
+
+async fn get_org_default_currency(config: ActixTemplateConfiguration) -> String {
+    let orgs = orgs_api::get_orgs_for_authed_user(
+        &config.clone().into(),
+        GetOrgsForAuthedUserParams {
+            limit: None,
+            offset: None,
+        },
+    )
+    .await
+    .ok()
+    .and_then(|response| response.entity);
+
+    match orgs {
+        Some(GetOrgsForAuthedUserSuccess::Status200(orgs)) => orgs
+            .into_iter()
+            .find(|org| org.id == config.org_id)
+            .map(|org| org.default_currency)
+            .unwrap_or("USD".to_string()),
+        _ => "USD".to_string(),
+    }
+}
+
+fn prompt_currency(default: &str) -> Result<String, inquire::InquireError> {
+    inquire::Text::new("Enter deal currency (ISO 4217):")
+        .with_default(default)
+        .with_validator(|value: &str| {
+            let value = value.trim();
+            if value.len() == 3 && value.chars().all(|c| c.is_ascii_alphabetic()) {
+                Ok(Validation::Valid)
+            } else {
+                Ok(Validation::Invalid(
+                    "Currency must be a three letter code such as USD".into(),
+                ))
+            }
+        })
+        .prompt()
+        .map(|currency| currency.trim().to_ascii_uppercase())
+}
+
+fn prompt_amount(
+    currency: &str,
+    default: Option<i64>,
+    exponents: &MinorUnitExponents,
+) -> Result<Option<i64>, DefaultError> {
+    let validator_currency = currency.to_string();
+    let validator_exponents = exponents.clone();
+    let default = default.map(|amount| {
+        format_amount(amount, currency, exponents)
+            .trim_end_matches(currency)
+            .trim()
+            .to_string()
+    });
+    let mut prompt = inquire::Text::new("Enter deal amount:")
+        .with_validator(move |value: &str| {
+            match parse_amount(value, &validator_currency, &validator_exponents) {
+                Ok(_) => Ok(Validation::Valid),
+                Err(e) => Ok(Validation::Invalid(e.into())),
+            }
+        })
+        .with_render_config(get_cancelable_render_config("No Amount"));
+    if let Some(default) = default.as_deref() {
+        prompt = prompt.with_default(default);
+    }
+
+    let amount = prompt.prompt_skippable()?;
+    amount
+        .map(|amount| parse_amount(&amount, currency, exponents).map_err(|e| DefaultError::new(&e)))
+        .transpose()
+}
+
+fn prompt_probability(default: Option<i32>) -> Result<Option<i32>, inquire::InquireError> {
+    let mut prompt = inquire::CustomType::<i32>::new("Enter win probability (0-100):")
+        .with_validator(|value: &i32| {
+            if (0..=100).contains(value) {
+                Ok(Validation::Valid)
+            } else {
+                Ok(Validation::Invalid(
+                    "Probability must be between 0 and 100".into(),
+                ))
+            }
+        })
+        .with_render_config(get_cancelable_render_config("No Probability"));
+    if let Some(default) = default {
+        prompt = prompt.with_default(default);
+    }
+    prompt.prompt_skippable()
+}
+
+fn prompt_expected_close_date(
+    default: Option<&str>,
+) -> Result<Option<String>, inquire::InquireError> {
+    let mut prompt = inquire::DateSelect::new("Enter an expected close date (or ESC):")
+        .with_render_config(get_cancelable_render_config("No Expected Close Date"));
+    if let Some(default) =
+        default.and_then(|date| chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+    {
+        prompt = prompt.with_starting_date(default);
+    }
+    prompt
+        .prompt_skippable()
+        .map(|date| date.map(|date| date.format("%Y-%m-%d").to_string()))
+}
+
 pub async fn create_deal_cmd(config: ActixTemplateConfiguration) -> Result<(), DefaultError> {
     let name = inquire::Text::new("Enter deal name:")
         .with_render_config(get_cancelable_render_config("No Description"))
@@ -46,18 +257,26 @@ pub async fn create_deal_cmd(config: ActixTemplateConfiguration) -> Result<(), D
         .with_default(true)
         .prompt()?;
 
-    let size = inquire::CustomType::<f32>::new("Enter deal size:")
-        .with_default(0.0)
-        .prompt()?;
+    let default_currency = get_org_default_currency(config.clone()).await;
+    let currency = prompt_currency(&default_currency)?;
+    let exponents = get_minor_unit_exponents(config.clone()).await?;
+    let amount = prompt_amount(&currency, None, &exponents)?;
+    let probability = prompt_probability(None)?;
+    let expected_close_date = prompt_expected_close_date(None)?;
 
     let result = deals_api::create_deal(
         &config.clone().into(),
         CreateDealParams {
+            idempotency_key: None,
             organization: config.org_id,
             create_deal_req_payload: CreateDealReqPayload {
                 active: Some(Some(active)),
                 name: Some(Some(name)),
-                size: Some(Some(size)),
+                amount: amount.map(Some),
+                contact_ids: None,
+                currency: Some(Some(currency)),
+                probability: probability.map(Some),
+                expected_close_date: expected_close_date.map(Some),
             },
         },
     )
@@ -108,7 +327,7 @@ pub async fn edit_deal_cmd(
         .name
         .unwrap_or(Some("".to_string()))
         .unwrap_or("".to_string());
-    let prev_deal_size = deal.size.unwrap_or(Some(0.0)).unwrap_or(0.0);
+    let prev_deal_amount = deal.amount.unwrap_or(None);
 
     let name = inquire::Text::new("Enter deal name:")
         .with_default(&prev_deal_name)
@@ -120,25 +339,58 @@ pub async fn edit_deal_cmd(
         .prompt()
         .unwrap_or(deal.active);
 
-    let size = inquire::CustomType::<f32>::new("Enter deal size:")
-        .with_default(prev_deal_size)
-        .prompt()
-        .unwrap_or(prev_deal_size);
+    let currency = prompt_currency(&deal.currency).unwrap_or(deal.currency.clone());
+
+    let exponents = get_minor_unit_exponents(config.clone()).await?;
+    let amount = prompt_amount(&currency, prev_deal_amount, &exponents)?;
+    let probability = prompt_probability(deal.probability.unwrap_or(None))?;
+    let expected_close_date =
+        prompt_expected_close_date(deal.expected_close_date.clone().flatten().as_deref())?;
+
+    let prev_won = deal.won.unwrap_or(None);
+    let won = match inquire::Select::new("Deal status:", vec!["Open", "Won", "Lost"])
+        .with_starting_cursor(match prev_won {
+            None => 0,
+            Some(true) => 1,
+            Some(false) => 2,
+        })
+        .prompt()?
+    {
+        "Won" => Some(true),
+        "Lost" => Some(false),
+        _ => None,
+    };
 
     let result = deals_api::update_deal(
         &config.clone().into(),
         UpdateDealParams {
             organization: config.org_id,
             deal_id,
+            // Refuse to overwrite changes made since the deal was fetched
+            if_match: Some(format!("\"{}\"", deal.updated_at)),
             update_deal_req_payload: UpdateDealReqPayload {
                 active: Some(Some(active)),
-                name: Some(Some(name)),
-                size: Some(Some(size)),
+                name: Some(Some(name).filter(|name| !name.is_empty())),
+                amount: Some(amount),
+                currency: Some(Some(currency)),
+                probability: Some(probability),
+                expected_close_date: Some(expected_close_date),
+                // Only send the status when it changes so the close date isn't reset
+                won: (won != prev_won).then_some(won),
+                owner_user_id: None,
             },
         },
     )
     .await
-    .map_err(|e| DefaultError::new(format!("Error updating deal: {:?}", e).as_str()))?
+    .map_err(|e| {
+        if is_edit_conflict(&e) {
+            DefaultError::new(
+                "This deal was changed by someone else since you opened it. Run edit again to start from the latest version.",
+            )
+        } else {
+            DefaultError::new(format!("Error updating deal: {:?}", e).as_str())
+        }
+    })?
     .entity
     .ok_or_else(|| DefaultError::new("No entity returned from API for update_deal"))?;
 
@@ -158,18 +410,50 @@ pub async fn view_deal_cmd(
     deal_id: String,
 ) -> Result<(), DefaultError> {
     let deal = get_deal(config.clone(), deal_id.clone()).await?;
+    let exponents = get_minor_unit_exponents(config.clone()).await?;
     let name = deal
         .name
         .clone()
         .unwrap_or(Some("".to_string()))
         .unwrap_or("".to_string());
-    let size = deal.size.unwrap_or(Some(0.0)).unwrap_or(0.0);
+    let amount = deal
+        .amount
+        .unwrap_or(None)
+        .map(|amount| format_amount(amount, &deal.currency, &exponents))
+        .unwrap_or("None".to_string());
     let active = deal.active;
 
     println!("Deal ID: {}", deal.id);
     println!("Name: {}", name);
-    println!("Size: {}", size);
+    println!("Amount: {}", amount);
     println!("Active: {}", active);
+    println!(
+        "Probability: {}",
+        deal.probability
+            .unwrap_or(None)
+            .map(|probability| format!("{}%", probability))
+            .unwrap_or("None".to_string())
+    );
+    println!(
+        "Expected Close Date: {}",
+        deal.expected_close_date
+            .unwrap_or(None)
+            .unwrap_or("None".to_string())
+    );
+    println!(
+        "Status: {}",
+        match deal.won.unwrap_or(None) {
+            Some(true) => "Won",
+            Some(false) => "Lost",
+            None => "Open",
+        }
+    );
+    println!(
+        "Last Contacted: {}",
+        deal.last_contacted_at
+            .unwrap_or(None)
+            .unwrap_or("Never".to_string())
+    );
 
     Ok(())
 }
@@ -189,6 +473,7 @@ pub async fn delete_deal_cmd(
         deals_api::DeleteDealParams {
             deal_id,
             organization: config.org_id,
+            if_match: None,
         },
     )
     .await?
@@ -210,12 +495,13 @@ pub async fn list_deals_cmd(config: ActixTemplateConfiguration) -> Result<(), De
     let mut page_num: usize = 0;
     let mut stop = false;
     let limit = get_limit()?;
+    let exponents = get_minor_unit_exponents(config.clone()).await?;
 
     while !stop {
         let offset = offsets.get(page_num).cloned().flatten();
         let deals = list_deals(config.clone(), Some(limit), offset).await?;
 
-        let table = build_deals_table(&deals);
+        let table = build_deals_table(&deals, &exponents);
         println!("{}", table);
 
         if let Some(last_deal) = deals.last() {
@@ -257,9 +543,9 @@ fn prompt_next_page(options: &[&str]) -> Result<String, inquire::InquireError> {
         .map(|s| s.to_string())
 }
 
-fn build_deals_table(deals: &[Deal]) -> String {
+fn build_deals_table(deals: &[Deal], exponents: &MinorUnitExponents) -> String {
     let mut builder = tabled::builder::Builder::default();
-    builder.push_record(["ID", "Name", "Size", "Active"]);
+    builder.push_record(["ID", "Name", "Amount", "Active"]);
 
     if deals.is_empty() {
         builder.push_record(["No deals found", "", "", ""]);
@@ -271,7 +557,10 @@ fn build_deals_table(deals: &[Deal]) -> String {
                     .clone()
                     .unwrap_or(Some("".to_string()))
                     .unwrap_or("".to_string()),
-                deal.size.unwrap_or(Some(0.0)).unwrap_or(0.0).to_string(),
+                deal.amount
+                    .unwrap_or(None)
+                    .map(|amount| format_amount(amount, &deal.currency, exponents))
+                    .unwrap_or("".to_string()),
                 deal.active.to_string(),
             ]);
         }
@@ -280,6 +569,146 @@ fn build_deals_table(deals: &[Deal]) -> String {
     builder.build().with(Style::rounded()).to_string()
 }
 
+fn format_month(month: Option<Option<String>>) -> String {
+    month
+        .unwrap_or(None)
+        .map(|month| month.chars().take(7).collect())
+        .unwrap_or("Unscheduled".to_string())
+}
+
+pub async fn deal_report_cmd(
+    config: ActixTemplateConfiguration,
+    args: DealReport,
+) -> Result<(), DefaultError> {
+    let exponents = get_minor_unit_exponents(config.clone()).await?;
+    let pipeline = deals_api::get_pipeline_report(
+        &config.clone().into(),
+        GetPipelineReportParams {
+            organization: config.org_id.clone(),
+            from: args.from.clone(),
+            to: args.to.clone(),
+        },
+    )
+    .await?
+    .entity
+    .ok_or_else(|| DefaultError::new("No entity returned from API for get_pipeline_report"))?;
+    let pipeline = match pipeline {
+        GetPipelineReportSuccess::Status200(report) => report.rows,
+        GetPipelineReportSuccess::UnknownValue(_) => {
+            return Err(DefaultError::new(
+                "Unknown response from API for get_pipeline_report",
+            ))
+        }
+    };
+
+    let mut builder = tabled::builder::Builder::default();
+    builder.push_record(["Month", "Active", "Deals", "Value"]);
+    if pipeline.is_empty() {
+        builder.push_record(["No open deals found", "", "", ""]);
+    }
+    for row in pipeline {
+        builder.push_record([
+            format_month(row.month),
+            row.active.to_string(),
+            row.deal_count.to_string(),
+            format_amount(row.total_amount, &row.currency, &exponents),
+        ]);
+    }
+    println!("Open Pipeline");
+    println!("{}", builder.build().with(Style::rounded()));
+
+    let forecast = deals_api::get_forecast_report(
+        &config.clone().into(),
+        GetForecastReportParams {
+            organization: config.org_id.clone(),
+            from: args.from.clone(),
+            to: args.to.clone(),
+        },
+    )
+    .await?
+    .entity
+    .ok_or_else(|| DefaultError::new("No entity returned from API for get_forecast_report"))?;
+    let forecast = match forecast {
+        GetForecastReportSuccess::Status200(report) => report.rows,
+        GetForecastReportSuccess::UnknownValue(_) => {
+            return Err(DefaultError::new(
+                "Unknown response from API for get_forecast_report",
+            ))
+        }
+    };
+
+    let mut builder = tabled::builder::Builder::default();
+    builder.push_record(["Month", "Deals", "Value", "Weighted"]);
+    if forecast.is_empty() {
+        builder.push_record(["No active deals found", "", "", ""]);
+    }
+    for row in forecast {
+        builder.push_record([
+            format_month(row.month),
+            row.deal_count.to_string(),
+            format_amount(row.total_amount, &row.currency, &exponents),
+            format_amount(row.weighted_amount, &row.currency, &exponents),
+        ]);
+    }
+    println!("Weighted Forecast");
+    println!("{}", builder.build().with(Style::rounded()));
+
+    let performance = deals_api::get_performance_report(
+        &config.clone().into(),
+        GetPerformanceReportParams {
+            organization: config.org_id,
+            from: args.from,
+            to: args.to,
+        },
+    )
+    .await?
+    .entity
+    .ok_or_else(|| DefaultError::new("No entity returned from API for get_performance_report"))?;
+    let performance = match performance {
+        GetPerformanceReportSuccess::Status200(report) => report,
+        GetPerformanceReportSuccess::UnknownValue(_) => {
+            return Err(DefaultError::new(
+                "Unknown response from API for get_performance_report",
+            ))
+        }
+    };
+
+    let mut builder = tabled::builder::Builder::default();
+    builder.push_record(["Won", "Lost", "Win Rate", "Avg. Sales Cycle"]);
+    builder.push_record([
+        performance.won_count.to_string(),
+        performance.lost_count.to_string(),
+        performance
+            .win_rate
+            .unwrap_or(None)
+            .map(|rate| format!("{:.1}%", rate * 100.0))
+            .unwrap_or("-".to_string()),
+        performance
+            .average_sales_cycle_days
+            .unwrap_or(None)
+            .map(|days| format!("{:.1} days", days))
+            .unwrap_or("-".to_string()),
+    ]);
+    println!("Performance");
+    println!("{}", builder.build().with(Style::rounded()));
+
+    let mut builder = tabled::builder::Builder::default();
+    builder.push_record(["Currency", "Won Deals", "Avg. Deal Size"]);
+    if performance.average_deal_size.is_empty() {
+        builder.push_record(["No won deals found", "", ""]);
+    }
+    for row in performance.average_deal_size {
+        builder.push_record([
+            row.currency.clone(),
+            row.deal_count.to_string(),
+            format_amount(row.average_amount, &row.currency, &exponents),
+        ]);
+    }
+    println!("{}", builder.build().with(Style::rounded()));
+
+    Ok(())
+}
+
 async fn list_deals(
     config: ActixTemplateConfiguration,
     limit: Option<i64>,
@@ -291,6 +720,7 @@ async fn list_deals(
             organization: config.org_id,
             limit,
             offset,
+            owner: None,
         },
     )
     .await?
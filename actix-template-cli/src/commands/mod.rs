@@ -1,3 +1,4 @@
+pub mod activities;
 pub mod api_key;
 pub mod configure;
 pub mod contacts;
@@ -6,4 +7,5 @@ pub mod login_server;
 pub mod notes;
 pub mod orgs;
 pub mod profile;
+pub mod show;
 pub mod tasks;
@@ -73,6 +73,7 @@ async fn set_note_body(
     let params = UpdateNoteParams {
         organization: configuration.clone().org_id,
         note_id,
+        if_match: None,
         update_note_req_payload: UpdateNoteReqPayload { body: new_body },
     };
     let UpdateNoteSuccess::Status200(new_note) =
@@ -214,6 +215,7 @@ pub async fn delete_note_cmd(
         notes_api::DeleteNoteParams {
             organization: config.clone().org_id,
             note_id: note.id,
+            if_match: None,
         },
     )
     .await
@@ -249,6 +251,7 @@ pub async fn create_note_cmd(
     let created_note = actix_web_starter_client::apis::notes_api::create_note(
         &config.clone().into(),
         CreateNoteParams {
+            idempotency_key: None,
             organization: config.clone().org_id.clone(),
             create_note_req_payload: payload,
         },
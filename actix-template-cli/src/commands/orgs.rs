@@ -5,8 +5,8 @@ use actix_web_starter_client::{
         configuration::Configuration,
         invitation_api,
         orgs_api::{
-            CreateOrgParams, CreateOrgSuccess, GetOrgsForAuthedUserParams,
-            GetOrgsForAuthedUserSuccess,
+            CreateOrgParams, CreateOrgSuccess, GetOrgUsageParams, GetOrgUsageSuccess,
+            GetOrgsForAuthedUserParams, GetOrgsForAuthedUserSuccess,
         },
     },
     models::{CreateOrgReqPayload, InvitationData, Org},
@@ -22,6 +22,8 @@ pub enum OrgCommands {
     Rename,
     Invite(InviteToOrg),
     Leave(LeaveOrg),
+    /// Show the organization's plan and how much of it is in use
+    Usage,
 }
 
 #[derive(Args)]
@@ -53,11 +55,15 @@ pub async fn create_org(
         name.unwrap()
     };
 
-    let payload = CreateOrgReqPayload { name };
+    let payload = CreateOrgReqPayload {
+        name,
+        default_currency: None,
+    };
 
     let created = actix_web_starter_client::apis::orgs_api::create_org(
         &settings.into(),
         CreateOrgParams {
+            idempotency_key: None,
             create_org_req_payload: payload,
         },
     )
@@ -165,6 +171,7 @@ pub async fn delete_org(settings: ActixTemplateConfiguration) -> Result<(), Defa
         actix_web_starter_client::apis::orgs_api::DeleteOrgParams {
             org_id: selected.id.clone(),
             organization: selected.id.to_string(),
+            if_match: None,
         },
     )
     .await
@@ -199,13 +206,17 @@ pub async fn rename_org(settings: ActixTemplateConfiguration) -> Result<(), Defa
         })?;
 
     // Send the rename request
-    let rename_payload = actix_web_starter_client::models::UpdateOrgReqPayload { name: new_name };
+    let rename_payload = actix_web_starter_client::models::UpdateOrgReqPayload {
+        name: new_name,
+        default_currency: None,
+    };
 
     let renamed = actix_web_starter_client::apis::orgs_api::update_org(
         &settings.into(),
         actix_web_starter_client::apis::orgs_api::UpdateOrgParams {
             organization: selected.id.clone(),
             org_id: selected.id,
+            if_match: None,
             update_org_req_payload: rename_payload,
         },
     )
@@ -304,3 +315,29 @@ pub async fn leave_org(
         Err(DefaultError::new("Error leaving organization."))
     }
 }
+
+pub async fn org_usage(settings: ActixTemplateConfiguration) -> Result<(), DefaultError> {
+    let usage = actix_web_starter_client::apis::orgs_api::get_org_usage(
+        &settings.clone().into(),
+        GetOrgUsageParams {
+            organization: settings.org_id.clone(),
+            org_id: settings.org_id,
+        },
+    )
+    .await
+    .map_err(|e| DefaultError::new(format!("Error fetching usage: {:?}", e).as_str()))?
+    .entity
+    .ok_or_else(|| DefaultError::new("No entity returned from API for get_org_usage"))?;
+
+    match usage {
+        GetOrgUsageSuccess::Status200(usage) => {
+            println!("Plan: {}", usage.plan.stripe_id);
+            println!("Members: {} / {}", usage.num_users, usage.plan.num_users);
+            println!("Deals: {} / {}", usage.num_deals, usage.plan.num_deals);
+            Ok(())
+        }
+        GetOrgUsageSuccess::UnknownValue(_) => Err(DefaultError::new(
+            "Unknown response from API for get_org_usage",
+        )),
+    }
+}
@@ -0,0 +1,42 @@
+use super::configure::ActixTemplateConfiguration;
+use crate::errors::DefaultError;
+use actix_web_starter_client::apis::resolve_api::{self, ResolveIdParams, ResolveIdSuccess};
+use clap::Args;
+
+#[derive(Args)]
+pub struct Show {
+    /// The prefixed id of the record, e.g. deal-… or contact-…
+    pub id: String,
+}
+
+pub async fn show_cmd(config: ActixTemplateConfiguration, id: String) -> Result<(), DefaultError> {
+    let response = resolve_api::resolve_id(
+        &config.clone().into(),
+        ResolveIdParams {
+            organization: config.org_id,
+            prefixed_id: id,
+        },
+    )
+    .await?
+    .entity
+    .ok_or_else(|| DefaultError::new("No entity returned from API for resolve_id"))?;
+
+    let entity = match response {
+        ResolveIdSuccess::Status200(entity) => entity,
+        ResolveIdSuccess::UnknownValue(_) => {
+            return Err(DefaultError::new(
+                "Unknown response from API for resolve_id",
+            ))
+        }
+    };
+
+    // The client has a variant per record type, its JSON has the type and the record
+    // side by side whichever one it is
+    let value = serde_json::to_value(entity).map_err(|e| DefaultError::new(&e.to_string()))?;
+    let record = serde_json::to_string_pretty(&value["entity"])
+        .map_err(|e| DefaultError::new(&e.to_string()))?;
+
+    println!("Type: {}", value["type"].as_str().unwrap_or("unknown"));
+    println!("{}", record);
+    Ok(())
+}
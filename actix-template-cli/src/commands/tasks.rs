@@ -1,5 +1,8 @@
 use super::configure::ActixTemplateConfiguration;
-use crate::{errors::DefaultError, ui::get_cancelable_render_config};
+use crate::{
+    errors::{is_edit_conflict, DefaultError},
+    ui::get_cancelable_render_config,
+};
 use actix_web_starter_client::{
     apis::tasks_api::{
         self, CreateTaskSuccess, GetTaskParams, UpdateTaskParams, UpdateTaskSuccess,
@@ -59,6 +62,7 @@ pub async fn create_task_cmd(config: ActixTemplateConfiguration) -> Result<(), D
     let result = tasks_api::create_task(
         &config.clone().into(),
         tasks_api::CreateTaskParams {
+            idempotency_key: None,
             organization: config.org_id,
             create_task_req_payload: CreateTaskReqPayload {
                 description: transform_option(description),
@@ -122,16 +126,27 @@ pub async fn edit_task_cmd(
     let update_params = UpdateTaskParams {
         task_id: task.id,
         organization: config.clone().org_id,
+        // Refuse to overwrite changes made since the task was fetched
+        if_match: Some(format!("\"{}\"", task.updated_at)),
         update_task_req_payload: UpdateTaskReqPayload {
             contact_id: None,
             description: Some(description),
             deadline: Some(due_date),
+            owner_user_id: None,
         },
     };
 
     let result = tasks_api::update_task(&config.into(), update_params)
         .await
-        .map_err(|e| DefaultError::new(format!("Error updating task: {:?}", e).as_str()))?
+        .map_err(|e| {
+            if is_edit_conflict(&e) {
+                DefaultError::new(
+                    "This task was changed by someone else since you opened it. Run edit again to start from the latest version.",
+                )
+            } else {
+                DefaultError::new(format!("Error updating task: {:?}", e).as_str())
+            }
+        })?
         .entity
         .expect("Task should be returned");
 
@@ -175,6 +190,7 @@ pub async fn delete_task_cmd(
         tasks_api::DeleteTaskParams {
             task_id,
             organization: config.org_id,
+            if_match: None,
         },
     )
     .await?
@@ -46,3 +46,12 @@ impl From<inquire::InquireError> for DefaultError {
         }
     }
 }
+
+/// Whether the API refused a write because the record changed after it was fetched.
+pub fn is_edit_conflict<T>(error: &actix_web_starter_client::apis::Error<T>) -> bool {
+    matches!(
+        error,
+        actix_web_starter_client::apis::Error::ResponseError(response)
+            if response.status.as_u16() == 412
+    )
+}
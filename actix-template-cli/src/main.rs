@@ -1,10 +1,12 @@
 use clap::{Args, Parser, Subcommand};
 use commands::{
+    activities::{self, ActivityCommands},
     configure::ActixTemplateProfile,
     contacts::{self, ContactCommands},
     deals::{self, DealCommands},
     notes::{self, NoteCommands},
     orgs::{self, OrgCommands},
+    show::{self, Show},
     tasks::{self, TaskCommands},
 };
 use errors::DefaultError;
@@ -54,6 +56,11 @@ enum Commands {
 
     #[command(subcommand)]
     Contacts(ContactCommands),
+    /// Log and review calls, meetings and emails
+    #[command(subcommand)]
+    Activities(ActivityCommands),
+    /// Show any record by its prefixed id, e.g. deal-… or contact-…
+    Show(Show),
 }
 
 #[derive(Subcommand)]
@@ -153,6 +160,7 @@ async fn main() {
             OrgCommands::Rename => orgs::rename_org(settings).await,
             OrgCommands::Invite(invite) => orgs::invite_user(invite.email, settings).await,
             OrgCommands::Leave(leave_org) => orgs::leave_org(leave_org.id, settings).await,
+            OrgCommands::Usage => orgs::org_usage(settings).await,
         },
 
         Some(Commands::Notes(note_option)) => match note_option {
@@ -190,6 +198,9 @@ async fn main() {
                 deals::manage_contacts_cmd(settings, mng_contact_option).await
             }
             DealCommands::List => deals::list_deals_cmd(settings).await,
+            DealCommands::Report(report_args) => {
+                deals::deal_report_cmd(settings, report_args).await
+            }
         },
 
         Some(Commands::Contacts(contact_option)) => match contact_option {
@@ -206,6 +217,23 @@ async fn main() {
             ContactCommands::List => contacts::list_contacts_cmd(settings).await,
         },
 
+        Some(Commands::Activities(activity_option)) => match activity_option {
+            ActivityCommands::Log(log_args) => {
+                activities::log_activity_cmd(settings, log_args).await
+            }
+            ActivityCommands::List(list_args) => {
+                activities::list_activities_cmd(settings, list_args).await
+            }
+            ActivityCommands::Edit(edit_args) => {
+                activities::edit_activity_cmd(settings, edit_args.id).await
+            }
+            ActivityCommands::Delete(delete_args) => {
+                activities::delete_activity_cmd(settings, delete_args.id).await
+            }
+        },
+
+        Some(Commands::Show(show_args)) => show::show_cmd(settings, show_args.id).await,
+
         _ => {
             println!("Command not implemented yet");
             Ok(())
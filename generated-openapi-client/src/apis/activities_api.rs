@@ -0,0 +1,433 @@
+/*
+ * Trieve API
+ *
+ * Trieve OpenAPI Specification. This document describes all of the operations available through the Trieve API.
+ *
+ * The version of the OpenAPI document: 0.0.1
+ * Contact: developers@trieve.ai
+ * Generated by: https://openapi-generator.tech
+ */
+
+
+use reqwest;
+
+use crate::{apis::ResponseContent, models};
+use super::{Error, configuration};
+
+/// struct for passing parameters to the method [`create_activity`]
+#[derive(Clone, Debug)]
+pub struct CreateActivityParams {
+    /// The organization id to use for the request
+    pub organization: String,
+    /// JSON request payload to log a new activity
+    pub create_activity_req_payload: models::CreateActivityReqPayload,
+    /// Unique key for this create. Retrying with the same key replays the first response instead of creating a duplicate
+    pub idempotency_key: Option<String>
+}
+
+/// struct for passing parameters to the method [`delete_activity`]
+#[derive(Clone, Debug)]
+pub struct DeleteActivityParams {
+    /// The organization id to use for the request
+    pub organization: String,
+    /// The id of the activity you want to delete.
+    pub activity_id: String,
+    /// ETag of the record as last fetched. The write fails with 412 if the record has changed since
+    pub if_match: Option<String>
+}
+
+/// struct for passing parameters to the method [`get_activity`]
+#[derive(Clone, Debug)]
+pub struct GetActivityParams {
+    /// The organization id to use for the request
+    pub organization: String,
+    /// The id of the activity you want to fetch.
+    pub activity_id: String
+}
+
+/// struct for passing parameters to the method [`list_activities`]
+#[derive(Clone, Debug)]
+pub struct ListActivitiesParams {
+    /// The organization id to use for the request
+    pub organization: String,
+    /// The number of records to return
+    pub limit: Option<i64>,
+    /// The UUID of the record to start from
+    pub offset: Option<String>,
+    /// Only return activities linked to this contact
+    pub contact_id: Option<String>,
+    /// Only return activities linked to this deal
+    pub deal_id: Option<String>,
+    /// Only return activities linked to this company
+    pub company_id: Option<String>
+}
+
+/// struct for passing parameters to the method [`update_activity`]
+#[derive(Clone, Debug)]
+pub struct UpdateActivityParams {
+    /// The organization id to use for the request
+    pub organization: String,
+    /// The id of the activity you want to update.
+    pub activity_id: String,
+    /// JSON request payload to update the activity
+    pub update_activity_req_payload: models::UpdateActivityReqPayload,
+    /// ETag of the record as last fetched. The write fails with 412 if the record has changed since
+    pub if_match: Option<String>
+}
+
+
+/// struct for typed successes of method [`create_activity`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum CreateActivitySuccess {
+    Status201(models::Activity),
+    UnknownValue(serde_json::Value),
+}
+
+/// struct for typed successes of method [`delete_activity`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum DeleteActivitySuccess {
+    Status204(),
+    UnknownValue(serde_json::Value),
+}
+
+/// struct for typed successes of method [`get_activity`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum GetActivitySuccess {
+    Status200(models::Activity),
+    UnknownValue(serde_json::Value),
+}
+
+/// struct for typed successes of method [`list_activities`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ListActivitiesSuccess {
+    Status200(models::ActivityList),
+    UnknownValue(serde_json::Value),
+}
+
+/// struct for typed successes of method [`update_activity`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum UpdateActivitySuccess {
+    Status200(models::Activity),
+    UnknownValue(serde_json::Value),
+}
+
+/// struct for typed errors of method [`create_activity`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum CreateActivityError {
+    Status400(models::ErrorRespPayload),
+    Status401(models::ErrorRespPayload),
+    Status409(models::ErrorRespPayload),
+    Status422(models::ErrorRespPayload),
+    Status429(models::ErrorRespPayload),
+    Status500(models::ErrorRespPayload),
+    Status503(models::ErrorRespPayload),
+    UnknownValue(serde_json::Value),
+}
+
+/// struct for typed errors of method [`delete_activity`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum DeleteActivityError {
+    Status400(models::ErrorRespPayload),
+    Status401(models::ErrorRespPayload),
+    Status412(models::ErrorRespPayload),
+    Status429(models::ErrorRespPayload),
+    Status500(models::ErrorRespPayload),
+    Status503(models::ErrorRespPayload),
+    UnknownValue(serde_json::Value),
+}
+
+/// struct for typed errors of method [`get_activity`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum GetActivityError {
+    Status400(models::ErrorRespPayload),
+    Status401(models::ErrorRespPayload),
+    Status404(models::ErrorRespPayload),
+    Status429(models::ErrorRespPayload),
+    Status500(models::ErrorRespPayload),
+    Status503(models::ErrorRespPayload),
+    UnknownValue(serde_json::Value),
+}
+
+/// struct for typed errors of method [`list_activities`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ListActivitiesError {
+    Status400(models::ErrorRespPayload),
+    Status401(models::ErrorRespPayload),
+    Status429(models::ErrorRespPayload),
+    Status500(models::ErrorRespPayload),
+    Status503(models::ErrorRespPayload),
+    UnknownValue(serde_json::Value),
+}
+
+/// struct for typed errors of method [`update_activity`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum UpdateActivityError {
+    Status400(models::ErrorRespPayload),
+    Status401(models::ErrorRespPayload),
+    Status412(models::ErrorRespPayload),
+    Status429(models::ErrorRespPayload),
+    Status500(models::ErrorRespPayload),
+    Status503(models::ErrorRespPayload),
+    UnknownValue(serde_json::Value),
+}
+
+
+pub async fn create_activity(configuration: &configuration::Configuration, params: CreateActivityParams) -> Result<ResponseContent<CreateActivitySuccess>, Error<CreateActivityError>> {
+    let local_var_configuration = configuration;
+
+    // unbox the parameters
+    let organization = params.organization;
+    let create_activity_req_payload = params.create_activity_req_payload;
+    let idempotency_key = params.idempotency_key;
+
+
+    let local_var_client = &local_var_configuration.client;
+
+    let local_var_uri_str = format!("{}/api/activities", local_var_configuration.base_path);
+    let mut local_var_req_builder = local_var_client.request(reqwest::Method::POST, local_var_uri_str.as_str());
+
+    if let Some(ref local_var_user_agent) = local_var_configuration.user_agent {
+        local_var_req_builder = local_var_req_builder.header(reqwest::header::USER_AGENT, local_var_user_agent.clone());
+    }
+    local_var_req_builder = local_var_req_builder.header("Organization", organization.to_string());
+    if let Some(local_var_param_value) = idempotency_key {
+        local_var_req_builder = local_var_req_builder.header("Idempotency-Key", local_var_param_value.to_string());
+    }
+    if let Some(ref local_var_apikey) = local_var_configuration.api_key {
+        let local_var_key = local_var_apikey.key.clone();
+        let local_var_value = match local_var_apikey.prefix {
+            Some(ref local_var_prefix) => format!("{} {}", local_var_prefix, local_var_key),
+            None => local_var_key,
+        };
+        local_var_req_builder = local_var_req_builder.header("Authorization", local_var_value);
+    };
+    local_var_req_builder = local_var_req_builder.json(&create_activity_req_payload);
+
+    let local_var_req = local_var_req_builder.build()?;
+    let local_var_resp = local_var_client.execute(local_var_req).await?;
+
+    let local_var_status = local_var_resp.status();
+    let local_var_content = local_var_resp.text().await?;
+
+    if !local_var_status.is_client_error() && !local_var_status.is_server_error() {
+        let local_var_entity: Option<CreateActivitySuccess> = serde_json::from_str(&local_var_content).ok();
+        let local_var_result = ResponseContent { status: local_var_status, content: local_var_content, entity: local_var_entity };
+        Ok(local_var_result)
+    } else {
+        let local_var_entity: Option<CreateActivityError> = serde_json::from_str(&local_var_content).ok();
+        let local_var_error = ResponseContent { status: local_var_status, content: local_var_content, entity: local_var_entity };
+        Err(Error::ResponseError(local_var_error))
+    }
+}
+
+pub async fn delete_activity(configuration: &configuration::Configuration, params: DeleteActivityParams) -> Result<ResponseContent<DeleteActivitySuccess>, Error<DeleteActivityError>> {
+    let local_var_configuration = configuration;
+
+    // unbox the parameters
+    let organization = params.organization;
+    let activity_id = params.activity_id;
+    let if_match = params.if_match;
+
+
+    let local_var_client = &local_var_configuration.client;
+
+    let local_var_uri_str = format!("{}/api/activities/{activity_id}", local_var_configuration.base_path, activity_id=crate::apis::urlencode(activity_id));
+    let mut local_var_req_builder = local_var_client.request(reqwest::Method::DELETE, local_var_uri_str.as_str());
+
+    if let Some(ref local_var_user_agent) = local_var_configuration.user_agent {
+        local_var_req_builder = local_var_req_builder.header(reqwest::header::USER_AGENT, local_var_user_agent.clone());
+    }
+    local_var_req_builder = local_var_req_builder.header("Organization", organization.to_string());
+    if let Some(local_var_param_value) = if_match {
+        local_var_req_builder = local_var_req_builder.header("If-Match", local_var_param_value.to_string());
+    }
+    if let Some(ref local_var_apikey) = local_var_configuration.api_key {
+        let local_var_key = local_var_apikey.key.clone();
+        let local_var_value = match local_var_apikey.prefix {
+            Some(ref local_var_prefix) => format!("{} {}", local_var_prefix, local_var_key),
+            None => local_var_key,
+        };
+        local_var_req_builder = local_var_req_builder.header("Authorization", local_var_value);
+    };
+
+    let local_var_req = local_var_req_builder.build()?;
+    let local_var_resp = local_var_client.execute(local_var_req).await?;
+
+    let local_var_status = local_var_resp.status();
+    let local_var_content = local_var_resp.text().await?;
+
+    if !local_var_status.is_client_error() && !local_var_status.is_server_error() {
+        let local_var_entity: Option<DeleteActivitySuccess> = serde_json::from_str(&local_var_content).ok();
+        let local_var_result = ResponseContent { status: local_var_status, content: local_var_content, entity: local_var_entity };
+        Ok(local_var_result)
+    } else {
+        let local_var_entity: Option<DeleteActivityError> = serde_json::from_str(&local_var_content).ok();
+        let local_var_error = ResponseContent { status: local_var_status, content: local_var_content, entity: local_var_entity };
+        Err(Error::ResponseError(local_var_error))
+    }
+}
+
+pub async fn get_activity(configuration: &configuration::Configuration, params: GetActivityParams) -> Result<ResponseContent<GetActivitySuccess>, Error<GetActivityError>> {
+    let local_var_configuration = configuration;
+
+    // unbox the parameters
+    let organization = params.organization;
+    let activity_id = params.activity_id;
+
+
+    let local_var_client = &local_var_configuration.client;
+
+    let local_var_uri_str = format!("{}/api/activities/{activity_id}", local_var_configuration.base_path, activity_id=crate::apis::urlencode(activity_id));
+    let mut local_var_req_builder = local_var_client.request(reqwest::Method::GET, local_var_uri_str.as_str());
+
+    if let Some(ref local_var_user_agent) = local_var_configuration.user_agent {
+        local_var_req_builder = local_var_req_builder.header(reqwest::header::USER_AGENT, local_var_user_agent.clone());
+    }
+    local_var_req_builder = local_var_req_builder.header("Organization", organization.to_string());
+    if let Some(ref local_var_apikey) = local_var_configuration.api_key {
+        let local_var_key = local_var_apikey.key.clone();
+        let local_var_value = match local_var_apikey.prefix {
+            Some(ref local_var_prefix) => format!("{} {}", local_var_prefix, local_var_key),
+            None => local_var_key,
+        };
+        local_var_req_builder = local_var_req_builder.header("Authorization", local_var_value);
+    };
+
+    let local_var_req = local_var_req_builder.build()?;
+    let local_var_resp = local_var_client.execute(local_var_req).await?;
+
+    let local_var_status = local_var_resp.status();
+    let local_var_content = local_var_resp.text().await?;
+
+    if !local_var_status.is_client_error() && !local_var_status.is_server_error() {
+        let local_var_entity: Option<GetActivitySuccess> = serde_json::from_str(&local_var_content).ok();
+        let local_var_result = ResponseContent { status: local_var_status, content: local_var_content, entity: local_var_entity };
+        Ok(local_var_result)
+    } else {
+        let local_var_entity: Option<GetActivityError> = serde_json::from_str(&local_var_content).ok();
+        let local_var_error = ResponseContent { status: local_var_status, content: local_var_content, entity: local_var_entity };
+        Err(Error::ResponseError(local_var_error))
+    }
+}
+
+pub async fn list_activities(configuration: &configuration::Configuration, params: ListActivitiesParams) -> Result<ResponseContent<ListActivitiesSuccess>, Error<ListActivitiesError>> {
+    let local_var_configuration = configuration;
+
+    // unbox the parameters
+    let organization = params.organization;
+    let limit = params.limit;
+    let offset = params.offset;
+    let contact_id = params.contact_id;
+    let deal_id = params.deal_id;
+    let company_id = params.company_id;
+
+
+    let local_var_client = &local_var_configuration.client;
+
+    let local_var_uri_str = format!("{}/api/activities", local_var_configuration.base_path);
+    let mut local_var_req_builder = local_var_client.request(reqwest::Method::GET, local_var_uri_str.as_str());
+
+    if let Some(ref local_var_str) = limit {
+        local_var_req_builder = local_var_req_builder.query(&[("limit", &local_var_str.to_string())]);
+    }
+    if let Some(ref local_var_str) = offset {
+        local_var_req_builder = local_var_req_builder.query(&[("offset", &local_var_str.to_string())]);
+    }
+    if let Some(ref local_var_str) = contact_id {
+        local_var_req_builder = local_var_req_builder.query(&[("contact_id", &local_var_str.to_string())]);
+    }
+    if let Some(ref local_var_str) = deal_id {
+        local_var_req_builder = local_var_req_builder.query(&[("deal_id", &local_var_str.to_string())]);
+    }
+    if let Some(ref local_var_str) = company_id {
+        local_var_req_builder = local_var_req_builder.query(&[("company_id", &local_var_str.to_string())]);
+    }
+    if let Some(ref local_var_user_agent) = local_var_configuration.user_agent {
+        local_var_req_builder = local_var_req_builder.header(reqwest::header::USER_AGENT, local_var_user_agent.clone());
+    }
+    local_var_req_builder = local_var_req_builder.header("Organization", organization.to_string());
+    if let Some(ref local_var_apikey) = local_var_configuration.api_key {
+        let local_var_key = local_var_apikey.key.clone();
+        let local_var_value = match local_var_apikey.prefix {
+            Some(ref local_var_prefix) => format!("{} {}", local_var_prefix, local_var_key),
+            None => local_var_key,
+        };
+        local_var_req_builder = local_var_req_builder.header("Authorization", local_var_value);
+    };
+
+    let local_var_req = local_var_req_builder.build()?;
+    let local_var_resp = local_var_client.execute(local_var_req).await?;
+
+    let local_var_status = local_var_resp.status();
+    let local_var_content = local_var_resp.text().await?;
+
+    if !local_var_status.is_client_error() && !local_var_status.is_server_error() {
+        let local_var_entity: Option<ListActivitiesSuccess> = serde_json::from_str(&local_var_content).ok();
+        let local_var_result = ResponseContent { status: local_var_status, content: local_var_content, entity: local_var_entity };
+        Ok(local_var_result)
+    } else {
+        let local_var_entity: Option<ListActivitiesError> = serde_json::from_str(&local_var_content).ok();
+        let local_var_error = ResponseContent { status: local_var_status, content: local_var_content, entity: local_var_entity };
+        Err(Error::ResponseError(local_var_error))
+    }
+}
+
+pub async fn update_activity(configuration: &configuration::Configuration, params: UpdateActivityParams) -> Result<ResponseContent<UpdateActivitySuccess>, Error<UpdateActivityError>> {
+    let local_var_configuration = configuration;
+
+    // unbox the parameters
+    let organization = params.organization;
+    let activity_id = params.activity_id;
+    let update_activity_req_payload = params.update_activity_req_payload;
+    let if_match = params.if_match;
+
+
+    let local_var_client = &local_var_configuration.client;
+
+    let local_var_uri_str = format!("{}/api/activities/{activity_id}", local_var_configuration.base_path, activity_id=crate::apis::urlencode(activity_id));
+    let mut local_var_req_builder = local_var_client.request(reqwest::Method::PUT, local_var_uri_str.as_str());
+
+    if let Some(ref local_var_user_agent) = local_var_configuration.user_agent {
+        local_var_req_builder = local_var_req_builder.header(reqwest::header::USER_AGENT, local_var_user_agent.clone());
+    }
+    local_var_req_builder = local_var_req_builder.header("Organization", organization.to_string());
+    if let Some(local_var_param_value) = if_match {
+        local_var_req_builder = local_var_req_builder.header("If-Match", local_var_param_value.to_string());
+    }
+    if let Some(ref local_var_apikey) = local_var_configuration.api_key {
+        let local_var_key = local_var_apikey.key.clone();
+        let local_var_value = match local_var_apikey.prefix {
+            Some(ref local_var_prefix) => format!("{} {}", local_var_prefix, local_var_key),
+            None => local_var_key,
+        };
+        local_var_req_builder = local_var_req_builder.header("Authorization", local_var_value);
+    };
+    local_var_req_builder = local_var_req_builder.json(&update_activity_req_payload);
+
+    let local_var_req = local_var_req_builder.build()?;
+    let local_var_resp = local_var_client.execute(local_var_req).await?;
+
+    let local_var_status = local_var_resp.status();
+    let local_var_content = local_var_resp.text().await?;
+
+    if !local_var_status.is_client_error() && !local_var_status.is_server_error() {
+        let local_var_entity: Option<UpdateActivitySuccess> = serde_json::from_str(&local_var_content).ok();
+        let local_var_result = ResponseContent { status: local_var_status, content: local_var_content, entity: local_var_entity };
+        Ok(local_var_result)
+    } else {
+        let local_var_entity: Option<UpdateActivityError> = serde_json::from_str(&local_var_content).ok();
+        let local_var_error = ResponseContent { status: local_var_status, content: local_var_content, entity: local_var_entity };
+        Err(Error::ResponseError(local_var_error))
+    }
+}
+
@@ -34,8 +34,11 @@ pub enum CreateApiKeySuccess {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum CreateApiKeyError {
+    Status400(models::ErrorRespPayload),
     Status401(models::ErrorRespPayload),
+    Status429(models::ErrorRespPayload),
     Status500(models::ErrorRespPayload),
+    Status503(models::ErrorRespPayload),
     UnknownValue(serde_json::Value),
 }
 
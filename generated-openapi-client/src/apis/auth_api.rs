@@ -75,6 +75,9 @@ pub enum WhoamiSuccess {
 #[serde(untagged)]
 pub enum CallbackError {
     Status400(models::ErrorRespPayload),
+    Status429(models::ErrorRespPayload),
+    Status500(models::ErrorRespPayload),
+    Status503(models::ErrorRespPayload),
     UnknownValue(serde_json::Value),
 }
 
@@ -83,6 +86,9 @@ pub enum CallbackError {
 #[serde(untagged)]
 pub enum LoginError {
     Status400(models::ErrorRespPayload),
+    Status429(models::ErrorRespPayload),
+    Status500(models::ErrorRespPayload),
+    Status503(models::ErrorRespPayload),
     UnknownValue(serde_json::Value),
 }
 
@@ -91,6 +97,9 @@ pub enum LoginError {
 #[serde(untagged)]
 pub enum LogoutError {
     Status401(),
+    Status429(models::ErrorRespPayload),
+    Status500(models::ErrorRespPayload),
+    Status503(models::ErrorRespPayload),
     UnknownValue(serde_json::Value),
 }
 
@@ -99,6 +108,10 @@ pub enum LogoutError {
 #[serde(untagged)]
 pub enum WhoamiError {
     Status400(models::ErrorRespPayload),
+    Status401(models::ErrorRespPayload),
+    Status429(models::ErrorRespPayload),
+    Status500(models::ErrorRespPayload),
+    Status503(models::ErrorRespPayload),
     UnknownValue(serde_json::Value),
 }
 
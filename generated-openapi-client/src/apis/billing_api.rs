@@ -0,0 +1,83 @@
+/*
+ * Trieve API
+ *
+ * Trieve OpenAPI Specification. This document describes all of the operations available through the Trieve API.
+ *
+ * The version of the OpenAPI document: 0.0.1
+ * Contact: developers@trieve.ai
+ * Generated by: https://openapi-generator.tech
+ */
+
+
+use reqwest;
+
+use crate::{apis::ResponseContent, models};
+use super::{Error, configuration};
+
+/// struct for passing parameters to the method [`billing_webhook`]
+#[derive(Clone, Debug)]
+pub struct BillingWebhookParams {
+    /// Signature of the payload computed with the webhook secret
+    pub stripe_signature: String,
+    /// Raw event payload as sent by Stripe
+    pub body: serde_json::Value
+}
+
+
+/// struct for typed successes of method [`billing_webhook`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum BillingWebhookSuccess {
+    Status200(),
+    UnknownValue(serde_json::Value),
+}
+
+/// struct for typed errors of method [`billing_webhook`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum BillingWebhookError {
+    Status400(models::ErrorRespPayload),
+    Status429(models::ErrorRespPayload),
+    Status500(models::ErrorRespPayload),
+    Status503(models::ErrorRespPayload),
+    UnknownValue(serde_json::Value),
+}
+
+
+/// Billing Webhook  Receives subscription lifecycle events from Stripe. Requests must carry a valid `Stripe-Signature` header signed with `STRIPE_WEBHOOK_SECRET`. Events are applied at most once, so redeliveries are acknowledged without changing anything. Events for organizations that don't exist are acknowledged and ignored. Events arrive out of order, so one older than the last event applied to its subscription, or for a canceled subscription, is acknowledged without changing anything.
+pub async fn billing_webhook(configuration: &configuration::Configuration, params: BillingWebhookParams) -> Result<ResponseContent<BillingWebhookSuccess>, Error<BillingWebhookError>> {
+    let local_var_configuration = configuration;
+
+    // unbox the parameters
+    let stripe_signature = params.stripe_signature;
+    let body = params.body;
+
+
+    let local_var_client = &local_var_configuration.client;
+
+    let local_var_uri_str = format!("{}/api/billing/webhook", local_var_configuration.base_path);
+    let mut local_var_req_builder = local_var_client.request(reqwest::Method::POST, local_var_uri_str.as_str());
+
+    if let Some(ref local_var_user_agent) = local_var_configuration.user_agent {
+        local_var_req_builder = local_var_req_builder.header(reqwest::header::USER_AGENT, local_var_user_agent.clone());
+    }
+    local_var_req_builder = local_var_req_builder.header("Stripe-Signature", stripe_signature.to_string());
+    local_var_req_builder = local_var_req_builder.json(&body);
+
+    let local_var_req = local_var_req_builder.build()?;
+    let local_var_resp = local_var_client.execute(local_var_req).await?;
+
+    let local_var_status = local_var_resp.status();
+    let local_var_content = local_var_resp.text().await?;
+
+    if !local_var_status.is_client_error() && !local_var_status.is_server_error() {
+        let local_var_entity: Option<BillingWebhookSuccess> = serde_json::from_str(&local_var_content).ok();
+        let local_var_result = ResponseContent { status: local_var_status, content: local_var_content, entity: local_var_entity };
+        Ok(local_var_result)
+    } else {
+        let local_var_entity: Option<BillingWebhookError> = serde_json::from_str(&local_var_content).ok();
+        let local_var_error = ResponseContent { status: local_var_status, content: local_var_content, entity: local_var_entity };
+        Err(Error::ResponseError(local_var_error))
+    }
+}
+
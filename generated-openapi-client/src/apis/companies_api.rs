@@ -20,7 +20,9 @@ pub struct CreateCompanyParams {
     /// The organization id to use for the request
     pub organization: String,
     /// JSON request payload to create a new company
-    pub create_company_req_payload: models::CreateCompanyReqPayload
+    pub create_company_req_payload: models::CreateCompanyReqPayload,
+    /// Unique key for this create. Retrying with the same key replays the first response instead of creating a duplicate
+    pub idempotency_key: Option<String>
 }
 
 /// struct for passing parameters to the method [`delete_company`]
@@ -29,7 +31,9 @@ pub struct DeleteCompanyParams {
     /// The organization id to use for the request
     pub organization: String,
     /// The id of the company you want to delete.
-    pub company_id: String
+    pub company_id: String,
+    /// ETag of the record as last fetched. The write fails with 412 if the record has changed since
+    pub if_match: Option<String>
 }
 
 /// struct for passing parameters to the method [`get_companies_for_org`]
@@ -40,7 +44,9 @@ pub struct GetCompaniesForOrgParams {
     /// Limit the number of results. Default is 10
     pub limit: Option<i64>,
     /// Offset the results. Default is 0
-    pub offset: Option<i64>
+    pub offset: Option<i64>,
+    /// Only return records owned by this user id, or `me` for the calling user
+    pub owner: Option<String>
 }
 
 /// struct for passing parameters to the method [`get_company_by_id`]
@@ -59,8 +65,10 @@ pub struct UpdateCompanyParams {
     pub organization: String,
     /// The id of the note you want to update.
     pub company_id: String,
-    /// JSON request payload to rename the company
-    pub update_company_req_payload: models::UpdateCompanyReqPayload
+    /// JSON merge patch to update the company. Omitted fields are left alone and null fields are cleared.
+    pub update_company_req_payload: models::UpdateCompanyReqPayload,
+    /// ETag of the record as last fetched. The write fails with 412 if the record has changed since
+    pub if_match: Option<String>
 }
 
 
@@ -108,7 +116,13 @@ pub enum UpdateCompanySuccess {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum CreateCompanyError {
+    Status400(models::ErrorRespPayload),
     Status401(models::ErrorRespPayload),
+    Status409(models::ErrorRespPayload),
+    Status422(models::ErrorRespPayload),
+    Status429(models::ErrorRespPayload),
+    Status500(models::ErrorRespPayload),
+    Status503(models::ErrorRespPayload),
     UnknownValue(serde_json::Value),
 }
 
@@ -116,7 +130,12 @@ pub enum CreateCompanyError {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum DeleteCompanyError {
+    Status400(models::ErrorRespPayload),
     Status401(models::ErrorRespPayload),
+    Status412(models::ErrorRespPayload),
+    Status429(models::ErrorRespPayload),
+    Status500(models::ErrorRespPayload),
+    Status503(models::ErrorRespPayload),
     UnknownValue(serde_json::Value),
 }
 
@@ -124,7 +143,11 @@ pub enum DeleteCompanyError {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum GetCompaniesForOrgError {
+    Status400(models::ErrorRespPayload),
     Status401(models::ErrorRespPayload),
+    Status429(models::ErrorRespPayload),
+    Status500(models::ErrorRespPayload),
+    Status503(models::ErrorRespPayload),
     UnknownValue(serde_json::Value),
 }
 
@@ -132,7 +155,11 @@ pub enum GetCompaniesForOrgError {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum GetCompanyByIdError {
+    Status400(models::ErrorRespPayload),
     Status401(models::ErrorRespPayload),
+    Status429(models::ErrorRespPayload),
+    Status500(models::ErrorRespPayload),
+    Status503(models::ErrorRespPayload),
     UnknownValue(serde_json::Value),
 }
 
@@ -140,7 +167,12 @@ pub enum GetCompanyByIdError {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum UpdateCompanyError {
+    Status400(models::ErrorRespPayload),
     Status401(models::ErrorRespPayload),
+    Status412(models::ErrorRespPayload),
+    Status429(models::ErrorRespPayload),
+    Status500(models::ErrorRespPayload),
+    Status503(models::ErrorRespPayload),
     UnknownValue(serde_json::Value),
 }
 
@@ -151,6 +183,7 @@ pub async fn create_company(configuration: &configuration::Configuration, params
     // unbox the parameters
     let organization = params.organization;
     let create_company_req_payload = params.create_company_req_payload;
+    let idempotency_key = params.idempotency_key;
 
 
     let local_var_client = &local_var_configuration.client;
@@ -162,6 +195,9 @@ pub async fn create_company(configuration: &configuration::Configuration, params
         local_var_req_builder = local_var_req_builder.header(reqwest::header::USER_AGENT, local_var_user_agent.clone());
     }
     local_var_req_builder = local_var_req_builder.header("Organization", organization.to_string());
+    if let Some(local_var_param_value) = idempotency_key {
+        local_var_req_builder = local_var_req_builder.header("Idempotency-Key", local_var_param_value.to_string());
+    }
     if let Some(ref local_var_apikey) = local_var_configuration.api_key {
         let local_var_key = local_var_apikey.key.clone();
         let local_var_value = match local_var_apikey.prefix {
@@ -195,6 +231,7 @@ pub async fn delete_company(configuration: &configuration::Configuration, params
     // unbox the parameters
     let organization = params.organization;
     let company_id = params.company_id;
+    let if_match = params.if_match;
 
 
     let local_var_client = &local_var_configuration.client;
@@ -206,6 +243,9 @@ pub async fn delete_company(configuration: &configuration::Configuration, params
         local_var_req_builder = local_var_req_builder.header(reqwest::header::USER_AGENT, local_var_user_agent.clone());
     }
     local_var_req_builder = local_var_req_builder.header("Organization", organization.to_string());
+    if let Some(local_var_param_value) = if_match {
+        local_var_req_builder = local_var_req_builder.header("If-Match", local_var_param_value.to_string());
+    }
     if let Some(ref local_var_apikey) = local_var_configuration.api_key {
         let local_var_key = local_var_apikey.key.clone();
         let local_var_value = match local_var_apikey.prefix {
@@ -239,6 +279,7 @@ pub async fn get_companies_for_org(configuration: &configuration::Configuration,
     let organization = params.organization;
     let limit = params.limit;
     let offset = params.offset;
+    let owner = params.owner;
 
 
     let local_var_client = &local_var_configuration.client;
@@ -252,6 +293,9 @@ pub async fn get_companies_for_org(configuration: &configuration::Configuration,
     if let Some(ref local_var_str) = offset {
         local_var_req_builder = local_var_req_builder.query(&[("offset", &local_var_str.to_string())]);
     }
+    if let Some(ref local_var_str) = owner {
+        local_var_req_builder = local_var_req_builder.query(&[("owner", &local_var_str.to_string())]);
+    }
     if let Some(ref local_var_user_agent) = local_var_configuration.user_agent {
         local_var_req_builder = local_var_req_builder.header(reqwest::header::USER_AGENT, local_var_user_agent.clone());
     }
@@ -332,17 +376,21 @@ pub async fn update_company(configuration: &configuration::Configuration, params
     let organization = params.organization;
     let company_id = params.company_id;
     let update_company_req_payload = params.update_company_req_payload;
+    let if_match = params.if_match;
 
 
     let local_var_client = &local_var_configuration.client;
 
     let local_var_uri_str = format!("{}/api/companies/{company_id}", local_var_configuration.base_path, company_id=crate::apis::urlencode(company_id));
-    let mut local_var_req_builder = local_var_client.request(reqwest::Method::PUT, local_var_uri_str.as_str());
+    let mut local_var_req_builder = local_var_client.request(reqwest::Method::PATCH, local_var_uri_str.as_str());
 
     if let Some(ref local_var_user_agent) = local_var_configuration.user_agent {
         local_var_req_builder = local_var_req_builder.header(reqwest::header::USER_AGENT, local_var_user_agent.clone());
     }
     local_var_req_builder = local_var_req_builder.header("Organization", organization.to_string());
+    if let Some(local_var_param_value) = if_match {
+        local_var_req_builder = local_var_req_builder.header("If-Match", local_var_param_value.to_string());
+    }
     if let Some(ref local_var_apikey) = local_var_configuration.api_key {
         let local_var_key = local_var_apikey.key.clone();
         let local_var_value = match local_var_apikey.prefix {
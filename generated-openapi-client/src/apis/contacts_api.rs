@@ -20,7 +20,9 @@ pub struct CreateContactParams {
     /// The org id to use for the request
     pub organization: String,
     /// JSON request payload to create a new contact
-    pub create_contact_req_payload: models::CreateContactReqPayload
+    pub create_contact_req_payload: models::CreateContactReqPayload,
+    /// Unique key for this create. Retrying with the same key replays the first response instead of creating a duplicate
+    pub idempotency_key: Option<String>
 }
 
 /// struct for passing parameters to the method [`delete_contact`]
@@ -29,7 +31,9 @@ pub struct DeleteContactParams {
     /// The contacts id to use for the request
     pub contact_id: String,
     /// The org id to use for the request
-    pub organization: String
+    pub organization: String,
+    /// ETag of the record as last fetched. The write fails with 412 if the record has changed since
+    pub if_match: Option<String>
 }
 
 /// struct for passing parameters to the method [`get_contact`]
@@ -49,7 +53,9 @@ pub struct ListContactsParams {
     /// The number of contacts to return
     pub limit: Option<i64>,
     /// The offset to start from
-    pub offset: Option<String>
+    pub offset: Option<String>,
+    /// Only return records owned by this user id, or `me` for the calling user
+    pub owner: Option<String>
 }
 
 /// struct for passing parameters to the method [`update_contact`]
@@ -59,8 +65,10 @@ pub struct UpdateContactParams {
     pub contact_id: String,
     /// The org id to use for the request
     pub organization: String,
-    /// JSON request payload to update the contact
-    pub update_contact_req_payload: models::UpdateContactReqPayload
+    /// JSON merge patch to update the contact. Omitted fields are left alone and null fields are cleared.
+    pub update_contact_req_payload: models::UpdateContactReqPayload,
+    /// ETag of the record as last fetched. The write fails with 412 if the record has changed since
+    pub if_match: Option<String>
 }
 
 
@@ -108,7 +116,13 @@ pub enum UpdateContactSuccess {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum CreateContactError {
+    Status400(models::ErrorRespPayload),
     Status401(models::ErrorRespPayload),
+    Status409(models::ErrorRespPayload),
+    Status422(models::ErrorRespPayload),
+    Status429(models::ErrorRespPayload),
+    Status500(models::ErrorRespPayload),
+    Status503(models::ErrorRespPayload),
     UnknownValue(serde_json::Value),
 }
 
@@ -116,7 +130,12 @@ pub enum CreateContactError {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum DeleteContactError {
+    Status400(models::ErrorRespPayload),
     Status401(models::ErrorRespPayload),
+    Status412(models::ErrorRespPayload),
+    Status429(models::ErrorRespPayload),
+    Status500(models::ErrorRespPayload),
+    Status503(models::ErrorRespPayload),
     UnknownValue(serde_json::Value),
 }
 
@@ -124,7 +143,11 @@ pub enum DeleteContactError {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum GetContactError {
+    Status400(models::ErrorRespPayload),
     Status401(models::ErrorRespPayload),
+    Status429(models::ErrorRespPayload),
+    Status500(models::ErrorRespPayload),
+    Status503(models::ErrorRespPayload),
     UnknownValue(serde_json::Value),
 }
 
@@ -133,6 +156,9 @@ pub enum GetContactError {
 #[serde(untagged)]
 pub enum ListContactsError {
     Status401(models::ErrorRespPayload),
+    Status429(models::ErrorRespPayload),
+    Status500(models::ErrorRespPayload),
+    Status503(models::ErrorRespPayload),
     UnknownValue(serde_json::Value),
 }
 
@@ -140,7 +166,12 @@ pub enum ListContactsError {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum UpdateContactError {
+    Status400(models::ErrorRespPayload),
     Status401(models::ErrorRespPayload),
+    Status412(models::ErrorRespPayload),
+    Status429(models::ErrorRespPayload),
+    Status500(models::ErrorRespPayload),
+    Status503(models::ErrorRespPayload),
     UnknownValue(serde_json::Value),
 }
 
@@ -151,6 +182,7 @@ pub async fn create_contact(configuration: &configuration::Configuration, params
     // unbox the parameters
     let organization = params.organization;
     let create_contact_req_payload = params.create_contact_req_payload;
+    let idempotency_key = params.idempotency_key;
 
 
     let local_var_client = &local_var_configuration.client;
@@ -162,6 +194,9 @@ pub async fn create_contact(configuration: &configuration::Configuration, params
         local_var_req_builder = local_var_req_builder.header(reqwest::header::USER_AGENT, local_var_user_agent.clone());
     }
     local_var_req_builder = local_var_req_builder.header("Organization", organization.to_string());
+    if let Some(local_var_param_value) = idempotency_key {
+        local_var_req_builder = local_var_req_builder.header("Idempotency-Key", local_var_param_value.to_string());
+    }
     if let Some(ref local_var_apikey) = local_var_configuration.api_key {
         let local_var_key = local_var_apikey.key.clone();
         let local_var_value = match local_var_apikey.prefix {
@@ -195,6 +230,7 @@ pub async fn delete_contact(configuration: &configuration::Configuration, params
     // unbox the parameters
     let contact_id = params.contact_id;
     let organization = params.organization;
+    let if_match = params.if_match;
 
 
     let local_var_client = &local_var_configuration.client;
@@ -206,6 +242,9 @@ pub async fn delete_contact(configuration: &configuration::Configuration, params
         local_var_req_builder = local_var_req_builder.header(reqwest::header::USER_AGENT, local_var_user_agent.clone());
     }
     local_var_req_builder = local_var_req_builder.header("Organization", organization.to_string());
+    if let Some(local_var_param_value) = if_match {
+        local_var_req_builder = local_var_req_builder.header("If-Match", local_var_param_value.to_string());
+    }
     if let Some(ref local_var_apikey) = local_var_configuration.api_key {
         let local_var_key = local_var_apikey.key.clone();
         let local_var_value = match local_var_apikey.prefix {
@@ -282,6 +321,7 @@ pub async fn list_contacts(configuration: &configuration::Configuration, params:
     let organization = params.organization;
     let limit = params.limit;
     let offset = params.offset;
+    let owner = params.owner;
 
 
     let local_var_client = &local_var_configuration.client;
@@ -295,6 +335,9 @@ pub async fn list_contacts(configuration: &configuration::Configuration, params:
     if let Some(ref local_var_str) = offset {
         local_var_req_builder = local_var_req_builder.query(&[("offset", &local_var_str.to_string())]);
     }
+    if let Some(ref local_var_str) = owner {
+        local_var_req_builder = local_var_req_builder.query(&[("owner", &local_var_str.to_string())]);
+    }
     if let Some(ref local_var_user_agent) = local_var_configuration.user_agent {
         local_var_req_builder = local_var_req_builder.header(reqwest::header::USER_AGENT, local_var_user_agent.clone());
     }
@@ -332,17 +375,21 @@ pub async fn update_contact(configuration: &configuration::Configuration, params
     let contact_id = params.contact_id;
     let organization = params.organization;
     let update_contact_req_payload = params.update_contact_req_payload;
+    let if_match = params.if_match;
 
 
     let local_var_client = &local_var_configuration.client;
 
     let local_var_uri_str = format!("{}/api/contacts/{contact_id}", local_var_configuration.base_path, contact_id=crate::apis::urlencode(contact_id));
-    let mut local_var_req_builder = local_var_client.request(reqwest::Method::PUT, local_var_uri_str.as_str());
+    let mut local_var_req_builder = local_var_client.request(reqwest::Method::PATCH, local_var_uri_str.as_str());
 
     if let Some(ref local_var_user_agent) = local_var_configuration.user_agent {
         local_var_req_builder = local_var_req_builder.header(reqwest::header::USER_AGENT, local_var_user_agent.clone());
     }
     local_var_req_builder = local_var_req_builder.header("Organization", organization.to_string());
+    if let Some(local_var_param_value) = if_match {
+        local_var_req_builder = local_var_req_builder.header("If-Match", local_var_param_value.to_string());
+    }
     if let Some(ref local_var_apikey) = local_var_configuration.api_key {
         let local_var_key = local_var_apikey.key.clone();
         let local_var_value = match local_var_apikey.prefix {
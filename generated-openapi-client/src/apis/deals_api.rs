@@ -20,7 +20,9 @@ pub struct CreateDealParams {
     /// The org id to use for the request
     pub organization: String,
     /// JSON request payload to create a new deal
-    pub create_deal_req_payload: models::CreateDealReqPayload
+    pub create_deal_req_payload: models::CreateDealReqPayload,
+    /// Unique key for this create. Retrying with the same key replays the first response instead of creating a duplicate
+    pub idempotency_key: Option<String>
 }
 
 /// struct for passing parameters to the method [`create_deal_resource`]
@@ -42,7 +44,9 @@ pub struct DeleteDealParams {
     /// The deal id to use for the request
     pub deal_id: String,
     /// The org id to use for the request
-    pub organization: String
+    pub organization: String,
+    /// ETag of the record as last fetched. The write fails with 412 if the record has changed since
+    pub if_match: Option<String>
 }
 
 /// struct for passing parameters to the method [`delete_deal_resource`]
@@ -67,6 +71,39 @@ pub struct GetDealParams {
     pub organization: String
 }
 
+/// struct for passing parameters to the method [`get_forecast_report`]
+#[derive(Clone, Debug)]
+pub struct GetForecastReportParams {
+    /// The organization id to use for the request
+    pub organization: String,
+    /// Only include deals expected to close on or after this date
+    pub from: Option<String>,
+    /// Only include deals expected to close on or before this date
+    pub to: Option<String>
+}
+
+/// struct for passing parameters to the method [`get_performance_report`]
+#[derive(Clone, Debug)]
+pub struct GetPerformanceReportParams {
+    /// The organization id to use for the request
+    pub organization: String,
+    /// Only include deals closed on or after this date
+    pub from: Option<String>,
+    /// Only include deals closed on or before this date
+    pub to: Option<String>
+}
+
+/// struct for passing parameters to the method [`get_pipeline_report`]
+#[derive(Clone, Debug)]
+pub struct GetPipelineReportParams {
+    /// The organization id to use for the request
+    pub organization: String,
+    /// Only include deals expected to close on or after this date
+    pub from: Option<String>,
+    /// Only include deals expected to close on or before this date
+    pub to: Option<String>
+}
+
 /// struct for passing parameters to the method [`list_deal_by_org`]
 #[derive(Clone, Debug)]
 pub struct ListDealByOrgParams {
@@ -75,7 +112,9 @@ pub struct ListDealByOrgParams {
     /// The number of records to return
     pub limit: Option<i64>,
     /// The UUID of the record to start from
-    pub offset: Option<String>
+    pub offset: Option<String>,
+    /// Only return records owned by this user id, or `me` for the calling user
+    pub owner: Option<String>
 }
 
 /// struct for passing parameters to the method [`list_deal_resource`]
@@ -100,8 +139,10 @@ pub struct UpdateDealParams {
     pub deal_id: String,
     /// The org id to use for the request
     pub organization: String,
-    /// JSON request payload to update the deal
-    pub update_deal_req_payload: models::UpdateDealReqPayload
+    /// JSON merge patch to update the deal. Omitted fields are left alone and null fields are cleared.
+    pub update_deal_req_payload: models::UpdateDealReqPayload,
+    /// ETag of the record as last fetched. The write fails with 412 if the record has changed since
+    pub if_match: Option<String>
 }
 
 
@@ -145,6 +186,38 @@ pub enum GetDealSuccess {
     UnknownValue(serde_json::Value),
 }
 
+/// struct for typed successes of method [`get_forecast_report`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum GetForecastReportSuccess {
+    Status200(models::ForecastReport),
+    UnknownValue(serde_json::Value),
+}
+
+/// struct for typed successes of method [`get_performance_report`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum GetPerformanceReportSuccess {
+    Status200(models::PerformanceReport),
+    UnknownValue(serde_json::Value),
+}
+
+/// struct for typed successes of method [`get_pipeline_report`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum GetPipelineReportSuccess {
+    Status200(models::PipelineReport),
+    UnknownValue(serde_json::Value),
+}
+
+/// struct for typed successes of method [`list_currencies`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ListCurrenciesSuccess {
+    Status200(Vec<models::Currency>),
+    UnknownValue(serde_json::Value),
+}
+
 /// struct for typed successes of method [`list_deal_by_org`]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
@@ -173,7 +246,14 @@ pub enum UpdateDealSuccess {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum CreateDealError {
+    Status400(models::ErrorRespPayload),
     Status401(models::ErrorRespPayload),
+    Status402(models::ErrorRespPayload),
+    Status409(models::ErrorRespPayload),
+    Status422(models::ErrorRespPayload),
+    Status429(models::ErrorRespPayload),
+    Status500(models::ErrorRespPayload),
+    Status503(models::ErrorRespPayload),
     UnknownValue(serde_json::Value),
 }
 
@@ -183,6 +263,9 @@ pub enum CreateDealError {
 pub enum CreateDealResourceError {
     Status400(models::ErrorRespPayload),
     Status401(models::ErrorRespPayload),
+    Status429(models::ErrorRespPayload),
+    Status500(models::ErrorRespPayload),
+    Status503(models::ErrorRespPayload),
     UnknownValue(serde_json::Value),
 }
 
@@ -190,7 +273,12 @@ pub enum CreateDealResourceError {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum DeleteDealError {
+    Status400(models::ErrorRespPayload),
     Status401(models::ErrorRespPayload),
+    Status412(models::ErrorRespPayload),
+    Status429(models::ErrorRespPayload),
+    Status500(models::ErrorRespPayload),
+    Status503(models::ErrorRespPayload),
     UnknownValue(serde_json::Value),
 }
 
@@ -200,6 +288,9 @@ pub enum DeleteDealError {
 pub enum DeleteDealResourceError {
     Status400(models::ErrorRespPayload),
     Status401(models::ErrorRespPayload),
+    Status429(models::ErrorRespPayload),
+    Status500(models::ErrorRespPayload),
+    Status503(models::ErrorRespPayload),
     UnknownValue(serde_json::Value),
 }
 
@@ -207,7 +298,58 @@ pub enum DeleteDealResourceError {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum GetDealError {
+    Status400(models::ErrorRespPayload),
     Status401(models::ErrorRespPayload),
+    Status429(models::ErrorRespPayload),
+    Status500(models::ErrorRespPayload),
+    Status503(models::ErrorRespPayload),
+    UnknownValue(serde_json::Value),
+}
+
+/// struct for typed errors of method [`get_forecast_report`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum GetForecastReportError {
+    Status400(models::ErrorRespPayload),
+    Status401(models::ErrorRespPayload),
+    Status429(models::ErrorRespPayload),
+    Status500(models::ErrorRespPayload),
+    Status503(models::ErrorRespPayload),
+    UnknownValue(serde_json::Value),
+}
+
+/// struct for typed errors of method [`get_performance_report`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum GetPerformanceReportError {
+    Status400(models::ErrorRespPayload),
+    Status401(models::ErrorRespPayload),
+    Status429(models::ErrorRespPayload),
+    Status500(models::ErrorRespPayload),
+    Status503(models::ErrorRespPayload),
+    UnknownValue(serde_json::Value),
+}
+
+/// struct for typed errors of method [`get_pipeline_report`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum GetPipelineReportError {
+    Status400(models::ErrorRespPayload),
+    Status401(models::ErrorRespPayload),
+    Status429(models::ErrorRespPayload),
+    Status500(models::ErrorRespPayload),
+    Status503(models::ErrorRespPayload),
+    UnknownValue(serde_json::Value),
+}
+
+/// struct for typed errors of method [`list_currencies`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ListCurrenciesError {
+    Status401(models::ErrorRespPayload),
+    Status429(models::ErrorRespPayload),
+    Status500(models::ErrorRespPayload),
+    Status503(models::ErrorRespPayload),
     UnknownValue(serde_json::Value),
 }
 
@@ -216,6 +358,9 @@ pub enum GetDealError {
 #[serde(untagged)]
 pub enum ListDealByOrgError {
     Status401(models::ErrorRespPayload),
+    Status429(models::ErrorRespPayload),
+    Status500(models::ErrorRespPayload),
+    Status503(models::ErrorRespPayload),
     UnknownValue(serde_json::Value),
 }
 
@@ -225,6 +370,9 @@ pub enum ListDealByOrgError {
 pub enum ListDealResourceError {
     Status400(models::ErrorRespPayload),
     Status401(models::ErrorRespPayload),
+    Status429(models::ErrorRespPayload),
+    Status500(models::ErrorRespPayload),
+    Status503(models::ErrorRespPayload),
     UnknownValue(serde_json::Value),
 }
 
@@ -232,7 +380,12 @@ pub enum ListDealResourceError {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum UpdateDealError {
+    Status400(models::ErrorRespPayload),
     Status401(models::ErrorRespPayload),
+    Status412(models::ErrorRespPayload),
+    Status429(models::ErrorRespPayload),
+    Status500(models::ErrorRespPayload),
+    Status503(models::ErrorRespPayload),
     UnknownValue(serde_json::Value),
 }
 
@@ -243,6 +396,7 @@ pub async fn create_deal(configuration: &configuration::Configuration, params: C
     // unbox the parameters
     let organization = params.organization;
     let create_deal_req_payload = params.create_deal_req_payload;
+    let idempotency_key = params.idempotency_key;
 
 
     let local_var_client = &local_var_configuration.client;
@@ -254,6 +408,9 @@ pub async fn create_deal(configuration: &configuration::Configuration, params: C
         local_var_req_builder = local_var_req_builder.header(reqwest::header::USER_AGENT, local_var_user_agent.clone());
     }
     local_var_req_builder = local_var_req_builder.header("Organization", organization.to_string());
+    if let Some(local_var_param_value) = idempotency_key {
+        local_var_req_builder = local_var_req_builder.header("Idempotency-Key", local_var_param_value.to_string());
+    }
     if let Some(ref local_var_apikey) = local_var_configuration.api_key {
         let local_var_key = local_var_apikey.key.clone();
         let local_var_value = match local_var_apikey.prefix {
@@ -332,6 +489,7 @@ pub async fn delete_deal(configuration: &configuration::Configuration, params: D
     // unbox the parameters
     let deal_id = params.deal_id;
     let organization = params.organization;
+    let if_match = params.if_match;
 
 
     let local_var_client = &local_var_configuration.client;
@@ -343,6 +501,9 @@ pub async fn delete_deal(configuration: &configuration::Configuration, params: D
         local_var_req_builder = local_var_req_builder.header(reqwest::header::USER_AGENT, local_var_user_agent.clone());
     }
     local_var_req_builder = local_var_req_builder.header("Organization", organization.to_string());
+    if let Some(local_var_param_value) = if_match {
+        local_var_req_builder = local_var_req_builder.header("If-Match", local_var_param_value.to_string());
+    }
     if let Some(ref local_var_apikey) = local_var_configuration.api_key {
         let local_var_key = local_var_apikey.key.clone();
         let local_var_value = match local_var_apikey.prefix {
@@ -457,6 +618,196 @@ pub async fn get_deal(configuration: &configuration::Configuration, params: GetD
     }
 }
 
+pub async fn get_forecast_report(configuration: &configuration::Configuration, params: GetForecastReportParams) -> Result<ResponseContent<GetForecastReportSuccess>, Error<GetForecastReportError>> {
+    let local_var_configuration = configuration;
+
+    // unbox the parameters
+    let organization = params.organization;
+    let from = params.from;
+    let to = params.to;
+
+
+    let local_var_client = &local_var_configuration.client;
+
+    let local_var_uri_str = format!("{}/api/deals/reports/forecast", local_var_configuration.base_path);
+    let mut local_var_req_builder = local_var_client.request(reqwest::Method::GET, local_var_uri_str.as_str());
+
+    if let Some(ref local_var_str) = from {
+        local_var_req_builder = local_var_req_builder.query(&[("from", &local_var_str.to_string())]);
+    }
+    if let Some(ref local_var_str) = to {
+        local_var_req_builder = local_var_req_builder.query(&[("to", &local_var_str.to_string())]);
+    }
+    if let Some(ref local_var_user_agent) = local_var_configuration.user_agent {
+        local_var_req_builder = local_var_req_builder.header(reqwest::header::USER_AGENT, local_var_user_agent.clone());
+    }
+    local_var_req_builder = local_var_req_builder.header("Organization", organization.to_string());
+    if let Some(ref local_var_apikey) = local_var_configuration.api_key {
+        let local_var_key = local_var_apikey.key.clone();
+        let local_var_value = match local_var_apikey.prefix {
+            Some(ref local_var_prefix) => format!("{} {}", local_var_prefix, local_var_key),
+            None => local_var_key,
+        };
+        local_var_req_builder = local_var_req_builder.header("Authorization", local_var_value);
+    };
+
+    let local_var_req = local_var_req_builder.build()?;
+    let local_var_resp = local_var_client.execute(local_var_req).await?;
+
+    let local_var_status = local_var_resp.status();
+    let local_var_content = local_var_resp.text().await?;
+
+    if !local_var_status.is_client_error() && !local_var_status.is_server_error() {
+        let local_var_entity: Option<GetForecastReportSuccess> = serde_json::from_str(&local_var_content).ok();
+        let local_var_result = ResponseContent { status: local_var_status, content: local_var_content, entity: local_var_entity };
+        Ok(local_var_result)
+    } else {
+        let local_var_entity: Option<GetForecastReportError> = serde_json::from_str(&local_var_content).ok();
+        let local_var_error = ResponseContent { status: local_var_status, content: local_var_content, entity: local_var_entity };
+        Err(Error::ResponseError(local_var_error))
+    }
+}
+
+pub async fn get_performance_report(configuration: &configuration::Configuration, params: GetPerformanceReportParams) -> Result<ResponseContent<GetPerformanceReportSuccess>, Error<GetPerformanceReportError>> {
+    let local_var_configuration = configuration;
+
+    // unbox the parameters
+    let organization = params.organization;
+    let from = params.from;
+    let to = params.to;
+
+
+    let local_var_client = &local_var_configuration.client;
+
+    let local_var_uri_str = format!("{}/api/deals/reports/performance", local_var_configuration.base_path);
+    let mut local_var_req_builder = local_var_client.request(reqwest::Method::GET, local_var_uri_str.as_str());
+
+    if let Some(ref local_var_str) = from {
+        local_var_req_builder = local_var_req_builder.query(&[("from", &local_var_str.to_string())]);
+    }
+    if let Some(ref local_var_str) = to {
+        local_var_req_builder = local_var_req_builder.query(&[("to", &local_var_str.to_string())]);
+    }
+    if let Some(ref local_var_user_agent) = local_var_configuration.user_agent {
+        local_var_req_builder = local_var_req_builder.header(reqwest::header::USER_AGENT, local_var_user_agent.clone());
+    }
+    local_var_req_builder = local_var_req_builder.header("Organization", organization.to_string());
+    if let Some(ref local_var_apikey) = local_var_configuration.api_key {
+        let local_var_key = local_var_apikey.key.clone();
+        let local_var_value = match local_var_apikey.prefix {
+            Some(ref local_var_prefix) => format!("{} {}", local_var_prefix, local_var_key),
+            None => local_var_key,
+        };
+        local_var_req_builder = local_var_req_builder.header("Authorization", local_var_value);
+    };
+
+    let local_var_req = local_var_req_builder.build()?;
+    let local_var_resp = local_var_client.execute(local_var_req).await?;
+
+    let local_var_status = local_var_resp.status();
+    let local_var_content = local_var_resp.text().await?;
+
+    if !local_var_status.is_client_error() && !local_var_status.is_server_error() {
+        let local_var_entity: Option<GetPerformanceReportSuccess> = serde_json::from_str(&local_var_content).ok();
+        let local_var_result = ResponseContent { status: local_var_status, content: local_var_content, entity: local_var_entity };
+        Ok(local_var_result)
+    } else {
+        let local_var_entity: Option<GetPerformanceReportError> = serde_json::from_str(&local_var_content).ok();
+        let local_var_error = ResponseContent { status: local_var_status, content: local_var_content, entity: local_var_entity };
+        Err(Error::ResponseError(local_var_error))
+    }
+}
+
+pub async fn get_pipeline_report(configuration: &configuration::Configuration, params: GetPipelineReportParams) -> Result<ResponseContent<GetPipelineReportSuccess>, Error<GetPipelineReportError>> {
+    let local_var_configuration = configuration;
+
+    // unbox the parameters
+    let organization = params.organization;
+    let from = params.from;
+    let to = params.to;
+
+
+    let local_var_client = &local_var_configuration.client;
+
+    let local_var_uri_str = format!("{}/api/deals/reports/pipeline", local_var_configuration.base_path);
+    let mut local_var_req_builder = local_var_client.request(reqwest::Method::GET, local_var_uri_str.as_str());
+
+    if let Some(ref local_var_str) = from {
+        local_var_req_builder = local_var_req_builder.query(&[("from", &local_var_str.to_string())]);
+    }
+    if let Some(ref local_var_str) = to {
+        local_var_req_builder = local_var_req_builder.query(&[("to", &local_var_str.to_string())]);
+    }
+    if let Some(ref local_var_user_agent) = local_var_configuration.user_agent {
+        local_var_req_builder = local_var_req_builder.header(reqwest::header::USER_AGENT, local_var_user_agent.clone());
+    }
+    local_var_req_builder = local_var_req_builder.header("Organization", organization.to_string());
+    if let Some(ref local_var_apikey) = local_var_configuration.api_key {
+        let local_var_key = local_var_apikey.key.clone();
+        let local_var_value = match local_var_apikey.prefix {
+            Some(ref local_var_prefix) => format!("{} {}", local_var_prefix, local_var_key),
+            None => local_var_key,
+        };
+        local_var_req_builder = local_var_req_builder.header("Authorization", local_var_value);
+    };
+
+    let local_var_req = local_var_req_builder.build()?;
+    let local_var_resp = local_var_client.execute(local_var_req).await?;
+
+    let local_var_status = local_var_resp.status();
+    let local_var_content = local_var_resp.text().await?;
+
+    if !local_var_status.is_client_error() && !local_var_status.is_server_error() {
+        let local_var_entity: Option<GetPipelineReportSuccess> = serde_json::from_str(&local_var_content).ok();
+        let local_var_result = ResponseContent { status: local_var_status, content: local_var_content, entity: local_var_entity };
+        Ok(local_var_result)
+    } else {
+        let local_var_entity: Option<GetPipelineReportError> = serde_json::from_str(&local_var_content).ok();
+        let local_var_error = ResponseContent { status: local_var_status, content: local_var_content, entity: local_var_entity };
+        Err(Error::ResponseError(local_var_error))
+    }
+}
+
+pub async fn list_currencies(configuration: &configuration::Configuration) -> Result<ResponseContent<ListCurrenciesSuccess>, Error<ListCurrenciesError>> {
+    let local_var_configuration = configuration;
+
+    // unbox the parameters
+
+
+    let local_var_client = &local_var_configuration.client;
+
+    let local_var_uri_str = format!("{}/api/deals/currencies", local_var_configuration.base_path);
+    let mut local_var_req_builder = local_var_client.request(reqwest::Method::GET, local_var_uri_str.as_str());
+
+    if let Some(ref local_var_user_agent) = local_var_configuration.user_agent {
+        local_var_req_builder = local_var_req_builder.header(reqwest::header::USER_AGENT, local_var_user_agent.clone());
+    }
+    if let Some(ref local_var_apikey) = local_var_configuration.api_key {
+        let local_var_key = local_var_apikey.key.clone();
+        let local_var_value = match local_var_apikey.prefix {
+            Some(ref local_var_prefix) => format!("{} {}", local_var_prefix, local_var_key),
+            None => local_var_key,
+        };
+        local_var_req_builder = local_var_req_builder.header("Authorization", local_var_value);
+    };
+
+    let local_var_req = local_var_req_builder.build()?;
+    let local_var_resp = local_var_client.execute(local_var_req).await?;
+
+    let local_var_status = local_var_resp.status();
+    let local_var_content = local_var_resp.text().await?;
+
+    if !local_var_status.is_client_error() && !local_var_status.is_server_error() {
+        let local_var_entity: Option<ListCurrenciesSuccess> = serde_json::from_str(&local_var_content).ok();
+        let local_var_result = ResponseContent { status: local_var_status, content: local_var_content, entity: local_var_entity };
+        Ok(local_var_result)
+    } else {
+        let local_var_entity: Option<ListCurrenciesError> = serde_json::from_str(&local_var_content).ok();
+        let local_var_error = ResponseContent { status: local_var_status, content: local_var_content, entity: local_var_entity };
+        Err(Error::ResponseError(local_var_error))
+    }
+}
+
 pub async fn list_deal_by_org(configuration: &configuration::Configuration, params: ListDealByOrgParams) -> Result<ResponseContent<ListDealByOrgSuccess>, Error<ListDealByOrgError>> {
     let local_var_configuration = configuration;
 
@@ -464,6 +815,7 @@ pub async fn list_deal_by_org(configuration: &configuration::Configuration, para
     let organization = params.organization;
     let limit = params.limit;
     let offset = params.offset;
+    let owner = params.owner;
 
 
     let local_var_client = &local_var_configuration.client;
@@ -477,6 +829,9 @@ pub async fn list_deal_by_org(configuration: &configuration::Configuration, para
     if let Some(ref local_var_str) = offset {
         local_var_req_builder = local_var_req_builder.query(&[("offset", &local_var_str.to_string())]);
     }
+    if let Some(ref local_var_str) = owner {
+        local_var_req_builder = local_var_req_builder.query(&[("owner", &local_var_str.to_string())]);
+    }
     if let Some(ref local_var_user_agent) = local_var_configuration.user_agent {
         local_var_req_builder = local_var_req_builder.header(reqwest::header::USER_AGENT, local_var_user_agent.clone());
     }
@@ -566,17 +921,21 @@ pub async fn update_deal(configuration: &configuration::Configuration, params: U
     let deal_id = params.deal_id;
     let organization = params.organization;
     let update_deal_req_payload = params.update_deal_req_payload;
+    let if_match = params.if_match;
 
 
     let local_var_client = &local_var_configuration.client;
 
     let local_var_uri_str = format!("{}/api/deals/{deal_id}", local_var_configuration.base_path, deal_id=crate::apis::urlencode(deal_id));
-    let mut local_var_req_builder = local_var_client.request(reqwest::Method::PUT, local_var_uri_str.as_str());
+    let mut local_var_req_builder = local_var_client.request(reqwest::Method::PATCH, local_var_uri_str.as_str());
 
     if let Some(ref local_var_user_agent) = local_var_configuration.user_agent {
         local_var_req_builder = local_var_req_builder.header(reqwest::header::USER_AGENT, local_var_user_agent.clone());
     }
     local_var_req_builder = local_var_req_builder.header("Organization", organization.to_string());
+    if let Some(local_var_param_value) = if_match {
+        local_var_req_builder = local_var_req_builder.header("If-Match", local_var_param_value.to_string());
+    }
     if let Some(ref local_var_apikey) = local_var_configuration.api_key {
         let local_var_key = local_var_apikey.key.clone();
         let local_var_value = match local_var_apikey.prefix {
@@ -0,0 +1,102 @@
+/*
+ * Trieve API
+ *
+ * Trieve OpenAPI Specification. This document describes all of the operations available through the Trieve API.
+ *
+ * The version of the OpenAPI document: 0.0.1
+ * Contact: developers@trieve.ai
+ * Generated by: https://openapi-generator.tech
+ */
+
+
+use reqwest;
+
+use crate::{apis::ResponseContent, models};
+use super::{Error, configuration};
+
+/// struct for passing parameters to the method [`stream_events`]
+#[derive(Clone, Debug)]
+pub struct StreamEventsParams {
+    /// The organization id to use for the request
+    pub organization: Option<String>,
+    /// Id of the last event the client received. Retained events after it are sent first.
+    pub last_event_id: Option<String>,
+    /// Organization to stream events for. Browsers can't set headers on an `EventSource`, so this can be used instead of the `Organization` header.
+    pub org_id: Option<String>
+}
+
+
+/// struct for typed successes of method [`stream_events`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum StreamEventsSuccess {
+    Status200(models::ChangeEvent),
+    UnknownValue(serde_json::Value),
+}
+
+/// struct for typed errors of method [`stream_events`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum StreamEventsError {
+    Status400(models::ErrorRespPayload),
+    Status401(models::ErrorRespPayload),
+    Status429(models::ErrorRespPayload),
+    Status500(models::ErrorRespPayload),
+    Status503(models::ErrorRespPayload),
+    UnknownValue(serde_json::Value),
+}
+
+
+/// Stream Org Changes  Server-sent events stream of changes to the records of an organization. Each message carries a ChangeEvent as its data and the event id as its SSE id. Reconnecting with `Last-Event-ID` replays the retained events that were missed. Clients that fall too far behind are disconnected and should reconnect the same way.
+pub async fn stream_events(configuration: &configuration::Configuration, params: StreamEventsParams) -> Result<ResponseContent<StreamEventsSuccess>, Error<StreamEventsError>> {
+    let local_var_configuration = configuration;
+
+    // unbox the parameters
+    let organization = params.organization;
+    let last_event_id = params.last_event_id;
+    let org_id = params.org_id;
+
+
+    let local_var_client = &local_var_configuration.client;
+
+    let local_var_uri_str = format!("{}/api/events/stream", local_var_configuration.base_path);
+    let mut local_var_req_builder = local_var_client.request(reqwest::Method::GET, local_var_uri_str.as_str());
+
+    if let Some(ref local_var_str) = org_id {
+        local_var_req_builder = local_var_req_builder.query(&[("org_id", &local_var_str.to_string())]);
+    }
+    if let Some(ref local_var_user_agent) = local_var_configuration.user_agent {
+        local_var_req_builder = local_var_req_builder.header(reqwest::header::USER_AGENT, local_var_user_agent.clone());
+    }
+    if let Some(local_var_param_value) = organization {
+        local_var_req_builder = local_var_req_builder.header("Organization", local_var_param_value.to_string());
+    }
+    if let Some(local_var_param_value) = last_event_id {
+        local_var_req_builder = local_var_req_builder.header("Last-Event-ID", local_var_param_value.to_string());
+    }
+    if let Some(ref local_var_apikey) = local_var_configuration.api_key {
+        let local_var_key = local_var_apikey.key.clone();
+        let local_var_value = match local_var_apikey.prefix {
+            Some(ref local_var_prefix) => format!("{} {}", local_var_prefix, local_var_key),
+            None => local_var_key,
+        };
+        local_var_req_builder = local_var_req_builder.header("Authorization", local_var_value);
+    };
+
+    let local_var_req = local_var_req_builder.build()?;
+    let local_var_resp = local_var_client.execute(local_var_req).await?;
+
+    let local_var_status = local_var_resp.status();
+    let local_var_content = local_var_resp.text().await?;
+
+    if !local_var_status.is_client_error() && !local_var_status.is_server_error() {
+        let local_var_entity: Option<StreamEventsSuccess> = serde_json::from_str(&local_var_content).ok();
+        let local_var_result = ResponseContent { status: local_var_status, content: local_var_content, entity: local_var_entity };
+        Ok(local_var_result)
+    } else {
+        let local_var_entity: Option<StreamEventsError> = serde_json::from_str(&local_var_content).ok();
+        let local_var_error = ResponseContent { status: local_var_status, content: local_var_content, entity: local_var_entity };
+        Err(Error::ResponseError(local_var_error))
+    }
+}
+
@@ -65,6 +65,10 @@ pub enum PostInvitationSuccess {
 #[serde(untagged)]
 pub enum DeleteInvitationError {
     Status400(models::ErrorRespPayload),
+    Status401(models::ErrorRespPayload),
+    Status429(models::ErrorRespPayload),
+    Status500(models::ErrorRespPayload),
+    Status503(models::ErrorRespPayload),
     UnknownValue(serde_json::Value),
 }
 
@@ -73,6 +77,10 @@ pub enum DeleteInvitationError {
 #[serde(untagged)]
 pub enum GetInvitationsError {
     Status400(models::ErrorRespPayload),
+    Status401(models::ErrorRespPayload),
+    Status429(models::ErrorRespPayload),
+    Status500(models::ErrorRespPayload),
+    Status503(models::ErrorRespPayload),
     UnknownValue(serde_json::Value),
 }
 
@@ -81,6 +89,11 @@ pub enum GetInvitationsError {
 #[serde(untagged)]
 pub enum PostInvitationError {
     Status400(models::ErrorRespPayload),
+    Status401(models::ErrorRespPayload),
+    Status402(models::ErrorRespPayload),
+    Status429(models::ErrorRespPayload),
+    Status500(models::ErrorRespPayload),
+    Status503(models::ErrorRespPayload),
     UnknownValue(serde_json::Value),
 }
 
@@ -20,7 +20,9 @@ pub struct CreateLinkParams {
     /// The org id to use for the request
     pub organization: String,
     /// JSON request payload to create a new link
-    pub create_link_req_payload: models::CreateLinkReqPayload
+    pub create_link_req_payload: models::CreateLinkReqPayload,
+    /// Unique key for this create. Retrying with the same key replays the first response instead of creating a duplicate
+    pub idempotency_key: Option<String>
 }
 
 /// struct for passing parameters to the method [`delete_link`]
@@ -29,7 +31,9 @@ pub struct DeleteLinkParams {
     /// The link id to use for the request
     pub link_id: String,
     /// The org id to use for the request
-    pub organization: String
+    pub organization: String,
+    /// ETag of the record as last fetched. The write fails with 412 if the record has changed since
+    pub if_match: Option<String>
 }
 
 /// struct for passing parameters to the method [`get_link`]
@@ -49,7 +53,9 @@ pub struct UpdateLinkParams {
     /// The org id to use for the request
     pub organization: String,
     /// JSON request payload to update the link
-    pub update_link_req_payload: models::UpdateLinkReqPayload
+    pub update_link_req_payload: models::UpdateLinkReqPayload,
+    /// ETag of the record as last fetched. The write fails with 412 if the record has changed since
+    pub if_match: Option<String>
 }
 
 
@@ -89,7 +95,13 @@ pub enum UpdateLinkSuccess {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum CreateLinkError {
+    Status400(models::ErrorRespPayload),
     Status401(models::ErrorRespPayload),
+    Status409(models::ErrorRespPayload),
+    Status422(models::ErrorRespPayload),
+    Status429(models::ErrorRespPayload),
+    Status500(models::ErrorRespPayload),
+    Status503(models::ErrorRespPayload),
     UnknownValue(serde_json::Value),
 }
 
@@ -97,7 +109,12 @@ pub enum CreateLinkError {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum DeleteLinkError {
+    Status400(models::ErrorRespPayload),
     Status401(models::ErrorRespPayload),
+    Status412(models::ErrorRespPayload),
+    Status429(models::ErrorRespPayload),
+    Status500(models::ErrorRespPayload),
+    Status503(models::ErrorRespPayload),
     UnknownValue(serde_json::Value),
 }
 
@@ -105,7 +122,11 @@ pub enum DeleteLinkError {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum GetLinkError {
+    Status400(models::ErrorRespPayload),
     Status401(models::ErrorRespPayload),
+    Status429(models::ErrorRespPayload),
+    Status500(models::ErrorRespPayload),
+    Status503(models::ErrorRespPayload),
     UnknownValue(serde_json::Value),
 }
 
@@ -113,7 +134,12 @@ pub enum GetLinkError {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum UpdateLinkError {
+    Status400(models::ErrorRespPayload),
     Status401(models::ErrorRespPayload),
+    Status412(models::ErrorRespPayload),
+    Status429(models::ErrorRespPayload),
+    Status500(models::ErrorRespPayload),
+    Status503(models::ErrorRespPayload),
     UnknownValue(serde_json::Value),
 }
 
@@ -124,6 +150,7 @@ pub async fn create_link(configuration: &configuration::Configuration, params: C
     // unbox the parameters
     let organization = params.organization;
     let create_link_req_payload = params.create_link_req_payload;
+    let idempotency_key = params.idempotency_key;
 
 
     let local_var_client = &local_var_configuration.client;
@@ -135,6 +162,9 @@ pub async fn create_link(configuration: &configuration::Configuration, params: C
         local_var_req_builder = local_var_req_builder.header(reqwest::header::USER_AGENT, local_var_user_agent.clone());
     }
     local_var_req_builder = local_var_req_builder.header("Organization", organization.to_string());
+    if let Some(local_var_param_value) = idempotency_key {
+        local_var_req_builder = local_var_req_builder.header("Idempotency-Key", local_var_param_value.to_string());
+    }
     if let Some(ref local_var_apikey) = local_var_configuration.api_key {
         let local_var_key = local_var_apikey.key.clone();
         let local_var_value = match local_var_apikey.prefix {
@@ -168,6 +198,7 @@ pub async fn delete_link(configuration: &configuration::Configuration, params: D
     // unbox the parameters
     let link_id = params.link_id;
     let organization = params.organization;
+    let if_match = params.if_match;
 
 
     let local_var_client = &local_var_configuration.client;
@@ -179,6 +210,9 @@ pub async fn delete_link(configuration: &configuration::Configuration, params: D
         local_var_req_builder = local_var_req_builder.header(reqwest::header::USER_AGENT, local_var_user_agent.clone());
     }
     local_var_req_builder = local_var_req_builder.header("Organization", organization.to_string());
+    if let Some(local_var_param_value) = if_match {
+        local_var_req_builder = local_var_req_builder.header("If-Match", local_var_param_value.to_string());
+    }
     if let Some(ref local_var_apikey) = local_var_configuration.api_key {
         let local_var_key = local_var_apikey.key.clone();
         let local_var_value = match local_var_apikey.prefix {
@@ -255,6 +289,7 @@ pub async fn update_link(configuration: &configuration::Configuration, params: U
     let link_id = params.link_id;
     let organization = params.organization;
     let update_link_req_payload = params.update_link_req_payload;
+    let if_match = params.if_match;
 
 
     let local_var_client = &local_var_configuration.client;
@@ -266,6 +301,9 @@ pub async fn update_link(configuration: &configuration::Configuration, params: U
         local_var_req_builder = local_var_req_builder.header(reqwest::header::USER_AGENT, local_var_user_agent.clone());
     }
     local_var_req_builder = local_var_req_builder.header("Organization", organization.to_string());
+    if let Some(local_var_param_value) = if_match {
+        local_var_req_builder = local_var_req_builder.header("If-Match", local_var_param_value.to_string());
+    }
     if let Some(ref local_var_apikey) = local_var_configuration.api_key {
         let local_var_key = local_var_apikey.key.clone();
         let local_var_value = match local_var_apikey.prefix {
@@ -90,18 +90,22 @@ pub fn parse_deep_object(prefix: &str, value: &serde_json::Value) -> Vec<(String
     unimplemented!("Only objects are supported with style=deepObject")
 }
 
+pub mod activities_api;
 pub mod api_key_api;
 pub mod auth_api;
+pub mod billing_api;
 pub mod companies_api;
 pub mod contacts_api;
 pub mod deals_api;
 pub mod emails_api;
+pub mod events_api;
 pub mod health_api;
 pub mod invitation_api;
 pub mod links_api;
 pub mod notes_api;
 pub mod orgs_api;
 pub mod phones_api;
+pub mod resolve_api;
 pub mod tasks_api;
 
 pub mod configuration;
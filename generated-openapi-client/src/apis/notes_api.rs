@@ -20,7 +20,9 @@ pub struct CreateNoteParams {
     /// The organization id to use for the request
     pub organization: String,
     /// JSON request payload to create a new note
-    pub create_note_req_payload: models::CreateNoteReqPayload
+    pub create_note_req_payload: models::CreateNoteReqPayload,
+    /// Unique key for this create. Retrying with the same key replays the first response instead of creating a duplicate
+    pub idempotency_key: Option<String>
 }
 
 /// struct for passing parameters to the method [`delete_note`]
@@ -29,7 +31,9 @@ pub struct DeleteNoteParams {
     /// The organization id to use for the request
     pub organization: String,
     /// The id of the note you want to delete.
-    pub note_id: String
+    pub note_id: String,
+    /// ETag of the record as last fetched. The write fails with 412 if the record has changed since
+    pub if_match: Option<String>
 }
 
 /// struct for passing parameters to the method [`get_note_by_id`]
@@ -60,7 +64,9 @@ pub struct UpdateNoteParams {
     /// The id of the note you want to update.
     pub note_id: String,
     /// JSON request payload to rename the note
-    pub update_note_req_payload: models::UpdateNoteReqPayload
+    pub update_note_req_payload: models::UpdateNoteReqPayload,
+    /// ETag of the record as last fetched. The write fails with 412 if the record has changed since
+    pub if_match: Option<String>
 }
 
 
@@ -108,7 +114,13 @@ pub enum UpdateNoteSuccess {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum CreateNoteError {
+    Status400(models::ErrorRespPayload),
     Status401(models::ErrorRespPayload),
+    Status409(models::ErrorRespPayload),
+    Status422(models::ErrorRespPayload),
+    Status429(models::ErrorRespPayload),
+    Status500(models::ErrorRespPayload),
+    Status503(models::ErrorRespPayload),
     UnknownValue(serde_json::Value),
 }
 
@@ -116,7 +128,12 @@ pub enum CreateNoteError {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum DeleteNoteError {
+    Status400(models::ErrorRespPayload),
     Status401(models::ErrorRespPayload),
+    Status412(models::ErrorRespPayload),
+    Status429(models::ErrorRespPayload),
+    Status500(models::ErrorRespPayload),
+    Status503(models::ErrorRespPayload),
     UnknownValue(serde_json::Value),
 }
 
@@ -124,7 +141,11 @@ pub enum DeleteNoteError {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum GetNoteByIdError {
+    Status400(models::ErrorRespPayload),
     Status401(models::ErrorRespPayload),
+    Status429(models::ErrorRespPayload),
+    Status500(models::ErrorRespPayload),
+    Status503(models::ErrorRespPayload),
     UnknownValue(serde_json::Value),
 }
 
@@ -133,6 +154,9 @@ pub enum GetNoteByIdError {
 #[serde(untagged)]
 pub enum GetNotesForOrgError {
     Status401(models::ErrorRespPayload),
+    Status429(models::ErrorRespPayload),
+    Status500(models::ErrorRespPayload),
+    Status503(models::ErrorRespPayload),
     UnknownValue(serde_json::Value),
 }
 
@@ -140,7 +164,12 @@ pub enum GetNotesForOrgError {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum UpdateNoteError {
+    Status400(models::ErrorRespPayload),
     Status401(models::ErrorRespPayload),
+    Status412(models::ErrorRespPayload),
+    Status429(models::ErrorRespPayload),
+    Status500(models::ErrorRespPayload),
+    Status503(models::ErrorRespPayload),
     UnknownValue(serde_json::Value),
 }
 
@@ -151,6 +180,7 @@ pub async fn create_note(configuration: &configuration::Configuration, params: C
     // unbox the parameters
     let organization = params.organization;
     let create_note_req_payload = params.create_note_req_payload;
+    let idempotency_key = params.idempotency_key;
 
 
     let local_var_client = &local_var_configuration.client;
@@ -162,6 +192,9 @@ pub async fn create_note(configuration: &configuration::Configuration, params: C
         local_var_req_builder = local_var_req_builder.header(reqwest::header::USER_AGENT, local_var_user_agent.clone());
     }
     local_var_req_builder = local_var_req_builder.header("Organization", organization.to_string());
+    if let Some(local_var_param_value) = idempotency_key {
+        local_var_req_builder = local_var_req_builder.header("Idempotency-Key", local_var_param_value.to_string());
+    }
     if let Some(ref local_var_apikey) = local_var_configuration.api_key {
         let local_var_key = local_var_apikey.key.clone();
         let local_var_value = match local_var_apikey.prefix {
@@ -195,6 +228,7 @@ pub async fn delete_note(configuration: &configuration::Configuration, params: D
     // unbox the parameters
     let organization = params.organization;
     let note_id = params.note_id;
+    let if_match = params.if_match;
 
 
     let local_var_client = &local_var_configuration.client;
@@ -206,6 +240,9 @@ pub async fn delete_note(configuration: &configuration::Configuration, params: D
         local_var_req_builder = local_var_req_builder.header(reqwest::header::USER_AGENT, local_var_user_agent.clone());
     }
     local_var_req_builder = local_var_req_builder.header("Organization", organization.to_string());
+    if let Some(local_var_param_value) = if_match {
+        local_var_req_builder = local_var_req_builder.header("If-Match", local_var_param_value.to_string());
+    }
     if let Some(ref local_var_apikey) = local_var_configuration.api_key {
         let local_var_key = local_var_apikey.key.clone();
         let local_var_value = match local_var_apikey.prefix {
@@ -332,6 +369,7 @@ pub async fn update_note(configuration: &configuration::Configuration, params: U
     let organization = params.organization;
     let note_id = params.note_id;
     let update_note_req_payload = params.update_note_req_payload;
+    let if_match = params.if_match;
 
 
     let local_var_client = &local_var_configuration.client;
@@ -343,6 +381,9 @@ pub async fn update_note(configuration: &configuration::Configuration, params: U
         local_var_req_builder = local_var_req_builder.header(reqwest::header::USER_AGENT, local_var_user_agent.clone());
     }
     local_var_req_builder = local_var_req_builder.header("Organization", organization.to_string());
+    if let Some(local_var_param_value) = if_match {
+        local_var_req_builder = local_var_req_builder.header("If-Match", local_var_param_value.to_string());
+    }
     if let Some(ref local_var_apikey) = local_var_configuration.api_key {
         let local_var_key = local_var_apikey.key.clone();
         let local_var_value = match local_var_apikey.prefix {
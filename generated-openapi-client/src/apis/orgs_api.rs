@@ -18,7 +18,9 @@ use super::{Error, configuration};
 #[derive(Clone, Debug)]
 pub struct CreateOrgParams {
     /// JSON request payload to create a new organization
-    pub create_org_req_payload: models::CreateOrgReqPayload
+    pub create_org_req_payload: models::CreateOrgReqPayload,
+    /// Unique key for this create. Retrying with the same key replays the first response instead of creating a duplicate
+    pub idempotency_key: Option<String>
 }
 
 /// struct for passing parameters to the method [`delete_org`]
@@ -27,6 +29,17 @@ pub struct DeleteOrgParams {
     /// The organization id to use for the request
     pub organization: String,
     /// The id of the organization you want to fetch.
+    pub org_id: String,
+    /// ETag of the record as last fetched. The write fails with 412 if the record has changed since
+    pub if_match: Option<String>
+}
+
+/// struct for passing parameters to the method [`get_org_usage`]
+#[derive(Clone, Debug)]
+pub struct GetOrgUsageParams {
+    /// The organization id to use for the request
+    pub organization: String,
+    /// The id of the organization you want to fetch usage for.
     pub org_id: String
 }
 
@@ -48,6 +61,17 @@ pub struct LeaveOrgParams {
     pub org_id: String
 }
 
+/// struct for passing parameters to the method [`reassign_records`]
+#[derive(Clone, Debug)]
+pub struct ReassignRecordsParams {
+    /// The organization id to use for the request
+    pub organization: String,
+    /// The id of the organization to reassign records in.
+    pub org_id: String,
+    /// JSON request payload to reassign every record owned by one user to another
+    pub reassign_records_req_payload: models::ReassignRecordsReqPayload
+}
+
 /// struct for passing parameters to the method [`update_org`]
 #[derive(Clone, Debug)]
 pub struct UpdateOrgParams {
@@ -56,7 +80,9 @@ pub struct UpdateOrgParams {
     /// The id of the organization you want to fetch.
     pub org_id: String,
     /// JSON request payload to rename the organization
-    pub update_org_req_payload: models::UpdateOrgReqPayload
+    pub update_org_req_payload: models::UpdateOrgReqPayload,
+    /// ETag of the record as last fetched. The write fails with 412 if the record has changed since
+    pub if_match: Option<String>
 }
 
 
@@ -76,6 +102,14 @@ pub enum DeleteOrgSuccess {
     UnknownValue(serde_json::Value),
 }
 
+/// struct for typed successes of method [`get_org_usage`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum GetOrgUsageSuccess {
+    Status200(models::OrgUsage),
+    UnknownValue(serde_json::Value),
+}
+
 /// struct for typed successes of method [`get_orgs_for_authed_user`]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
@@ -92,6 +126,14 @@ pub enum LeaveOrgSuccess {
     UnknownValue(serde_json::Value),
 }
 
+/// struct for typed successes of method [`reassign_records`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ReassignRecordsSuccess {
+    Status200(models::ReassignedRecords),
+    UnknownValue(serde_json::Value),
+}
+
 /// struct for typed successes of method [`update_org`]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
@@ -104,7 +146,13 @@ pub enum UpdateOrgSuccess {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum CreateOrgError {
+    Status400(models::ErrorRespPayload),
     Status401(models::ErrorRespPayload),
+    Status409(models::ErrorRespPayload),
+    Status422(models::ErrorRespPayload),
+    Status429(models::ErrorRespPayload),
+    Status500(models::ErrorRespPayload),
+    Status503(models::ErrorRespPayload),
     UnknownValue(serde_json::Value),
 }
 
@@ -112,7 +160,24 @@ pub enum CreateOrgError {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum DeleteOrgError {
+    Status400(models::ErrorRespPayload),
+    Status401(models::ErrorRespPayload),
+    Status412(models::ErrorRespPayload),
+    Status429(models::ErrorRespPayload),
+    Status500(models::ErrorRespPayload),
+    Status503(models::ErrorRespPayload),
+    UnknownValue(serde_json::Value),
+}
+
+/// struct for typed errors of method [`get_org_usage`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum GetOrgUsageError {
+    Status400(models::ErrorRespPayload),
     Status401(models::ErrorRespPayload),
+    Status429(models::ErrorRespPayload),
+    Status500(models::ErrorRespPayload),
+    Status503(models::ErrorRespPayload),
     UnknownValue(serde_json::Value),
 }
 
@@ -121,6 +186,9 @@ pub enum DeleteOrgError {
 #[serde(untagged)]
 pub enum GetOrgsForAuthedUserError {
     Status401(models::ErrorRespPayload),
+    Status429(models::ErrorRespPayload),
+    Status500(models::ErrorRespPayload),
+    Status503(models::ErrorRespPayload),
     UnknownValue(serde_json::Value),
 }
 
@@ -130,6 +198,21 @@ pub enum GetOrgsForAuthedUserError {
 pub enum LeaveOrgError {
     Status400(models::ErrorRespPayload),
     Status401(models::ErrorRespPayload),
+    Status429(models::ErrorRespPayload),
+    Status500(models::ErrorRespPayload),
+    Status503(models::ErrorRespPayload),
+    UnknownValue(serde_json::Value),
+}
+
+/// struct for typed errors of method [`reassign_records`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ReassignRecordsError {
+    Status400(models::ErrorRespPayload),
+    Status401(models::ErrorRespPayload),
+    Status429(models::ErrorRespPayload),
+    Status500(models::ErrorRespPayload),
+    Status503(models::ErrorRespPayload),
     UnknownValue(serde_json::Value),
 }
 
@@ -137,7 +220,12 @@ pub enum LeaveOrgError {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum UpdateOrgError {
+    Status400(models::ErrorRespPayload),
     Status401(models::ErrorRespPayload),
+    Status412(models::ErrorRespPayload),
+    Status429(models::ErrorRespPayload),
+    Status500(models::ErrorRespPayload),
+    Status503(models::ErrorRespPayload),
     UnknownValue(serde_json::Value),
 }
 
@@ -147,6 +235,7 @@ pub async fn create_org(configuration: &configuration::Configuration, params: Cr
 
     // unbox the parameters
     let create_org_req_payload = params.create_org_req_payload;
+    let idempotency_key = params.idempotency_key;
 
 
     let local_var_client = &local_var_configuration.client;
@@ -157,6 +246,9 @@ pub async fn create_org(configuration: &configuration::Configuration, params: Cr
     if let Some(ref local_var_user_agent) = local_var_configuration.user_agent {
         local_var_req_builder = local_var_req_builder.header(reqwest::header::USER_AGENT, local_var_user_agent.clone());
     }
+    if let Some(local_var_param_value) = idempotency_key {
+        local_var_req_builder = local_var_req_builder.header("Idempotency-Key", local_var_param_value.to_string());
+    }
     if let Some(ref local_var_apikey) = local_var_configuration.api_key {
         let local_var_key = local_var_apikey.key.clone();
         let local_var_value = match local_var_apikey.prefix {
@@ -190,6 +282,7 @@ pub async fn delete_org(configuration: &configuration::Configuration, params: De
     // unbox the parameters
     let organization = params.organization;
     let org_id = params.org_id;
+    let if_match = params.if_match;
 
 
     let local_var_client = &local_var_configuration.client;
@@ -201,6 +294,9 @@ pub async fn delete_org(configuration: &configuration::Configuration, params: De
         local_var_req_builder = local_var_req_builder.header(reqwest::header::USER_AGENT, local_var_user_agent.clone());
     }
     local_var_req_builder = local_var_req_builder.header("Organization", organization.to_string());
+    if let Some(local_var_param_value) = if_match {
+        local_var_req_builder = local_var_req_builder.header("If-Match", local_var_param_value.to_string());
+    }
     if let Some(ref local_var_apikey) = local_var_configuration.api_key {
         let local_var_key = local_var_apikey.key.clone();
         let local_var_value = match local_var_apikey.prefix {
@@ -227,6 +323,49 @@ pub async fn delete_org(configuration: &configuration::Configuration, params: De
     }
 }
 
+pub async fn get_org_usage(configuration: &configuration::Configuration, params: GetOrgUsageParams) -> Result<ResponseContent<GetOrgUsageSuccess>, Error<GetOrgUsageError>> {
+    let local_var_configuration = configuration;
+
+    // unbox the parameters
+    let organization = params.organization;
+    let org_id = params.org_id;
+
+
+    let local_var_client = &local_var_configuration.client;
+
+    let local_var_uri_str = format!("{}/api/orgs/{org_id}/usage", local_var_configuration.base_path, org_id=crate::apis::urlencode(org_id));
+    let mut local_var_req_builder = local_var_client.request(reqwest::Method::GET, local_var_uri_str.as_str());
+
+    if let Some(ref local_var_user_agent) = local_var_configuration.user_agent {
+        local_var_req_builder = local_var_req_builder.header(reqwest::header::USER_AGENT, local_var_user_agent.clone());
+    }
+    local_var_req_builder = local_var_req_builder.header("Organization", organization.to_string());
+    if let Some(ref local_var_apikey) = local_var_configuration.api_key {
+        let local_var_key = local_var_apikey.key.clone();
+        let local_var_value = match local_var_apikey.prefix {
+            Some(ref local_var_prefix) => format!("{} {}", local_var_prefix, local_var_key),
+            None => local_var_key,
+        };
+        local_var_req_builder = local_var_req_builder.header("Authorization", local_var_value);
+    };
+
+    let local_var_req = local_var_req_builder.build()?;
+    let local_var_resp = local_var_client.execute(local_var_req).await?;
+
+    let local_var_status = local_var_resp.status();
+    let local_var_content = local_var_resp.text().await?;
+
+    if !local_var_status.is_client_error() && !local_var_status.is_server_error() {
+        let local_var_entity: Option<GetOrgUsageSuccess> = serde_json::from_str(&local_var_content).ok();
+        let local_var_result = ResponseContent { status: local_var_status, content: local_var_content, entity: local_var_entity };
+        Ok(local_var_result)
+    } else {
+        let local_var_entity: Option<GetOrgUsageError> = serde_json::from_str(&local_var_content).ok();
+        let local_var_error = ResponseContent { status: local_var_status, content: local_var_content, entity: local_var_entity };
+        Err(Error::ResponseError(local_var_error))
+    }
+}
+
 pub async fn get_orgs_for_authed_user(configuration: &configuration::Configuration, params: GetOrgsForAuthedUserParams) -> Result<ResponseContent<GetOrgsForAuthedUserSuccess>, Error<GetOrgsForAuthedUserError>> {
     let local_var_configuration = configuration;
 
@@ -318,6 +457,51 @@ pub async fn leave_org(configuration: &configuration::Configuration, params: Lea
     }
 }
 
+pub async fn reassign_records(configuration: &configuration::Configuration, params: ReassignRecordsParams) -> Result<ResponseContent<ReassignRecordsSuccess>, Error<ReassignRecordsError>> {
+    let local_var_configuration = configuration;
+
+    // unbox the parameters
+    let organization = params.organization;
+    let org_id = params.org_id;
+    let reassign_records_req_payload = params.reassign_records_req_payload;
+
+
+    let local_var_client = &local_var_configuration.client;
+
+    let local_var_uri_str = format!("{}/api/orgs/{org_id}/reassign", local_var_configuration.base_path, org_id=crate::apis::urlencode(org_id));
+    let mut local_var_req_builder = local_var_client.request(reqwest::Method::POST, local_var_uri_str.as_str());
+
+    if let Some(ref local_var_user_agent) = local_var_configuration.user_agent {
+        local_var_req_builder = local_var_req_builder.header(reqwest::header::USER_AGENT, local_var_user_agent.clone());
+    }
+    local_var_req_builder = local_var_req_builder.header("Organization", organization.to_string());
+    if let Some(ref local_var_apikey) = local_var_configuration.api_key {
+        let local_var_key = local_var_apikey.key.clone();
+        let local_var_value = match local_var_apikey.prefix {
+            Some(ref local_var_prefix) => format!("{} {}", local_var_prefix, local_var_key),
+            None => local_var_key,
+        };
+        local_var_req_builder = local_var_req_builder.header("Authorization", local_var_value);
+    };
+    local_var_req_builder = local_var_req_builder.json(&reassign_records_req_payload);
+
+    let local_var_req = local_var_req_builder.build()?;
+    let local_var_resp = local_var_client.execute(local_var_req).await?;
+
+    let local_var_status = local_var_resp.status();
+    let local_var_content = local_var_resp.text().await?;
+
+    if !local_var_status.is_client_error() && !local_var_status.is_server_error() {
+        let local_var_entity: Option<ReassignRecordsSuccess> = serde_json::from_str(&local_var_content).ok();
+        let local_var_result = ResponseContent { status: local_var_status, content: local_var_content, entity: local_var_entity };
+        Ok(local_var_result)
+    } else {
+        let local_var_entity: Option<ReassignRecordsError> = serde_json::from_str(&local_var_content).ok();
+        let local_var_error = ResponseContent { status: local_var_status, content: local_var_content, entity: local_var_entity };
+        Err(Error::ResponseError(local_var_error))
+    }
+}
+
 pub async fn update_org(configuration: &configuration::Configuration, params: UpdateOrgParams) -> Result<ResponseContent<UpdateOrgSuccess>, Error<UpdateOrgError>> {
     let local_var_configuration = configuration;
 
@@ -325,6 +509,7 @@ pub async fn update_org(configuration: &configuration::Configuration, params: Up
     let organization = params.organization;
     let org_id = params.org_id;
     let update_org_req_payload = params.update_org_req_payload;
+    let if_match = params.if_match;
 
 
     let local_var_client = &local_var_configuration.client;
@@ -336,6 +521,9 @@ pub async fn update_org(configuration: &configuration::Configuration, params: Up
         local_var_req_builder = local_var_req_builder.header(reqwest::header::USER_AGENT, local_var_user_agent.clone());
     }
     local_var_req_builder = local_var_req_builder.header("Organization", organization.to_string());
+    if let Some(local_var_param_value) = if_match {
+        local_var_req_builder = local_var_req_builder.header("If-Match", local_var_param_value.to_string());
+    }
     if let Some(ref local_var_apikey) = local_var_configuration.api_key {
         let local_var_key = local_var_apikey.key.clone();
         let local_var_value = match local_var_apikey.prefix {
@@ -20,7 +20,9 @@ pub struct CreatePhoneParams {
     /// The org id to use for the request
     pub organization: String,
     /// JSON request payload to create a new phone
-    pub create_phone_req_payload: models::CreatePhoneReqPayload
+    pub create_phone_req_payload: models::CreatePhoneReqPayload,
+    /// Unique key for this create. Retrying with the same key replays the first response instead of creating a duplicate
+    pub idempotency_key: Option<String>
 }
 
 /// struct for passing parameters to the method [`delete_phone`]
@@ -29,7 +31,9 @@ pub struct DeletePhoneParams {
     /// The phone id to use for the request
     pub phone_id: String,
     /// The org id to use for the request
-    pub organization: String
+    pub organization: String,
+    /// ETag of the record as last fetched. The write fails with 412 if the record has changed since
+    pub if_match: Option<String>
 }
 
 /// struct for passing parameters to the method [`get_phone`]
@@ -49,7 +53,9 @@ pub struct UpdatePhoneParams {
     /// The org id to use for the request
     pub organization: String,
     /// JSON request payload to update the phone
-    pub update_phone_req_payload: models::UpdatePhoneReqPayload
+    pub update_phone_req_payload: models::UpdatePhoneReqPayload,
+    /// ETag of the record as last fetched. The write fails with 412 if the record has changed since
+    pub if_match: Option<String>
 }
 
 
@@ -89,7 +95,13 @@ pub enum UpdatePhoneSuccess {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum CreatePhoneError {
+    Status400(models::ErrorRespPayload),
     Status401(models::ErrorRespPayload),
+    Status409(models::ErrorRespPayload),
+    Status422(models::ErrorRespPayload),
+    Status429(models::ErrorRespPayload),
+    Status500(models::ErrorRespPayload),
+    Status503(models::ErrorRespPayload),
     UnknownValue(serde_json::Value),
 }
 
@@ -97,7 +109,12 @@ pub enum CreatePhoneError {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum DeletePhoneError {
+    Status400(models::ErrorRespPayload),
     Status401(models::ErrorRespPayload),
+    Status412(models::ErrorRespPayload),
+    Status429(models::ErrorRespPayload),
+    Status500(models::ErrorRespPayload),
+    Status503(models::ErrorRespPayload),
     UnknownValue(serde_json::Value),
 }
 
@@ -105,7 +122,11 @@ pub enum DeletePhoneError {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum GetPhoneError {
+    Status400(models::ErrorRespPayload),
     Status401(models::ErrorRespPayload),
+    Status429(models::ErrorRespPayload),
+    Status500(models::ErrorRespPayload),
+    Status503(models::ErrorRespPayload),
     UnknownValue(serde_json::Value),
 }
 
@@ -113,7 +134,12 @@ pub enum GetPhoneError {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum UpdatePhoneError {
+    Status400(models::ErrorRespPayload),
     Status401(models::ErrorRespPayload),
+    Status412(models::ErrorRespPayload),
+    Status429(models::ErrorRespPayload),
+    Status500(models::ErrorRespPayload),
+    Status503(models::ErrorRespPayload),
     UnknownValue(serde_json::Value),
 }
 
@@ -124,6 +150,7 @@ pub async fn create_phone(configuration: &configuration::Configuration, params:
     // unbox the parameters
     let organization = params.organization;
     let create_phone_req_payload = params.create_phone_req_payload;
+    let idempotency_key = params.idempotency_key;
 
 
     let local_var_client = &local_var_configuration.client;
@@ -135,6 +162,9 @@ pub async fn create_phone(configuration: &configuration::Configuration, params:
         local_var_req_builder = local_var_req_builder.header(reqwest::header::USER_AGENT, local_var_user_agent.clone());
     }
     local_var_req_builder = local_var_req_builder.header("Organization", organization.to_string());
+    if let Some(local_var_param_value) = idempotency_key {
+        local_var_req_builder = local_var_req_builder.header("Idempotency-Key", local_var_param_value.to_string());
+    }
     if let Some(ref local_var_apikey) = local_var_configuration.api_key {
         let local_var_key = local_var_apikey.key.clone();
         let local_var_value = match local_var_apikey.prefix {
@@ -168,6 +198,7 @@ pub async fn delete_phone(configuration: &configuration::Configuration, params:
     // unbox the parameters
     let phone_id = params.phone_id;
     let organization = params.organization;
+    let if_match = params.if_match;
 
 
     let local_var_client = &local_var_configuration.client;
@@ -179,6 +210,9 @@ pub async fn delete_phone(configuration: &configuration::Configuration, params:
         local_var_req_builder = local_var_req_builder.header(reqwest::header::USER_AGENT, local_var_user_agent.clone());
     }
     local_var_req_builder = local_var_req_builder.header("Organization", organization.to_string());
+    if let Some(local_var_param_value) = if_match {
+        local_var_req_builder = local_var_req_builder.header("If-Match", local_var_param_value.to_string());
+    }
     if let Some(ref local_var_apikey) = local_var_configuration.api_key {
         let local_var_key = local_var_apikey.key.clone();
         let local_var_value = match local_var_apikey.prefix {
@@ -255,6 +289,7 @@ pub async fn update_phone(configuration: &configuration::Configuration, params:
     let phone_id = params.phone_id;
     let organization = params.organization;
     let update_phone_req_payload = params.update_phone_req_payload;
+    let if_match = params.if_match;
 
 
     let local_var_client = &local_var_configuration.client;
@@ -266,6 +301,9 @@ pub async fn update_phone(configuration: &configuration::Configuration, params:
         local_var_req_builder = local_var_req_builder.header(reqwest::header::USER_AGENT, local_var_user_agent.clone());
     }
     local_var_req_builder = local_var_req_builder.header("Organization", organization.to_string());
+    if let Some(local_var_param_value) = if_match {
+        local_var_req_builder = local_var_req_builder.header("If-Match", local_var_param_value.to_string());
+    }
     if let Some(ref local_var_apikey) = local_var_configuration.api_key {
         let local_var_key = local_var_apikey.key.clone();
         let local_var_value = match local_var_apikey.prefix {
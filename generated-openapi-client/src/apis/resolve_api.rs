@@ -0,0 +1,89 @@
+/*
+ * Trieve API
+ *
+ * Trieve OpenAPI Specification. This document describes all of the operations available through the Trieve API.
+ *
+ * The version of the OpenAPI document: 0.0.1
+ * Contact: developers@trieve.ai
+ * Generated by: https://openapi-generator.tech
+ */
+
+
+use reqwest;
+
+use crate::{apis::ResponseContent, models};
+use super::{Error, configuration};
+
+/// struct for passing parameters to the method [`resolve_id`]
+#[derive(Clone, Debug)]
+pub struct ResolveIdParams {
+    /// The organization id to use for the request
+    pub organization: String,
+    /// The prefixed id of the record, e.g. deal-0190e3b4-…
+    pub prefixed_id: String
+}
+
+
+/// struct for typed successes of method [`resolve_id`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ResolveIdSuccess {
+    Status200(models::ResolvedEntity),
+    UnknownValue(serde_json::Value),
+}
+
+/// struct for typed errors of method [`resolve_id`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ResolveIdError {
+    Status400(models::ErrorRespPayload),
+    Status401(models::ErrorRespPayload),
+    Status404(models::ErrorRespPayload),
+    UnknownValue(serde_json::Value),
+}
+
+
+/// Look up any record by its prefixed id, e.g. `deal-…` or `contact-…`, without knowing its type up front. Only records of the organization in the Organization header are found; records of other organizations are reported as not found.
+pub async fn resolve_id(configuration: &configuration::Configuration, params: ResolveIdParams) -> Result<ResponseContent<ResolveIdSuccess>, Error<ResolveIdError>> {
+    let local_var_configuration = configuration;
+
+    // unbox the parameters
+    let organization = params.organization;
+    let prefixed_id = params.prefixed_id;
+
+
+    let local_var_client = &local_var_configuration.client;
+
+    let local_var_uri_str = format!("{}/api/resolve/{prefixed_id}", local_var_configuration.base_path, prefixed_id=crate::apis::urlencode(prefixed_id));
+    let mut local_var_req_builder = local_var_client.request(reqwest::Method::GET, local_var_uri_str.as_str());
+
+    if let Some(ref local_var_user_agent) = local_var_configuration.user_agent {
+        local_var_req_builder = local_var_req_builder.header(reqwest::header::USER_AGENT, local_var_user_agent.clone());
+    }
+    local_var_req_builder = local_var_req_builder.header("Organization", organization.to_string());
+    if let Some(ref local_var_apikey) = local_var_configuration.api_key {
+        let local_var_key = local_var_apikey.key.clone();
+        let local_var_value = match local_var_apikey.prefix {
+            Some(ref local_var_prefix) => format!("{} {}", local_var_prefix, local_var_key),
+            None => local_var_key,
+        };
+        local_var_req_builder = local_var_req_builder.header("Authorization", local_var_value);
+    };
+
+    let local_var_req = local_var_req_builder.build()?;
+    let local_var_resp = local_var_client.execute(local_var_req).await?;
+
+    let local_var_status = local_var_resp.status();
+    let local_var_content = local_var_resp.text().await?;
+
+    if !local_var_status.is_client_error() && !local_var_status.is_server_error() {
+        let local_var_entity: Option<ResolveIdSuccess> = serde_json::from_str(&local_var_content).ok();
+        let local_var_result = ResponseContent { status: local_var_status, content: local_var_content, entity: local_var_entity };
+        Ok(local_var_result)
+    } else {
+        let local_var_entity: Option<ResolveIdError> = serde_json::from_str(&local_var_content).ok();
+        let local_var_error = ResponseContent { status: local_var_status, content: local_var_content, entity: local_var_entity };
+        Err(Error::ResponseError(local_var_error))
+    }
+}
+
@@ -20,7 +20,9 @@ pub struct CreateTaskParams {
     /// The organization id to use for the request
     pub organization: String,
     /// JSON request payload to create a new task
-    pub create_task_req_payload: models::CreateTaskReqPayload
+    pub create_task_req_payload: models::CreateTaskReqPayload,
+    /// Unique key for this create. Retrying with the same key replays the first response instead of creating a duplicate
+    pub idempotency_key: Option<String>
 }
 
 /// struct for passing parameters to the method [`create_task_resource`]
@@ -42,7 +44,9 @@ pub struct DeleteTaskParams {
     /// The task id to use for the request
     pub task_id: String,
     /// The organization id to use for the request
-    pub organization: String
+    pub organization: String,
+    /// ETag of the record as last fetched. The write fails with 412 if the record has changed since
+    pub if_match: Option<String>
 }
 
 /// struct for passing parameters to the method [`delete_task_resource`]
@@ -82,6 +86,19 @@ pub struct ListTaskResourceParams {
     pub offset: Option<String>
 }
 
+/// struct for passing parameters to the method [`list_tasks`]
+#[derive(Clone, Debug)]
+pub struct ListTasksParams {
+    /// The organization id to use for the request
+    pub organization: String,
+    /// The number of records to return
+    pub limit: Option<i64>,
+    /// The UUID of the record to start from
+    pub offset: Option<String>,
+    /// Only return records owned by this user id, or `me` for the calling user
+    pub owner: Option<String>
+}
+
 /// struct for passing parameters to the method [`update_task`]
 #[derive(Clone, Debug)]
 pub struct UpdateTaskParams {
@@ -89,8 +106,10 @@ pub struct UpdateTaskParams {
     pub task_id: String,
     /// The organization id to use for the request
     pub organization: String,
-    /// JSON request payload to update the task
-    pub update_task_req_payload: models::UpdateTaskReqPayload
+    /// JSON merge patch to update the task. Omitted fields are left alone and null fields are cleared.
+    pub update_task_req_payload: models::UpdateTaskReqPayload,
+    /// ETag of the record as last fetched. The write fails with 412 if the record has changed since
+    pub if_match: Option<String>
 }
 
 
@@ -142,6 +161,14 @@ pub enum ListTaskResourceSuccess {
     UnknownValue(serde_json::Value),
 }
 
+/// struct for typed successes of method [`list_tasks`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ListTasksSuccess {
+    Status200(models::TaskList),
+    UnknownValue(serde_json::Value),
+}
+
 /// struct for typed successes of method [`update_task`]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
@@ -156,6 +183,11 @@ pub enum UpdateTaskSuccess {
 pub enum CreateTaskError {
     Status400(models::ErrorRespPayload),
     Status401(models::ErrorRespPayload),
+    Status409(models::ErrorRespPayload),
+    Status422(models::ErrorRespPayload),
+    Status429(models::ErrorRespPayload),
+    Status500(models::ErrorRespPayload),
+    Status503(models::ErrorRespPayload),
     UnknownValue(serde_json::Value),
 }
 
@@ -165,6 +197,9 @@ pub enum CreateTaskError {
 pub enum CreateTaskResourceError {
     Status400(models::ErrorRespPayload),
     Status401(models::ErrorRespPayload),
+    Status429(models::ErrorRespPayload),
+    Status500(models::ErrorRespPayload),
+    Status503(models::ErrorRespPayload),
     UnknownValue(serde_json::Value),
 }
 
@@ -172,7 +207,12 @@ pub enum CreateTaskResourceError {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum DeleteTaskError {
+    Status400(models::ErrorRespPayload),
     Status401(models::ErrorRespPayload),
+    Status412(models::ErrorRespPayload),
+    Status429(models::ErrorRespPayload),
+    Status500(models::ErrorRespPayload),
+    Status503(models::ErrorRespPayload),
     UnknownValue(serde_json::Value),
 }
 
@@ -182,6 +222,9 @@ pub enum DeleteTaskError {
 pub enum DeleteTaskResourceError {
     Status400(models::ErrorRespPayload),
     Status401(models::ErrorRespPayload),
+    Status429(models::ErrorRespPayload),
+    Status500(models::ErrorRespPayload),
+    Status503(models::ErrorRespPayload),
     UnknownValue(serde_json::Value),
 }
 
@@ -189,7 +232,11 @@ pub enum DeleteTaskResourceError {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum GetTaskError {
+    Status400(models::ErrorRespPayload),
     Status401(models::ErrorRespPayload),
+    Status429(models::ErrorRespPayload),
+    Status500(models::ErrorRespPayload),
+    Status503(models::ErrorRespPayload),
     UnknownValue(serde_json::Value),
 }
 
@@ -199,6 +246,21 @@ pub enum GetTaskError {
 pub enum ListTaskResourceError {
     Status400(models::ErrorRespPayload),
     Status401(models::ErrorRespPayload),
+    Status429(models::ErrorRespPayload),
+    Status500(models::ErrorRespPayload),
+    Status503(models::ErrorRespPayload),
+    UnknownValue(serde_json::Value),
+}
+
+/// struct for typed errors of method [`list_tasks`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ListTasksError {
+    Status400(models::ErrorRespPayload),
+    Status401(models::ErrorRespPayload),
+    Status429(models::ErrorRespPayload),
+    Status500(models::ErrorRespPayload),
+    Status503(models::ErrorRespPayload),
     UnknownValue(serde_json::Value),
 }
 
@@ -208,6 +270,10 @@ pub enum ListTaskResourceError {
 pub enum UpdateTaskError {
     Status400(models::ErrorRespPayload),
     Status401(models::ErrorRespPayload),
+    Status412(models::ErrorRespPayload),
+    Status429(models::ErrorRespPayload),
+    Status500(models::ErrorRespPayload),
+    Status503(models::ErrorRespPayload),
     UnknownValue(serde_json::Value),
 }
 
@@ -218,6 +284,7 @@ pub async fn create_task(configuration: &configuration::Configuration, params: C
     // unbox the parameters
     let organization = params.organization;
     let create_task_req_payload = params.create_task_req_payload;
+    let idempotency_key = params.idempotency_key;
 
 
     let local_var_client = &local_var_configuration.client;
@@ -229,6 +296,9 @@ pub async fn create_task(configuration: &configuration::Configuration, params: C
         local_var_req_builder = local_var_req_builder.header(reqwest::header::USER_AGENT, local_var_user_agent.clone());
     }
     local_var_req_builder = local_var_req_builder.header("Organization", organization.to_string());
+    if let Some(local_var_param_value) = idempotency_key {
+        local_var_req_builder = local_var_req_builder.header("Idempotency-Key", local_var_param_value.to_string());
+    }
     if let Some(ref local_var_apikey) = local_var_configuration.api_key {
         let local_var_key = local_var_apikey.key.clone();
         let local_var_value = match local_var_apikey.prefix {
@@ -307,6 +377,7 @@ pub async fn delete_task(configuration: &configuration::Configuration, params: D
     // unbox the parameters
     let task_id = params.task_id;
     let organization = params.organization;
+    let if_match = params.if_match;
 
 
     let local_var_client = &local_var_configuration.client;
@@ -318,6 +389,9 @@ pub async fn delete_task(configuration: &configuration::Configuration, params: D
         local_var_req_builder = local_var_req_builder.header(reqwest::header::USER_AGENT, local_var_user_agent.clone());
     }
     local_var_req_builder = local_var_req_builder.header("Organization", organization.to_string());
+    if let Some(local_var_param_value) = if_match {
+        local_var_req_builder = local_var_req_builder.header("If-Match", local_var_param_value.to_string());
+    }
     if let Some(ref local_var_apikey) = local_var_configuration.api_key {
         let local_var_key = local_var_apikey.key.clone();
         let local_var_value = match local_var_apikey.prefix {
@@ -484,6 +558,60 @@ pub async fn list_task_resource(configuration: &configuration::Configuration, pa
     }
 }
 
+pub async fn list_tasks(configuration: &configuration::Configuration, params: ListTasksParams) -> Result<ResponseContent<ListTasksSuccess>, Error<ListTasksError>> {
+    let local_var_configuration = configuration;
+
+    // unbox the parameters
+    let organization = params.organization;
+    let limit = params.limit;
+    let offset = params.offset;
+    let owner = params.owner;
+
+
+    let local_var_client = &local_var_configuration.client;
+
+    let local_var_uri_str = format!("{}/api/tasks/list", local_var_configuration.base_path);
+    let mut local_var_req_builder = local_var_client.request(reqwest::Method::GET, local_var_uri_str.as_str());
+
+    if let Some(ref local_var_str) = limit {
+        local_var_req_builder = local_var_req_builder.query(&[("limit", &local_var_str.to_string())]);
+    }
+    if let Some(ref local_var_str) = offset {
+        local_var_req_builder = local_var_req_builder.query(&[("offset", &local_var_str.to_string())]);
+    }
+    if let Some(ref local_var_str) = owner {
+        local_var_req_builder = local_var_req_builder.query(&[("owner", &local_var_str.to_string())]);
+    }
+    if let Some(ref local_var_user_agent) = local_var_configuration.user_agent {
+        local_var_req_builder = local_var_req_builder.header(reqwest::header::USER_AGENT, local_var_user_agent.clone());
+    }
+    local_var_req_builder = local_var_req_builder.header("Organization", organization.to_string());
+    if let Some(ref local_var_apikey) = local_var_configuration.api_key {
+        let local_var_key = local_var_apikey.key.clone();
+        let local_var_value = match local_var_apikey.prefix {
+            Some(ref local_var_prefix) => format!("{} {}", local_var_prefix, local_var_key),
+            None => local_var_key,
+        };
+        local_var_req_builder = local_var_req_builder.header("Authorization", local_var_value);
+    };
+
+    let local_var_req = local_var_req_builder.build()?;
+    let local_var_resp = local_var_client.execute(local_var_req).await?;
+
+    let local_var_status = local_var_resp.status();
+    let local_var_content = local_var_resp.text().await?;
+
+    if !local_var_status.is_client_error() && !local_var_status.is_server_error() {
+        let local_var_entity: Option<ListTasksSuccess> = serde_json::from_str(&local_var_content).ok();
+        let local_var_result = ResponseContent { status: local_var_status, content: local_var_content, entity: local_var_entity };
+        Ok(local_var_result)
+    } else {
+        let local_var_entity: Option<ListTasksError> = serde_json::from_str(&local_var_content).ok();
+        let local_var_error = ResponseContent { status: local_var_status, content: local_var_content, entity: local_var_entity };
+        Err(Error::ResponseError(local_var_error))
+    }
+}
+
 pub async fn update_task(configuration: &configuration::Configuration, params: UpdateTaskParams) -> Result<ResponseContent<UpdateTaskSuccess>, Error<UpdateTaskError>> {
     let local_var_configuration = configuration;
 
@@ -491,17 +619,21 @@ pub async fn update_task(configuration: &configuration::Configuration, params: U
     let task_id = params.task_id;
     let organization = params.organization;
     let update_task_req_payload = params.update_task_req_payload;
+    let if_match = params.if_match;
 
 
     let local_var_client = &local_var_configuration.client;
 
     let local_var_uri_str = format!("{}/api/tasks/{task_id}", local_var_configuration.base_path, task_id=crate::apis::urlencode(task_id));
-    let mut local_var_req_builder = local_var_client.request(reqwest::Method::PUT, local_var_uri_str.as_str());
+    let mut local_var_req_builder = local_var_client.request(reqwest::Method::PATCH, local_var_uri_str.as_str());
 
     if let Some(ref local_var_user_agent) = local_var_configuration.user_agent {
         local_var_req_builder = local_var_req_builder.header(reqwest::header::USER_AGENT, local_var_user_agent.clone());
     }
     local_var_req_builder = local_var_req_builder.header("Organization", organization.to_string());
+    if let Some(local_var_param_value) = if_match {
+        local_var_req_builder = local_var_req_builder.header("If-Match", local_var_param_value.to_string());
+    }
     if let Some(ref local_var_apikey) = local_var_configuration.api_key {
         let local_var_key = local_var_apikey.key.clone();
         let local_var_value = match local_var_apikey.prefix {
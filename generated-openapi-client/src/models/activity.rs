@@ -0,0 +1,50 @@
+/*
+ * Trieve API
+ *
+ * Trieve OpenAPI Specification. This document describes all of the operations available through the Trieve API.
+ *
+ * The version of the OpenAPI document: 0.0.1
+ * Contact: developers@trieve.ai
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Activity {
+    #[serde(rename = "company_id", default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
+    pub company_id: Option<Option<String>>,
+    #[serde(rename = "contact_id", default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
+    pub contact_id: Option<Option<String>>,
+    #[serde(rename = "created_at")]
+    pub created_at: String,
+    #[serde(rename = "deal_id", default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
+    pub deal_id: Option<Option<String>>,
+    #[serde(rename = "id")]
+    pub id: String,
+    #[serde(rename = "occurred_at")]
+    pub occurred_at: String,
+    #[serde(rename = "org_id")]
+    pub org_id: String,
+    #[serde(rename = "payload")]
+    pub payload: Box<models::ActivityPayload>,
+    #[serde(rename = "updated_at")]
+    pub updated_at: String,
+}
+
+impl Activity {
+    pub fn new(created_at: String, id: String, occurred_at: String, org_id: String, payload: models::ActivityPayload, updated_at: String) -> Activity {
+        Activity {
+            company_id: None,
+            contact_id: None,
+            created_at,
+            deal_id: None,
+            id,
+            occurred_at,
+            org_id,
+            payload: Box::new(payload),
+            updated_at,
+        }
+    }
+}
+
@@ -0,0 +1,29 @@
+/*
+ * Trieve API
+ *
+ * Trieve OpenAPI Specification. This document describes all of the operations available through the Trieve API.
+ *
+ * The version of the OpenAPI document: 0.0.1
+ * Contact: developers@trieve.ai
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ActivityList {
+    #[serde(rename = "activities")]
+    pub activities: Vec<models::Activity>,
+    #[serde(rename = "total")]
+    pub total: i64,
+}
+
+impl ActivityList {
+    pub fn new(activities: Vec<models::Activity>, total: i64) -> ActivityList {
+        ActivityList {
+            activities,
+            total,
+        }
+    }
+}
+
@@ -0,0 +1,27 @@
+/*
+ * Trieve API
+ *
+ * Trieve OpenAPI Specification. This document describes all of the operations available through the Trieve API.
+ *
+ * The version of the OpenAPI document: 0.0.1
+ * Contact: developers@trieve.ai
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+
+/// The typed body of an activity. Stored as JSONB in the same externally tagged shape it is serialized with, e.g. `{"Call": {...}}`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ActivityPayload {
+    ActivityPayloadOneOf(Box<models::ActivityPayloadOneOf>),
+    ActivityPayloadOneOf1(Box<models::ActivityPayloadOneOf1>),
+    ActivityPayloadOneOf2(Box<models::ActivityPayloadOneOf2>),
+}
+
+impl Default for ActivityPayload {
+    fn default() -> Self {
+        Self::ActivityPayloadOneOf(Default::default())
+    }
+}
+
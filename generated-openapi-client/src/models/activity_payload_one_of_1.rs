@@ -0,0 +1,26 @@
+/*
+ * Trieve API
+ *
+ * Trieve OpenAPI Specification. This document describes all of the operations available through the Trieve API.
+ *
+ * The version of the OpenAPI document: 0.0.1
+ * Contact: developers@trieve.ai
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ActivityPayloadOneOf1 {
+    #[serde(rename = "Meeting")]
+    pub meeting: Box<models::MeetingActivity>,
+}
+
+impl ActivityPayloadOneOf1 {
+    pub fn new(meeting: models::MeetingActivity) -> ActivityPayloadOneOf1 {
+        ActivityPayloadOneOf1 {
+            meeting: Box::new(meeting),
+        }
+    }
+}
+
@@ -0,0 +1,32 @@
+/*
+ * Trieve API
+ *
+ * Trieve OpenAPI Specification. This document describes all of the operations available through the Trieve API.
+ *
+ * The version of the OpenAPI document: 0.0.1
+ * Contact: developers@trieve.ai
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AverageDealSizeRow {
+    #[serde(rename = "average_amount")]
+    pub average_amount: i64,
+    #[serde(rename = "currency")]
+    pub currency: String,
+    #[serde(rename = "deal_count")]
+    pub deal_count: i64,
+}
+
+impl AverageDealSizeRow {
+    pub fn new(average_amount: i64, currency: String, deal_count: i64) -> AverageDealSizeRow {
+        AverageDealSizeRow {
+            average_amount,
+            currency,
+            deal_count,
+        }
+    }
+}
+
@@ -0,0 +1,29 @@
+/*
+ * Trieve API
+ *
+ * Trieve OpenAPI Specification. This document describes all of the operations available through the Trieve API.
+ *
+ * The version of the OpenAPI document: 0.0.1
+ * Contact: developers@trieve.ai
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CallActivity {
+    #[serde(rename = "duration_seconds")]
+    pub duration_seconds: i32,
+    #[serde(rename = "outcome")]
+    pub outcome: models::CallOutcome,
+}
+
+impl CallActivity {
+    pub fn new(duration_seconds: i32, outcome: models::CallOutcome) -> CallActivity {
+        CallActivity {
+            duration_seconds,
+            outcome,
+        }
+    }
+}
+
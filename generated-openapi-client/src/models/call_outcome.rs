@@ -0,0 +1,43 @@
+/*
+ * Trieve API
+ *
+ * Trieve OpenAPI Specification. This document describes all of the operations available through the Trieve API.
+ *
+ * The version of the OpenAPI document: 0.0.1
+ * Contact: developers@trieve.ai
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+
+/// 
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub enum CallOutcome {
+    #[serde(rename = "Connected")]
+    Connected,
+    #[serde(rename = "NoAnswer")]
+    NoAnswer,
+    #[serde(rename = "LeftVoicemail")]
+    LeftVoicemail,
+    #[serde(rename = "WrongNumber")]
+    WrongNumber,
+
+}
+
+impl ToString for CallOutcome {
+    fn to_string(&self) -> String {
+        match self {
+            Self::Connected => String::from("Connected"),
+            Self::NoAnswer => String::from("NoAnswer"),
+            Self::LeftVoicemail => String::from("LeftVoicemail"),
+            Self::WrongNumber => String::from("WrongNumber"),
+        }
+    }
+}
+
+impl Default for CallOutcome {
+    fn default() -> CallOutcome {
+        Self::Connected
+    }
+}
+
@@ -0,0 +1,40 @@
+/*
+ * Trieve API
+ *
+ * Trieve OpenAPI Specification. This document describes all of the operations available through the Trieve API.
+ *
+ * The version of the OpenAPI document: 0.0.1
+ * Contact: developers@trieve.ai
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+
+/// 
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub enum ChangeAction {
+    #[serde(rename = "created")]
+    Created,
+    #[serde(rename = "updated")]
+    Updated,
+    #[serde(rename = "deleted")]
+    Deleted,
+
+}
+
+impl ToString for ChangeAction {
+    fn to_string(&self) -> String {
+        match self {
+            Self::Created => String::from("created"),
+            Self::Updated => String::from("updated"),
+            Self::Deleted => String::from("deleted"),
+        }
+    }
+}
+
+impl Default for ChangeAction {
+    fn default() -> ChangeAction {
+        Self::Created
+    }
+}
+
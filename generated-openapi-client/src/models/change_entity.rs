@@ -0,0 +1,58 @@
+/*
+ * Trieve API
+ *
+ * Trieve OpenAPI Specification. This document describes all of the operations available through the Trieve API.
+ *
+ * The version of the OpenAPI document: 0.0.1
+ * Contact: developers@trieve.ai
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+
+/// 
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub enum ChangeEntity {
+    #[serde(rename = "activity")]
+    Activity,
+    #[serde(rename = "company")]
+    Company,
+    #[serde(rename = "contact")]
+    Contact,
+    #[serde(rename = "deal")]
+    Deal,
+    #[serde(rename = "email")]
+    Email,
+    #[serde(rename = "link")]
+    Link,
+    #[serde(rename = "note")]
+    Note,
+    #[serde(rename = "phone")]
+    Phone,
+    #[serde(rename = "task")]
+    Task,
+
+}
+
+impl ToString for ChangeEntity {
+    fn to_string(&self) -> String {
+        match self {
+            Self::Activity => String::from("activity"),
+            Self::Company => String::from("company"),
+            Self::Contact => String::from("contact"),
+            Self::Deal => String::from("deal"),
+            Self::Email => String::from("email"),
+            Self::Link => String::from("link"),
+            Self::Note => String::from("note"),
+            Self::Phone => String::from("phone"),
+            Self::Task => String::from("task"),
+        }
+    }
+}
+
+impl Default for ChangeEntity {
+    fn default() -> ChangeEntity {
+        Self::Activity
+    }
+}
+
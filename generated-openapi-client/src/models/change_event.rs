@@ -0,0 +1,40 @@
+/*
+ * Trieve API
+ *
+ * Trieve OpenAPI Specification. This document describes all of the operations available through the Trieve API.
+ *
+ * The version of the OpenAPI document: 0.0.1
+ * Contact: developers@trieve.ai
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ChangeEvent {
+    #[serde(rename = "action")]
+    pub action: models::ChangeAction,
+    #[serde(rename = "entity")]
+    pub entity: models::ChangeEntity,
+    /// Position of the event in the organization's event stream. Sent as the SSE event id, so clients can resume from it with `Last-Event-ID`.
+    #[serde(rename = "event_id")]
+    pub event_id: String,
+    /// Prefixed id of the record that changed.
+    #[serde(rename = "id")]
+    pub id: String,
+    #[serde(rename = "org_id")]
+    pub org_id: String,
+}
+
+impl ChangeEvent {
+    pub fn new(action: models::ChangeAction, entity: models::ChangeEntity, event_id: String, id: String, org_id: String) -> ChangeEvent {
+        ChangeEvent {
+            action,
+            entity,
+            event_id,
+            id,
+            org_id,
+        }
+    }
+}
+
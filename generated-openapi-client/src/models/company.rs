@@ -20,6 +20,9 @@ pub struct Company {
     pub name: String,
     #[serde(rename = "org_id")]
     pub org_id: String,
+    /// The user responsible for this company. Defaults to whoever created it.
+    #[serde(rename = "owner_user_id", default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
+    pub owner_user_id: Option<Option<String>>,
     #[serde(rename = "updated_at")]
     pub updated_at: String,
 }
@@ -31,6 +34,7 @@ impl Company {
             id,
             name,
             org_id,
+            owner_user_id: None,
             updated_at,
         }
     }
@@ -18,10 +18,16 @@ pub struct Contact {
     pub first_name: String,
     #[serde(rename = "id")]
     pub id: String,
+    /// When the latest activity linked to the contact occurred. Kept in sync with its activities, changing it doesn't change `updated_at`.
+    #[serde(rename = "last_contacted_at", default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
+    pub last_contacted_at: Option<Option<String>>,
     #[serde(rename = "last_name")]
     pub last_name: String,
     #[serde(rename = "org_id")]
     pub org_id: String,
+    /// The user responsible for this contact. Defaults to whoever created it.
+    #[serde(rename = "owner_user_id", default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
+    pub owner_user_id: Option<Option<String>>,
     #[serde(rename = "updated_at")]
     pub updated_at: String,
 }
@@ -32,8 +38,10 @@ impl Contact {
             created_at,
             first_name,
             id,
+            last_contacted_at: None,
             last_name,
             org_id,
+            owner_user_id: None,
             updated_at,
         }
     }
@@ -14,18 +14,34 @@ use crate::models;
 pub struct CreateDealReqPayload {
     #[serde(rename = "active", default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
     pub active: Option<Option<bool>>,
+    /// Deal value in the minor unit of the currency (e.g. cents for USD).
+    #[serde(rename = "amount", default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
+    pub amount: Option<Option<i64>>,
+    /// Contacts to link to the deal. The deal isn't created if any of them can't be linked.
+    #[serde(rename = "contact_ids", default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
+    pub contact_ids: Option<Option<Vec<String>>>,
+    /// ISO 4217 currency code. Defaults to the organization's default currency.
+    #[serde(rename = "currency", default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
+    pub currency: Option<Option<String>>,
+    #[serde(rename = "expected_close_date", default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
+    pub expected_close_date: Option<Option<String>>,
     #[serde(rename = "name", default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
     pub name: Option<Option<String>>,
-    #[serde(rename = "size", default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
-    pub size: Option<Option<f32>>,
+    /// Likelihood of the deal closing as won, from 0 to 100.
+    #[serde(rename = "probability", default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
+    pub probability: Option<Option<i32>>,
 }
 
 impl CreateDealReqPayload {
     pub fn new() -> CreateDealReqPayload {
         CreateDealReqPayload {
             active: None,
+            amount: None,
+            contact_ids: None,
+            currency: None,
+            expected_close_date: None,
             name: None,
-            size: None,
+            probability: None,
         }
     }
 }
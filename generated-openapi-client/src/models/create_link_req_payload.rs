@@ -12,6 +12,7 @@ use crate::models;
 
 #[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
 pub struct CreateLinkReqPayload {
+    /// Absolute http or https URL.
     #[serde(rename = "link")]
     pub link: String,
 }
@@ -12,6 +12,9 @@ use crate::models;
 
 #[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
 pub struct CreateOrgReqPayload {
+    /// ISO 4217 code used for new deals that don't specify a currency. Defaults to USD.
+    #[serde(rename = "default_currency", default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
+    pub default_currency: Option<Option<String>>,
     #[serde(rename = "name")]
     pub name: String,
 }
@@ -19,6 +22,7 @@ pub struct CreateOrgReqPayload {
 impl CreateOrgReqPayload {
     pub fn new(name: String) -> CreateOrgReqPayload {
         CreateOrgReqPayload {
+            default_currency: None,
             name,
         }
     }
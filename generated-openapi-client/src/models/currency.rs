@@ -0,0 +1,31 @@
+/*
+ * Trieve API
+ *
+ * Trieve OpenAPI Specification. This document describes all of the operations available through the Trieve API.
+ *
+ * The version of the OpenAPI document: 0.0.1
+ * Contact: developers@trieve.ai
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Currency {
+    /// ISO 4217 currency code.
+    #[serde(rename = "code")]
+    pub code: String,
+    /// Number of decimal places in the minor unit amounts are stored in, e.g. 2 for USD and 0 for JPY.
+    #[serde(rename = "minor_unit_exponent")]
+    pub minor_unit_exponent: i32,
+}
+
+impl Currency {
+    pub fn new(code: String, minor_unit_exponent: i32) -> Currency {
+        Currency {
+            code,
+            minor_unit_exponent,
+        }
+    }
+}
+
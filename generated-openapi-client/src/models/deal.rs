@@ -14,30 +14,57 @@ use crate::models;
 pub struct Deal {
     #[serde(rename = "active")]
     pub active: bool,
+    /// Deal value in the minor unit of `currency` (e.g. cents for USD).
+    #[serde(rename = "amount", default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
+    pub amount: Option<Option<i64>>,
+    #[serde(rename = "closed_at", default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
+    pub closed_at: Option<Option<String>>,
     #[serde(rename = "created_at")]
     pub created_at: String,
+    /// ISO 4217 currency code of `amount`.
+    #[serde(rename = "currency")]
+    pub currency: String,
+    #[serde(rename = "expected_close_date", default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
+    pub expected_close_date: Option<Option<String>>,
     #[serde(rename = "id")]
     pub id: String,
+    /// When the latest activity linked to the deal occurred. Kept in sync with its activities, changing it doesn't change `updated_at`.
+    #[serde(rename = "last_contacted_at", default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
+    pub last_contacted_at: Option<Option<String>>,
     #[serde(rename = "name", default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
     pub name: Option<Option<String>>,
     #[serde(rename = "org_id")]
     pub org_id: String,
-    #[serde(rename = "size", default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
-    pub size: Option<Option<f32>>,
+    /// The user responsible for this deal. Defaults to whoever created it.
+    #[serde(rename = "owner_user_id", default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
+    pub owner_user_id: Option<Option<String>>,
+    /// Likelihood of the deal closing as won, from 0 to 100.
+    #[serde(rename = "probability", default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
+    pub probability: Option<Option<i32>>,
     #[serde(rename = "updated_at")]
     pub updated_at: String,
+    /// Whether the deal was won or lost. Null while the deal is still open.
+    #[serde(rename = "won", default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
+    pub won: Option<Option<bool>>,
 }
 
 impl Deal {
-    pub fn new(active: bool, created_at: String, id: String, org_id: String, updated_at: String) -> Deal {
+    pub fn new(active: bool, created_at: String, currency: String, id: String, org_id: String, updated_at: String) -> Deal {
         Deal {
             active,
+            amount: None,
+            closed_at: None,
             created_at,
+            currency,
+            expected_close_date: None,
             id,
+            last_contacted_at: None,
             name: None,
             org_id,
-            size: None,
+            owner_user_id: None,
+            probability: None,
             updated_at,
+            won: None,
         }
     }
 }
@@ -0,0 +1,29 @@
+/*
+ * Trieve API
+ *
+ * Trieve OpenAPI Specification. This document describes all of the operations available through the Trieve API.
+ *
+ * The version of the OpenAPI document: 0.0.1
+ * Contact: developers@trieve.ai
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DealReportQuery {
+    #[serde(rename = "from", default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
+    pub from: Option<Option<String>>,
+    #[serde(rename = "to", default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
+    pub to: Option<Option<String>>,
+}
+
+impl DealReportQuery {
+    pub fn new() -> DealReportQuery {
+        DealReportQuery {
+            from: None,
+            to: None,
+        }
+    }
+}
+
@@ -0,0 +1,34 @@
+/*
+ * Trieve API
+ *
+ * Trieve OpenAPI Specification. This document describes all of the operations available through the Trieve API.
+ *
+ * The version of the OpenAPI document: 0.0.1
+ * Contact: developers@trieve.ai
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DependencyHealth {
+    /// Why the dependency is unavailable.
+    #[serde(rename = "error", default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
+    pub error: Option<Option<String>>,
+    /// How long the check took, in milliseconds.
+    #[serde(rename = "latency_ms")]
+    pub latency_ms: i64,
+    #[serde(rename = "status")]
+    pub status: models::HealthStatus,
+}
+
+impl DependencyHealth {
+    pub fn new(latency_ms: i64, status: models::HealthStatus) -> DependencyHealth {
+        DependencyHealth {
+            error: None,
+            latency_ms,
+            status,
+        }
+    }
+}
+
@@ -0,0 +1,29 @@
+/*
+ * Trieve API
+ *
+ * Trieve OpenAPI Specification. This document describes all of the operations available through the Trieve API.
+ *
+ * The version of the OpenAPI document: 0.0.1
+ * Contact: developers@trieve.ai
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct EmailActivity {
+    #[serde(rename = "direction")]
+    pub direction: models::EmailDirection,
+    #[serde(rename = "subject")]
+    pub subject: String,
+}
+
+impl EmailActivity {
+    pub fn new(direction: models::EmailDirection, subject: String) -> EmailActivity {
+        EmailActivity {
+            direction,
+            subject,
+        }
+    }
+}
+
@@ -0,0 +1,37 @@
+/*
+ * Trieve API
+ *
+ * Trieve OpenAPI Specification. This document describes all of the operations available through the Trieve API.
+ *
+ * The version of the OpenAPI document: 0.0.1
+ * Contact: developers@trieve.ai
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+
+/// 
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub enum EmailDirection {
+    #[serde(rename = "Inbound")]
+    Inbound,
+    #[serde(rename = "Outbound")]
+    Outbound,
+
+}
+
+impl ToString for EmailDirection {
+    fn to_string(&self) -> String {
+        match self {
+            Self::Inbound => String::from("Inbound"),
+            Self::Outbound => String::from("Outbound"),
+        }
+    }
+}
+
+impl Default for EmailDirection {
+    fn default() -> EmailDirection {
+        Self::Inbound
+    }
+}
+
@@ -0,0 +1,67 @@
+/*
+ * Trieve API
+ *
+ * Trieve OpenAPI Specification. This document describes all of the operations available through the Trieve API.
+ *
+ * The version of the OpenAPI document: 0.0.1
+ * Contact: developers@trieve.ai
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+
+/// Stable, machine-readable identifier of an error. Messages may change, codes don't.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub enum ErrorCode {
+    #[serde(rename = "internal_server_error")]
+    InternalServerError,
+    #[serde(rename = "bad_request")]
+    BadRequest,
+    #[serde(rename = "validation_failed")]
+    ValidationFailed,
+    #[serde(rename = "unauthorized")]
+    Unauthorized,
+    #[serde(rename = "forbidden")]
+    Forbidden,
+    #[serde(rename = "payment_required")]
+    PaymentRequired,
+    #[serde(rename = "not_found")]
+    NotFound,
+    #[serde(rename = "conflict")]
+    Conflict,
+    #[serde(rename = "precondition_failed")]
+    PreconditionFailed,
+    #[serde(rename = "unprocessable_entity")]
+    UnprocessableEntity,
+    #[serde(rename = "too_many_requests")]
+    TooManyRequests,
+    #[serde(rename = "service_unavailable")]
+    ServiceUnavailable,
+
+}
+
+impl ToString for ErrorCode {
+    fn to_string(&self) -> String {
+        match self {
+            Self::InternalServerError => String::from("internal_server_error"),
+            Self::BadRequest => String::from("bad_request"),
+            Self::ValidationFailed => String::from("validation_failed"),
+            Self::Unauthorized => String::from("unauthorized"),
+            Self::Forbidden => String::from("forbidden"),
+            Self::PaymentRequired => String::from("payment_required"),
+            Self::NotFound => String::from("not_found"),
+            Self::Conflict => String::from("conflict"),
+            Self::PreconditionFailed => String::from("precondition_failed"),
+            Self::UnprocessableEntity => String::from("unprocessable_entity"),
+            Self::TooManyRequests => String::from("too_many_requests"),
+            Self::ServiceUnavailable => String::from("service_unavailable"),
+        }
+    }
+}
+
+impl Default for ErrorCode {
+    fn default() -> ErrorCode {
+        Self::InternalServerError
+    }
+}
+
@@ -12,14 +12,25 @@ use crate::models;
 
 #[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
 pub struct ErrorRespPayload {
+    #[serde(rename = "code")]
+    pub code: models::ErrorCode,
+    /// Per-field problems when `code` is `validation_failed`.
+    #[serde(rename = "details", skip_serializing_if = "Option::is_none")]
+    pub details: Option<Vec<models::FieldError>>,
     #[serde(rename = "message")]
     pub message: String,
+    /// Id of the failed request, also sent as `X-Request-Id`. Include it when reporting a problem.
+    #[serde(rename = "request_id", default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<Option<String>>,
 }
 
 impl ErrorRespPayload {
-    pub fn new(message: String) -> ErrorRespPayload {
+    pub fn new(code: models::ErrorCode, message: String) -> ErrorRespPayload {
         ErrorRespPayload {
+            code,
+            details: None,
             message,
+            request_id: None,
         }
     }
 }
@@ -0,0 +1,31 @@
+/*
+ * Trieve API
+ *
+ * Trieve OpenAPI Specification. This document describes all of the operations available through the Trieve API.
+ *
+ * The version of the OpenAPI document: 0.0.1
+ * Contact: developers@trieve.ai
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+
+/// Why a single field of the request was rejected.
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FieldError {
+    #[serde(rename = "field")]
+    pub field: String,
+    #[serde(rename = "message")]
+    pub message: String,
+}
+
+impl FieldError {
+    /// Why a single field of the request was rejected.
+    pub fn new(field: String, message: String) -> FieldError {
+        FieldError {
+            field,
+            message,
+        }
+    }
+}
+
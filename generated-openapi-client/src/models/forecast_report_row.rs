@@ -0,0 +1,40 @@
+/*
+ * Trieve API
+ *
+ * Trieve OpenAPI Specification. This document describes all of the operations available through the Trieve API.
+ *
+ * The version of the OpenAPI document: 0.0.1
+ * Contact: developers@trieve.ai
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ForecastReportRow {
+    #[serde(rename = "currency")]
+    pub currency: String,
+    #[serde(rename = "deal_count")]
+    pub deal_count: i64,
+    /// First day of the expected close month. Null for deals without an expected close date.
+    #[serde(rename = "month", default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
+    pub month: Option<Option<String>>,
+    #[serde(rename = "total_amount")]
+    pub total_amount: i64,
+    /// Sum of each deal amount multiplied by its probability.
+    #[serde(rename = "weighted_amount")]
+    pub weighted_amount: i64,
+}
+
+impl ForecastReportRow {
+    pub fn new(currency: String, deal_count: i64, total_amount: i64, weighted_amount: i64) -> ForecastReportRow {
+        ForecastReportRow {
+            currency,
+            deal_count,
+            month: None,
+            total_amount,
+            weighted_amount,
+        }
+    }
+}
+
@@ -0,0 +1,37 @@
+/*
+ * Trieve API
+ *
+ * Trieve OpenAPI Specification. This document describes all of the operations available through the Trieve API.
+ *
+ * The version of the OpenAPI document: 0.0.1
+ * Contact: developers@trieve.ai
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+
+/// 
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub enum HealthStatus {
+    #[serde(rename = "ok")]
+    Ok,
+    #[serde(rename = "unavailable")]
+    Unavailable,
+
+}
+
+impl ToString for HealthStatus {
+    fn to_string(&self) -> String {
+        match self {
+            Self::Ok => String::from("ok"),
+            Self::Unavailable => String::from("unavailable"),
+        }
+    }
+}
+
+impl Default for HealthStatus {
+    fn default() -> HealthStatus {
+        Self::Ok
+    }
+}
+
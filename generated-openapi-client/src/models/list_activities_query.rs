@@ -0,0 +1,38 @@
+/*
+ * Trieve API
+ *
+ * Trieve OpenAPI Specification. This document describes all of the operations available through the Trieve API.
+ *
+ * The version of the OpenAPI document: 0.0.1
+ * Contact: developers@trieve.ai
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ListActivitiesQuery {
+    #[serde(rename = "company_id", default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
+    pub company_id: Option<Option<String>>,
+    #[serde(rename = "contact_id", default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
+    pub contact_id: Option<Option<String>>,
+    #[serde(rename = "deal_id", default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
+    pub deal_id: Option<Option<String>>,
+    #[serde(rename = "limit", default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
+    pub limit: Option<Option<i64>>,
+    #[serde(rename = "offset", default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
+    pub offset: Option<Option<String>>,
+}
+
+impl ListActivitiesQuery {
+    pub fn new() -> ListActivitiesQuery {
+        ListActivitiesQuery {
+            company_id: None,
+            contact_id: None,
+            deal_id: None,
+            limit: None,
+            offset: None,
+        }
+    }
+}
+
@@ -16,6 +16,8 @@ pub struct ListContactsQuery {
     pub limit: Option<Option<i64>>,
     #[serde(rename = "offset", default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
     pub offset: Option<Option<String>>,
+    #[serde(rename = "owner", default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
+    pub owner: Option<Option<String>>,
 }
 
 impl ListContactsQuery {
@@ -23,6 +25,7 @@ impl ListContactsQuery {
         ListContactsQuery {
             limit: None,
             offset: None,
+            owner: None,
         }
     }
 }
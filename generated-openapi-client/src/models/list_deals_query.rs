@@ -0,0 +1,32 @@
+/*
+ * Trieve API
+ *
+ * Trieve OpenAPI Specification. This document describes all of the operations available through the Trieve API.
+ *
+ * The version of the OpenAPI document: 0.0.1
+ * Contact: developers@trieve.ai
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ListDealsQuery {
+    #[serde(rename = "limit", default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
+    pub limit: Option<Option<i64>>,
+    #[serde(rename = "offset", default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
+    pub offset: Option<Option<String>>,
+    #[serde(rename = "owner", default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
+    pub owner: Option<Option<String>>,
+}
+
+impl ListDealsQuery {
+    pub fn new() -> ListDealsQuery {
+        ListDealsQuery {
+            limit: None,
+            offset: None,
+            owner: None,
+        }
+    }
+}
+
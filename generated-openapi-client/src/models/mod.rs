@@ -1,9 +1,35 @@
+pub mod activity;
+pub use self::activity::Activity;
+pub mod activity_list;
+pub use self::activity_list::ActivityList;
+pub mod activity_payload;
+pub use self::activity_payload::ActivityPayload;
+pub mod activity_payload_one_of;
+pub use self::activity_payload_one_of::ActivityPayloadOneOf;
+pub mod activity_payload_one_of_1;
+pub use self::activity_payload_one_of_1::ActivityPayloadOneOf1;
+pub mod activity_payload_one_of_2;
+pub use self::activity_payload_one_of_2::ActivityPayloadOneOf2;
+pub mod average_deal_size_row;
+pub use self::average_deal_size_row::AverageDealSizeRow;
+pub mod call_activity;
+pub use self::call_activity::CallActivity;
+pub mod call_outcome;
+pub use self::call_outcome::CallOutcome;
+pub mod change_action;
+pub use self::change_action::ChangeAction;
+pub mod change_entity;
+pub use self::change_entity::ChangeEntity;
+pub mod change_event;
+pub use self::change_event::ChangeEvent;
 pub mod company;
 pub use self::company::Company;
 pub mod contact;
 pub use self::contact::Contact;
 pub mod contact_list;
 pub use self::contact_list::ContactList;
+pub mod create_activity_req_payload;
+pub use self::create_activity_req_payload::CreateActivityReqPayload;
 pub mod create_api_key_req_payload;
 pub use self::create_api_key_req_payload::CreateApiKeyReqPayload;
 pub mod create_api_key_resp_payload;
@@ -26,10 +52,14 @@ pub mod create_phone_req_payload;
 pub use self::create_phone_req_payload::CreatePhoneReqPayload;
 pub mod create_task_req_payload;
 pub use self::create_task_req_payload::CreateTaskReqPayload;
+pub mod currency;
+pub use self::currency::Currency;
 pub mod deal;
 pub use self::deal::Deal;
 pub mod deal_contact;
 pub use self::deal_contact::DealContact;
+pub mod deal_report_query;
+pub use self::deal_report_query::DealReportQuery;
 pub mod deal_res_type;
 pub use self::deal_res_type::DealResType;
 pub mod deal_resource;
@@ -42,12 +72,28 @@ pub mod deal_resource_list_with_pagination;
 pub use self::deal_resource_list_with_pagination::DealResourceListWithPagination;
 pub mod deal_resource_one_of;
 pub use self::deal_resource_one_of::DealResourceOneOf;
+pub mod dependency_health;
+pub use self::dependency_health::DependencyHealth;
 pub mod email;
 pub use self::email::Email;
+pub mod email_activity;
+pub use self::email_activity::EmailActivity;
+pub mod email_direction;
+pub use self::email_direction::EmailDirection;
+pub mod error_code;
+pub use self::error_code::ErrorCode;
 pub mod error_resp_payload;
 pub use self::error_resp_payload::ErrorRespPayload;
+pub mod field_error;
+pub use self::field_error::FieldError;
+pub mod forecast_report;
+pub use self::forecast_report::ForecastReport;
+pub mod forecast_report_row;
+pub use self::forecast_report_row::ForecastReportRow;
 pub mod get_task_resource_query;
 pub use self::get_task_resource_query::GetTaskResourceQuery;
+pub mod health_status;
+pub use self::health_status::HealthStatus;
 pub mod invitation;
 pub use self::invitation::Invitation;
 pub mod invitation_data;
@@ -56,24 +102,74 @@ pub mod invitation_response;
 pub use self::invitation_response::InvitationResponse;
 pub mod link;
 pub use self::link::Link;
+pub mod list_activities_query;
+pub use self::list_activities_query::ListActivitiesQuery;
 pub mod list_contacts_query;
 pub use self::list_contacts_query::ListContactsQuery;
 pub mod list_deal_by_org_resp_body;
 pub use self::list_deal_by_org_resp_body::ListDealByOrgRespBody;
 pub mod list_deal_resource_query;
 pub use self::list_deal_resource_query::ListDealResourceQuery;
+pub mod list_deals_query;
+pub use self::list_deals_query::ListDealsQuery;
+pub mod list_tasks_query;
+pub use self::list_tasks_query::ListTasksQuery;
+pub mod meeting_activity;
+pub use self::meeting_activity::MeetingActivity;
 pub mod note;
 pub use self::note::Note;
 pub mod org;
 pub use self::org::Org;
+pub mod org_usage;
+pub use self::org_usage::OrgUsage;
+pub mod performance_report;
+pub use self::performance_report::PerformanceReport;
 pub mod phone;
 pub use self::phone::Phone;
+pub mod pipeline_report;
+pub use self::pipeline_report::PipelineReport;
+pub mod pipeline_report_row;
+pub use self::pipeline_report_row::PipelineReportRow;
+pub mod plan;
+pub use self::plan::Plan;
+pub mod readiness_resp_payload;
+pub use self::readiness_resp_payload::ReadinessRespPayload;
+pub mod reassign_records_req_payload;
+pub use self::reassign_records_req_payload::ReassignRecordsReqPayload;
+pub mod reassigned_records;
+pub use self::reassigned_records::ReassignedRecords;
+pub mod resolved_entity;
+pub use self::resolved_entity::ResolvedEntity;
+pub mod resolved_entity_one_of;
+pub use self::resolved_entity_one_of::ResolvedEntityOneOf;
+pub mod resolved_entity_one_of_1;
+pub use self::resolved_entity_one_of_1::ResolvedEntityOneOf1;
+pub mod resolved_entity_one_of_10;
+pub use self::resolved_entity_one_of_10::ResolvedEntityOneOf10;
+pub mod resolved_entity_one_of_2;
+pub use self::resolved_entity_one_of_2::ResolvedEntityOneOf2;
+pub mod resolved_entity_one_of_3;
+pub use self::resolved_entity_one_of_3::ResolvedEntityOneOf3;
+pub mod resolved_entity_one_of_4;
+pub use self::resolved_entity_one_of_4::ResolvedEntityOneOf4;
+pub mod resolved_entity_one_of_5;
+pub use self::resolved_entity_one_of_5::ResolvedEntityOneOf5;
+pub mod resolved_entity_one_of_6;
+pub use self::resolved_entity_one_of_6::ResolvedEntityOneOf6;
+pub mod resolved_entity_one_of_7;
+pub use self::resolved_entity_one_of_7::ResolvedEntityOneOf7;
+pub mod resolved_entity_one_of_8;
+pub use self::resolved_entity_one_of_8::ResolvedEntityOneOf8;
+pub mod resolved_entity_one_of_9;
+pub use self::resolved_entity_one_of_9::ResolvedEntityOneOf9;
 pub mod task;
 pub use self::task::Task;
 pub mod task_deal;
 pub use self::task_deal::TaskDeal;
 pub mod task_link;
 pub use self::task_link::TaskLink;
+pub mod task_list;
+pub use self::task_list::TaskList;
 pub mod task_res_type;
 pub use self::task_res_type::TaskResType;
 pub mod task_resource;
@@ -96,6 +192,8 @@ pub mod task_resource_one_of_2;
 pub use self::task_resource_one_of_2::TaskResourceOneOf2;
 pub mod task_user;
 pub use self::task_user::TaskUser;
+pub mod update_activity_req_payload;
+pub use self::update_activity_req_payload::UpdateActivityReqPayload;
 pub mod update_company_req_payload;
 pub use self::update_company_req_payload::UpdateCompanyReqPayload;
 pub mod update_contact_req_payload;
@@ -116,3 +214,7 @@ pub mod update_task_req_payload;
 pub use self::update_task_req_payload::UpdateTaskReqPayload;
 pub mod user;
 pub use self::user::User;
+pub mod worker_health;
+pub use self::worker_health::WorkerHealth;
+pub mod worker_state;
+pub use self::worker_state::WorkerState;
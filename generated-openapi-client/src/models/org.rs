@@ -14,6 +14,9 @@ use crate::models;
 pub struct Org {
     #[serde(rename = "created_at")]
     pub created_at: String,
+    /// ISO 4217 code used for new deals that don't specify a currency.
+    #[serde(rename = "default_currency")]
+    pub default_currency: String,
     #[serde(rename = "id")]
     pub id: String,
     #[serde(rename = "name")]
@@ -23,9 +26,10 @@ pub struct Org {
 }
 
 impl Org {
-    pub fn new(created_at: String, id: String, name: String, updated_at: String) -> Org {
+    pub fn new(created_at: String, default_currency: String, id: String, name: String, updated_at: String) -> Org {
         Org {
             created_at,
+            default_currency,
             id,
             name,
             updated_at,
@@ -0,0 +1,35 @@
+/*
+ * Trieve API
+ *
+ * Trieve OpenAPI Specification. This document describes all of the operations available through the Trieve API.
+ *
+ * The version of the OpenAPI document: 0.0.1
+ * Contact: developers@trieve.ai
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct OrgUsage {
+    /// Number of deals in the organization.
+    #[serde(rename = "num_deals")]
+    pub num_deals: i64,
+    /// Number of members in the organization.
+    #[serde(rename = "num_users")]
+    pub num_users: i64,
+    /// The plan the organization is on. Organizations without a subscription are on the free plan.
+    #[serde(rename = "plan")]
+    pub plan: Box<models::Plan>,
+}
+
+impl OrgUsage {
+    pub fn new(num_deals: i64, num_users: i64, plan: models::Plan) -> OrgUsage {
+        OrgUsage {
+            num_deals,
+            num_users,
+            plan: Box::new(plan),
+        }
+    }
+}
+
@@ -0,0 +1,41 @@
+/*
+ * Trieve API
+ *
+ * Trieve OpenAPI Specification. This document describes all of the operations available through the Trieve API.
+ *
+ * The version of the OpenAPI document: 0.0.1
+ * Contact: developers@trieve.ai
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PerformanceReport {
+    /// Average amount of won deals, per currency.
+    #[serde(rename = "average_deal_size")]
+    pub average_deal_size: Vec<models::AverageDealSizeRow>,
+    /// Average number of days from creation to close for won deals.
+    #[serde(rename = "average_sales_cycle_days", default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
+    pub average_sales_cycle_days: Option<Option<f64>>,
+    #[serde(rename = "lost_count")]
+    pub lost_count: i64,
+    /// Share of closed deals that were won, from 0 to 1. Null when no deals were closed.
+    #[serde(rename = "win_rate", default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
+    pub win_rate: Option<Option<f64>>,
+    #[serde(rename = "won_count")]
+    pub won_count: i64,
+}
+
+impl PerformanceReport {
+    pub fn new(average_deal_size: Vec<models::AverageDealSizeRow>, lost_count: i64, won_count: i64) -> PerformanceReport {
+        PerformanceReport {
+            average_deal_size,
+            average_sales_cycle_days: None,
+            lost_count,
+            win_rate: None,
+            won_count,
+        }
+    }
+}
+
@@ -0,0 +1,40 @@
+/*
+ * Trieve API
+ *
+ * Trieve OpenAPI Specification. This document describes all of the operations available through the Trieve API.
+ *
+ * The version of the OpenAPI document: 0.0.1
+ * Contact: developers@trieve.ai
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PipelineReportRow {
+    #[serde(rename = "active")]
+    pub active: bool,
+    #[serde(rename = "currency")]
+    pub currency: String,
+    #[serde(rename = "deal_count")]
+    pub deal_count: i64,
+    /// First day of the expected close month. Null for deals without an expected close date.
+    #[serde(rename = "month", default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
+    pub month: Option<Option<String>>,
+    /// Sum of deal amounts in the minor unit of `currency`.
+    #[serde(rename = "total_amount")]
+    pub total_amount: i64,
+}
+
+impl PipelineReportRow {
+    pub fn new(active: bool, currency: String, deal_count: i64, total_amount: i64) -> PipelineReportRow {
+        PipelineReportRow {
+            active,
+            currency,
+            deal_count,
+            month: None,
+            total_amount,
+        }
+    }
+}
+
@@ -0,0 +1,44 @@
+/*
+ * Trieve API
+ *
+ * Trieve OpenAPI Specification. This document describes all of the operations available through the Trieve API.
+ *
+ * The version of the OpenAPI document: 0.0.1
+ * Contact: developers@trieve.ai
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Plan {
+    #[serde(rename = "created_at")]
+    pub created_at: String,
+    #[serde(rename = "id")]
+    pub id: uuid::Uuid,
+    #[serde(rename = "num_deals")]
+    pub num_deals: i32,
+    #[serde(rename = "num_users")]
+    pub num_users: i32,
+    #[serde(rename = "price_per_month")]
+    pub price_per_month: i32,
+    #[serde(rename = "stripe_id")]
+    pub stripe_id: String,
+    #[serde(rename = "updated_at")]
+    pub updated_at: String,
+}
+
+impl Plan {
+    pub fn new(created_at: String, id: uuid::Uuid, num_deals: i32, num_users: i32, price_per_month: i32, stripe_id: String, updated_at: String) -> Plan {
+        Plan {
+            created_at,
+            id,
+            num_deals,
+            num_users,
+            price_per_month,
+            stripe_id,
+            updated_at,
+        }
+    }
+}
+
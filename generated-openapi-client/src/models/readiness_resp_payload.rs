@@ -0,0 +1,37 @@
+/*
+ * Trieve API
+ *
+ * Trieve OpenAPI Specification. This document describes all of the operations available through the Trieve API.
+ *
+ * The version of the OpenAPI document: 0.0.1
+ * Contact: developers@trieve.ai
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ReadinessRespPayload {
+    #[serde(rename = "database")]
+    pub database: Box<models::DependencyHealth>,
+    #[serde(rename = "redis")]
+    pub redis: Box<models::DependencyHealth>,
+    /// `ok` when every dependency is and the server isn't shutting down.
+    #[serde(rename = "status")]
+    pub status: models::HealthStatus,
+    /// Background workers of this instance. Informational, a failing worker doesn't make the server unready.
+    #[serde(rename = "workers")]
+    pub workers: Vec<models::WorkerHealth>,
+}
+
+impl ReadinessRespPayload {
+    pub fn new(database: models::DependencyHealth, redis: models::DependencyHealth, status: models::HealthStatus, workers: Vec<models::WorkerHealth>) -> ReadinessRespPayload {
+        ReadinessRespPayload {
+            database: Box::new(database),
+            redis: Box::new(redis),
+            status,
+            workers,
+        }
+    }
+}
+
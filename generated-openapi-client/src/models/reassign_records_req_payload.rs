@@ -0,0 +1,31 @@
+/*
+ * Trieve API
+ *
+ * Trieve OpenAPI Specification. This document describes all of the operations available through the Trieve API.
+ *
+ * The version of the OpenAPI document: 0.0.1
+ * Contact: developers@trieve.ai
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ReassignRecordsReqPayload {
+    /// The user whose contacts, deals, companies and tasks should be handed over.
+    #[serde(rename = "from_user_id")]
+    pub from_user_id: String,
+    /// The member of the organization who will own the records.
+    #[serde(rename = "to_user_id")]
+    pub to_user_id: String,
+}
+
+impl ReassignRecordsReqPayload {
+    pub fn new(from_user_id: String, to_user_id: String) -> ReassignRecordsReqPayload {
+        ReassignRecordsReqPayload {
+            from_user_id,
+            to_user_id,
+        }
+    }
+}
+
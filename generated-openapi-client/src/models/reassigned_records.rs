@@ -0,0 +1,35 @@
+/*
+ * Trieve API
+ *
+ * Trieve OpenAPI Specification. This document describes all of the operations available through the Trieve API.
+ *
+ * The version of the OpenAPI document: 0.0.1
+ * Contact: developers@trieve.ai
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ReassignedRecords {
+    #[serde(rename = "companies")]
+    pub companies: i64,
+    #[serde(rename = "contacts")]
+    pub contacts: i64,
+    #[serde(rename = "deals")]
+    pub deals: i64,
+    #[serde(rename = "tasks")]
+    pub tasks: i64,
+}
+
+impl ReassignedRecords {
+    pub fn new(companies: i64, contacts: i64, deals: i64, tasks: i64) -> ReassignedRecords {
+        ReassignedRecords {
+            companies,
+            contacts,
+            deals,
+            tasks,
+        }
+    }
+}
+
@@ -0,0 +1,35 @@
+/*
+ * Trieve API
+ *
+ * Trieve OpenAPI Specification. This document describes all of the operations available through the Trieve API.
+ *
+ * The version of the OpenAPI document: 0.0.1
+ * Contact: developers@trieve.ai
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+
+/// A record looked up by its prefixed id, along with the type the prefix stands for.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ResolvedEntity {
+    ResolvedEntityOneOf(Box<models::ResolvedEntityOneOf>),
+    ResolvedEntityOneOf1(Box<models::ResolvedEntityOneOf1>),
+    ResolvedEntityOneOf2(Box<models::ResolvedEntityOneOf2>),
+    ResolvedEntityOneOf3(Box<models::ResolvedEntityOneOf3>),
+    ResolvedEntityOneOf4(Box<models::ResolvedEntityOneOf4>),
+    ResolvedEntityOneOf5(Box<models::ResolvedEntityOneOf5>),
+    ResolvedEntityOneOf6(Box<models::ResolvedEntityOneOf6>),
+    ResolvedEntityOneOf7(Box<models::ResolvedEntityOneOf7>),
+    ResolvedEntityOneOf8(Box<models::ResolvedEntityOneOf8>),
+    ResolvedEntityOneOf9(Box<models::ResolvedEntityOneOf9>),
+    ResolvedEntityOneOf10(Box<models::ResolvedEntityOneOf10>),
+}
+
+impl Default for ResolvedEntity {
+    fn default() -> Self {
+        Self::ResolvedEntityOneOf(Default::default())
+    }
+}
+
@@ -0,0 +1,42 @@
+/*
+ * Trieve API
+ *
+ * Trieve OpenAPI Specification. This document describes all of the operations available through the Trieve API.
+ *
+ * The version of the OpenAPI document: 0.0.1
+ * Contact: developers@trieve.ai
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ResolvedEntityOneOf1 {
+    #[serde(rename = "entity")]
+    pub entity: Box<models::Company>,
+    #[serde(rename = "type")]
+    pub r#type: Type,
+}
+
+impl ResolvedEntityOneOf1 {
+    pub fn new(entity: models::Company, r#type: Type) -> ResolvedEntityOneOf1 {
+        ResolvedEntityOneOf1 {
+            entity: Box::new(entity),
+            r#type,
+        }
+    }
+}
+
+/// 
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub enum Type {
+    #[serde(rename = "company")]
+    Company,
+}
+
+impl Default for Type {
+    fn default() -> Type {
+        Self::Company
+    }
+}
+
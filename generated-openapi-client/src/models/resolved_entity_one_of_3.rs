@@ -0,0 +1,42 @@
+/*
+ * Trieve API
+ *
+ * Trieve OpenAPI Specification. This document describes all of the operations available through the Trieve API.
+ *
+ * The version of the OpenAPI document: 0.0.1
+ * Contact: developers@trieve.ai
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ResolvedEntityOneOf3 {
+    #[serde(rename = "entity")]
+    pub entity: Box<models::Deal>,
+    #[serde(rename = "type")]
+    pub r#type: Type,
+}
+
+impl ResolvedEntityOneOf3 {
+    pub fn new(entity: models::Deal, r#type: Type) -> ResolvedEntityOneOf3 {
+        ResolvedEntityOneOf3 {
+            entity: Box::new(entity),
+            r#type,
+        }
+    }
+}
+
+/// 
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub enum Type {
+    #[serde(rename = "deal")]
+    Deal,
+}
+
+impl Default for Type {
+    fn default() -> Type {
+        Self::Deal
+    }
+}
+
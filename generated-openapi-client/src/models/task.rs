@@ -24,6 +24,9 @@ pub struct Task {
     pub id: String,
     #[serde(rename = "org_id")]
     pub org_id: String,
+    /// The user responsible for this task. Defaults to whoever created it.
+    #[serde(rename = "owner_user_id", default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
+    pub owner_user_id: Option<Option<String>>,
     #[serde(rename = "updated_at")]
     pub updated_at: String,
 }
@@ -37,6 +40,7 @@ impl Task {
             description: None,
             id,
             org_id,
+            owner_user_id: None,
             updated_at,
         }
     }
@@ -0,0 +1,29 @@
+/*
+ * Trieve API
+ *
+ * Trieve OpenAPI Specification. This document describes all of the operations available through the Trieve API.
+ *
+ * The version of the OpenAPI document: 0.0.1
+ * Contact: developers@trieve.ai
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TaskList {
+    #[serde(rename = "tasks")]
+    pub tasks: Vec<models::Task>,
+    #[serde(rename = "total")]
+    pub total: i64,
+}
+
+impl TaskList {
+    pub fn new(tasks: Vec<models::Task>, total: i64) -> TaskList {
+        TaskList {
+            tasks,
+            total,
+        }
+    }
+}
+
@@ -0,0 +1,38 @@
+/*
+ * Trieve API
+ *
+ * Trieve OpenAPI Specification. This document describes all of the operations available through the Trieve API.
+ *
+ * The version of the OpenAPI document: 0.0.1
+ * Contact: developers@trieve.ai
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct UpdateActivityReqPayload {
+    #[serde(rename = "company_id", default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
+    pub company_id: Option<Option<String>>,
+    #[serde(rename = "contact_id", default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
+    pub contact_id: Option<Option<String>>,
+    #[serde(rename = "deal_id", default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
+    pub deal_id: Option<Option<String>>,
+    #[serde(rename = "occurred_at", default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
+    pub occurred_at: Option<Option<String>>,
+    #[serde(rename = "payload", default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
+    pub payload: Option<Option<Box<models::ActivityPayload>>>,
+}
+
+impl UpdateActivityReqPayload {
+    pub fn new() -> UpdateActivityReqPayload {
+        UpdateActivityReqPayload {
+            company_id: None,
+            contact_id: None,
+            deal_id: None,
+            occurred_at: None,
+            payload: None,
+        }
+    }
+}
+
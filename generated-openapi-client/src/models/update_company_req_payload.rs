@@ -12,14 +12,18 @@ use crate::models;
 
 #[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
 pub struct UpdateCompanyReqPayload {
-    #[serde(rename = "name")]
-    pub name: String,
+    #[serde(rename = "name", default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
+    pub name: Option<Option<String>>,
+    /// Reassigns the company to another member of the organization.
+    #[serde(rename = "owner_user_id", default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
+    pub owner_user_id: Option<Option<String>>,
 }
 
 impl UpdateCompanyReqPayload {
-    pub fn new(name: String) -> UpdateCompanyReqPayload {
+    pub fn new() -> UpdateCompanyReqPayload {
         UpdateCompanyReqPayload {
-            name,
+            name: None,
+            owner_user_id: None,
         }
     }
 }
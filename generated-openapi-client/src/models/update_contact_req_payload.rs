@@ -16,6 +16,9 @@ pub struct UpdateContactReqPayload {
     pub first_name: Option<Option<String>>,
     #[serde(rename = "last_name", default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
     pub last_name: Option<Option<String>>,
+    /// Reassigns the contact to another member of the organization.
+    #[serde(rename = "owner_user_id", default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
+    pub owner_user_id: Option<Option<String>>,
 }
 
 impl UpdateContactReqPayload {
@@ -23,6 +26,7 @@ impl UpdateContactReqPayload {
         UpdateContactReqPayload {
             first_name: None,
             last_name: None,
+            owner_user_id: None,
         }
     }
 }
@@ -14,18 +14,38 @@ use crate::models;
 pub struct UpdateDealReqPayload {
     #[serde(rename = "active", default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
     pub active: Option<Option<bool>>,
+    /// Deal value in the minor unit of the currency (e.g. cents for USD).
+    #[serde(rename = "amount", default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
+    pub amount: Option<Option<i64>>,
+    /// ISO 4217 currency code. Changing it requires sending `amount` in the new currency, unless the deal has no amount.
+    #[serde(rename = "currency", default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
+    pub currency: Option<Option<String>>,
+    #[serde(rename = "expected_close_date", default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
+    pub expected_close_date: Option<Option<String>>,
     #[serde(rename = "name", default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
     pub name: Option<Option<String>>,
-    #[serde(rename = "size", default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
-    pub size: Option<Option<f32>>,
+    /// Reassigns the deal to another member of the organization.
+    #[serde(rename = "owner_user_id", default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
+    pub owner_user_id: Option<Option<String>>,
+    /// Likelihood of the deal closing as won, from 0 to 100.
+    #[serde(rename = "probability", default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
+    pub probability: Option<Option<i32>>,
+    /// Closes the deal as won or lost and records when it was closed. Null reopens the deal.
+    #[serde(rename = "won", default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
+    pub won: Option<Option<bool>>,
 }
 
 impl UpdateDealReqPayload {
     pub fn new() -> UpdateDealReqPayload {
         UpdateDealReqPayload {
             active: None,
+            amount: None,
+            currency: None,
+            expected_close_date: None,
             name: None,
-            size: None,
+            owner_user_id: None,
+            probability: None,
+            won: None,
         }
     }
 }
@@ -12,6 +12,9 @@ use crate::models;
 
 #[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
 pub struct UpdateOrgReqPayload {
+    /// ISO 4217 code used for new deals that don't specify a currency.
+    #[serde(rename = "default_currency", default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
+    pub default_currency: Option<Option<String>>,
     #[serde(rename = "name")]
     pub name: String,
 }
@@ -19,6 +22,7 @@ pub struct UpdateOrgReqPayload {
 impl UpdateOrgReqPayload {
     pub fn new(name: String) -> UpdateOrgReqPayload {
         UpdateOrgReqPayload {
+            default_currency: None,
             name,
         }
     }
@@ -18,6 +18,9 @@ pub struct UpdateTaskReqPayload {
     pub deadline: Option<Option<String>>,
     #[serde(rename = "description", default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
     pub description: Option<Option<String>>,
+    /// Reassigns the task to another member of the organization.
+    #[serde(rename = "owner_user_id", default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
+    pub owner_user_id: Option<Option<String>>,
 }
 
 impl UpdateTaskReqPayload {
@@ -26,6 +29,7 @@ impl UpdateTaskReqPayload {
             contact_id: None,
             deadline: None,
             description: None,
+            owner_user_id: None,
         }
     }
 }
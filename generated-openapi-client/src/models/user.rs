@@ -14,10 +14,16 @@ use crate::models;
 pub struct User {
     #[serde(rename = "created_at")]
     pub created_at: String,
+    /// Disabled users are treated as logged out.
+    #[serde(rename = "disabled", skip_serializing_if = "Option::is_none")]
+    pub disabled: Option<bool>,
     #[serde(rename = "email")]
     pub email: String,
     #[serde(rename = "id")]
     pub id: String,
+    /// Whether the user can manage the whole instance through the admin routes.
+    #[serde(rename = "is_superuser", skip_serializing_if = "Option::is_none")]
+    pub is_superuser: Option<bool>,
     #[serde(rename = "name", default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
     pub name: Option<Option<String>>,
     #[serde(rename = "updated_at")]
@@ -28,8 +34,10 @@ impl User {
     pub fn new(created_at: String, email: String, id: String, updated_at: String) -> User {
         User {
             created_at,
+            disabled: None,
             email,
             id,
+            is_superuser: None,
             name: None,
             updated_at,
         }
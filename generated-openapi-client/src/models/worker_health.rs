@@ -0,0 +1,42 @@
+/*
+ * Trieve API
+ *
+ * Trieve OpenAPI Specification. This document describes all of the operations available through the Trieve API.
+ *
+ * The version of the OpenAPI document: 0.0.1
+ * Contact: developers@trieve.ai
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WorkerHealth {
+    #[serde(rename = "consecutive_failures")]
+    pub consecutive_failures: i32,
+    /// Error of the last run, cleared once a run succeeds.
+    #[serde(rename = "last_error", default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<Option<String>>,
+    #[serde(rename = "last_run_at", default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
+    pub last_run_at: Option<Option<String>>,
+    #[serde(rename = "last_success_at", default, with = "::serde_with::rust::double_option", skip_serializing_if = "Option::is_none")]
+    pub last_success_at: Option<Option<String>>,
+    #[serde(rename = "name")]
+    pub name: String,
+    #[serde(rename = "state")]
+    pub state: models::WorkerState,
+}
+
+impl WorkerHealth {
+    pub fn new(consecutive_failures: i32, name: String, state: models::WorkerState) -> WorkerHealth {
+        WorkerHealth {
+            consecutive_failures,
+            last_error: None,
+            last_run_at: None,
+            last_success_at: None,
+            name,
+            state,
+        }
+    }
+}
+
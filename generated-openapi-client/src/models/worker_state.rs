@@ -0,0 +1,43 @@
+/*
+ * Trieve API
+ *
+ * Trieve OpenAPI Specification. This document describes all of the operations available through the Trieve API.
+ *
+ * The version of the OpenAPI document: 0.0.1
+ * Contact: developers@trieve.ai
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+
+/// 
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub enum WorkerState {
+    #[serde(rename = "idle")]
+    Idle,
+    #[serde(rename = "running")]
+    Running,
+    #[serde(rename = "standby")]
+    Standby,
+    #[serde(rename = "stopped")]
+    Stopped,
+
+}
+
+impl ToString for WorkerState {
+    fn to_string(&self) -> String {
+        match self {
+            Self::Idle => String::from("idle"),
+            Self::Running => String::from("running"),
+            Self::Standby => String::from("standby"),
+            Self::Stopped => String::from("stopped"),
+        }
+    }
+}
+
+impl Default for WorkerState {
+    fn default() -> WorkerState {
+        Self::Idle
+    }
+}
+